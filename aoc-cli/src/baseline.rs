@@ -0,0 +1,228 @@
+//! Answers and timings of a run of the days stored as JSON, used by `aoc compare` to find regressions.
+//!
+//! A baseline looks like `{"days": [{"day": 1, "answers": ["7", "5"], "elapsed_ms": 1.25}, ...]}`,
+//! days that failed have an `"error"` instead of their answers.
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::{fmt::Display, fs, path::Path, time::Duration};
+
+/// Slowdowns by less than this are noise, however large they are relative to the baseline.
+pub const MIN_SLOWDOWN: Duration = Duration::from_millis(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRun {
+    pub day: u32,
+    pub answers: Result<Vec<String>, String>,
+    pub elapsed: Duration,
+}
+
+impl DayRun {
+    fn to_json(&self) -> Value {
+        let mut run = json!({
+            "day": self.day,
+            "elapsed_ms": self.elapsed.as_secs_f64() * 1000.0,
+        });
+        match &self.answers {
+            Ok(answers) => run["answers"] = json!(answers),
+            Err(error) => run["error"] = json!(error),
+        }
+        run
+    }
+
+    fn from_json(value: &Value) -> Result<DayRun> {
+        let field = |name: &str| {
+            value
+                .get(name)
+                .ok_or_else(|| anyhow!("Baseline entry without {}: {}", name, value))
+        };
+        let day = field("day")?
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid day in {}", value))?;
+        let elapsed_ms = field("elapsed_ms")?
+            .as_f64()
+            .ok_or_else(|| anyhow!("Invalid time in {}", value))?;
+        let answers = match value.get("error") {
+            Some(error) => Err(error.as_str().unwrap_or_default().to_string()),
+            None => Ok(field("answers")?
+                .as_array()
+                .ok_or_else(|| anyhow!("Invalid answers in {}", value))?
+                .iter()
+                .map(|answer| answer.as_str().map(String::from))
+                .collect::<Option<_>>()
+                .ok_or_else(|| anyhow!("Answers must be strings in {}", value))?),
+        };
+        Ok(DayRun {
+            day: day as u32,
+            answers,
+            elapsed: Duration::from_secs_f64(elapsed_ms / 1000.0),
+        })
+    }
+}
+
+pub fn to_json(runs: &[DayRun]) -> String {
+    let days: Vec<Value> = runs.iter().map(DayRun::to_json).collect();
+    serde_json::to_string_pretty(&json!({ "days": days })).unwrap()
+}
+
+pub fn from_json(json: &str) -> Result<Vec<DayRun>> {
+    let baseline: Value = serde_json::from_str(json)?;
+    baseline["days"]
+        .as_array()
+        .ok_or_else(|| anyhow!("The baseline has no list of days"))?
+        .iter()
+        .map(DayRun::from_json)
+        .collect()
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<DayRun>> {
+    from_json(&fs::read_to_string(path)?)
+}
+
+pub fn save<P: AsRef<Path>>(path: P, runs: &[DayRun]) -> Result<()> {
+    Ok(fs::write(path, to_json(runs) + "\n")?)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Regression {
+    ChangedAnswers {
+        day: u32,
+        before: Vec<String>,
+        after: Vec<String>,
+    },
+    /// The day failed now, no matter whether it worked in the baseline
+    Failed { day: u32, error: String },
+    Slowdown {
+        day: u32,
+        before: Duration,
+        after: Duration,
+        percent: f64,
+    },
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Regression::ChangedAnswers { day, before, after } => write!(
+                f,
+                "Day {:02} | answers changed from {} to {}",
+                day,
+                before.join(" | "),
+                after.join(" | ")
+            ),
+            Regression::Failed { day, error } => write!(f, "Day {:02} | failed: {}", day, error),
+            Regression::Slowdown {
+                day,
+                before,
+                after,
+                percent,
+            } => write!(
+                f,
+                "Day {:02} | {:.2?} -> {:.2?}, {:+.1}% slower",
+                day, before, after, percent
+            ),
+        }
+    }
+}
+
+/// Regressions of `current` compared to `baseline`, in the order of `current`.
+/// Days missing from the baseline or failing in it can't regress, slowdowns only count above `max_slowdown_percent`.
+pub fn compare(
+    baseline: &[DayRun],
+    current: &[DayRun],
+    max_slowdown_percent: f64,
+) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for run in current {
+        let answers = match &run.answers {
+            Ok(answers) => answers,
+            Err(error) => {
+                regressions.push(Regression::Failed {
+                    day: run.day,
+                    error: error.clone(),
+                });
+                continue;
+            }
+        };
+        let Some(before) = baseline.iter().find(|before| before.day == run.day) else {
+            continue;
+        };
+        // A day that failed in the baseline has nothing to compare with, it was fixed in the meantime
+        let Ok(before_answers) = &before.answers else {
+            continue;
+        };
+        if before_answers != answers {
+            regressions.push(Regression::ChangedAnswers {
+                day: run.day,
+                before: before_answers.clone(),
+                after: answers.clone(),
+            });
+        }
+        let percent =
+            (run.elapsed.as_secs_f64() / before.elapsed.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0)
+                * 100.0;
+        if percent > max_slowdown_percent && run.elapsed >= before.elapsed + MIN_SLOWDOWN {
+            regressions.push(Regression::Slowdown {
+                day: run.day,
+                before: before.elapsed,
+                after: run.elapsed,
+                percent,
+            });
+        }
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_with_baseline() {
+        let ms = Duration::from_millis;
+        let run = |day, answers: Result<&[&str], &str>, elapsed| DayRun {
+            day,
+            answers: answers
+                .map(|answers| answers.iter().map(|a| a.to_string()).collect())
+                .map_err(String::from),
+            elapsed: ms(elapsed),
+        };
+        let old = vec![
+            run(1, Ok(&["7", "5"]), 10),
+            run(2, Ok(&["150", "900"]), 100),
+            run(3, Err("not built"), 1),
+            run(4, Ok(&["4512", "1924"]), 1),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("answers.json");
+        save(&path, &old).unwrap();
+        assert_eq!(load(&path).unwrap(), old);
+        assert!(from_json("{\"days\": [{\"day\": 1}]}").is_err());
+        assert!(from_json("[]").is_err());
+
+        let new = vec![
+            run(1, Ok(&["7", "6"]), 10),
+            run(2, Ok(&["150", "900"]), 130),
+            run(3, Ok(&["198", "230"]), 50),
+            // Far slower relative to the baseline, but only by a few milliseconds
+            run(4, Ok(&["4512", "1924"]), 3),
+            run(5, Err("day05 is not built"), 1),
+            run(6, Ok(&["5934"]), 10),
+        ];
+        let regressions = compare(&old, &new, 20.0);
+        assert_eq!(
+            regressions
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "Day 01 | answers changed from 7 | 5 to 7 | 6",
+                "Day 02 | 100.00ms -> 130.00ms, +30.0% slower",
+                "Day 05 | failed: day05 is not built",
+            ]
+        );
+        assert_eq!(compare(&old, &new, 50.0).len(), 2);
+        assert!(compare(&old, &old[..2], 0.0).is_empty());
+        drop(dir);
+    }
+}
@@ -0,0 +1,213 @@
+//! Statistics over repeated runs of a day and a history of them, used by `aoc bench`.
+//!
+//! The history is a plain text file with one line per recorded benchmark:
+//! `<day> <unix time> <runs> <mean ns> <median ns> <stddev ns>`.
+
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default location of the benchmark history, relative to the working directory.
+pub const HISTORY_FILE: &str = ".aoc_bench_history";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub runs: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    /// Runs outside of the Tukey fences, i.e. more than 1.5 interquartile ranges away from the middle half
+    pub outliers: usize,
+}
+
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (low, high) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (pos - low as f64)
+}
+
+impl Summary {
+    /// `None` if there are no samples.
+    pub fn new(samples: &[Duration]) -> Option<Summary> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        nanos.sort_by(|a, b| a.total_cmp(b));
+        let n = nanos.len() as f64;
+        let mean = nanos.iter().sum::<f64>() / n;
+        let variance = if nanos.len() > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let (q1, q3) = (quantile(&nanos, 0.25), quantile(&nanos, 0.75));
+        let fence = 1.5 * (q3 - q1);
+        let outliers = nanos
+            .iter()
+            .filter(|&&x| x < q1 - fence || x > q3 + fence)
+            .count();
+        let duration = |nanos: f64| Duration::from_nanos(nanos.round() as u64);
+        Some(Summary {
+            runs: nanos.len(),
+            mean: duration(mean),
+            median: duration(quantile(&nanos, 0.5)),
+            stddev: duration(variance.sqrt()),
+            min: duration(nanos[0]),
+            max: duration(nanos[nanos.len() - 1]),
+            outliers,
+        })
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} runs | mean {:>10.2?} | median {:>10.2?} | stddev {:>9.2?} | min {:>10.2?} | max {:>10.2?} | {} outliers",
+            self.runs, self.mean, self.median, self.stddev, self.min, self.max, self.outliers
+        )
+    }
+}
+
+/// A benchmark of a day as stored in the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record {
+    pub day: u32,
+    pub timestamp: u64,
+    pub runs: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+}
+
+impl Record {
+    pub fn new(day: u32, summary: &Summary) -> Record {
+        Record {
+            day,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            runs: summary.runs,
+            mean: summary.mean,
+            median: summary.median,
+            stddev: summary.stddev,
+        }
+    }
+
+    fn parse(line: &str) -> Result<Record> {
+        let fields: Vec<u64> = line
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        match fields[..] {
+            [day, timestamp, runs, mean, median, stddev] => Ok(Record {
+                day: day as u32,
+                timestamp,
+                runs: runs as usize,
+                mean: Duration::from_nanos(mean),
+                median: Duration::from_nanos(median),
+                stddev: Duration::from_nanos(stddev),
+            }),
+            _ => Err(anyhow!("Malformed benchmark record {}", line)),
+        }
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.day,
+            self.timestamp,
+            self.runs,
+            self.mean.as_nanos(),
+            self.median.as_nanos(),
+            self.stddev.as_nanos()
+        )
+    }
+}
+
+/// All records of the history file in the order they were added, a missing file is an empty history.
+pub fn load_history<P: AsRef<Path>>(path: P) -> Result<Vec<Record>> {
+    match fs::read_to_string(path) {
+        Ok(content) => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Record::parse)
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn append_history<P: AsRef<Path>>(path: P, record: &Record) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", record)?;
+    Ok(())
+}
+
+/// The most recent record of `day`.
+pub fn previous(history: &[Record], day: u32) -> Option<&Record> {
+    history.iter().rev().find(|record| record.day == day)
+}
+
+/// Relative change of the mean and the median compared to `previous`, e.g. `mean -4.2%, median -3.9%`.
+pub fn describe_delta(previous: &Record, summary: &Summary) -> String {
+    let change = |before: Duration, after: Duration| {
+        (after.as_secs_f64() / before.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0) * 100.0
+    };
+    format!(
+        "mean {:+.1}%, median {:+.1}% vs previous run",
+        change(previous.mean, summary.mean),
+        change(previous.median, summary.median)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_statistics() {
+        let ms = Duration::from_millis;
+        let samples = [10, 12, 11, 13, 12, 11, 40, 12].map(ms);
+        let summary = Summary::new(&samples).unwrap();
+        assert_eq!(summary.runs, 8);
+        assert_eq!(summary.mean, ms(121) / 8);
+        assert_eq!(summary.median, ms(12));
+        assert_eq!((summary.min, summary.max), (ms(10), ms(40)));
+        assert_eq!(summary.outliers, 1);
+        assert!(summary.stddev > ms(9) && summary.stddev < ms(11));
+        assert_eq!(Summary::new(&[ms(5)]).unwrap().stddev, Duration::ZERO);
+        assert_eq!(Summary::new(&[]), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+        assert!(load_history(&path).unwrap().is_empty());
+        let first = Record::new(19, &summary);
+        append_history(&path, &first).unwrap();
+        append_history(&path, &Record::new(22, &summary)).unwrap();
+        let faster = Summary::new(&[ms(9), ms(11)]).unwrap();
+        append_history(&path, &Record::new(19, &faster)).unwrap();
+
+        let history = load_history(&path).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0], first);
+        let last = previous(&history, 19).unwrap();
+        assert_eq!(last.mean, ms(10));
+        assert_eq!(
+            describe_delta(last, &summary),
+            "mean +51.2%, median +20.0% vs previous run"
+        );
+        assert_eq!(previous(&history, 5), None);
+    }
+}
@@ -0,0 +1,755 @@
+//! Runs the binaries of several days and collects their answers in a table.
+//! The days are built as separate binaries, so build them first with `cargo build --release --bins`.
+//! `aoc run --day N --example` solves the day's example embedded into the runner and checks the answers.
+//! `--checkpoint DIR` lets the long simulations save their state into `DIR`, `--resume` continues from it.
+//! `aoc play --day N` starts the interactive mode of a day instead, `aoc repl --day N` the REPL
+//! exploring its parsed input, `aoc bench --day N` runs a day repeatedly and compares the timings
+//! with the previous benchmark.
+//! `aoc compare --baseline FILE` runs the days and reports changed answers and slowdowns compared to an earlier run.
+//! `aoc stats --day N` prints the structure of a day's parsed input without solving it.
+//! `aoc fix --day 10 --output FILE` writes a repaired copy of a day's input.
+//! `aoc leaderboard --id X` shows a private leaderboard, it needs the `online` feature and a session cookie.
+//! `aoc progress` shows when each day first passed `--example`, which the runner records in a state file.
+//! `aoc fetch --day N` downloads a missing input, the day binaries call it when their input file is missing.
+
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::{self, AllocStats};
+use aoc2021::examples::{self, check_answers, parse_answers};
+use aoc2021::report::{Verbosity, VERBOSITY_VAR};
+use aoc_cli::baseline::{self, DayRun};
+use aoc_cli::bench::{self, Record, Summary};
+use aoc_cli::fetch;
+use aoc_cli::progress::{self, Progress};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    process::Command,
+    sync::{mpsc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use tempfile::TempPath;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Runs every day once and prints the answers
+    Run,
+    /// Hands the terminal to the interactive mode of a single day
+    Play,
+    /// Hands the terminal to the REPL of a single day
+    Repl,
+    /// Runs every day repeatedly and prints statistics of the timings
+    Bench,
+    /// Runs every day once and compares answers and timings with a baseline
+    Compare,
+    /// Prints statistics of the parsed input of the days that support it
+    Stats,
+    /// Writes a repaired copy of the input of a single day
+    Fix,
+    /// Shows the stars of a private leaderboard and the completion times of the given days
+    Leaderboard,
+    /// Shows when each day was first verified on its example
+    Progress,
+    /// Downloads the inputs of the days that are missing
+    Fetch,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    mode: Mode,
+    days: Vec<u32>,
+    threads: usize,
+    /// Benchmark mode runs one day at a time so the timings don't influence each other
+    bench: bool,
+    /// Passed on to the days, which write their intermediate state into a subdirectory of it
+    artifacts: Option<PathBuf>,
+    /// Runs the days on their main example instead of the puzzle input and checks the answers
+    example: bool,
+    /// Passed on to the days with checkpoints, which save their state as `dayNN.json` in it
+    checkpoints: Option<PathBuf>,
+    /// Lets the days with checkpoints continue from the saved state
+    resume: bool,
+    /// Passed on to the days through the environment, not every day accepts the flags
+    verbosity: Option<Verbosity>,
+    /// Measured runs per day in `Mode::Bench`
+    runs: usize,
+    /// Runs per day before the measurement starts in `Mode::Bench`
+    warmup: usize,
+    /// Where `Mode::Bench` records its results
+    history: PathBuf,
+    /// The earlier run `Mode::Compare` compares with, it is recorded if it doesn't exist yet
+    baseline: Option<PathBuf>,
+    /// Slowdowns by more than this many percent are regressions in `Mode::Compare`
+    max_slowdown: u32,
+    /// Replaces the baseline with the current run after comparing
+    update_baseline: bool,
+    /// The private leaderboard `Mode::Leaderboard` shows
+    leaderboard: Option<String>,
+    /// Where the first verification of each day is recorded
+    progress: PathBuf,
+    /// Where `Mode::Fix` writes the repaired input
+    output: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            mode: Mode::Run,
+            days: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            bench: false,
+            artifacts: None,
+            example: false,
+            checkpoints: None,
+            resume: false,
+            verbosity: None,
+            runs: 20,
+            warmup: 2,
+            history: PathBuf::from(bench::HISTORY_FILE),
+            baseline: None,
+            max_slowdown: 20,
+            update_baseline: false,
+            leaderboard: None,
+            progress: PathBuf::from(progress::PROGRESS_FILE),
+            output: None,
+        }
+    }
+}
+
+/// Days with an interactive mode that `aoc play` can start.
+const PLAYABLE_DAYS: &[u32] = &[23];
+
+/// Days with a REPL for their parsed input that `aoc repl` can start.
+const REPL_DAYS: &[u32] = &[12, 16, 19, 22];
+
+/// Days that print statistics of their input with `--stats`.
+const STATS_DAYS: &[u32] = &[12, 19, 22];
+
+/// Days that write a repaired copy of their input with `--fix FILE`.
+const FIX_DAYS: &[u32] = &[10];
+
+/// Days that write their intermediate state with `--artifacts DIR`, the others reject the flag.
+const ARTIFACT_DAYS: &[u32] = &[2, 13, 14, 19, 22, 23];
+
+/// Days that save checkpoints with `--checkpoint FILE` and continue from them with `--resume`.
+const CHECKPOINT_DAYS: &[u32] = &[20, 25];
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+        match arg.as_str() {
+            "--all" => options.days = (1..=25).collect(),
+            "--day" => options.days.push(value()?.parse()?),
+            "--threads" => options.threads = value()?.parse::<usize>()?.max(1),
+            "--bench" => options.bench = true,
+            "--artifacts" => options.artifacts = Some(value()?.into()),
+            "--example" => options.example = true,
+            "--checkpoint" => options.checkpoints = Some(value()?.into()),
+            "--resume" => options.resume = true,
+            "--quiet" => options.verbosity = Some(Verbosity::Quiet),
+            "--verbose" => options.verbosity = Some(Verbosity::Verbose),
+            "--runs" => options.runs = value()?.parse::<usize>()?.max(1),
+            "--warmup" => options.warmup = value()?.parse()?,
+            "--history" => options.history = value()?.into(),
+            "--baseline" => options.baseline = Some(value()?.into()),
+            "--max-slowdown" => options.max_slowdown = value()?.parse()?,
+            "--update" => options.update_baseline = true,
+            "--id" => options.leaderboard = Some(value()?),
+            "--progress" => options.progress = value()?.into(),
+            "--output" => options.output = Some(value()?.into()),
+            "run" => options.mode = Mode::Run,
+            "play" => options.mode = Mode::Play,
+            "repl" => options.mode = Mode::Repl,
+            "bench" => options.mode = Mode::Bench,
+            "compare" => options.mode = Mode::Compare,
+            "stats" => options.mode = Mode::Stats,
+            "fix" => options.mode = Mode::Fix,
+            "leaderboard" => options.mode = Mode::Leaderboard,
+            "progress" => options.mode = Mode::Progress,
+            "fetch" => options.mode = Mode::Fetch,
+            _ => bail!("Unknown argument {}", arg),
+        }
+    }
+    if options.mode == Mode::Compare {
+        if options.baseline.is_none() {
+            bail!("Pass the answers to compare with as --baseline FILE");
+        }
+        if options.days.is_empty() {
+            options.days = (1..=25).collect();
+        }
+    }
+    if options.resume && options.checkpoints.is_none() {
+        bail!("--resume needs the directory of the checkpoints as --checkpoint DIR");
+    }
+    if options.mode == Mode::Progress {
+        return Ok(options);
+    }
+    if options.mode == Mode::Leaderboard {
+        // The days are optional here, they select which completion times are shown
+        if options.leaderboard.is_none() {
+            bail!("Pass the number of the private leaderboard as --id X");
+        }
+        return Ok(options);
+    }
+    if options.days.is_empty() {
+        bail!("Nothing to run, pass --all or --day N");
+    }
+    if options.mode == Mode::Play {
+        match options.days[..] {
+            [day] if PLAYABLE_DAYS.contains(&day) => (),
+            [day] => bail!(
+                "Day {} has no play mode, try one of {:?}",
+                day,
+                PLAYABLE_DAYS
+            ),
+            _ => bail!("Pass a single day to play"),
+        }
+    }
+    if options.mode == Mode::Repl {
+        match options.days[..] {
+            [day] if REPL_DAYS.contains(&day) => (),
+            [day] => bail!("Day {} has no REPL, try one of {:?}", day, REPL_DAYS),
+            _ => bail!("Pass a single day to explore"),
+        }
+    }
+    if options.mode == Mode::Fix {
+        match options.days[..] {
+            [day] if FIX_DAYS.contains(&day) => (),
+            [day] => bail!("Day {} has no repair mode, try one of {:?}", day, FIX_DAYS),
+            _ => bail!("Pass a single day to fix"),
+        }
+        if options.output.is_none() {
+            bail!("Pass the file for the repaired input as --output FILE");
+        }
+    }
+    if options.mode == Mode::Stats {
+        if let Some(day) = options.days.iter().find(|day| !STATS_DAYS.contains(day)) {
+            bail!(
+                "Day {} has no input statistics, try one of {:?}",
+                day,
+                STATS_DAYS
+            );
+        }
+    }
+    Ok(options)
+}
+
+#[derive(Debug)]
+struct DayResult {
+    day: u32,
+    elapsed: Duration,
+    outcome: Result<Vec<String>>,
+    /// What the day reported on stderr when it is built with the `alloc_stats` feature
+    alloc_stats: Vec<(String, AllocStats)>,
+}
+
+fn day_binary(day: u32) -> Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    Ok(exe.with_file_name(format!("day{:02}{}", day, std::env::consts::EXE_SUFFIX)))
+}
+
+/// The day's main example written to a temporary file, which is deleted once the returned path is dropped.
+fn example_input(day: u32) -> Result<(&'static str, TempPath)> {
+    let fixture = *examples::fixtures(day)
+        .first()
+        .ok_or_else(|| anyhow!("There is no example for day {}", day))?;
+    Ok((fixture, examples::input_file(fixture)?))
+}
+
+fn run_day(day: u32, options: &Options) -> DayResult {
+    let start = Instant::now();
+    let mut alloc_stats = Vec::new();
+    let outcome = day_binary(day).and_then(|binary| {
+        if !binary.exists() {
+            bail!("{} is not built", binary.display());
+        }
+        let mut command = Command::new(binary);
+        if let Some(dir) = options
+            .artifacts
+            .as_ref()
+            .filter(|_| ARTIFACT_DAYS.contains(&day))
+        {
+            command.arg("--artifacts").arg(dir);
+        }
+        if let Some(dir) = options
+            .checkpoints
+            .as_ref()
+            .filter(|_| CHECKPOINT_DAYS.contains(&day))
+        {
+            std::fs::create_dir_all(dir)?;
+            command
+                .arg("--checkpoint")
+                .arg(dir.join(format!("day{:02}.json", day)));
+            if options.resume {
+                command.arg("--resume");
+            }
+        }
+        if let Some(verbosity) = options.verbosity {
+            command.env(VERBOSITY_VAR, verbosity.to_string());
+        }
+        // The temporary input has to outlive the run
+        let example = options.example.then(|| example_input(day)).transpose()?;
+        if let Some((_, input)) = &example {
+            command.env(aoc2021::INPUT_VAR, input);
+        }
+        let output = command.output()?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            bail!("{}", stderr.lines().next().unwrap_or("failed"));
+        }
+        alloc_stats = alloc_stats::parse_reports(&stderr);
+        let answers = parse_answers(&String::from_utf8_lossy(&output.stdout));
+        if let Some((fixture, _)) = example {
+            if let Some(mismatch) = check_answers(day, fixture, &answers).first() {
+                bail!("{}", mismatch);
+            }
+        }
+        Ok(answers)
+    });
+    DayResult {
+        day,
+        elapsed: start.elapsed(),
+        outcome,
+        alloc_stats,
+    }
+}
+
+/// Runs the days on a pool of threads, results are handed to `on_result` as soon as they are available.
+fn run_days<F: FnMut(DayResult)>(options: &Options, mut on_result: F) {
+    // Timings that get compared with a baseline shouldn't depend on what else is running
+    if options.bench || options.threads == 1 || options.mode == Mode::Compare {
+        options
+            .days
+            .iter()
+            .for_each(|&day| on_result(run_day(day, options)));
+        return;
+    }
+
+    let queue = Mutex::new(options.days.iter().copied().collect::<VecDeque<_>>());
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..options.threads.min(options.days.len()) {
+            let queue = &queue;
+            let sender = sender.clone();
+            scope.spawn(move || loop {
+                let day = queue.lock().unwrap().pop_front();
+                match day {
+                    Some(day) => sender.send(run_day(day, options)).unwrap(),
+                    None => break,
+                }
+            });
+        }
+        drop(sender);
+        receiver.into_iter().for_each(&mut on_result);
+    });
+}
+
+/// The answers of a day, followed by one line per allocation report if it sent any.
+fn format_row(result: &DayResult) -> String {
+    let answers = match &result.outcome {
+        Ok(answers) => answers.join(" | "),
+        Err(e) => format!("error: {}", e),
+    };
+    let mut row = format!(
+        "Day {:02} | {:>10.2?} | {}",
+        result.day, result.elapsed, answers
+    );
+    for (label, stats) in &result.alloc_stats {
+        row.push_str(&format!("\n       | {}", stats.report(label)));
+    }
+    row
+}
+
+/// Runs a day with `flag`, `--play` or `--repl`, and lets it talk to the terminal directly.
+fn interact(day: u32, options: &Options, flag: &str) -> Result<()> {
+    let binary = day_binary(day)?;
+    if !binary.exists() {
+        bail!("{} is not built", binary.display());
+    }
+    let mut command = Command::new(binary);
+    command.arg(flag);
+    // The temporary input has to outlive the session
+    let example = options.example.then(|| example_input(day)).transpose()?;
+    if let Some((_, input)) = &example {
+        command.env(aoc2021::INPUT_VAR, input);
+    }
+    let status = command.status()?;
+    if !status.success() {
+        bail!("Day {} exited with {}", day, status);
+    }
+    Ok(())
+}
+
+/// Runs a day with `--stats` and returns what it printed.
+fn day_stats(day: u32, options: &Options) -> Result<String> {
+    run_with_args(day, options, &["--stats".into()])
+}
+
+/// Runs a day with `--fix` and returns its summary of the repair.
+fn fix(day: u32, options: &Options) -> Result<String> {
+    let output = options.output.as_ref().unwrap();
+    run_with_args(day, options, &["--fix".into(), output.into()])
+}
+
+/// Runs a day with the given arguments instead of solving it and returns what it printed.
+fn run_with_args(day: u32, options: &Options, args: &[std::ffi::OsString]) -> Result<String> {
+    let binary = day_binary(day)?;
+    if !binary.exists() {
+        bail!("{} is not built", binary.display());
+    }
+    let mut command = Command::new(binary);
+    command.args(args);
+    // The temporary input has to outlive the run
+    let example = options.example.then(|| example_input(day)).transpose()?;
+    if let Some((_, input)) = &example {
+        command.env(aoc2021::INPUT_VAR, input);
+    }
+    let output = command.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{}", stderr.lines().next().unwrap_or("failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Times `runs` runs of a day after `warmup` unmeasured ones, the days run one after another.
+fn bench_day(day: u32, options: &Options) -> Result<Summary> {
+    let mut samples = Vec::with_capacity(options.runs);
+    for run in 0..options.warmup + options.runs {
+        let result = run_day(day, options);
+        if let Err(e) = result.outcome {
+            bail!("Day {} failed: {}", day, e);
+        }
+        if run >= options.warmup {
+            samples.push(result.elapsed);
+        }
+    }
+    Summary::new(&samples).ok_or_else(|| anyhow!("No runs of day {}", day))
+}
+
+/// Benchmarks the days and records the results, printing the change to the previous benchmark of each day.
+fn bench_days(options: &Options) -> Result<()> {
+    let history = bench::load_history(&options.history)?;
+    for &day in &options.days {
+        let summary = bench_day(day, options)?;
+        let delta = bench::previous(&history, day).map_or_else(
+            || "first recorded run".to_string(),
+            |previous| bench::describe_delta(previous, &summary),
+        );
+        println!("Day {:02} | {} | {}", day, summary, delta);
+        bench::append_history(&options.history, &Record::new(day, &summary))?;
+    }
+    Ok(())
+}
+
+/// Runs the days and prints the regressions compared to the baseline, which is recorded first if it doesn't exist.
+fn compare(options: &Options) -> Result<()> {
+    let path = options.baseline.as_ref().unwrap();
+    let mut runs = Vec::new();
+    run_days(options, |result| {
+        println!("{}", format_row(&result));
+        runs.push(DayRun {
+            day: result.day,
+            answers: result.outcome.map_err(|e| e.to_string()),
+            elapsed: result.elapsed,
+        });
+    });
+    if !path.exists() {
+        baseline::save(path, &runs)?;
+        println!("Recorded the baseline in {}", path.display());
+        return Ok(());
+    }
+    let regressions = baseline::compare(&baseline::load(path)?, &runs, options.max_slowdown as f64);
+    for regression in &regressions {
+        println!("{}", regression);
+    }
+    if options.update_baseline {
+        baseline::save(path, &runs)?;
+    }
+    match regressions.len() {
+        0 => {
+            println!("No regressions compared to {}", path.display());
+            Ok(())
+        }
+        n => bail!("{} regressions compared to {}", n, path.display()),
+    }
+}
+
+/// Records the days that passed verification and prints the ones that did so for the first time.
+fn record_progress(options: &Options, verified: &[u32]) -> Result<()> {
+    if verified.is_empty() {
+        return Ok(());
+    }
+    let mut progress = Progress::load(&options.progress)?;
+    let now = progress::now();
+    let mut first_time = verified
+        .iter()
+        .copied()
+        .filter(|&day| progress.record(day, now))
+        .collect::<Vec<_>>();
+    if first_time.is_empty() {
+        return Ok(());
+    }
+    first_time.sort_unstable();
+    for day in first_time {
+        println!("Day {:02} verified for the first time", day);
+    }
+    progress.save(&options.progress)
+}
+
+#[cfg(feature = "online")]
+fn show_leaderboard(options: &Options) -> Result<()> {
+    let client = aoc_cli::online::Client::from_env()?;
+    let leaderboard = aoc_cli::leaderboard::fetch(&client, options.leaderboard.as_ref().unwrap())?;
+    print!("{}", leaderboard.render());
+    for &day in &options.days {
+        println!();
+        print!("{}", leaderboard.render_day(day));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "online"))]
+fn show_leaderboard(_options: &Options) -> Result<()> {
+    bail!("The leaderboard needs network access, build with --features online")
+}
+
+fn main() -> Result<()> {
+    let options = parse_args(std::env::args().skip(1))?;
+    match options.mode {
+        Mode::Play => interact(options.days[0], &options, "--play"),
+        Mode::Repl => interact(options.days[0], &options, "--repl"),
+        Mode::Bench => bench_days(&options),
+        Mode::Compare => compare(&options),
+        Mode::Leaderboard => show_leaderboard(&options),
+        Mode::Fetch => options
+            .days
+            .iter()
+            .try_for_each(|&day| fetch::ensure_input(&fetch::input_file(day))),
+        Mode::Progress => {
+            print!("{}", Progress::load(&options.progress)?.report());
+            Ok(())
+        }
+        Mode::Fix => {
+            print!("{}", fix(options.days[0], &options)?);
+            Ok(())
+        }
+        Mode::Stats => {
+            for &day in &options.days {
+                println!("Day {:02}", day);
+                print!("{}", day_stats(day, &options)?);
+            }
+            Ok(())
+        }
+        Mode::Run => {
+            let start = Instant::now();
+            let mut verified = Vec::new();
+            run_days(&options, |result| {
+                println!("{}", format_row(&result));
+                // Only runs on the examples have answers to check
+                if options.example && result.outcome.is_ok() {
+                    verified.push(result.day);
+                }
+            });
+            println!("Total    | {:>10.2?}", start.elapsed());
+            record_progress(&options, &verified)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(&["--all", "--threads", "3"])).unwrap();
+        assert_eq!(options.days, (1..=25).collect::<Vec<_>>());
+        assert_eq!(options.threads, 3);
+        assert!(!options.bench);
+
+        let options = parse_args(args(&["--day", "5", "--day", "7", "--bench"])).unwrap();
+        assert_eq!(options.days, vec![5, 7]);
+        assert!(options.bench);
+        assert_eq!(options.artifacts, None);
+
+        let options = parse_args(args(&["--day", "13", "--artifacts", "out"])).unwrap();
+        assert_eq!(options.artifacts, Some(PathBuf::from("out")));
+        assert!(!options.example);
+        assert_eq!(options.verbosity, None);
+
+        let options = parse_args(args(&["--day", "24", "--quiet"])).unwrap();
+        assert_eq!(options.verbosity, Some(Verbosity::Quiet));
+
+        let options = parse_args(args(&["--day", "25", "--checkpoint", "cp", "--resume"])).unwrap();
+        assert_eq!(options.checkpoints, Some(PathBuf::from("cp")));
+        assert!(options.resume);
+        assert!(parse_args(args(&["--day", "25", "--resume"])).is_err());
+
+        assert!(
+            parse_args(args(&["--day", "1", "--example"]))
+                .unwrap()
+                .example
+        );
+        let options = parse_args(args(&["run", "--day", "1", "--example"])).unwrap();
+        assert_eq!((options.mode, options.example), (Mode::Run, true));
+        let (fixture, input) = example_input(1).unwrap();
+        assert_eq!(fixture, "day01");
+        assert!(input.exists());
+        assert!(example_input(26).is_err());
+
+        assert_eq!(
+            parse_args(args(&["play", "--day", "23"])).unwrap().mode,
+            Mode::Play
+        );
+        assert!(parse_args(args(&["play", "--day", "5"])).is_err());
+        assert!(parse_args(args(&["play", "--all"])).is_err());
+        let options = parse_args(args(&["repl", "--day", "19", "--example"])).unwrap();
+        assert_eq!((options.mode, options.days), (Mode::Repl, vec![19]));
+        assert!(parse_args(args(&["repl", "--day", "23"])).is_err());
+        assert!(parse_args(args(&["repl", "--day", "12", "--day", "16"])).is_err());
+
+        let options = parse_args(args(&["bench", "--day", "19", "--runs", "100"])).unwrap();
+        assert_eq!(options.mode, Mode::Bench);
+        assert_eq!((options.runs, options.warmup), (100, 2));
+        let options =
+            parse_args(args(&["bench", "--all", "--warmup", "5", "--history", "h"])).unwrap();
+        assert_eq!(options.warmup, 5);
+        assert_eq!(options.history, PathBuf::from("h"));
+
+        let options = parse_args(args(&["compare", "--baseline", "old.json"])).unwrap();
+        assert_eq!(options.mode, Mode::Compare);
+        assert_eq!(options.days.len(), 25);
+        assert_eq!(options.baseline, Some(PathBuf::from("old.json")));
+        assert_eq!((options.max_slowdown, options.update_baseline), (20, false));
+        let options = parse_args(args(&[
+            "compare",
+            "--day",
+            "3",
+            "--baseline",
+            "b.json",
+            "--max-slowdown",
+            "50",
+            "--update",
+        ]))
+        .unwrap();
+        assert_eq!(options.days, vec![3]);
+        assert_eq!((options.max_slowdown, options.update_baseline), (50, true));
+        assert!(parse_args(args(&["compare", "--all"])).is_err());
+
+        let options = parse_args(args(&["stats", "--day", "22", "--example"])).unwrap();
+        assert_eq!((options.mode, options.days), (Mode::Stats, vec![22]));
+        assert!(parse_args(args(&["stats", "--day", "5"])).is_err());
+        assert!(parse_args(args(&["stats", "--all"])).is_err());
+
+        let options = parse_args(args(&["fix", "--day", "10", "--output", "fixed.txt"])).unwrap();
+        assert_eq!((options.mode, options.days), (Mode::Fix, vec![10]));
+        assert_eq!(options.output, Some(PathBuf::from("fixed.txt")));
+        assert!(parse_args(args(&["fix", "--day", "10"])).is_err());
+        assert!(parse_args(args(&["fix", "--day", "9", "--output", "f"])).is_err());
+
+        let options = parse_args(args(&["leaderboard", "--id", "12345"])).unwrap();
+        assert_eq!(options.mode, Mode::Leaderboard);
+        assert_eq!(options.leaderboard.as_deref(), Some("12345"));
+        assert!(options.days.is_empty());
+        assert!(parse_args(args(&["leaderboard", "--day", "3"])).is_err());
+
+        let options = parse_args(args(&["progress", "--progress", "p"])).unwrap();
+        assert_eq!(options.mode, Mode::Progress);
+        assert_eq!(options.progress, PathBuf::from("p"));
+
+        let options = parse_args(args(&["fetch", "--day", "5"])).unwrap();
+        assert_eq!((options.mode, options.days), (Mode::Fetch, vec![5]));
+        assert!(parse_args(args(&["fetch"])).is_err());
+
+        assert!(parse_args(args(&[])).is_err());
+        assert!(parse_args(args(&["--day"])).is_err());
+        assert!(parse_args(args(&["--everything"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let stdout = "Some debug output\nAnswer for part 1: 42\nAnswer for part 2: abc: def\n";
+        assert_eq!(parse_answers(stdout), vec!["42", "abc: def"]);
+    }
+
+    #[test]
+    fn test_format_row() {
+        let mut result = DayResult {
+            day: 3,
+            elapsed: Duration::from_millis(5),
+            outcome: Ok(vec!["198".to_string(), "230".to_string()]),
+            alloc_stats: Vec::new(),
+        };
+        assert_eq!(format_row(&result), "Day 03 |     5.00ms | 198 | 230");
+        result.alloc_stats = alloc_stats::parse_reports(
+            "[part 1] 12 allocations, peak heap usage 640 bytes\n",
+        );
+        assert_eq!(
+            format_row(&result),
+            "Day 03 |     5.00ms | 198 | 230\n       \
+             | [part 1] 12 allocations, peak heap usage 640 bytes"
+        );
+    }
+
+    #[test]
+    fn test_missing_binary() {
+        let options = Options {
+            days: vec![98, 99],
+            threads: 2,
+            ..Options::default()
+        };
+        let mut results = Vec::new();
+        run_days(&options, |result| results.push(result));
+        results.sort_by_key(|result| result.day);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].day, 98);
+        assert!(results.iter().all(|result| result.outcome.is_err()));
+        assert!(bench_day(98, &options).is_err());
+        assert!(day_stats(98, &options).is_err());
+        assert!(interact(98, &options, "--repl").is_err());
+        assert!(fix(
+            98,
+            &Options {
+                output: Some(PathBuf::from("fixed.txt")),
+                ..Options::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_record_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = Options {
+            progress: dir.path().join("progress"),
+            ..Options::default()
+        };
+        assert_eq!(
+            Progress::load(&options.progress).unwrap(),
+            Progress::default()
+        );
+        record_progress(&options, &[]).unwrap();
+        assert!(!options.progress.exists());
+
+        record_progress(&options, &[5, 3]).unwrap();
+        let progress = Progress::load(&options.progress).unwrap();
+        let first = progress.first_verified(3).unwrap();
+        assert_eq!(progress.first_verified(5), Some(first));
+        assert_eq!(progress.first_verified(4), None);
+
+        // Later verifications keep the first timestamp
+        record_progress(&options, &[3, 4]).unwrap();
+        let progress = Progress::load(&options.progress).unwrap();
+        assert_eq!(progress.first_verified(3), Some(first));
+        assert!(progress.first_verified(4).is_some());
+
+        drop(dir);
+    }
+}
@@ -0,0 +1,103 @@
+//! Downloads the puzzle inputs of the user to `input/dayNN.txt`, so they don't have to be copied there by hand.
+//! A day binary whose input file is missing runs `aoc fetch` when it starts. Downloading needs the `online` feature
+//! and the session cookie of adventofcode.com, taken from `AOC_SESSION` or the session file.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+
+/// Environment variable with the value of the `session` cookie of a logged in browser.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Where the day binaries expect their inputs.
+pub const INPUT_DIR: &str = "input";
+
+/// The session file within the user's config directory, used if `SESSION_VAR` isn't set.
+const SESSION_FILE: &str = "aoc2021/session";
+
+pub fn input_file(day: u32) -> PathBuf {
+    Path::new(INPUT_DIR).join(format!("day{:02}.txt", day))
+}
+
+/// The day of an input file named like the ones of `input_file`.
+pub fn day_of(path: &Path) -> Option<u32> {
+    let day = path
+        .file_stem()?
+        .to_str()?
+        .strip_prefix("day")?
+        .parse()
+        .ok()?;
+    (1..=crate::leaderboard::DAYS).contains(&day).then_some(day)
+}
+
+/// `$XDG_CONFIG_HOME/aoc2021/session`, or `~/.config/aoc2021/session` without `XDG_CONFIG_HOME`.
+pub fn session_file() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?;
+    Some(config.join(SESSION_FILE))
+}
+
+/// The session cookie from `SESSION_VAR`, or else from the session file. A blank `SESSION_VAR` is an error
+/// rather than an empty cookie that adventofcode.com would turn away.
+pub fn session() -> Result<String> {
+    if let Ok(session) = std::env::var(SESSION_VAR) {
+        let session = session.trim();
+        if session.is_empty() {
+            return Err(anyhow!(
+                "{} is set but empty, set it to the session cookie of adventofcode.com or unset it",
+                SESSION_VAR
+            ));
+        }
+        return Ok(session.to_string());
+    }
+    session_file()
+        .and_then(|file| std::fs::read_to_string(file).ok())
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+        .ok_or_else(|| {
+            anyhow!(
+                "Set {} or write the session cookie of adventofcode.com to {}",
+                SESSION_VAR,
+                session_file().map_or("the session file".to_string(), |f| f.display().to_string())
+            )
+        })
+}
+
+/// Downloads the input of `day` to `path`. It is written to a temporary file first, so an
+/// interrupted download doesn't leave a truncated input behind.
+#[cfg(feature = "online")]
+pub fn fetch_input(client: &crate::online::Client, day: u32, path: &Path) -> Result<()> {
+    let input = client.get(&format!("{}/day/{}/input", crate::online::YEAR, day))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let partial = path.with_extension("partial");
+    std::fs::write(&partial, input)?;
+    std::fs::rename(partial, path)?;
+    Ok(())
+}
+
+#[cfg(feature = "online")]
+fn download(day: u32, path: &Path) -> Result<()> {
+    let client = crate::online::Client::from_env()?;
+    eprintln!("Downloading the input of day {} to {}", day, path.display());
+    fetch_input(&client, day, path)
+}
+
+#[cfg(not(feature = "online"))]
+fn download(day: u32, path: &Path) -> Result<()> {
+    Err(anyhow!(
+        "{} is missing, copy the input of day {} there or build with --features online to download it",
+        path.display(),
+        day
+    ))
+}
+
+/// Downloads the input at `path` if the file is missing, which needs `path` to name its day.
+pub fn ensure_input(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    let day = day_of(path).ok_or_else(|| anyhow!("{} is missing", path.display()))?;
+    download(day, path)
+}
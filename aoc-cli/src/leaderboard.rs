@@ -0,0 +1,188 @@
+//! Private leaderboards of adventofcode.com as returned by their JSON API, rendered for the terminal.
+//! Fetching them needs the `online` feature, parsing and rendering also work on saved responses.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::{collections::BTreeMap, time::Duration};
+
+pub const DAYS: u32 = 25;
+
+/// Unix timestamp of the first puzzle of the event, every following day unlocks 24 hours later.
+const FIRST_UNLOCK: u64 = 1_638_334_800;
+
+/// Unix timestamp at which the puzzle of a day was released.
+pub fn unlock_time(day: u32) -> u64 {
+    FIRST_UNLOCK + u64::from(day - 1) * 24 * 60 * 60
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    pub local_score: u64,
+    pub stars: u32,
+    /// Unix timestamps of the first and the second star of each day with at least one star.
+    pub completions: BTreeMap<u32, [Option<u64>; 2]>,
+}
+
+impl Member {
+    fn from_json(value: &Value) -> Result<Member> {
+        let number = |name: &str| {
+            value[name]
+                .as_u64()
+                .ok_or_else(|| anyhow!("Member without {}: {}", name, value))
+        };
+        let name = match value["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => format!("(anonymous user #{})", number("id")?),
+        };
+        let mut completions = BTreeMap::new();
+        if let Some(days) = value["completion_day_level"].as_object() {
+            for (day, parts) in days {
+                let star = |part: &str| parts[part]["get_star_ts"].as_u64();
+                completions.insert(day.parse()?, [star("1"), star("2")]);
+            }
+        }
+        Ok(Member {
+            name,
+            local_score: number("local_score")?,
+            stars: number("stars")? as u32,
+            completions,
+        })
+    }
+
+    pub fn stars_on(&self, day: u32) -> usize {
+        self.completions
+            .get(&day)
+            .map_or(0, |parts| parts.iter().flatten().count())
+    }
+
+    /// How long after the puzzle unlocked the star of a part (1 or 2) was collected.
+    pub fn time_to_solve(&self, day: u32, part: usize) -> Option<Duration> {
+        let collected = self.completions.get(&day)?[part - 1]?;
+        Some(Duration::from_secs(
+            collected.saturating_sub(unlock_time(day)),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub event: String,
+    /// Ordered by their rank, best first.
+    pub members: Vec<Member>,
+}
+
+fn format_time(time: Option<Duration>) -> String {
+    match time {
+        Some(time) => {
+            let secs = time.as_secs();
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        }
+        None => "-".to_string(),
+    }
+}
+
+impl Leaderboard {
+    pub fn from_json(json: &str) -> Result<Leaderboard> {
+        let leaderboard: Value = serde_json::from_str(json)?;
+        let mut members = leaderboard["members"]
+            .as_object()
+            .ok_or_else(|| anyhow!("The leaderboard has no members"))?
+            .values()
+            .map(Member::from_json)
+            .collect::<Result<Vec<_>>>()?;
+        members.sort_by(|a, b| {
+            (b.local_score, b.stars)
+                .cmp(&(a.local_score, a.stars))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(Leaderboard {
+            event: leaderboard["event"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            members,
+        })
+    }
+
+    /// One line per member with the score and the stars of every day, `*` for both parts and `+` for the first.
+    pub fn render(&self) -> String {
+        let days = |digit: fn(u32) -> u32| {
+            (1..=DAYS)
+                .map(|day| match digit(day) {
+                    0 if day < 10 => ' ',
+                    d => char::from_digit(d, 10).unwrap(),
+                })
+                .collect::<String>()
+        };
+        let mut lines = vec![
+            format!("{:11}{}", "", days(|day| day / 10)),
+            format!("{:11}{}", "", days(|day| day % 10)),
+        ];
+        for (rank, member) in self.members.iter().enumerate() {
+            let stars: String = (1..=DAYS)
+                .map(|day| match member.stars_on(day) {
+                    2 => '*',
+                    1 => '+',
+                    _ => '.',
+                })
+                .collect();
+            lines.push(format!(
+                "{:>3}) {:>5} {} {:>2}* {}",
+                rank + 1,
+                member.local_score,
+                stars,
+                member.stars,
+                member.name
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// The time every member needed for both parts of a day, fastest first.
+    pub fn render_day(&self, day: u32) -> String {
+        let mut solvers = self
+            .members
+            .iter()
+            .filter(|member| member.stars_on(day) > 0)
+            .map(|member| {
+                let times = [1, 2].map(|part| member.time_to_solve(day, part));
+                (member, times)
+            })
+            .collect::<Vec<_>>();
+        solvers.sort_by_key(|(_, [part1, part2])| (part2.is_none(), *part2, *part1));
+        let width = solvers
+            .iter()
+            .map(|(member, _)| member.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let mut lines = vec![format!(
+            "{:width$} {:>10} {:>10}",
+            format!("Day {}", day),
+            "part 1",
+            "part 2",
+            width = width
+        )];
+        for (member, [part1, part2]) in solvers {
+            lines.push(format!(
+                "{:width$} {:>10} {:>10}",
+                member.name,
+                format_time(part1),
+                format_time(part2),
+                width = width
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Reads a private leaderboard of this year's event, `id` is the number in its URL.
+#[cfg(feature = "online")]
+pub fn fetch(client: &crate::online::Client, id: &str) -> Result<Leaderboard> {
+    Leaderboard::from_json(&client.get(&format!(
+        "{}/leaderboard/private/view/{}.json",
+        crate::online::YEAR,
+        id
+    ))?)
+}
@@ -0,0 +1,10 @@
+//! The tooling around the day binaries of `aoc2021-solutions`: the `aoc` runner with its benchmarks,
+//! baselines and progress, and the downloads of inputs and leaderboards from adventofcode.com.
+
+pub mod baseline;
+pub mod bench;
+pub mod fetch;
+pub mod leaderboard;
+#[cfg(feature = "online")]
+pub mod online;
+pub mod progress;
@@ -0,0 +1,49 @@
+//! Requests to adventofcode.com on behalf of the user, authenticated with their session cookie.
+//! Only built with the `online` feature, everything else works without network access.
+
+use anyhow::{anyhow, Result};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u32 = 2021;
+
+pub use crate::fetch::SESSION_VAR;
+
+/// The site asks automated requests to name where they come from.
+const USER_AGENT: &str = "github.com/mayjs/advent_of_code_2021";
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(session: &str) -> Self {
+        Self::with_base_url(session, BASE_URL)
+    }
+
+    pub fn with_base_url(session: &str, base_url: &str) -> Self {
+        Client {
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+        }
+    }
+
+    /// A client with the session from `SESSION_VAR` or the session file, see `fetch::session`.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(&crate::fetch::session()?))
+    }
+
+    /// Fetches a page relative to the base url, e.g. `2021/day/1/input`.
+    pub fn get(&self, path: &str) -> Result<String> {
+        let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(|e| anyhow!("Request to {} failed: {}", url, e))?;
+        Ok(response.into_string()?)
+    }
+}
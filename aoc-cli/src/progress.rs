@@ -0,0 +1,166 @@
+//! When each day first produced verified answers, recorded by the runner and shown by `aoc progress`.
+//!
+//! A day counts as verified once a run on its example (`aoc --day N --example`) matched all expected answers.
+//! The state file is plain text with one line per verified day: `<day> <unix time>`.
+
+use crate::leaderboard::{unlock_time, DAYS};
+use anyhow::{anyhow, Result};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Default location of the progress state, relative to the working directory.
+pub const PROGRESS_FILE: &str = ".aoc_progress";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Progress {
+    verified: BTreeMap<u32, u64>,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// A unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+fn format_utc(timestamp: u64) -> String {
+    // Converts days since the epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (timestamp / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let minutes = timestamp % 86400 / 60;
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// A number of seconds as days, hours and minutes, e.g. `2d 03h 15m`.
+fn format_span(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    format!(
+        "{}d {:02}h {:02}m",
+        minutes / (24 * 60),
+        minutes / 60 % 24,
+        minutes % 60
+    )
+}
+
+impl Progress {
+    /// The recorded progress, a missing file means nothing was verified yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Progress> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Progress::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut progress = Progress::default();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [day, timestamp] => progress.record(day.parse()?, timestamp.parse()?),
+                _ => return Err(anyhow!("Malformed progress record {}", line)),
+            };
+        }
+        Ok(progress)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content: String = self
+            .verified
+            .iter()
+            .map(|(day, timestamp)| format!("{} {}\n", day, timestamp))
+            .collect();
+        Ok(fs::write(path, content)?)
+    }
+
+    /// Marks a day as verified at `timestamp`, returns whether this is the first time.
+    /// An earlier timestamp replaces a later one.
+    pub fn record(&mut self, day: u32, timestamp: u64) -> bool {
+        match self.verified.get(&day) {
+            Some(&first) => {
+                if timestamp < first {
+                    self.verified.insert(day, timestamp);
+                }
+                false
+            }
+            None => {
+                self.verified.insert(day, timestamp);
+                true
+            }
+        }
+    }
+
+    pub fn first_verified(&self, day: u32) -> Option<u64> {
+        self.verified.get(&day).copied()
+    }
+
+    /// One line per day with the time of its first verification and how long after the puzzle's release that was.
+    pub fn report(&self) -> String {
+        let mut lines: Vec<String> = (1..=DAYS)
+            .map(|day| match self.first_verified(day) {
+                Some(timestamp) => format!(
+                    "Day {:02} | {} UTC | {} after release",
+                    day,
+                    format_utc(timestamp),
+                    format_span(timestamp.saturating_sub(unlock_time(day)))
+                ),
+                None => format!("Day {:02} | not verified yet", day),
+            })
+            .collect();
+        lines.push(format!("{} of {} days verified", self.verified.len(), DAYS));
+        lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let mut progress = Progress::default();
+        // Three hours and five minutes after the release of day 1
+        assert!(progress.record(1, 1_638_334_800 + 3 * 3600 + 5 * 60));
+        assert!(!progress.record(1, 1_638_334_800 + 4 * 3600));
+        assert!(progress.record(2, 1_638_421_200 + 2 * 86400 + 59));
+        let report = progress.report();
+        let lines = report.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "Day 01 | 2021-12-01 08:05 UTC | 0d 03h 05m after release"
+        );
+        assert_eq!(
+            lines[1],
+            "Day 02 | 2021-12-04 05:00 UTC | 2d 00h 00m after release"
+        );
+        assert_eq!(lines[2], "Day 03 | not verified yet");
+        assert_eq!(lines[25], "2 of 25 days verified");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress");
+        assert_eq!(Progress::load(&path).unwrap(), Progress::default());
+        progress.save(&path).unwrap();
+        assert_eq!(Progress::load(&path).unwrap(), progress);
+        std::fs::write(&path, "1 2 3\n").unwrap();
+        assert!(Progress::load(&path).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Locating, caching and downloading inputs with `aoc_cli::fetch`.
+
+use aoc_cli::fetch::{self, day_of, ensure_input, input_file};
+use std::path::Path;
+
+#[test]
+fn test_input_files() {
+    assert_eq!(input_file(7), Path::new("input/day07.txt"));
+    assert_eq!(day_of(&input_file(7)), Some(7));
+    assert_eq!(day_of(Path::new("input/day25.txt")), Some(25));
+    assert_eq!(day_of(Path::new("input/day26.txt")), None);
+    assert_eq!(day_of(Path::new("input/dayXX.txt")), None);
+    assert_eq!(day_of(Path::new("notes.txt")), None);
+}
+
+#[test]
+fn test_session() {
+    let config = tempfile::tempdir().unwrap();
+    std::env::remove_var(fetch::SESSION_VAR);
+    std::env::set_var("XDG_CONFIG_HOME", config.path());
+    assert_eq!(
+        fetch::session_file().unwrap(),
+        config.path().join("aoc2021/session")
+    );
+    assert!(fetch::session().is_err());
+
+    std::fs::create_dir_all(config.path().join("aoc2021")).unwrap();
+    std::fs::write(config.path().join("aoc2021/session"), "from-file\n").unwrap();
+    assert_eq!(fetch::session().unwrap(), "from-file");
+    std::env::set_var(fetch::SESSION_VAR, " from-env ");
+    assert_eq!(fetch::session().unwrap(), "from-env");
+    std::env::set_var(fetch::SESSION_VAR, "  ");
+    let error = fetch::session().unwrap_err().to_string();
+    assert!(error.contains("AOC_SESSION is set but empty"), "{}", error);
+    std::env::remove_var(fetch::SESSION_VAR);
+}
+
+#[test]
+fn test_existing_inputs_are_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("day03.txt");
+    std::fs::write(&path, "00100\n").unwrap();
+    ensure_input(&path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "00100\n");
+    // Without a day in the name there is nothing to download
+    assert!(ensure_input(&dir.path().join("missing.txt")).is_err());
+}
+
+#[cfg(feature = "online")]
+#[test]
+fn test_fetch_input() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        for line in BufReader::new(&stream).lines() {
+            let line = line.unwrap();
+            if line.is_empty() {
+                break;
+            }
+            request.push(line);
+        }
+        let body = "forward 5\ndown 5\n";
+        write!(
+            &stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        request
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("input").join("day02.txt");
+    let client = aoc_cli::online::Client::with_base_url("secret", &base_url);
+    fetch::fetch_input(&client, 2, &path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "forward 5\ndown 5\n"
+    );
+
+    let request = server.join().unwrap();
+    assert_eq!(request[0], "GET /2021/day/2/input HTTP/1.1");
+    assert!(request.contains(&"Cookie: session=secret".to_string()));
+}
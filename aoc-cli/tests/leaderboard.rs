@@ -0,0 +1,84 @@
+//! `aoc_cli::leaderboard` on a response in the format of the private leaderboard API.
+
+use aoc_cli::leaderboard::Leaderboard;
+use indoc::indoc;
+use std::time::Duration;
+
+const RESPONSE: &str = r#"{
+    "event": "2021",
+    "owner_id": 1,
+    "members": {
+        "1": {
+            "id": 1, "name": "Bob", "stars": 2, "local_score": 8, "global_score": 0,
+            "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1638334920}, "2": {"get_star_ts": 1638335700}}
+            }
+        },
+        "2": {
+            "id": 2, "name": "Alice", "stars": 3, "local_score": 10, "global_score": 0,
+            "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1638335100}, "2": {"get_star_ts": 1638335400}},
+                "10": {"1": {"get_star_ts": 1639116061}}
+            }
+        },
+        "42": {
+            "id": 42, "name": null, "stars": 0, "local_score": 0, "global_score": 0,
+            "completion_day_level": {}
+        }
+    }
+}"#;
+
+#[test]
+fn test_parse_leaderboard() {
+    let leaderboard = Leaderboard::from_json(RESPONSE).unwrap();
+    assert_eq!(leaderboard.event, "2021");
+    let names: Vec<_> = leaderboard
+        .members
+        .iter()
+        .map(|m| m.name.as_str())
+        .collect();
+    assert_eq!(names, ["Alice", "Bob", "(anonymous user #42)"]);
+
+    let alice = &leaderboard.members[0];
+    assert_eq!(
+        (alice.stars_on(1), alice.stars_on(10), alice.stars_on(2)),
+        (2, 1, 0)
+    );
+    assert_eq!(alice.time_to_solve(1, 1), Some(Duration::from_secs(300)));
+    assert_eq!(alice.time_to_solve(10, 1), Some(Duration::from_secs(3661)));
+    assert_eq!(alice.time_to_solve(10, 2), None);
+
+    assert!(Leaderboard::from_json("{}").is_err());
+    assert!(Leaderboard::from_json(r#"{"members": {"1": {"id": 1}}}"#).is_err());
+}
+
+#[test]
+fn test_render_leaderboard() {
+    let leaderboard = Leaderboard::from_json(RESPONSE).unwrap();
+    let rendered = leaderboard.render();
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines[0], format!("{:20}1111111111222222", ""));
+    assert_eq!(lines[1], "           1234567890123456789012345");
+    assert_eq!(lines[2], "  1)    10 *........+...............  3* Alice");
+    assert_eq!(lines[3], "  2)     8 *........................  2* Bob");
+    assert_eq!(
+        lines[4],
+        "  3)     0 .........................  0* (anonymous user #42)"
+    );
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        leaderboard.render_day(1),
+        indoc! {"
+            Day 1      part 1     part 2
+            Alice     0:05:00    0:10:00
+            Bob       0:02:00    0:15:00
+        "}
+    );
+    assert_eq!(
+        leaderboard.render_day(10),
+        indoc! {"
+            Day 10     part 1     part 2
+            Alice     1:01:01          -
+        "}
+    );
+}
@@ -0,0 +1,54 @@
+//! Runs the days through the `aoc` runner, which starts the day binaries of `aoc2021-solutions`.
+
+use std::{path::PathBuf, process::Command};
+
+#[test]
+fn test_runner_with_artifacts() {
+    let day = PathBuf::from(env!("CARGO_BIN_EXE_aoc"))
+        .with_file_name(format!("day13{}", std::env::consts::EXE_SUFFIX));
+    assert!(
+        day.exists(),
+        "Build the days first, e.g. with `cargo test --workspace`"
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let artifacts = dir.path().join("artifacts");
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["--all", "--example", "--artifacts"])
+        .arg(&artifacts)
+        .arg("--progress")
+        .arg(dir.path().join("progress"))
+        .output()
+        .expect("Could not run the runner");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let failed = stdout
+        .lines()
+        .filter(|line| line.contains("error:"))
+        .collect::<Vec<_>>();
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+    for day in ["day13", "day14", "day22"] {
+        assert!(artifacts.join(day).is_dir(), "No artifacts of {}", day);
+    }
+}
+
+#[test]
+fn test_days_fetch_missing_inputs_with_the_runner() {
+    let day = PathBuf::from(env!("CARGO_BIN_EXE_aoc"))
+        .with_file_name(format!("day02{}", std::env::consts::EXE_SUFFIX));
+    let dir = tempfile::tempdir().unwrap();
+    // Neither an input file nor a session, so `aoc fetch` is started but can't download anything
+    let output = Command::new(day)
+        .current_dir(dir.path())
+        .env_remove("AOC_INPUT")
+        .env("AOC_SESSION", "")
+        .env("XDG_CONFIG_HOME", dir.path())
+        .output()
+        .expect("Could not run the day");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("input/day02.txt is missing and `aoc fetch` couldn't download it"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,116 @@
+use core::cmp::Ordering;
+
+/// Values a `BidiRange` can walk over.
+pub trait Step: Copy + PartialOrd {
+    /// The distance between two consecutive values, always positive.
+    type Delta: Copy + PartialOrd + Default;
+
+    /// Moves by `delta` towards larger values if `ascending` and towards smaller ones otherwise.
+    /// Returns `None` if that leaves the values the type can represent.
+    fn step(self, delta: Self::Delta, ascending: bool) -> Option<Self>;
+
+    /// Whether `self` has arrived at `end`. Types with rounding errors treat values close to `end` as arrived.
+    fn arrived_at(self, end: Self, _delta: Self::Delta) -> bool {
+        self == end
+    }
+}
+
+macro_rules! impl_step_int {
+    ($($t:ty),*) => {
+        $(
+            impl Step for $t {
+                type Delta = $t;
+
+                fn step(self, delta: Self::Delta, ascending: bool) -> Option<Self> {
+                    if ascending {
+                        self.checked_add(delta)
+                    } else {
+                        self.checked_sub(delta)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_step_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_step_float {
+    ($($t:ty),*) => {
+        $(
+            impl Step for $t {
+                type Delta = $t;
+
+                fn step(self, delta: Self::Delta, ascending: bool) -> Option<Self> {
+                    Some(if ascending { self + delta } else { self - delta })
+                }
+
+                fn arrived_at(self, end: Self, delta: Self::Delta) -> bool {
+                    // Accumulated rounding errors are way below a thousandth of a step
+                    (self - end).abs() <= delta * 1e-3
+                }
+            }
+        )*
+    };
+}
+
+impl_step_float!(f32, f64);
+
+/// Chars step over their code points, a range ends early if it would enter the surrogate gap.
+impl Step for char {
+    type Delta = u32;
+
+    fn step(self, delta: Self::Delta, ascending: bool) -> Option<Self> {
+        (self as u32)
+            .step(delta, ascending)
+            .and_then(char::from_u32)
+    }
+}
+
+/// Inclusive range that walks towards `end`, no matter if that is above or below the start.
+#[derive(Debug, Clone)]
+pub struct BidiRange<T: Step> {
+    cur: Option<T>,
+    end: T,
+    step: T::Delta,
+    ascending: bool,
+}
+
+impl<T: Step> Iterator for BidiRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.cur?;
+        if cur.arrived_at(self.end, self.step) {
+            self.cur = None;
+            return Some(self.end);
+        }
+        let passed_end = match cur.partial_cmp(&self.end) {
+            Some(Ordering::Less) => !self.ascending,
+            Some(Ordering::Greater) => self.ascending,
+            _ => true,
+        };
+        if passed_end {
+            self.cur = None;
+            return None;
+        }
+        self.cur = cur.step(self.step, self.ascending);
+        Some(cur)
+    }
+}
+
+/// Walks from `start` towards `end` in steps of `step`, which must be positive.
+/// `end` is included if it is hit, for floats that only needs to happen up to rounding errors.
+pub fn bidi_range_of<T: Step>(start: T, end: T, step: T::Delta) -> BidiRange<T> {
+    assert!(step > T::Delta::default(), "The step must be positive");
+    BidiRange {
+        cur: Some(start),
+        end,
+        step,
+        ascending: start <= end,
+    }
+}
+
+pub fn bidi_range(start: isize, end: isize) -> BidiRange<isize> {
+    bidi_range_of(start, end, 1)
+}
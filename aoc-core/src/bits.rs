@@ -0,0 +1,117 @@
+//! Conversion of transmissions in various encodings into a sequence of bits.
+//! Bits are always produced most significant bit first, independent of the platform.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::{fs, io, path::Path};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BitsError {
+    #[error("invalid hex digit {0:?}")]
+    InvalidHexDigit(char),
+    #[error("invalid base64 character {0:?}")]
+    InvalidBase64Char(char),
+    #[error("base64 input ends with a single dangling character")]
+    TruncatedBase64,
+    #[error("unknown encoding {0}, expected hex, base64 or raw")]
+    UnknownEncoding(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+    /// The bytes of the file as they are
+    Raw,
+}
+
+impl FromStr for Encoding {
+    type Err = BitsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hex" => Ok(Encoding::Hex),
+            "base64" => Ok(Encoding::Base64),
+            "raw" => Ok(Encoding::Raw),
+            _ => Err(BitsError::UnknownEncoding(s.to_string())),
+        }
+    }
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u32, count: usize) {
+    bits.extend((0..count).rev().map(|bit| value >> bit & 1 == 1));
+}
+
+pub fn bits_from_bytes(bytes: &[u8]) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(bytes.len() * 8);
+    for &byte in bytes {
+        push_bits(&mut bits, byte as u32, 8);
+    }
+    bits
+}
+
+/// Parses hex digits in either case, whitespace in between is ignored.
+pub fn parse_hex(input: &str) -> Result<Vec<bool>, BitsError> {
+    let mut bits = Vec::with_capacity(input.len() * 4);
+    for c in input.chars().filter(|c| !c.is_whitespace()) {
+        let digit = c.to_digit(16).ok_or(BitsError::InvalidHexDigit(c))?;
+        push_bits(&mut bits, digit, 4);
+    }
+    Ok(bits)
+}
+
+fn base64_value(c: char) -> Option<u32> {
+    match c {
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 26),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 52),
+        '+' => Some(62),
+        '/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Parses standard base64, whitespace is ignored and the trailing `=` padding is optional.
+pub fn parse_base64(input: &str) -> Result<Vec<bool>, BitsError> {
+    let mut bytes = Vec::with_capacity(input.len() * 3 / 4);
+    let (mut buffer, mut buffered_bits) = (0u32, 0);
+    let mut sextets = 0;
+    for c in input
+        .trim_end_matches(|c: char| c == '=' || c.is_whitespace())
+        .chars()
+        .filter(|c| !c.is_whitespace())
+    {
+        let value = base64_value(c).ok_or(BitsError::InvalidBase64Char(c))?;
+        buffer = (buffer << 6) | value;
+        buffered_bits += 6;
+        sextets += 1;
+        if buffered_bits >= 8 {
+            buffered_bits -= 8;
+            bytes.push((buffer >> buffered_bits) as u8);
+            buffer &= (1 << buffered_bits) - 1;
+        }
+    }
+    if sextets % 4 == 1 {
+        return Err(BitsError::TruncatedBase64);
+    }
+    Ok(bits_from_bytes(&bytes))
+}
+
+#[cfg(feature = "std")]
+pub fn read_bits<P: AsRef<Path>>(path: P, encoding: Encoding) -> anyhow::Result<Vec<bool>> {
+    Ok(match encoding {
+        Encoding::Hex => parse_hex(&fs::read_to_string(path)?)?,
+        Encoding::Base64 => parse_base64(&fs::read_to_string(path)?)?,
+        Encoding::Raw => read_raw(path)?,
+    })
+}
+
+#[cfg(feature = "std")]
+pub fn read_raw<P: AsRef<Path>>(path: P) -> io::Result<Vec<bool>> {
+    Ok(bits_from_bytes(&fs::read(path)?))
+}
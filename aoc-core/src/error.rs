@@ -0,0 +1,136 @@
+//! Parse errors that point at the place in an input where parsing failed.
+//!
+//! Every layer fills in the part of the location it knows: a `FromStr` impl knows the token and
+//! its column, the line streams know the line number and the file the line came from.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    convert::Infallible,
+    fmt::{self, Display},
+    num::ParseIntError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub path: Option<String>,
+    /// Line number, starting at 1
+    pub line: Option<usize>,
+    /// Column of the first character of the token, starting at 1
+    pub column: Option<usize>,
+    /// The text that could not be parsed, empty if there is nothing to point at
+    pub token: String,
+    pub reason: String,
+}
+
+/// The column where `token` starts if it is a slice of `text`, starting at 1.
+pub fn column_of(text: &str, token: &str) -> Option<usize> {
+    let offset = (token.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    text.get(..offset).map(|before| before.chars().count() + 1)
+}
+
+impl InputError {
+    pub fn new<R: Display>(token: &str, reason: R) -> Self {
+        InputError {
+            path: None,
+            line: None,
+            column: None,
+            token: token.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// An error about `token`, a slice of `text`, located at the column where it starts.
+    pub fn in_text<R: Display>(text: &str, token: &str, reason: R) -> Self {
+        InputError {
+            column: column_of(text, token),
+            ..Self::new(token, reason)
+        }
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// Sets the line number unless it is already known.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Sets the file unless it is already known.
+    pub fn in_file<P: Display>(mut self, path: P) -> Self {
+        self.path.get_or_insert_with(|| path.to_string());
+        self
+    }
+
+    /// Points at all of `content` if the error doesn't name a token yet.
+    pub fn or_token(mut self, content: &str) -> Self {
+        if self.token.is_empty() {
+            self.token = content.to_string();
+        }
+        self
+    }
+}
+
+/// `path:line:column: reason at "token"` like compiler messages, inputs without a path get
+/// `line 3, column 7: reason at "token"` instead.
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = match &self.path {
+            Some(path) => [
+                Some(path.clone()),
+                self.line.map(|line| line.to_string()),
+                self.column.map(|column| column.to_string()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(":"),
+            None => [
+                self.line.map(|line| format!("line {}", line)),
+                self.column.map(|column| format!("column {}", column)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", "),
+        };
+        if !location.is_empty() {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}", self.reason)?;
+        if !self.token.is_empty() {
+            write!(f, " at {:?}", self.token)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for InputError {}
+
+impl From<ParseIntError> for InputError {
+    fn from(e: ParseIntError) -> Self {
+        InputError::new("", e)
+    }
+}
+
+impl From<Infallible> for InputError {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+/// Keeps the location of an `InputError` that was passed on as `anyhow::Error`.
+impl From<anyhow::Error> for InputError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<InputError>() {
+            Ok(e) => e,
+            Err(e) => InputError::new("", format!("{:#}", e)),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{
+    iter::{repeat_with, FusedIterator},
+    ops::{Index, IndexMut},
+};
+
+use crate::error::InputError;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Field2D<T> {
+    values: Vec<T>,
+    width: usize,
+}
+
+impl<T> Field2D<T>
+where
+    T: Default,
+{
+    pub fn add_row(&mut self) {
+        self.values
+            .extend(repeat_with(Default::default).take(self.width()))
+    }
+
+    pub fn new_empty(width: usize, height: usize) -> Self {
+        let mut res = Field2D {
+            values: Vec::with_capacity(width * height),
+            width,
+        };
+        for _ in 0..height {
+            res.add_row();
+        }
+        res
+    }
+}
+
+impl<T> Field2D<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.values.len() / self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The 4-neighborhood of `(x, y)` within the field: right, down, left and up.
+    pub fn neighbors(&self, x: usize, y: usize) -> NeighborIter {
+        NeighborIter::new((self.width(), self.height()), (x, y), &STRAIGHT)
+    }
+
+    /// The 8-neighborhood of `(x, y)` within the field, the straight neighbors come first.
+    pub fn neighbors_diag(&self, x: usize, y: usize) -> NeighborIter {
+        NeighborIter::new((self.width(), self.height()), (x, y), &ALL_DIRECTIONS)
+    }
+
+    /// The 4-neighborhood of `(x, y)`, restricted to the positions whose value matches `predicate`.
+    pub fn neighbors_filtered<'a, F>(
+        &'a self,
+        x: usize,
+        y: usize,
+        mut predicate: F,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        F: FnMut(&T) -> bool + 'a,
+    {
+        self.neighbors(x, y).filter(move |&pos| predicate(&self[pos]))
+    }
+
+    /// Like `neighbors_filtered`, but for the 8-neighborhood.
+    pub fn neighbors_diag_filtered<'a, F>(
+        &'a self,
+        x: usize,
+        y: usize,
+        mut predicate: F,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        F: FnMut(&T) -> bool + 'a,
+    {
+        self.neighbors_diag(x, y).filter(move |&pos| predicate(&self[pos]))
+    }
+
+    pub fn parse<R, F, I>(mut rows: impl Iterator<Item = R>, mut parser: F) -> Option<Self>
+    where
+        F: FnMut(R) -> I,
+        I: IntoIterator<Item = T>,
+    {
+        let mut res = Vec::new();
+        if let Some(first) = rows.next() {
+            res.extend(parser(first));
+            let width = res.len();
+            for row in rows {
+                res.extend(parser(row));
+            }
+            Some(Self {values: res, width})
+        } else {
+            None
+        }
+    }
+
+    /// Like `parse`, but all rows must have as many cells as the first one.
+    /// Errors are located at the row, which counts as line starting at 1.
+    pub fn try_parse<R, F, I>(
+        rows: impl Iterator<Item = R>,
+        mut parser: F,
+    ) -> Result<Self, InputError>
+    where
+        F: FnMut(R) -> Result<I, InputError>,
+        I: IntoIterator<Item = T>,
+    {
+        let mut values = Vec::new();
+        let mut width = None;
+        for (index, row) in rows.enumerate() {
+            let before = values.len();
+            values.extend(parser(row).map_err(|e| e.at_line(index + 1))?);
+            let cells = values.len() - before;
+            match width {
+                Some(width) if width != cells => {
+                    let reason = format!("{} cells, the rows above have {}", cells, width);
+                    return Err(InputError::new("", reason).at_line(index + 1));
+                }
+                _ => width = Some(cells),
+            }
+        }
+        let width = width.ok_or_else(|| InputError::new("", "the grid has no rows"))?;
+        Ok(Self { values, width })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=&mut T> {
+        self.values.iter_mut()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&T> {
+        self.values.iter()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.values[y * self.width..(y + 1) * self.width]
+    }
+
+    /// All values, row after row, e.g. to split them into rows with `chunks_mut(width)`.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+}
+
+impl<T> Field2D<T>
+where
+    T: PartialEq,
+{
+    /// Positions where the two fields differ, together with the values of `self` and `other`.
+    /// Only the area both fields cover is compared.
+    pub fn diff<'a>(&'a self, other: &'a Self) -> Vec<((usize, usize), &'a T, &'a T)> {
+        let width = self.width().min(other.width());
+        let height = self.height().min(other.height());
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|pos| (pos, &self[pos], &other[pos]))
+            .filter(|(_, mine, theirs)| mine != theirs)
+            .collect()
+    }
+
+    /// Renders both fields side by side, followed by an overlay in which differing cells are marked with `*`.
+    pub fn render_diff<F>(&self, other: &Self, mut render: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        let height = self.height().max(other.height());
+        let mut row = |field: &Self, y: usize| -> String {
+            (0..field.width())
+                .map(|x| if y < field.height() { render(&field[(x, y)]) } else { ' ' })
+                .collect()
+        };
+        let mut result = format!(
+            "{} differences ({}x{} vs {}x{})\n",
+            self.diff(other).len(),
+            self.width(),
+            self.height(),
+            other.width(),
+            other.height()
+        );
+        for y in 0..height {
+            let left = row(self, y);
+            let right = row(other, y);
+            let overlay: String = left
+                .chars()
+                .zip(right.chars())
+                .map(|(l, r)| if l == r { l } else { '*' })
+                .collect();
+            result.push_str(&format!("{} | {} | {}\n", left, right, overlay));
+        }
+        result
+    }
+}
+
+impl<T> Index<(usize, usize)> for Field2D<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (x, y) = index;
+        assert!(x < self.width());
+        assert!(y < self.height());
+        &self.values[x + y * self.width()]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Field2D<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        let (x, y) = index;
+        assert!(x < self.width());
+        assert!(y < self.height());
+        let width = self.width();
+        &mut self.values[x + y * width]
+    }
+}
+
+impl<T> IntoIterator for Field2D<T> {
+    type Item=T;
+
+    type IntoIter=<Vec<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+const STRAIGHT: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (0, -1),
+    (-1, -1),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+];
+
+/// The neighbors of a position, computed up front into a fixed-size buffer so iterating never allocates.
+#[derive(Debug, Clone)]
+pub struct NeighborIter {
+    neighbors: [(usize, usize); 8],
+    next: usize,
+    len: usize,
+}
+
+impl NeighborIter {
+    fn new(field_size: (usize, usize), pos: (usize, usize), directions: &[(isize, isize)]) -> Self {
+        let mut iter = NeighborIter {
+            neighbors: [(0, 0); 8],
+            next: 0,
+            len: 0,
+        };
+        for &(dx, dy) in directions {
+            let x = pos.0.checked_add_signed(dx).filter(|&x| x < field_size.0);
+            let y = pos.1.checked_add_signed(dy).filter(|&y| y < field_size.1);
+            if let (Some(x), Some(y)) = (x, y) {
+                iter.neighbors[iter.len] = (x, y);
+                iter.len += 1;
+            }
+        }
+        iter
+    }
+}
+
+impl Iterator for NeighborIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next < self.len {
+            self.next += 1;
+            Some(self.neighbors[self.next - 1])
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for NeighborIter {}
+
+impl FusedIterator for NeighborIter {}
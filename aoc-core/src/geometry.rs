@@ -0,0 +1,570 @@
+use crate::parse::extract_ints;
+use crate::vecn::{NumVecParsingError, Vec3D};
+use alloc::vec::Vec;
+use core::{
+    cmp,
+    fmt::Display,
+    num::ParseIntError,
+    ops::{Mul, Sub},
+    str::FromStr,
+};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use thiserror::Error;
+
+pub type Point = Vec3D<i64>;
+
+/// A rigid transformation, i.e. a rotation followed by a translation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub rotation: [[i64; 3]; 3],
+    pub translation: Point,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Transform {
+            rotation: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+            translation: Point::default(),
+        }
+    }
+
+    pub fn translation(offset: Point) -> Self {
+        Transform {
+            translation: offset,
+            ..Self::identity()
+        }
+    }
+
+    fn from_rotation(rotation: [[i64; 3]; 3]) -> Self {
+        Transform {
+            rotation,
+            translation: Point::default(),
+        }
+    }
+
+    fn sin(degrees: usize) -> i64 {
+        match degrees {
+            0 => 0,
+            90 => 1,
+            180 => 0,
+            270 => -1,
+            _ => panic!("Invalid degrees"),
+        }
+    }
+
+    fn cos(degrees: usize) -> i64 {
+        match degrees {
+            0 => 1,
+            90 => 0,
+            180 => -1,
+            270 => 0,
+            _ => panic!("Invalid degrees"),
+        }
+    }
+
+    pub fn rot_x(degrees: usize) -> Self {
+        let sin = Self::sin(degrees);
+        let cos = Self::cos(degrees);
+        Self::from_rotation([[1, 0, 0], [0, cos, -sin], [0, sin, cos]])
+    }
+
+    pub fn rot_y(degrees: usize) -> Self {
+        let sin = Self::sin(degrees);
+        let cos = Self::cos(degrees);
+        Self::from_rotation([[cos, 0, sin], [0, 1, 0], [-sin, 0, cos]])
+    }
+
+    pub fn rot_z(degrees: usize) -> Self {
+        let sin = Self::sin(degrees);
+        let cos = Self::cos(degrees);
+        Self::from_rotation([[cos, -sin, 0], [sin, cos, 0], [0, 0, 1]])
+    }
+
+    /// All 24 rotations that keep a cube axis-aligned.
+    pub fn cardinal_rotations() -> &'static [Transform] {
+        lazy_static! {
+            static ref CARDINAL_ROTATIONS: Vec<Transform> = {
+                let up_rots = [0, 90, 180, 270].map(Transform::rot_x);
+                let facing_rots = [
+                    Transform::rot_y(0),
+                    Transform::rot_y(90),
+                    Transform::rot_y(180),
+                    Transform::rot_y(270),
+                    Transform::rot_z(90),
+                    Transform::rot_z(270),
+                ];
+
+                up_rots
+                    .iter()
+                    .cartesian_product(facing_rots.iter())
+                    .map(|(up, facing)| up.compose(facing))
+                    .collect()
+            };
+        }
+        &CARDINAL_ROTATIONS
+    }
+
+    /// The 4 rotations around the z axis, for alignment problems in the x/y plane.
+    pub fn planar_rotations() -> &'static [Transform] {
+        lazy_static! {
+            static ref PLANAR_ROTATIONS: Vec<Transform> =
+                [0, 90, 180, 270].map(Transform::rot_z).to_vec();
+        }
+        &PLANAR_ROTATIONS
+    }
+
+    fn rotate(&self, point: &Point) -> Point {
+        Point::from_coords(core::array::from_fn(|y| {
+            (0..3).map(|x| self.rotation[y][x] * point[x]).sum()
+        }))
+    }
+
+    pub fn apply(&self, point: &Point) -> Point {
+        self.rotate(point) + self.translation
+    }
+
+    /// The transformation that applies `other` first and `self` afterwards.
+    pub fn compose(&self, other: &Transform) -> Transform {
+        let rotation = core::array::from_fn(|y| {
+            core::array::from_fn(|x| {
+                (0..3)
+                    .map(|d| self.rotation[y][d] * other.rotation[d][x])
+                    .sum()
+            })
+        });
+        Transform {
+            rotation,
+            translation: self.apply(&other.translation),
+        }
+    }
+
+    pub fn inverse(&self) -> Transform {
+        // Rotation matrices are orthogonal, so the transposed matrix is the inverse
+        let rotation = core::array::from_fn(|y| core::array::from_fn(|x| self.rotation[x][y]));
+        let inverse_rotation = Transform::from_rotation(rotation);
+        Transform {
+            translation: -inverse_rotation.rotate(&self.translation),
+            ..inverse_rotation
+        }
+    }
+
+    /// Transforms both corners of the cuboid.
+    /// Since we only deal with cardinal rotations the result is axis-aligned again.
+    pub fn apply_cuboid(&self, cuboid: &Cuboid) -> Cuboid {
+        let a = self.apply(&cuboid.from);
+        let b = self.apply(&cuboid.to);
+        Cuboid {
+            from: Point::from_coords(core::array::from_fn(|i| cmp::min(a[i], b[i]))),
+            to: Point::from_coords(core::array::from_fn(|i| cmp::max(a[i], b[i]))),
+        }
+    }
+}
+
+impl Mul for &Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.compose(rhs)
+    }
+}
+
+impl Mul<&Point> for &Transform {
+    type Output = Point;
+
+    fn mul(self, rhs: &Point) -> Self::Output {
+        self.apply(rhs)
+    }
+}
+
+/// An inclusive range of integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval(pub i64, pub i64);
+
+impl Interval {
+    pub fn contains(&self, value: i64) -> bool {
+        value >= self.0 && value <= self.1
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        other.contains(self.0)
+            || other.contains(self.1)
+            || self.contains(other.0)
+            || self.contains(other.1)
+    }
+
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.0 <= other.0 && other.1 <= self.1
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.0 <= self.1
+    }
+
+    pub fn clamp(&self, other: &Interval) -> Interval {
+        Interval(cmp::max(self.0, other.0), cmp::min(self.1, other.1))
+    }
+
+    pub fn len(&self) -> usize {
+        (self.1 - self.0 + 1) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.is_valid()
+    }
+
+    /// The number of integers in the interval, unlike `len` this can't overflow even for `i64::MIN..i64::MAX`.
+    pub fn count(&self) -> u128 {
+        if self.is_valid() {
+            (self.1 as i128 - self.0 as i128 + 1) as u128
+        } else {
+            0
+        }
+    }
+}
+
+impl Sub for &Interval {
+    type Output = Vec<Interval>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut result = Vec::new();
+        if self.0 < rhs.0 {
+            result.push(Interval(self.0, rhs.0 - 1));
+        }
+        if self.1 > rhs.1 {
+            result.push(Interval(rhs.1 + 1, self.1));
+        }
+        result
+    }
+}
+
+impl Sub for Interval {
+    type Output = Vec<Interval>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl FromStr for Interval {
+    type Err = NumVecParsingError<ParseIntError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [from, to] = extract_ints(s)?;
+        Ok(Self(from, to))
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}..{}", self.0, self.1)
+    }
+}
+
+/// An axis-aligned box, both corners are inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cuboid {
+    pub from: Point,
+    pub to: Point,
+}
+
+impl Cuboid {
+    pub fn from_intervals(
+        x_interval: &Interval,
+        y_interval: &Interval,
+        z_interval: &Interval,
+    ) -> Self {
+        Cuboid {
+            from: Point::new(x_interval.0, y_interval.0, z_interval.0),
+            to: Point::new(x_interval.1, y_interval.1, z_interval.1),
+        }
+    }
+
+    pub fn x_interval(&self) -> Interval {
+        Interval(self.from.x(), self.to.x())
+    }
+
+    pub fn y_interval(&self) -> Interval {
+        Interval(self.from.y(), self.to.y())
+    }
+
+    pub fn z_interval(&self) -> Interval {
+        Interval(self.from.z(), self.to.z())
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.x_interval().intersects(&other.x_interval())
+            && self.y_interval().intersects(&other.y_interval())
+            && self.z_interval().intersects(&other.z_interval())
+    }
+
+    pub fn contains(&self, other: &Cuboid) -> bool {
+        self.x_interval().contains_interval(&other.x_interval())
+            && self.y_interval().contains_interval(&other.y_interval())
+            && self.z_interval().contains_interval(&other.z_interval())
+    }
+
+    /// The part of the cuboid inside `region`, if there is any.
+    pub fn clipped_to(&self, region: &Cuboid) -> Option<Cuboid> {
+        let xi = self.x_interval().clamp(&region.x_interval());
+        let yi = self.y_interval().clamp(&region.y_interval());
+        let zi = self.z_interval().clamp(&region.z_interval());
+        if xi.is_valid() && yi.is_valid() && zi.is_valid() {
+            Some(Cuboid::from_intervals(&xi, &yi, &zi))
+        } else {
+            None
+        }
+    }
+
+    pub fn volume(&self) -> Result<u128, VolumeOverflow> {
+        self.x_interval()
+            .count()
+            .checked_mul(self.y_interval().count())
+            .and_then(|area| area.checked_mul(self.z_interval().count()))
+            .ok_or(VolumeOverflow)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("the volume does not fit into 128 bits")]
+pub struct VolumeOverflow;
+
+/// The summed up volume of the cuboids, which should not overlap.
+pub fn total_volume<'a, I: IntoIterator<Item = &'a Cuboid>>(
+    cuboids: I,
+) -> Result<u128, VolumeOverflow> {
+    cuboids.into_iter().try_fold(0u128, |total, cuboid| {
+        total.checked_add(cuboid.volume()?).ok_or(VolumeOverflow)
+    })
+}
+
+impl Sub for &Cuboid {
+    type Output = Vec<Cuboid>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut res = Vec::new();
+
+        if let Some(below) = rhs.from.x().checked_sub(1) {
+            let xi = Interval(self.from.x(), below);
+            if self.x_interval().contains(rhs.x_interval().0) && xi.is_valid() {
+                res.push(Cuboid::from_intervals(
+                    &xi,
+                    &self.y_interval(),
+                    &self.z_interval(),
+                ));
+            }
+        }
+
+        if let Some(below) = rhs.from.y().checked_sub(1) {
+            let yi = Interval(self.from.y(), below);
+            if self.y_interval().contains(rhs.y_interval().0) && yi.is_valid() {
+                res.push(Cuboid::from_intervals(
+                    &rhs.x_interval().clamp(&self.x_interval()),
+                    &yi,
+                    &rhs.z_interval().clamp(&self.z_interval()),
+                ));
+            }
+        }
+
+        if let Some(below) = rhs.from.z().checked_sub(1) {
+            let zi = Interval(self.from.z(), below);
+            if self.z_interval().contains(rhs.z_interval().0) && zi.is_valid() {
+                res.push(Cuboid::from_intervals(
+                    &rhs.x_interval().clamp(&self.x_interval()),
+                    &self.y_interval(),
+                    &zi,
+                ));
+            }
+        }
+
+        if let Some(above) = rhs.to.x().checked_add(1) {
+            let xi = Interval(above, self.to.x());
+            if self.x_interval().contains(rhs.x_interval().1) && xi.is_valid() {
+                res.push(Cuboid::from_intervals(
+                    &xi,
+                    &self.y_interval(),
+                    &self.z_interval(),
+                ));
+            }
+        }
+
+        if let Some(above) = rhs.to.y().checked_add(1) {
+            let yi = Interval(above, self.to.y());
+            if self.y_interval().contains(rhs.y_interval().1) && yi.is_valid() {
+                res.push(Cuboid::from_intervals(
+                    &rhs.x_interval().clamp(&self.x_interval()),
+                    &yi,
+                    &rhs.z_interval().clamp(&self.z_interval()),
+                ));
+            }
+        }
+
+        if let Some(above) = rhs.to.z().checked_add(1) {
+            let zi = Interval(above, self.to.z());
+            if self.z_interval().contains(rhs.z_interval().1) && zi.is_valid() {
+                res.push(Cuboid::from_intervals(
+                    &rhs.x_interval().clamp(&self.x_interval()),
+                    &self.y_interval(),
+                    &zi,
+                ));
+            }
+        }
+
+        res
+    }
+}
+
+/// Parses the format of `Display`, e.g. `x=-20..26,y=-36..17,z=-47..7`.
+impl FromStr for Cuboid {
+    type Err = NumVecParsingError<ParseIntError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x1, x2, y1, y2, z1, z2] = extract_ints(s)?;
+        Ok(Cuboid::from_intervals(
+            &Interval(x1, x2),
+            &Interval(y1, y2),
+            &Interval(z1, z2),
+        ))
+    }
+}
+
+impl Display for Cuboid {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "x={},y={},z={}",
+            self.x_interval(),
+            self.y_interval(),
+            self.z_interval()
+        )
+    }
+}
+
+fn distance_squared(a: &Point, b: &Point) -> i64 {
+    (a - b).norm_squared()
+}
+
+/// A static KD-tree for nearest-neighbor and radius queries on 3D points.
+/// The tree is stored implicitly: every (sub-)slice holds its median at the center,
+/// with the smaller half on the left and the larger half on the right.
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<Point>,
+}
+
+impl KdTree {
+    pub fn new<I: IntoIterator<Item = Point>>(points: I) -> Self {
+        let mut points = points.into_iter().collect_vec();
+        Self::build(&mut points, 0);
+        KdTree { points }
+    }
+
+    fn build(points: &mut [Point], depth: usize) {
+        if points.len() <= 1 {
+            return;
+        }
+        let axis = depth % 3;
+        let mid = points.len() / 2;
+        points.select_nth_unstable_by_key(mid, |p| p[axis]);
+        let (left, right) = points.split_at_mut(mid);
+        Self::build(left, depth + 1);
+        Self::build(&mut right[1..], depth + 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Point> {
+        self.points.iter()
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.nearest(point) == Some(point)
+    }
+
+    /// Finds the point closest to `target` in terms of euclidean distance.
+    pub fn nearest(&self, target: &Point) -> Option<&Point> {
+        let mut best = None;
+        Self::nearest_in(&self.points, 0, target, &mut best);
+        best.map(|(point, _)| point)
+    }
+
+    fn nearest_in<'a>(
+        points: &'a [Point],
+        depth: usize,
+        target: &Point,
+        best: &mut Option<(&'a Point, i64)>,
+    ) {
+        if points.is_empty() {
+            return;
+        }
+        let mid = points.len() / 2;
+        let candidate = &points[mid];
+        let dist = distance_squared(candidate, target);
+        if best.is_none_or(|(_, best_dist)| dist < best_dist) {
+            *best = Some((candidate, dist));
+        }
+
+        let axis = depth % 3;
+        let diff = target[axis] - candidate[axis];
+        let (near, far) = if diff < 0 {
+            (&points[..mid], &points[mid + 1..])
+        } else {
+            (&points[mid + 1..], &points[..mid])
+        };
+        Self::nearest_in(near, depth + 1, target, best);
+        // Only descend into the other half if the splitting plane is closer than the best match
+        if best.is_none_or(|(_, best_dist)| diff * diff <= best_dist) {
+            Self::nearest_in(far, depth + 1, target, best);
+        }
+    }
+
+    /// Returns all points with a euclidean distance of at most `radius` to `center`.
+    pub fn within_radius(&self, center: &Point, radius: i64) -> Vec<&Point> {
+        let mut result = Vec::new();
+        Self::within_radius_in(&self.points, 0, center, radius, &mut result);
+        result
+    }
+
+    fn within_radius_in<'a>(
+        points: &'a [Point],
+        depth: usize,
+        center: &Point,
+        radius: i64,
+        result: &mut Vec<&'a Point>,
+    ) {
+        if points.is_empty() {
+            return;
+        }
+        let mid = points.len() / 2;
+        let candidate = &points[mid];
+        if distance_squared(candidate, center) <= radius * radius {
+            result.push(candidate);
+        }
+
+        let axis = depth % 3;
+        if center[axis] - radius <= candidate[axis] {
+            Self::within_radius_in(&points[..mid], depth + 1, center, radius, result);
+        }
+        if center[axis] + radius >= candidate[axis] {
+            Self::within_radius_in(&points[mid + 1..], depth + 1, center, radius, result);
+        }
+    }
+}
+
+impl FromIterator<Point> for KdTree {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
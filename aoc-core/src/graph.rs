@@ -0,0 +1,78 @@
+use std::{fmt::Display, io};
+
+/// A graph description in the DOT language of graphviz.
+#[derive(Debug, Clone)]
+pub struct DotGraph {
+    name: String,
+    directed: bool,
+    nodes: Vec<(String, Option<String>)>,
+    edges: Vec<(String, String, Option<String>)>,
+}
+
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('"', "\\\""))
+}
+
+impl DotGraph {
+    pub fn undirected(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            directed: false,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn directed(name: &str) -> Self {
+        Self {
+            directed: true,
+            ..Self::undirected(name)
+        }
+    }
+
+    /// Declares a node, this is only required for isolated nodes or to set a label.
+    pub fn node<N: Display>(&mut self, id: N, label: Option<&str>) -> &mut Self {
+        self.nodes
+            .push((id.to_string(), label.map(|l| l.to_string())));
+        self
+    }
+
+    pub fn edge<N: Display>(&mut self, from: N, to: N, label: Option<&str>) -> &mut Self {
+        self.edges.push((
+            from.to_string(),
+            to.to_string(),
+            label.map(|l| l.to_string()),
+        ));
+        self
+    }
+
+    pub fn write<W: io::Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "{}", self)
+    }
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (keyword, connector) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        writeln!(f, "{} {} {{", keyword, quote(&self.name))?;
+        for (id, label) in self.nodes.iter() {
+            write!(f, "    {}", quote(id))?;
+            if let Some(label) = label {
+                write!(f, " [label={}]", quote(label))?;
+            }
+            writeln!(f, ";")?;
+        }
+        for (from, to, label) in self.edges.iter() {
+            write!(f, "    {} {} {}", quote(from), connector, quote(to))?;
+            if let Some(label) = label {
+                write!(f, " [label={}]", quote(label))?;
+            }
+            writeln!(f, ";")?;
+        }
+        write!(f, "}}")
+    }
+}
@@ -0,0 +1,28 @@
+//! Puzzle-independent building blocks: grids, vectors, geometry, graphs, parsing and simulation helpers.
+//! The yearly solutions in `aoc2021-solutions` re-export these modules, so they can keep using `aoc2021::field2d` and friends.
+//!
+//! The `std` feature is on by default. Without it the crate is `no_std` and only contains the pure algorithms,
+//! the modules that need files, clocks or I/O traits are left out.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod bidirange;
+pub mod bits;
+pub mod error;
+pub mod field2d;
+pub mod geometry;
+#[cfg(feature = "std")]
+pub mod graph;
+pub mod parse;
+pub mod rle_field;
+pub mod search;
+#[cfg(feature = "std")]
+pub mod simulation;
+#[cfg(feature = "std")]
+pub mod syntax;
+pub mod transform2d;
+pub mod vec2d;
+pub mod vecn;
+pub mod walk;
@@ -0,0 +1,153 @@
+//! Regex-free helpers to pull numbers out of the puzzle descriptions.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{array, fmt::Display, str::FromStr};
+
+use crate::vecn::NumVecParsingError;
+
+/// Iterator over the integer literals in a string, a `-` directly in front of a digit is taken as the sign.
+pub struct IntTokens<'a> {
+    bytes: &'a [u8],
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for IntTokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.bytes;
+        while self.pos < bytes.len() {
+            let start = self.pos;
+            let digits_start = if bytes[start] == b'-' {
+                start + 1
+            } else {
+                start
+            };
+            if digits_start < bytes.len() && bytes[digits_start].is_ascii_digit() {
+                let end = bytes[digits_start..]
+                    .iter()
+                    .position(|b| !b.is_ascii_digit())
+                    .map_or(bytes.len(), |len| digits_start + len);
+                self.pos = end;
+                return Some(&self.s[start..end]);
+            }
+            self.pos += 1;
+        }
+        None
+    }
+}
+
+pub fn int_tokens(s: &str) -> IntTokens<'_> {
+    IntTokens {
+        bytes: s.as_bytes(),
+        s,
+        pos: 0,
+    }
+}
+
+/// Parses the first `N` integers in `s`, anything after them is ignored.
+pub fn extract_ints<T, const N: usize>(s: &str) -> Result<[T; N], NumVecParsingError<T::Err>>
+where
+    T: FromStr,
+{
+    // Filled in place to avoid allocating, which matters for the long inputs of some days
+    let mut tokens = int_tokens(s);
+    let mut error = None;
+    let values: [Option<T>; N] = array::from_fn(|_| match tokens.next()?.parse() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error.get_or_insert(e);
+            None
+        }
+    });
+    if let Some(e) = error {
+        return Err(e.into());
+    }
+    if values.iter().any(Option::is_none) {
+        return Err(NumVecParsingError::MissingValue);
+    }
+    Ok(values.map(Option::unwrap))
+}
+
+pub fn extract_all_ints<T: FromStr>(s: &str) -> Result<Vec<T>, T::Err> {
+    int_tokens(s).map(|token| token.parse()).collect()
+}
+
+/// A line that lenient parsing skipped, numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIssue {
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+impl Display for ParseIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}: {} ({})", self.line, self.content, self.reason)
+    }
+}
+
+/// What could be parsed from an input together with the lines that were skipped to get there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lenient<T> {
+    pub parsed: T,
+    pub issues: Vec<ParseIssue>,
+}
+
+impl<T> Lenient<T> {
+    /// Parses every line on its own, lines that fail become issues instead of ending the parsing.
+    /// Blank lines are skipped without an issue.
+    pub fn parse_lines<I, S, U, E, F>(lines: I, mut parse: F) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        F: FnMut(&str) -> Result<U, E>,
+        E: Display,
+        T: FromIterator<U>,
+    {
+        let mut issues = Vec::new();
+        let parsed = lines
+            .into_iter()
+            .enumerate()
+            .filter(|(_, line)| !line.as_ref().trim().is_empty())
+            .filter_map(|(index, line)| match parse(line.as_ref()) {
+                Ok(item) => Some(item),
+                Err(e) => {
+                    issues.push(ParseIssue {
+                        line: index + 1,
+                        content: line.as_ref().to_string(),
+                        reason: e.to_string(),
+                    });
+                    None
+                }
+            })
+            .collect();
+        Lenient { parsed, issues }
+    }
+
+    pub fn into_parts(self) -> (T, Vec<ParseIssue>) {
+        (self.parsed, self.issues)
+    }
+
+    /// The parsed value if no line was skipped, otherwise the first issue.
+    pub fn strict(self) -> Result<T, ParseIssue> {
+        match self.issues.into_iter().next() {
+            Some(issue) => Err(issue),
+            None => Ok(self.parsed),
+        }
+    }
+
+    /// How many lines were skipped, followed by one line per issue.
+    pub fn summary(&self) -> String {
+        let mut summary = format!("Skipped {} invalid lines", self.issues.len());
+        for issue in &self.issues {
+            summary += &format!("\n  {}", issue);
+        }
+        summary
+    }
+}
@@ -0,0 +1,197 @@
+//! Run-length encoded boolean grids.
+//!
+//! Every row only stores the columns at which its value flips, starting from `false`.
+//! Mostly uniform images like the day 20 enhancement after many steps need a few positions per row instead of a byte per cell.
+
+use crate::field2d::Field2D;
+use alloc::{vec, vec::Vec};
+use core::ops::Index;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RleField {
+    width: usize,
+    rows: Vec<Vec<usize>>,
+}
+
+/// The toggles of the first `width` values, a shorter row is padded with `false`.
+fn encode_row<I: IntoIterator<Item = bool>>(width: usize, values: I) -> Vec<usize> {
+    let mut toggles = Vec::new();
+    let mut current = false;
+    let mut len = 0;
+    for (x, value) in values.into_iter().take(width).enumerate() {
+        if value != current {
+            toggles.push(x);
+            current = value;
+        }
+        len = x + 1;
+    }
+    // A run of `true` at the end of a short row stops where the row does
+    if current && len < width {
+        toggles.push(len);
+    }
+    toggles
+}
+
+impl RleField {
+    /// A field of `width` x `height` cells that are all `false`.
+    pub fn new_empty(width: usize, height: usize) -> Self {
+        RleField {
+            width,
+            rows: vec![Vec::new(); height],
+        }
+    }
+
+    /// Builds the field row by row, so only a single dense row exists at a time.
+    /// Rows that are shorter than `width` are padded with `false`, longer ones are cut off.
+    pub fn from_rows<R, I>(width: usize, rows: R) -> Self
+    where
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = bool>,
+    {
+        let mut field = RleField::new_empty(width, 0);
+        for row in rows {
+            field.push_row(row);
+        }
+        field
+    }
+
+    /// A field in which exactly the given positions are `true`, positions outside of the field are ignored.
+    pub fn from_points<I: IntoIterator<Item = (usize, usize)>>(
+        width: usize,
+        height: usize,
+        points: I,
+    ) -> Self {
+        let mut columns = vec![Vec::new(); height];
+        for (x, y) in points {
+            if x < width && y < height {
+                columns[y].push(x);
+            }
+        }
+        let mut field = RleField::new_empty(width, 0);
+        for mut xs in columns {
+            xs.sort_unstable();
+            xs.dedup();
+            let mut toggles = Vec::new();
+            for x in xs {
+                // Extend the previous run if it ends right here instead of starting a new one
+                if toggles.last() == Some(&x) {
+                    toggles.pop();
+                } else {
+                    toggles.push(x);
+                }
+                toggles.push(x + 1);
+            }
+            if toggles.last() == Some(&width) {
+                toggles.pop();
+            }
+            field.rows.push(toggles);
+        }
+        field
+    }
+
+    /// A field from the columns at which each row flips, as returned by `toggles`.
+    /// `None` if the columns of a row aren't increasing or lie outside of the field.
+    pub fn from_toggles(width: usize, rows: Vec<Vec<usize>>) -> Option<Self> {
+        let valid = rows.iter().all(|toggles| {
+            toggles.windows(2).all(|pair| pair[0] < pair[1])
+                && toggles.last().is_none_or(|&last| last < width)
+        });
+        valid.then_some(RleField { width, rows })
+    }
+
+    /// The columns at which row `y` flips, starting from `false`.
+    pub fn toggles(&self, y: usize) -> &[usize] {
+        &self.rows[y]
+    }
+
+    pub fn push_row<I: IntoIterator<Item = bool>>(&mut self, row: I) {
+        let toggles = encode_row(self.width, row);
+        self.rows.push(toggles);
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.width());
+        self.rows[y].partition_point(|&toggle| toggle <= x) % 2 == 1
+    }
+
+    /// Changing a single cell re-encodes its row, prefer building whole rows with `push_row`.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let mut row = self.row(y);
+        row[x] = value;
+        self.rows[y] = encode_row(self.width, row);
+    }
+
+    /// The dense values of row `y`.
+    pub fn row(&self, y: usize) -> Vec<bool> {
+        let mut row = Vec::with_capacity(self.width);
+        let mut current = false;
+        for &toggle in &self.rows[y] {
+            row.resize(toggle, current);
+            current = !current;
+        }
+        row.resize(self.width, current);
+        row
+    }
+
+    /// Number of `true` cells, computed from the runs without decoding them.
+    pub fn count_set(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|toggles| {
+                toggles
+                    .chunks(2)
+                    .map(|run| run.get(1).unwrap_or(&self.width) - run[0])
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Number of stored run boundaries, a measure of the memory the field needs.
+    pub fn run_boundaries(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+}
+
+impl Index<(usize, usize)> for RleField {
+    type Output = bool;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        if self.get(x, y) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl From<&Field2D<bool>> for RleField {
+    fn from(field: &Field2D<bool>) -> Self {
+        RleField::from_rows(
+            field.width(),
+            (0..field.height()).map(|y| (0..field.width()).map(move |x| field[(x, y)])),
+        )
+    }
+}
+
+impl From<&RleField> for Field2D<bool> {
+    fn from(field: &RleField) -> Self {
+        Field2D::parse((0..field.height()).map(|y| field.row(y)), |row| row)
+            .unwrap_or_else(|| Field2D::new_empty(field.width(), 0))
+    }
+}
@@ -0,0 +1,291 @@
+//! Hooks into best-first searches to see what they are doing.
+//!
+//! A search reports every node it expands and discovers to a `SearchObserver`, and the final path once it has one.
+//! `()` ignores everything, a `SearchRecord` keeps it to render it afterwards, either as a textual log or on top of a `Field2D`.
+//!
+//! `astar` finds cheapest paths and reports to an observer, `bfs_states` is a plain breadth-first search
+//! over any hashable state space, for puzzles where every move costs the same. `weighted_astar` inflates the heuristic
+//! to expand fewer nodes at the price of possibly returning a more expensive path.
+
+use crate::field2d::Field2D;
+use alloc::{
+    collections::{BTreeSet, BinaryHeap, VecDeque},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cmp::Reverse,
+    hash::Hash,
+    ops::{Add, Div, Mul},
+};
+use hashbrown::HashMap;
+
+pub trait SearchObserver<N> {
+    /// `node` was taken from the open set and its successors are generated next.
+    fn expanded(&mut self, _node: &N) {}
+
+    /// `node` was added to the open set, either for the first time or with a better score.
+    fn discovered(&mut self, _node: &N) {}
+
+    /// The search reached the goal via `path`, which includes the start and the goal.
+    fn finished(&mut self, _path: &[N]) {}
+}
+
+impl<N> SearchObserver<N> for () {}
+
+/// Everything a search reported, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchRecord<N> {
+    pub expansions: Vec<N>,
+    pub discoveries: Vec<N>,
+    pub path: Option<Vec<N>>,
+}
+
+impl<N> Default for SearchRecord<N> {
+    fn default() -> Self {
+        Self {
+            expansions: Vec::new(),
+            discoveries: Vec::new(),
+            path: None,
+        }
+    }
+}
+
+impl<N: Clone> SearchObserver<N> for SearchRecord<N> {
+    fn expanded(&mut self, node: &N) {
+        self.expansions.push(node.clone());
+    }
+
+    fn discovered(&mut self, node: &N) {
+        self.discoveries.push(node.clone());
+    }
+
+    fn finished(&mut self, path: &[N]) {
+        self.path = Some(path.to_vec());
+    }
+}
+
+impl<N: Ord> SearchRecord<N> {
+    /// Nodes that were discovered but never expanded, i.e. the open set when the search stopped.
+    pub fn frontier(&self) -> BTreeSet<&N> {
+        let expanded: BTreeSet<&N> = self.expansions.iter().collect();
+        self.discoveries
+            .iter()
+            .filter(|node| !expanded.contains(node))
+            .collect()
+    }
+}
+
+impl<N> SearchRecord<N> {
+    /// One line per expansion followed by the path, `describe` renders a single node.
+    pub fn log<F: FnMut(&N) -> String>(&self, mut describe: F) -> String {
+        let mut log: Vec<String> = self
+            .expansions
+            .iter()
+            .enumerate()
+            .map(|(i, node)| format!("expand {:>6}: {}", i + 1, describe(node)))
+            .collect();
+        match &self.path {
+            Some(path) => {
+                log.push(format!("path of {} nodes:", path.len()));
+                log.extend(path.iter().map(|node| format!("    {}", describe(node))));
+            }
+            None => log.push("no path found".to_string()),
+        }
+        log.join("\n")
+    }
+}
+
+const DIM: &str = "\x1b[2m";
+const FRONTIER: &str = "\x1b[33m";
+const BRIGHT: &str = "\x1b[1;97m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders `field` with the search on top: expanded cells are dim, the frontier is colored and the path is bright.
+/// Cells the search never touched are printed as they are.
+pub fn render_on_field<T, F>(
+    field: &Field2D<T>,
+    record: &SearchRecord<(usize, usize)>,
+    mut render: F,
+) -> String
+where
+    F: FnMut(&T) -> char,
+{
+    let expanded: BTreeSet<&(usize, usize)> = record.expansions.iter().collect();
+    let frontier = record.frontier();
+    let path: BTreeSet<&(usize, usize)> = record.path.iter().flatten().collect();
+    (0..field.height())
+        .map(|y| {
+            (0..field.width())
+                .map(|x| {
+                    let pos = (x, y);
+                    let cell = render(&field[pos]);
+                    let style = if path.contains(&pos) {
+                        BRIGHT
+                    } else if frontier.contains(&pos) {
+                        FRONTIER
+                    } else if expanded.contains(&pos) {
+                        DIM
+                    } else {
+                        return cell.to_string();
+                    };
+                    format!("{}{}{}", style, cell, RESET)
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Everything a breadth-first search reached, see `bfs_states`.
+#[derive(Debug, Clone)]
+pub struct BfsStates<N> {
+    /// The states in the order they were visited, so their depths never decrease.
+    pub order: Vec<N>,
+    /// The state each state was first reached from and its depth, `None` for the start.
+    parents: HashMap<N, (Option<N>, usize)>,
+}
+
+impl<N: Clone + Eq + Hash> BfsStates<N> {
+    pub fn contains(&self, state: &N) -> bool {
+        self.parents.contains_key(state)
+    }
+
+    /// The number of moves needed to reach `state`.
+    pub fn depth(&self, state: &N) -> Option<usize> {
+        self.parents.get(state).map(|(_, depth)| *depth)
+    }
+
+    pub fn parent(&self, state: &N) -> Option<&N> {
+        self.parents.get(state)?.0.as_ref()
+    }
+
+    /// A shortest sequence of states from the start to `state`, both included.
+    pub fn path_to(&self, state: &N) -> Option<Vec<N>> {
+        let mut path = vec![state.clone()];
+        let mut parent = self.parents.get(state)?.0.clone();
+        while let Some(state) = parent {
+            parent = self.parents[&state].0.clone();
+            path.push(state);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The first visited state that matches `goal`, i.e. one of the closest ones.
+    pub fn find<F: FnMut(&N) -> bool>(&self, mut goal: F) -> Option<&N> {
+        self.order.iter().find(|state| goal(state))
+    }
+}
+
+/// Visits every state reachable from `start` in breadth-first order, each state only once.
+/// With a `max_depth` the states that far away are still visited, but their successors are not generated anymore.
+pub fn bfs_states<N, F, I>(start: N, mut successors: F, max_depth: Option<usize>) -> BfsStates<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::from([(start.clone(), (None, 0))]);
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((state, depth)) = queue.pop_front() {
+        if max_depth.is_none_or(|max| depth < max) {
+            for next in successors(&state) {
+                if !parents.contains_key(&next) {
+                    parents.insert(next.clone(), (Some(state.clone()), depth + 1));
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        order.push(state);
+    }
+    BfsStates { order, parents }
+}
+
+/// A* search from `start` to the first node matching `is_goal`, returns the cost and the path including both ends.
+/// `successors` yields the neighbors of a node with the cost of moving there. `heuristic` estimates the remaining
+/// cost and must never overestimate it for the result to be optimal, a heuristic of zero turns this into Dijkstra's algorithm.
+/// Costs start at `C::default()`, nodes with equal estimates are expanded in their order.
+pub fn astar<N, C, S, I, H, G, O>(
+    start: N,
+    mut successors: S,
+    mut heuristic: H,
+    mut is_goal: G,
+    observer: &mut O,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Default + Add<Output = C>,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+    O: SearchObserver<N>,
+{
+    let mut open = BinaryHeap::new();
+    let mut best: HashMap<N, C> = HashMap::from([(start.clone(), C::default())]);
+    let mut came_from = HashMap::<N, N>::new();
+
+    open.push(Reverse((heuristic(&start), start.clone())));
+    observer.discovered(&start);
+
+    while let Some(Reverse((estimate, node))) = open.pop() {
+        let cost = best[&node];
+        // A better way to this node was found after this entry was queued
+        if cost + heuristic(&node) < estimate {
+            continue;
+        }
+        observer.expanded(&node);
+        if is_goal(&node) {
+            let mut path = vec![node];
+            while let Some(prev) = came_from.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            observer.finished(&path);
+            return Some((cost, path));
+        }
+        for (next, step) in successors(&node) {
+            let candidate = cost + step;
+            if best.get(&next).is_none_or(|&known| candidate < known) {
+                best.insert(next.clone(), candidate);
+                came_from.insert(next.clone(), node.clone());
+                observer.discovered(&next);
+                open.push(Reverse((candidate + heuristic(&next), next)));
+            }
+        }
+    }
+    None
+}
+
+/// A* with the heuristic scaled by `numerator / denominator`, a weight above one trades optimality for speed.
+/// With an admissible heuristic the returned cost is at most the weight times the optimal cost,
+/// the returned cost is always the actual cost of the returned path.
+pub fn weighted_astar<N, C, S, I, H, G, O>(
+    start: N,
+    successors: S,
+    mut heuristic: H,
+    (numerator, denominator): (C, C),
+    is_goal: G,
+    observer: &mut O,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Default + Add<Output = C> + Mul<Output = C> + Div<Output = C>,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+    O: SearchObserver<N>,
+{
+    astar(
+        start,
+        successors,
+        |node| heuristic(node) * numerator / denominator,
+        is_goal,
+        observer,
+    )
+}
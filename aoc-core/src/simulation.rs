@@ -0,0 +1,141 @@
+//! Drivers for simulations that run for a number of steps or until some condition is met,
+//! with guards against running forever.
+
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("simulation did not converge within {steps} steps")]
+pub struct DidNotConverge {
+    pub steps: usize,
+}
+
+/// Bounds for a simulation, nothing is limited by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub max_steps: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+impl Limits {
+    pub fn max_steps(max_steps: usize) -> Self {
+        Self {
+            max_steps: Some(max_steps),
+            ..Default::default()
+        }
+    }
+
+    /// Reads `--max-steps N` and `--timeout SECONDS` from the command line arguments.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> anyhow::Result<Self> {
+        let mut limits = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--max-steps" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("--max-steps needs a value"))?;
+                    limits.max_steps = Some(value.parse()?);
+                }
+                "--timeout" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("--timeout needs a value"))?;
+                    limits.timeout = Some(Duration::from_secs_f64(value.parse()?));
+                }
+                _ => (),
+            }
+        }
+        Ok(limits)
+    }
+
+    fn check(&self, steps: usize, started: Instant) -> Result<(), DidNotConverge> {
+        let out_of_steps = self.max_steps.is_some_and(|max| steps >= max);
+        let out_of_time = self
+            .timeout
+            .is_some_and(|timeout| started.elapsed() >= timeout);
+        if out_of_steps || out_of_time {
+            Err(DidNotConverge { steps })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Applies `step` `n` times, it receives the state and the index of the step.
+/// Returns the final state and the number of steps, which is always `n`.
+pub fn iterate_n<T, F>(init: T, n: usize, step: F) -> (T, usize)
+where
+    F: FnMut(T, usize) -> T,
+{
+    ((0..n).fold(init, step), n)
+}
+
+/// Applies `step` until `done` holds for the new state and returns it with the number of steps.
+/// `done` is not checked for `init`, so there is always at least one step.
+pub fn iterate_until<T, F, P>(init: T, step: F, done: P) -> (T, usize)
+where
+    F: FnMut(T) -> T,
+    P: FnMut(&T) -> bool,
+{
+    match iterate_until_within(init, step, done, &Limits::default()) {
+        Ok(result) => result,
+        Err(_) => unreachable!("a simulation without limits can't run out of them"),
+    }
+}
+
+/// Like [`iterate_until`], but gives up once the limits are exceeded.
+pub fn iterate_until_within<T, F, P>(
+    init: T,
+    mut step: F,
+    mut done: P,
+    limits: &Limits,
+) -> Result<(T, usize), DidNotConverge>
+where
+    F: FnMut(T) -> T,
+    P: FnMut(&T) -> bool,
+{
+    let started = Instant::now();
+    let mut state = init;
+    let mut steps = 0;
+    loop {
+        limits.check(steps, started)?;
+        state = step(state);
+        steps += 1;
+        if done(&state) {
+            return Ok((state, steps));
+        }
+    }
+}
+
+/// Calls `step` until it returns true and returns the number of calls.
+pub fn run_until<F: FnMut() -> bool>(
+    mut step: F,
+    limits: &Limits,
+) -> Result<usize, DidNotConverge> {
+    iterate_until_within(false, |_| step(), |&finished| finished, limits).map(|(_, steps)| steps)
+}
+
+/// Applies `conversion` until the state doesn't change anymore.
+/// Returns the final state and the number of conversions, including the last one that changed nothing.
+pub fn find_fixed_point<T, F>(
+    init: T,
+    mut conversion: F,
+    limits: &Limits,
+) -> Result<(T, usize), DidNotConverge>
+where
+    F: FnMut(&T) -> T,
+    T: PartialEq,
+{
+    iterate_until_within(
+        (init, false),
+        |(cur, _)| {
+            let next = conversion(&cur);
+            let converged = next == cur;
+            (next, converged)
+        },
+        |&(_, converged)| converged,
+        limits,
+    )
+    .map(|((state, _), steps)| (state, steps))
+}
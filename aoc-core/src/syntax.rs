@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+};
+use thiserror::Error;
+
+/// A pair of matching delimiters together with the scores used for corrupted and incomplete lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelimiterPair {
+    pub open: char,
+    pub close: char,
+    pub error_score: u64,
+    pub completion_score: u64,
+}
+
+impl DelimiterPair {
+    pub fn new(open: char, close: char, error_score: u64, completion_score: u64) -> Self {
+        Self {
+            open,
+            close,
+            error_score,
+            completion_score,
+        }
+    }
+}
+
+/// Index of a delimiter pair in its `Alphabet`
+pub type DelimiterId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Opening,
+    Closing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub delimiter: DelimiterId,
+    pub kind: TokenKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub found: DelimiterId,
+    pub expected: Option<DelimiterId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Valid,
+    /// The line ended while these delimiters were still open, innermost last
+    Incomplete(Vec<DelimiterId>),
+    Corrupted(SyntaxError),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AlphabetError {
+    #[error("character {0} is used by more than one delimiter")]
+    DuplicateChar(char),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TokenizeError {
+    #[error("invalid character {0}")]
+    InvalidChar(char),
+}
+
+/// The set of delimiter pairs a balanced-delimiter checker works with.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    pairs: Vec<DelimiterPair>,
+    lookup: HashMap<char, Token>,
+    completion_base: u64,
+}
+
+impl Default for Alphabet {
+    /// The alphabet and scores from day 10
+    fn default() -> Self {
+        Self::new(vec![
+            DelimiterPair::new('(', ')', 3, 1),
+            DelimiterPair::new('[', ']', 57, 2),
+            DelimiterPair::new('{', '}', 1197, 3),
+            DelimiterPair::new('<', '>', 25137, 4),
+        ])
+        .unwrap()
+    }
+}
+
+impl Alphabet {
+    pub fn new(pairs: Vec<DelimiterPair>) -> Result<Self, AlphabetError> {
+        let mut lookup = HashMap::new();
+        for (delimiter, pair) in pairs.iter().enumerate() {
+            for (c, kind) in [
+                (pair.open, TokenKind::Opening),
+                (pair.close, TokenKind::Closing),
+            ] {
+                if lookup.insert(c, Token { delimiter, kind }).is_some() {
+                    return Err(AlphabetError::DuplicateChar(c));
+                }
+            }
+        }
+        Ok(Self {
+            pairs,
+            lookup,
+            completion_base: 5,
+        })
+    }
+
+    /// Sets the factor that completion scores are multiplied with for every further missing delimiter.
+    pub fn with_completion_base(mut self, completion_base: u64) -> Self {
+        self.completion_base = completion_base;
+        self
+    }
+
+    pub fn pair(&self, delimiter: DelimiterId) -> &DelimiterPair {
+        &self.pairs[delimiter]
+    }
+
+    pub fn tokenize(&self, line: &str) -> Result<Vec<Token>, TokenizeError> {
+        line.chars()
+            .map(|c| {
+                self.lookup
+                    .get(&c)
+                    .copied()
+                    .ok_or(TokenizeError::InvalidChar(c))
+            })
+            .collect()
+    }
+
+    pub fn check(&self, line: &str) -> Result<Verdict, TokenizeError> {
+        self.check_chars(line.chars())
+    }
+
+    /// Like `check`, for lines that are not known to be valid UTF-8.
+    pub fn check_bytes(&self, line: &[u8]) -> Result<Verdict, TokenizeError> {
+        // Only allocates if the line contains invalid UTF-8, which is then reported as invalid character
+        self.check_chars(String::from_utf8_lossy(line).chars())
+    }
+
+    /// Checks every line of `reader` without holding more than a single line in memory.
+    pub fn check_reader<R: BufRead>(&self, reader: R) -> CheckedLines<'_, R> {
+        CheckedLines {
+            alphabet: self,
+            reader,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Tokenizes lazily, so a corrupted line is only read up to its first error.
+    fn check_chars(&self, chars: impl Iterator<Item = char>) -> Result<Verdict, TokenizeError> {
+        let mut stack = Vec::new();
+        for c in chars {
+            let token = self
+                .lookup
+                .get(&c)
+                .copied()
+                .ok_or(TokenizeError::InvalidChar(c))?;
+            match token.kind {
+                TokenKind::Opening => stack.push(token.delimiter),
+                TokenKind::Closing => {
+                    let expected = stack.pop();
+                    if expected != Some(token.delimiter) {
+                        return Ok(Verdict::Corrupted(SyntaxError {
+                            found: token.delimiter,
+                            expected,
+                        }));
+                    }
+                }
+            }
+        }
+
+        Ok(if stack.is_empty() {
+            Verdict::Valid
+        } else {
+            Verdict::Incomplete(stack)
+        })
+    }
+
+    pub fn score_error(&self, error: &SyntaxError) -> u64 {
+        self.pair(error.found).error_score
+    }
+
+    /// Scores the delimiters that are still open, see `Verdict::Incomplete`.
+    pub fn score_completion(&self, missing: &[DelimiterId]) -> u64 {
+        missing.iter().rev().fold(0, |acc, &delimiter| {
+            acc * self.completion_base + self.pair(delimiter).completion_score
+        })
+    }
+
+    /// The string that closes all of the given open delimiters.
+    pub fn completion(&self, missing: &[DelimiterId]) -> String {
+        missing
+            .iter()
+            .rev()
+            .map(|&delimiter| self.pair(delimiter).close)
+            .collect()
+    }
+}
+
+/// Iterator over the verdicts for the lines of a reader, see `Alphabet::check_reader`.
+pub struct CheckedLines<'a, R> {
+    alphabet: &'a Alphabet,
+    reader: R,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> Iterator for CheckedLines<'_, R> {
+    type Item = io::Result<Result<Verdict, TokenizeError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line = self.buffer.strip_suffix(b"\n").unwrap_or(&self.buffer);
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                Some(Ok(self.alphabet.check_bytes(line)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
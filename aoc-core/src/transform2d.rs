@@ -0,0 +1,157 @@
+//! Axis-aligned reflections and translations of points in the plane, and folds built from them.
+//!
+//! A `Transform2D` maps every coordinate to `sign * value + offset` with a sign of `1` or `-1`,
+//! which covers every sequence of reflections at axis-parallel lines and translations.
+
+use crate::vec2d::{IVec2D, UVec2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform2D {
+    sign_x: isize,
+    sign_y: isize,
+    offset: IVec2D,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform2D {
+    pub fn identity() -> Self {
+        Transform2D {
+            sign_x: 1,
+            sign_y: 1,
+            offset: IVec2D::new(0, 0),
+        }
+    }
+
+    pub fn translation(offset: IVec2D) -> Self {
+        Transform2D {
+            offset,
+            ..Self::identity()
+        }
+    }
+
+    /// Mirrors at the vertical line `x = axis`.
+    pub fn reflect_x(axis: isize) -> Self {
+        Transform2D {
+            sign_x: -1,
+            offset: IVec2D::new(2 * axis, 0),
+            ..Self::identity()
+        }
+    }
+
+    /// Mirrors at the horizontal line `y = axis`.
+    pub fn reflect_y(axis: isize) -> Self {
+        Transform2D {
+            sign_y: -1,
+            offset: IVec2D::new(0, 2 * axis),
+            ..Self::identity()
+        }
+    }
+
+    /// The transform that applies `self` first and `next` afterwards.
+    pub fn then(&self, next: &Transform2D) -> Transform2D {
+        Transform2D {
+            sign_x: self.sign_x * next.sign_x,
+            sign_y: self.sign_y * next.sign_y,
+            offset: next.apply(self.offset),
+        }
+    }
+
+    pub fn inverse(&self) -> Transform2D {
+        Transform2D {
+            sign_x: self.sign_x,
+            sign_y: self.sign_y,
+            offset: IVec2D::new(
+                -self.sign_x * self.offset.x(),
+                -self.sign_y * self.offset.y(),
+            ),
+        }
+    }
+
+    pub fn apply(&self, point: IVec2D) -> IVec2D {
+        IVec2D::new(
+            self.sign_x * point.x() + self.offset.x(),
+            self.sign_y * point.y() + self.offset.y(),
+        )
+    }
+
+    /// Applies the transform to a point with unsigned coordinates, `None` if it ends up at a negative coordinate.
+    pub fn apply_unsigned(&self, point: UVec2D) -> Option<UVec2D> {
+        UVec2D::try_from(self.apply(IVec2D::try_from(point).ok()?)).ok()
+    }
+
+    /// Transforms a whole point set, e.g. into a `HashSet` or a `Vec`.
+    pub fn apply_all<'a, I, C>(&self, points: I) -> C
+    where
+        I: IntoIterator<Item = &'a IVec2D>,
+        C: FromIterator<IVec2D>,
+    {
+        points.into_iter().map(|&point| self.apply(point)).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// Folding paper along the line `axis = line`: everything behind the line is mirrored in front of it.
+/// Points on the line stay where they are, points that end up beyond the edge of the paper at `0` are lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fold {
+    pub axis: Axis,
+    pub line: usize,
+}
+
+impl Fold {
+    pub fn along_x(line: usize) -> Self {
+        Fold {
+            axis: Axis::X,
+            line,
+        }
+    }
+
+    pub fn along_y(line: usize) -> Self {
+        Fold {
+            axis: Axis::Y,
+            line,
+        }
+    }
+
+    /// The reflection the fold applies to the points behind its line.
+    pub fn reflection(&self) -> Transform2D {
+        match self.axis {
+            Axis::X => Transform2D::reflect_x(self.line as isize),
+            Axis::Y => Transform2D::reflect_y(self.line as isize),
+        }
+    }
+
+    pub fn apply(&self, point: UVec2D) -> Option<UVec2D> {
+        let pos = match self.axis {
+            Axis::X => point.x(),
+            Axis::Y => point.y(),
+        };
+        if pos > self.line {
+            self.reflection().apply_unsigned(point)
+        } else {
+            Some(point)
+        }
+    }
+
+    /// Folds a whole point set, the points that are lost are left out.
+    pub fn apply_all<'a, I, C>(&self, points: I) -> C
+    where
+        I: IntoIterator<Item = &'a UVec2D>,
+        C: FromIterator<UVec2D>,
+    {
+        points
+            .into_iter()
+            .filter_map(|&point| self.apply(point))
+            .collect()
+    }
+}
@@ -0,0 +1,32 @@
+use core::num::TryFromIntError;
+
+pub use crate::vecn::{NumVecParsingError, Vec2D};
+
+pub type IVec2D = Vec2D<isize>;
+pub type UVec2D = Vec2D<usize>;
+
+impl TryFrom<IVec2D> for UVec2D {
+    type Error = TryFromIntError;
+
+    fn try_from(value: IVec2D) -> Result<Self, Self::Error> {
+        value.try_map(usize::try_from)
+    }
+}
+
+impl TryFrom<UVec2D> for IVec2D {
+    type Error = TryFromIntError;
+
+    fn try_from(value: UVec2D) -> Result<Self, Self::Error> {
+        value.try_map(isize::try_from)
+    }
+}
+
+impl UVec2D {
+    /// Moves by a signed offset, `None` if a coordinate would leave the range of `usize`.
+    pub fn checked_add_signed(&self, offset: IVec2D) -> Option<Self> {
+        Some(Self::new(
+            self.x().checked_add_signed(offset.x())?,
+            self.y().checked_add_signed(offset.y())?,
+        ))
+    }
+}
@@ -0,0 +1,295 @@
+use core::{
+    fmt::Display,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// A fixed-size vector with `N` components of type `T`.
+#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq, PartialOrd, Ord)]
+pub struct VecN<T, const N: usize> {
+    pub coords: [T; N],
+}
+
+pub type Vec2D<T> = VecN<T, 2>;
+pub type Vec3D<T> = VecN<T, 3>;
+
+impl<T, const N: usize> VecN<T, N> {
+    pub fn from_coords(coords: [T; N]) -> Self {
+        Self { coords }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.coords.iter()
+    }
+
+    /// Applies `f` to every component.
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> VecN<U, N> {
+        VecN {
+            coords: self.coords.map(f),
+        }
+    }
+
+    /// Like `map`, but fails with the first error returned by `f`.
+    pub fn try_map<U, E, F: FnMut(T) -> Result<U, E>>(self, mut f: F) -> Result<VecN<U, N>, E> {
+        let mut error = None;
+        let coords = self.coords.map(|c| match f(c) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                error.get_or_insert(e);
+                None
+            }
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(VecN {
+                coords: coords.map(Option::unwrap),
+            }),
+        }
+    }
+}
+
+impl<T> Vec2D<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { coords: [x, y] }
+    }
+}
+
+impl<T> Vec3D<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { coords: [x, y, z] }
+    }
+}
+
+/// The named accessors only exist where the component does, other sizes index the coordinates.
+///
+/// ```compile_fail
+/// aoc_core::vecn::Vec2D::new(1, 2).z();
+/// ```
+impl<T: Copy> Vec2D<T> {
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+}
+
+impl<T: Copy> Vec3D<T> {
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.coords[2]
+    }
+}
+
+impl<T, const N: usize> VecN<T, N>
+where
+    T: Copy + Default + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    pub fn dot(&self, other: &Self) -> T {
+        self.coords
+            .iter()
+            .zip(other.coords.iter())
+            .fold(T::default(), |sum, (&a, &b)| sum + a * b)
+    }
+
+    /// Squared euclidean length, this avoids leaving the integers.
+    pub fn norm_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        self.coords
+            .iter()
+            .zip(other.coords.iter())
+            .fold(T::default(), |sum, (&a, &b)| sum + abs_diff(a, b))
+    }
+
+    pub fn manhattan_norm(&self) -> T {
+        self.manhattan_distance(&Self::default())
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        self.coords
+            .iter()
+            .zip(other.coords.iter())
+            .map(|(&a, &b)| abs_diff(a, b))
+            .fold(T::default(), |max, d| if d > max { d } else { max })
+    }
+}
+
+// Works for signed and unsigned types alike since we never produce a negative intermediate
+fn abs_diff<T: PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: Default, const N: usize> Default for VecN<T, N> {
+    fn default() -> Self {
+        Self {
+            coords: [(); N].map(|_| T::default()),
+        }
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for VecN<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Self { coords }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for VecN<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.coords[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for VecN<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.coords[index]
+    }
+}
+
+impl<T, const N: usize> Display for VecN<T, N>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "(")?;
+        for (i, v) in self.coords.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<T, O, const N: usize> Add<VecN<O, N>> for VecN<T, N>
+where
+    T: Add<O> + Copy,
+    O: Copy,
+{
+    type Output = VecN<T::Output, N>;
+
+    fn add(self, rhs: VecN<O, N>) -> Self::Output {
+        VecN {
+            coords: core::array::from_fn(|i| self.coords[i] + rhs.coords[i]),
+        }
+    }
+}
+
+impl<T, const N: usize> Add for &VecN<T, N>
+where
+    T: Add<Output = T> + Copy,
+{
+    type Output = VecN<T, N>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl<T, const N: usize> AddAssign for VecN<T, N>
+where
+    T: AddAssign + Copy,
+{
+    fn add_assign(&mut self, rhs: Self) {
+        for (l, r) in self.coords.iter_mut().zip(rhs.coords) {
+            *l += r;
+        }
+    }
+}
+
+impl<T, O, const N: usize> Sub<VecN<O, N>> for VecN<T, N>
+where
+    T: Sub<O> + Copy,
+    O: Copy,
+{
+    type Output = VecN<T::Output, N>;
+
+    fn sub(self, rhs: VecN<O, N>) -> Self::Output {
+        VecN {
+            coords: core::array::from_fn(|i| self.coords[i] - rhs.coords[i]),
+        }
+    }
+}
+
+impl<T, const N: usize> Sub for &VecN<T, N>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type Output = VecN<T, N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl<T, const N: usize> SubAssign for VecN<T, N>
+where
+    T: SubAssign + Copy,
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        for (l, r) in self.coords.iter_mut().zip(rhs.coords) {
+            *l -= r;
+        }
+    }
+}
+
+impl<T, const N: usize> Neg for VecN<T, N>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.map(|v| -v)
+    }
+}
+
+impl<T, const N: usize> Mul<T> for VecN<T, N>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|v| v * rhs)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum NumVecParsingError<E> {
+    #[error("missing value in input")]
+    MissingValue,
+    #[error("Invalid integer")]
+    ParseNumberError(#[from] E),
+}
+
+impl<T, const N: usize> FromStr for VecN<T, N>
+where
+    T: FromStr,
+{
+    type Err = NumVecParsingError<T::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            coords: crate::parse::extract_ints(s)?,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! Depth-first walks over recursive structures like packet trees or snailfish numbers.
+//!
+//! A structure implements [`Walk`] once by handing out its direct children, everything that
+//! needs to look at the whole structure (statistics, pretty printers, depth checks) is then a
+//! [`Visitor`] that gets called when the walk enters and leaves a node.
+
+/// How a walk continues after a visitor callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    Continue,
+    /// Don't descend into the children of the node that was just entered
+    SkipChildren,
+    /// End the whole walk, no further callbacks happen
+    Stop,
+}
+
+/// A recursive structure whose nodes are walked depth-first.
+pub trait Walk {
+    /// Calls `f` with the direct children in order, stops as soon as `f` returns `false`.
+    /// Returns whether all children were handed out.
+    fn for_each_child<F: FnMut(&Self) -> bool>(&self, f: F) -> bool;
+}
+
+/// Callbacks of a walk, the root has depth 0.
+pub trait Visitor<N: ?Sized> {
+    /// Called before the children of `node`.
+    fn enter(&mut self, node: &N, depth: usize) -> Visit;
+
+    /// Called after the children of `node`, also when they were skipped.
+    fn leave(&mut self, _node: &N, _depth: usize) -> Visit {
+        Visit::Continue
+    }
+}
+
+/// Walks `root` and all its descendants, returns `false` if the visitor stopped the walk.
+pub fn walk<N: Walk + ?Sized, V: Visitor<N>>(root: &N, visitor: &mut V) -> bool {
+    walk_at(root, visitor, 0)
+}
+
+fn walk_at<N: Walk + ?Sized, V: Visitor<N>>(node: &N, visitor: &mut V, depth: usize) -> bool {
+    let complete = match visitor.enter(node, depth) {
+        Visit::Stop => return false,
+        Visit::SkipChildren => true,
+        Visit::Continue => node.for_each_child(|child| walk_at(child, visitor, depth + 1)),
+    };
+    complete && visitor.leave(node, depth) != Visit::Stop
+}
+
+/// Counts the nodes and tracks how deep they are nested.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Shape {
+    pub nodes: usize,
+    /// Depth of the deepest node, 0 if there is only the root
+    pub depth: usize,
+}
+
+impl<N: ?Sized> Visitor<N> for Shape {
+    fn enter(&mut self, _node: &N, depth: usize) -> Visit {
+        self.nodes += 1;
+        self.depth = self.depth.max(depth);
+        Visit::Continue
+    }
+}
+
+/// The number of nodes and the nesting depth of `root`.
+pub fn shape<N: Walk + ?Sized>(root: &N) -> Shape {
+    let mut shape = Shape::default();
+    walk(root, &mut shape);
+    shape
+}
+
+/// The depth of the first node in walking order for which `matches` holds, given the node and
+/// its depth. The walk ends at that node.
+pub fn find<N, F>(root: &N, matches: F) -> Option<usize>
+where
+    N: Walk + ?Sized,
+    F: FnMut(&N, usize) -> bool,
+{
+    struct Finder<F> {
+        matches: F,
+        found: Option<usize>,
+    }
+
+    impl<N: ?Sized, F: FnMut(&N, usize) -> bool> Visitor<N> for Finder<F> {
+        fn enter(&mut self, node: &N, depth: usize) -> Visit {
+            if (self.matches)(node, depth) {
+                self.found = Some(depth);
+                Visit::Stop
+            } else {
+                Visit::Continue
+            }
+        }
+    }
+
+    let mut finder = Finder {
+        matches,
+        found: None,
+    };
+    walk(root, &mut finder);
+    finder.found
+}
@@ -0,0 +1,65 @@
+//! Compares the allocations and runtime of the line streaming helpers.
+//!
+//! Run with `cargo bench --bench line_streaming`, add `--features fastpaths` to include the
+//! memory-mapped variants.
+
+use std::time::Instant;
+
+use aoc2021::{
+    alloc_stats, for_each_line, stream_items_from_file, stream_items_from_file_with_buf,
+    test_helpers::create_line_file,
+};
+
+// With the alloc_stats feature the library already installs the counting allocator
+#[cfg(not(feature = "alloc_stats"))]
+#[global_allocator]
+static GLOBAL: alloc_stats::CountingAllocator = alloc_stats::CountingAllocator;
+
+fn measure<F: FnOnce() -> i64>(name: &str, f: F) {
+    let allocations_before = alloc_stats::allocations();
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let allocations = alloc_stats::allocations() - allocations_before;
+    println!(
+        "{:<32} {:>10} allocations {:>10.2?} (checksum {})",
+        name, allocations, elapsed, result
+    );
+}
+
+fn main() {
+    const LINES: i64 = 200_000;
+    let (dir, file) = create_line_file((0..LINES).map(|i| i * 7919 - LINES), None);
+
+    measure("stream_items_from_file", || {
+        stream_items_from_file::<_, i64>(&file).unwrap().sum()
+    });
+    measure("stream_items_from_file_with_buf", || {
+        stream_items_from_file_with_buf::<_, i64>(&file)
+            .unwrap()
+            .map(Result::unwrap)
+            .sum()
+    });
+    measure("for_each_line", || {
+        let mut sum = 0;
+        for_each_line(std::fs::File::open(&file).unwrap(), |line| {
+            sum += line.parse::<i64>().unwrap()
+        })
+        .unwrap();
+        sum
+    });
+    #[cfg(feature = "fastpaths")]
+    measure("stream_items_mmap", || {
+        aoc2021::stream_items_mmap::<_, i64>(&file).unwrap().sum()
+    });
+    #[cfg(feature = "fastpaths")]
+    measure("MappedFile::lines", || {
+        let mapped = aoc2021::MappedFile::open(&file).unwrap();
+        mapped
+            .lines()
+            .map(|line| line.parse::<i64>().unwrap())
+            .sum()
+    });
+
+    drop(dir);
+}
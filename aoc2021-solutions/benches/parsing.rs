@@ -0,0 +1,91 @@
+//! Compares the regex based number extraction with the byte scanner of the `parse` module.
+//!
+//! Run with `cargo bench --bench parsing`.
+
+use std::time::Instant;
+
+use aoc2021::{
+    alloc_stats,
+    parse::{extract_all_ints, extract_ints},
+    vec2d::IVec2D,
+};
+use regex::Regex;
+
+#[cfg(not(feature = "alloc_stats"))]
+#[global_allocator]
+static GLOBAL: alloc_stats::CountingAllocator = alloc_stats::CountingAllocator;
+
+fn measure<F: FnOnce() -> i64>(name: &str, f: F) {
+    let allocations_before = alloc_stats::allocations();
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let allocations = alloc_stats::allocations() - allocations_before;
+    println!(
+        "{:<32} {:>10} allocations {:>10.2?} (checksum {})",
+        name, allocations, elapsed, result
+    );
+}
+
+fn main() {
+    const LINES: i64 = 20_000;
+    // Reboot steps in the format of day 22
+    let lines: Vec<String> = (0..LINES)
+        .map(|i| {
+            let (a, b) = (i * 7919 % 100_000 - 50_000, i * 104_729 % 100_000 - 50_000);
+            format!(
+                "{} x={}..{},y={}..{},z={}..{}",
+                if i % 3 == 0 { "off" } else { "on" },
+                a,
+                b,
+                b - 7,
+                a + 11,
+                -a,
+                b * 2
+            )
+        })
+        .collect();
+
+    measure("day22 regex", || {
+        // This is how day 22 found its intervals before the parse module existed
+        let interval_re = Regex::new(r"[\-\d]+..[\-\d]+").unwrap();
+        let number_re = Regex::new(r"[\-\d]+").unwrap();
+        lines
+            .iter()
+            .flat_map(|line| interval_re.find_iter(line))
+            .flat_map(|interval| number_re.find_iter(interval.as_str()))
+            .map(|number| number.as_str().parse::<i64>().unwrap())
+            .sum()
+    });
+    measure("day22 extract_all_ints", || {
+        lines
+            .iter()
+            .flat_map(|line| extract_all_ints::<i64>(line).unwrap())
+            .sum()
+    });
+    measure("day22 extract_ints", || {
+        lines
+            .iter()
+            .flat_map(|line| extract_ints::<i64, 6>(line).unwrap())
+            .sum()
+    });
+
+    let points: Vec<String> = (0..LINES)
+        .map(|i| format!("{},{}", i * 31 % 1000, i * 17 % 1000 - 500))
+        .collect();
+    measure("vec2d regex", || {
+        let re = Regex::new(r"-?\d+").unwrap();
+        points
+            .iter()
+            .flat_map(|point| re.find_iter(point))
+            .map(|number| number.as_str().parse::<i64>().unwrap())
+            .sum()
+    });
+    measure("vec2d from_str", || {
+        points
+            .iter()
+            .map(|point| point.parse::<IVec2D>().unwrap())
+            .map(|point| (point.x() + point.y()) as i64)
+            .sum()
+    });
+}
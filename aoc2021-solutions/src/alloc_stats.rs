@@ -0,0 +1,126 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+static BASELINE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator and counts allocations as well as the heap size.
+/// With the `alloc_stats` feature this is installed as the global allocator for all days.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn grow(size: usize) {
+        let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        Self::grow(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        Self::grow(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(feature = "alloc_stats")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    /// Highest heap usage since the last reset, not counting what was allocated before
+    pub peak_bytes: usize,
+}
+
+/// Total number of allocations so far, only counted if `CountingAllocator` is the global allocator.
+pub fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+pub fn reset() {
+    let current = CURRENT_BYTES.load(Ordering::Relaxed);
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    BASELINE_BYTES.store(current, Ordering::Relaxed);
+    PEAK_BYTES.store(current, Ordering::Relaxed);
+}
+
+pub fn stats() -> AllocStats {
+    AllocStats {
+        allocations: allocations(),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed) - BASELINE_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Runs `f` and reports its allocation statistics on stderr if the `alloc_stats` feature is enabled.
+pub fn measure<T, F: FnOnce() -> T>(label: &str, f: F) -> T {
+    if !cfg!(feature = "alloc_stats") {
+        return f();
+    }
+    reset();
+    let result = f();
+    eprintln!("{}", stats().report(label));
+    result
+}
+
+impl AllocStats {
+    /// The line `measure` writes to stderr, `parse_reports` reads it back.
+    pub fn report(&self, label: &str) -> String {
+        format!(
+            "[{}] {} allocations, peak heap usage {} bytes",
+            label, self.allocations, self.peak_bytes
+        )
+    }
+}
+
+/// The statistics `measure` reported in the stderr of a day, other lines are skipped.
+pub fn parse_reports(stderr: &str) -> Vec<(String, AllocStats)> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let (label, rest) = line.strip_prefix('[')?.split_once("] ")?;
+            let (allocations, peak) = rest.split_once(" allocations, peak heap usage ")?;
+            let stats = AllocStats {
+                allocations: allocations.parse().ok()?,
+                peak_bytes: peak.strip_suffix(" bytes")?.parse().ok()?,
+            };
+            Some((label.to_string(), stats))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reports() {
+        let stats = AllocStats {
+            allocations: 42,
+            peak_bytes: 1024,
+        };
+        let stderr = format!("warning: slow\n{}\n[part 2] broken\n", stats.report("part 1"));
+        assert_eq!(
+            parse_reports(&stderr),
+            vec![("part 1".to_string(), stats)]
+        );
+        assert!(parse_reports("").is_empty());
+    }
+}
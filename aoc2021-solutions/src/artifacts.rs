@@ -0,0 +1,57 @@
+//! Intermediate state of a day, written to a directory with `--artifacts DIR` for inspection after the run.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// State that can be dumped as a set of text files.
+pub trait Artifacts {
+    /// The files to write as pairs of file name and content.
+    fn artifacts(&self) -> Vec<(String, String)>;
+}
+
+/// The directory passed with `--artifacts DIR`, if any.
+pub fn requested_dir() -> Option<PathBuf> {
+    dir_from_args(std::env::args().skip(1))
+}
+
+pub fn dir_from_args<I: IntoIterator<Item = String>>(args: I) -> Option<PathBuf> {
+    args.into_iter()
+        .skip_while(|arg| arg != "--artifacts")
+        .nth(1)
+        .map(PathBuf::from)
+}
+
+/// Writes the artifacts into `<dir>/<day>/` and returns the paths of the written files.
+pub fn write<A: Artifacts + ?Sized>(
+    dir: &Path,
+    day: &str,
+    artifacts: &A,
+) -> io::Result<Vec<PathBuf>> {
+    let day_dir = dir.join(day);
+    fs::create_dir_all(&day_dir)?;
+    artifacts
+        .artifacts()
+        .into_iter()
+        .map(|(name, content)| {
+            let path = day_dir.join(name);
+            fs::write(&path, content)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Writes the artifacts if the binary was started with `--artifacts DIR`.
+/// Only the location is reported, on stderr, so the answers on stdout stay untouched.
+pub fn write_if_requested<A: Artifacts + ?Sized>(day: &str, artifacts: &A) -> io::Result<()> {
+    if let Some(dir) = requested_dir() {
+        let written = write(&dir, day, artifacts)?;
+        eprintln!(
+            "Wrote {} artifacts to {}",
+            written.len(),
+            dir.join(day).display()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::{count_adjacent, lenient, try_stream_items_from_file};
+use itertools::process_results;
+
+const INPUT: &str = "input/day01.txt";
+
+/// How a window of reads relates to the window before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Increasing,
+    Decreasing,
+    NonDecreasing,
+    NonIncreasing,
+    Equal,
+}
+
+impl Comparison {
+    fn holds(self, previous: &usize, next: &usize) -> bool {
+        match self {
+            Comparison::Increasing => next > previous,
+            Comparison::Decreasing => next < previous,
+            Comparison::NonDecreasing => next >= previous,
+            Comparison::NonIncreasing => next <= previous,
+            Comparison::Equal => next == previous,
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "increasing" => Ok(Comparison::Increasing),
+            "decreasing" => Ok(Comparison::Decreasing),
+            "non-decreasing" => Ok(Comparison::NonDecreasing),
+            "non-increasing" => Ok(Comparison::NonIncreasing),
+            "equal" => Ok(Comparison::Equal),
+            _ => Err(anyhow!(
+                "Unknown comparison {:?}, expected increasing, decreasing, \
+                 non-decreasing, non-increasing or equal",
+                s
+            )),
+        }
+    }
+}
+
+/// `--window N` and `--compare COMPARISON` for exploring the reads beyond the two parts,
+/// `None` if neither is given. The window defaults to 1 and the comparison to increasing.
+fn requested_analysis(args: &[String]) -> Result<Option<(usize, Comparison)>> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .map(|i| args.get(i + 1).ok_or_else(|| anyhow!("{} needs a value", flag)))
+            .transpose()
+    };
+    let window = value("--window")?;
+    let comparison = value("--compare")?;
+    if window.is_none() && comparison.is_none() {
+        return Ok(None);
+    }
+    let window = window.map_or(Ok(1), |window| window.parse())?;
+    if window == 0 {
+        return Err(anyhow!("The window needs at least one read"));
+    }
+    let comparison = comparison.map_or(Ok(Comparison::Increasing), |c| c.parse())?;
+    Ok(Some((window, comparison)))
+}
+
+fn number_of_increasing_reads<I: Iterator<Item = usize>>(input: I, window: usize) -> usize {
+    count_adjacent(input, window, |previous, next| {
+        Comparison::Increasing.holds(previous, next)
+    })
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let reads = try_stream_items_from_file::<_, usize>(input)?;
+    Ok(process_results(reads, |reads| number_of_increasing_reads(reads, 1))?)
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let reads = try_stream_items_from_file::<_, usize>(input)?;
+    Ok(process_results(reads, |reads| number_of_increasing_reads(reads, 3))?)
+}
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((window, comparison)) = requested_analysis(&args)? {
+        let reads = try_stream_items_from_file::<_, usize>(input)?;
+        let count = process_results(reads, |reads| {
+            count_adjacent(reads, window, |previous, next| comparison.holds(previous, next))
+        })?;
+        println!("Windows of {} reads {:?} to the previous: {}", window, comparison, count);
+        return Ok(());
+    }
+    if lenient::requested() {
+        let reads = lenient::report(lenient::read_items::<_, usize>(input)?);
+        let part1 = number_of_increasing_reads(reads.iter().copied(), 1);
+        let part2 = number_of_increasing_reads(reads.into_iter(), 3);
+        println!("Answer for part 1: {}", part1);
+        println!("Answer for part 2: {}", part2);
+        return Ok(());
+    }
+    #[cfg(feature = "fastpaths")]
+    if let InputSource::File(path) = input {
+        // Counts on the memory-mapped file, lines that aren't reads are skipped instead of reported
+        use aoc2021::fastpaths::count_increases_in_file;
+        let part1 = measure("part 1", || count_increases_in_file(path, 1))?;
+        println!("Answer for part 1: {}", part1);
+        let part2 = measure("part 2", || count_increases_in_file(path, 3))?;
+        println!("Answer for part 2: {}", part2);
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2021::test_helpers::create_line_file;
+
+    #[test]
+    fn test_comparisons() {
+        let reads = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let (dir, file) = create_line_file(reads.iter(), None);
+        assert_eq!(part1(&file).unwrap(), 7);
+        assert_eq!(part2(&file).unwrap(), 5);
+        drop(dir);
+
+        let count = |window, comparison: &str| {
+            let comparison: Comparison = comparison.parse().unwrap();
+            count_adjacent(reads, window, |previous, next| comparison.holds(previous, next))
+        };
+        assert_eq!(count(1, "decreasing"), 2);
+        assert_eq!(count(3, "decreasing"), 1);
+        assert_eq!(count(1, "non-increasing"), 2);
+        assert_eq!(count(1, "equal"), 0);
+        assert_eq!(count(3, "equal"), 1);
+        assert_eq!(count(2, "non-decreasing"), 5);
+        assert_eq!(count(10, "increasing"), 0);
+        assert_eq!(count(20, "increasing"), 0);
+        assert!("up".parse::<Comparison>().is_err());
+
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(requested_analysis(&args(&[])).unwrap(), None);
+        assert_eq!(
+            requested_analysis(&args(&["--compare", "equal"])).unwrap(),
+            Some((1, Comparison::Equal))
+        );
+        assert!(requested_analysis(&args(&["--window", "0"])).is_err());
+        assert!(requested_analysis(&args(&["--window"])).is_err());
+    }
+
+    #[cfg(feature = "fastpaths")]
+    #[test]
+    fn test_fastpath_matches() {
+        use aoc2021::fastpaths::count_increases_in_file;
+
+        let reads = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263, 1, 1000, 999, 1000];
+        let (dir, file) = create_line_file(reads.iter(), None);
+        assert_eq!(
+            count_increases_in_file(&file, 1).unwrap(),
+            part1(&file).unwrap()
+        );
+        assert_eq!(
+            count_increases_in_file(&file, 3).unwrap(),
+            part2(&file).unwrap()
+        );
+        drop(dir);
+    }
+}
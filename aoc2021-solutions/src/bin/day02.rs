@@ -0,0 +1,305 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::artifacts::{self, Artifacts};
+use aoc2021::{error::InputError, field2d::Field2D, lenient, try_stream_items_from_file};
+use aoc2021::{Input, InputSource};
+use itertools::process_results;
+use std::{ops::Add, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IntVec(isize, isize);
+
+impl FromStr for IntVec {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dir, amount) = s
+            .split_once(' ')
+            .ok_or_else(|| InputError::new(s, "expected a direction and an amount"))?;
+        let amount = isize::from_str(amount)
+            .map_err(|e| InputError::in_text(s, amount, format!("invalid amount, {}", e)))?;
+        match dir {
+            "forward" => Ok(IntVec(amount, 0)),
+            "up" => Ok(IntVec(0, -amount)),
+            "down" => Ok(IntVec(0, amount)),
+            _ => Err(InputError::in_text(s, dir, "invalid movement")),
+        }
+    }
+}
+
+impl Add for IntVec {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        IntVec(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl IntVec {
+    fn prod(&self) -> isize {
+        self.0 * self.1
+    }
+}
+
+fn apply_movements_part1<I: Iterator<Item = IntVec>>(iter: I) -> IntVec {
+    iter.fold(IntVec(0, 0), |acc, x| acc + x)
+}
+
+fn apply_movements_part2<I: Iterator<Item = IntVec>>(iter: I) -> IntVec {
+    let (x, y, _) = iter.fold((0, 0, 0), |(x, y, aim), command| {
+        (x + command.0, y + command.0 * aim, aim + command.1)
+    });
+    IntVec(x, y)
+}
+
+/// The position after every command under both movement models, starting at the origin.
+struct Trajectories {
+    direct: Vec<IntVec>,
+    aimed: Vec<IntVec>,
+}
+
+impl Trajectories {
+    fn trace(commands: &[IntVec]) -> Self {
+        let origin = std::iter::once(IntVec(0, 0));
+        let direct = origin
+            .clone()
+            .chain(commands.iter().scan(IntVec(0, 0), |position, &command| {
+                *position = *position + command;
+                Some(*position)
+            }))
+            .collect();
+        let aimed = origin
+            .chain(
+                commands
+                    .iter()
+                    .scan((IntVec(0, 0), 0), |(position, aim), command| {
+                        *position = *position + IntVec(command.0, command.0 * *aim);
+                        *aim += command.1;
+                        Some(*position)
+                    }),
+            )
+            .collect();
+        Trajectories { direct, aimed }
+    }
+
+    fn load<I: Input>(input: I) -> Result<Self> {
+        let commands =
+            try_stream_items_from_file::<_, IntVec>(input)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::trace(&commands))
+    }
+
+    /// Depth over distance scaled into `width` columns and `height` rows with the surface on top,
+    /// `1` marks the part 1 model, `2` the aimed one and `*` cells crossed by both.
+    fn plot(&self, width: usize, height: usize) -> String {
+        let positions = || self.direct.iter().chain(&self.aimed);
+        let max_distance = positions().map(|p| p.0).max().unwrap_or(0).max(1);
+        let min_depth = positions().map(|p| p.1).min().unwrap_or(0).min(0);
+        let max_depth = positions()
+            .map(|p| p.1)
+            .max()
+            .unwrap_or(0)
+            .max(min_depth + 1);
+        let cell = |p: &IntVec| {
+            let x = p.0 * (width as isize - 1) / max_distance;
+            let y = (p.1 - min_depth) * (height as isize - 1) / (max_depth - min_depth);
+            (x, y)
+        };
+
+        let mut field = Field2D::<char>::new_empty(width, height);
+        for (mark, trace) in [('1', &self.direct), ('2', &self.aimed)] {
+            for (from, to) in trace.iter().zip(trace.iter().skip(1)) {
+                let (from, to) = (cell(from), cell(to));
+                let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+                for step in 0..=steps {
+                    let x = from.0 + (to.0 - from.0) * step / steps;
+                    let y = from.1 + (to.1 - from.1) * step / steps;
+                    let value = &mut field[(x as usize, y as usize)];
+                    *value = match *value {
+                        '\0' => mark,
+                        other if other == mark => mark,
+                        _ => '*',
+                    };
+                }
+            }
+        }
+
+        let mut plot = format!(
+            "distance 0..={} across, depth {}..={} down, 1: part 1, 2: part 2 (aim), *: both\n",
+            max_distance, min_depth, max_depth
+        );
+        for y in 0..height {
+            let row: String = field
+                .row(y)
+                .iter()
+                .map(|&c| if c == '\0' { ' ' } else { c })
+                .collect();
+            plot.push_str(row.trim_end());
+            plot.push('\n');
+        }
+        plot
+    }
+
+    /// One line per command with the distance and the depths of both models.
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("step,distance,depth,aimed_depth\n");
+        for (step, (direct, aimed)) in self.direct.iter().zip(&self.aimed).enumerate() {
+            csv.push_str(&format!("{},{},{},{}\n", step, direct.0, direct.1, aimed.1));
+        }
+        csv
+    }
+}
+
+impl Artifacts for Trajectories {
+    fn artifacts(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "trajectory.txt".to_string(),
+                self.plot(PLOT_WIDTH, PLOT_HEIGHT),
+            ),
+            ("trajectory.csv".to_string(), self.to_csv()),
+        ]
+    }
+}
+
+const PLOT_WIDTH: usize = 100;
+const PLOT_HEIGHT: usize = 40;
+
+fn part1<I: Input>(input: I) -> Result<isize> {
+    let commands = try_stream_items_from_file::<_, IntVec>(input)?;
+    Ok(process_results(commands, |commands| apply_movements_part1(commands))?.prod())
+}
+
+fn part2<I: Input>(input: I) -> Result<isize> {
+    let commands = try_stream_items_from_file::<_, IntVec>(input)?;
+    Ok(process_results(commands, |commands| apply_movements_part2(commands))?.prod())
+}
+
+const INPUT: &str = "input/day02.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if std::env::args().skip(1).any(|arg| arg == "--plot") {
+        print!(
+            "{}",
+            Trajectories::load(input)?.plot(PLOT_WIDTH, PLOT_HEIGHT)
+        );
+        return Ok(());
+    }
+    if artifacts::requested_dir().is_some() {
+        artifacts::write_if_requested("day02", &Trajectories::load(input)?)?;
+    }
+    if lenient::requested() {
+        let commands = lenient::report(lenient::read_items::<_, IntVec>(input)?);
+        let part1 = apply_movements_part1(commands.iter().copied());
+        let part2 = apply_movements_part2(commands.into_iter());
+        println!("Answer for part 1: {}", part1.prod());
+        println!("Answer for part 2: {}", part2.prod());
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2021::{stream_items_from_file, test_helpers::create_line_file};
+    use indoc::indoc;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_movements() -> Vec<IntVec> {
+        vec![
+            IntVec(5, 0),
+            IntVec(0, 5),
+            IntVec(8, 0),
+            IntVec(0, -3),
+            IntVec(0, 8),
+            IntVec(2, 0),
+        ]
+    }
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [
+                "forward 5",
+                "down 5",
+                "forward 8",
+                "up 3",
+                "down 8",
+                "forward 2",
+            ]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_parse_movements() {
+        let (dir, file) = example_file();
+        assert_eq!(
+            stream_items_from_file::<_, IntVec>(file)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            example_movements()
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_apply_movements_part1() {
+        let movements = example_movements();
+        assert_eq!(apply_movements_part1(movements.into_iter()), IntVec(15, 10));
+    }
+
+    #[test]
+    fn test_apply_movements_part2() {
+        let movements = example_movements();
+        assert_eq!(apply_movements_part2(movements.into_iter()), IntVec(15, 60));
+    }
+
+    #[test]
+    fn test_trajectories() {
+        let trajectories = Trajectories::trace(&example_movements());
+        assert_eq!(
+            trajectories.direct,
+            vec![
+                IntVec(0, 0),
+                IntVec(5, 0),
+                IntVec(5, 5),
+                IntVec(13, 5),
+                IntVec(13, 2),
+                IntVec(13, 10),
+                IntVec(15, 10),
+            ]
+        );
+        assert_eq!(
+            trajectories.aimed,
+            vec![
+                IntVec(0, 0),
+                IntVec(5, 0),
+                IntVec(5, 0),
+                IntVec(13, 40),
+                IntVec(13, 40),
+                IntVec(13, 40),
+                IntVec(15, 60),
+            ]
+        );
+        assert_eq!(
+            trajectories.plot(16, 7),
+            indoc! {"
+                distance 0..=15 across, depth 0..=60 down, 1: part 1, 2: part 2 (aim), *: both
+                *******1111111
+                       22    111
+                         22
+                           22
+                             2
+                              2
+                               2
+            "}
+        );
+        assert!(trajectories.to_csv().ends_with("5,13,10,40\n6,15,10,60\n"));
+    }
+}
@@ -0,0 +1,321 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::stream_items_from_file;
+use aoc2021::{Input, InputSource};
+
+fn convert_line(line: &str) -> impl Iterator<Item = usize> + '_ {
+    line.chars().map(|c| match c {
+        '0' => 0,
+        '1' => 1,
+        _ => panic!("Unexpected value in input"),
+    })
+}
+
+/// How many of the binaries have a one in each column, counted from the left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BitCounts {
+    ones: Vec<usize>,
+    lines: usize,
+}
+
+impl BitCounts {
+    fn zeros(&self, column: usize) -> usize {
+        self.lines - self.ones[column]
+    }
+
+    /// Whether the ones are the most common bit of the column, ties count as zeros.
+    fn ones_most_common(&self, column: usize) -> bool {
+        self.ones[column] > self.lines / 2
+    }
+
+    fn gamma(&self) -> usize {
+        (0..self.ones.len()).fold(0, |gamma, column| {
+            gamma << 1 | usize::from(self.ones_most_common(column))
+        })
+    }
+
+    fn epsilon(&self) -> usize {
+        !self.gamma() & ((1 << self.ones.len()) - 1)
+    }
+}
+
+fn count_digits<S: AsRef<str>, I: Iterator<Item = S>>(mut binaries: I) -> BitCounts {
+    let init = convert_line(binaries.next().expect("Input is empty").as_ref()).collect();
+    binaries.fold(
+        BitCounts {
+            ones: init,
+            lines: 1,
+        },
+        |mut counts, next| {
+            counts
+                .ones
+                .iter_mut()
+                .zip(convert_line(next.as_ref()))
+                .for_each(|(digit_counter, digit)| *digit_counter += digit);
+            counts.lines += 1;
+            counts
+        },
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keep {
+    Ones,
+    Zeros,
+}
+
+/// Keep the most common bit, ties keep the ones
+fn oxygen_criterion(ones: usize, zeros: usize) -> Keep {
+    if ones >= zeros {
+        Keep::Ones
+    } else {
+        Keep::Zeros
+    }
+}
+
+/// Keep the least common bit, ties keep the zeros
+fn co2_criterion(ones: usize, zeros: usize) -> Keep {
+    match oxygen_criterion(ones, zeros) {
+        Keep::Ones => Keep::Zeros,
+        Keep::Zeros => Keep::Ones,
+    }
+}
+
+/// Filters the binaries bit by bit from the left, the criterion receives the number of ones and zeros
+/// at the current position and decides which of them survive, until only a single binary is left.
+fn bit_filter<F>(mut binaries: Vec<String>, criterion: F) -> Result<usize>
+where
+    F: Fn(usize, usize) -> Keep,
+{
+    let digits = binaries.first().map(|b| b.len()).unwrap_or(0);
+
+    for idx in 0..digits {
+        let counts = count_digits(binaries.iter());
+        let pat = match criterion(counts.ones[idx], counts.zeros(idx)) {
+            Keep::Ones => b'1',
+            Keep::Zeros => b'0',
+        };
+        binaries.retain(|s| s.as_bytes()[idx] == pat);
+        match binaries.len() {
+            0 => anyhow::bail!("No binary left after filtering bit {}", idx),
+            1 => return Ok(usize::from_str_radix(&binaries[0], 2)?),
+            _ => (),
+        }
+    }
+    anyhow::bail!("Invalid search");
+}
+
+/// Everything the diagnostic report reveals, computed from a single parse of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiagnosticReport {
+    gamma: usize,
+    epsilon: usize,
+    oxygen: usize,
+    co2: usize,
+    counts: BitCounts,
+}
+
+impl DiagnosticReport {
+    fn new(binaries: Vec<String>) -> Result<Self> {
+        if binaries.is_empty() {
+            anyhow::bail!("The diagnostic report is empty");
+        }
+        let counts = count_digits(binaries.iter());
+        Ok(DiagnosticReport {
+            gamma: counts.gamma(),
+            epsilon: counts.epsilon(),
+            oxygen: bit_filter(binaries.clone(), oxygen_criterion)?,
+            co2: bit_filter(binaries, co2_criterion)?,
+            counts,
+        })
+    }
+
+    fn load<I: Input>(input: I) -> Result<Self> {
+        Self::new(stream_items_from_file(input)?.collect())
+    }
+
+    fn power_consumption(&self) -> usize {
+        self.gamma * self.epsilon
+    }
+
+    fn life_support_rating(&self) -> usize {
+        self.oxygen * self.co2
+    }
+
+    /// The number of ones and zeros per column from the left, followed by the ratings.
+    fn explain(&self) -> String {
+        let mut explanation = String::from("column  ones  zeros  most common\n");
+        for column in 0..self.counts.ones.len() {
+            explanation.push_str(&format!(
+                "{:>6}  {:>4}  {:>5}  {}\n",
+                column,
+                self.counts.ones[column],
+                self.counts.zeros(column),
+                u8::from(self.counts.ones_most_common(column))
+            ));
+        }
+        let width = self.counts.ones.len();
+        explanation.push_str(&format!(
+            "gamma {:0w$b} ({}), epsilon {:0w$b} ({}), power consumption {}\n",
+            self.gamma,
+            self.gamma,
+            self.epsilon,
+            self.epsilon,
+            self.power_consumption(),
+            w = width
+        ));
+        explanation.push_str(&format!(
+            "oxygen {:0w$b} ({}), co2 {:0w$b} ({}), life support rating {}\n",
+            self.oxygen,
+            self.oxygen,
+            self.co2,
+            self.co2,
+            self.life_support_rating(),
+            w = width
+        ));
+        explanation
+    }
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let counts = count_digits(stream_items_from_file::<_, String>(input)?);
+    Ok(counts.gamma() * counts.epsilon())
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    Ok(DiagnosticReport::load(input)?.life_support_rating())
+}
+
+const INPUT: &str = "input/day03.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if std::env::args().skip(1).any(|arg| arg == "--explain") {
+        print!("{}", DiagnosticReport::load(input)?.explain());
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use aoc2021::{stream_items_from_file, test_helpers::create_line_file};
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [
+                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+                "11001", "00010", "01010",
+            ]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_count_digits() {
+        let (dir, file) = example_file();
+        assert_eq!(
+            count_digits(stream_items_from_file::<_, String>(file).unwrap()),
+            BitCounts {
+                ones: vec![7, 5, 8, 7, 5],
+                lines: 12
+            }
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_gamma() {
+        let (dir, file) = example_file();
+        let counts = count_digits(stream_items_from_file::<_, String>(file).unwrap());
+        assert_eq!((counts.gamma(), counts.epsilon()), (22, 9));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_oxygen() {
+        let (dir, file) = example_file();
+        let input = stream_items_from_file(file).unwrap().collect();
+        assert_eq!(bit_filter(input, oxygen_criterion).unwrap(), 23);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_co2() {
+        let (dir, file) = example_file();
+        let input = stream_items_from_file(file).unwrap().collect();
+        assert_eq!(bit_filter(input, co2_criterion).unwrap(), 10);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_diagnostic_report() {
+        let (dir, file) = example_file();
+        let report = DiagnosticReport::load(&file).unwrap();
+        drop(dir);
+        assert_eq!(
+            (report.gamma, report.epsilon, report.oxygen, report.co2),
+            (22, 9, 23, 10)
+        );
+        assert_eq!(report.power_consumption(), 198);
+        assert_eq!(report.life_support_rating(), 230);
+        assert_eq!(
+            report.explain(),
+            indoc! {"
+                column  ones  zeros  most common
+                     0     7      5  1
+                     1     5      7  0
+                     2     8      4  1
+                     3     7      5  1
+                     4     5      7  0
+                gamma 10110 (22), epsilon 01001 (9), power consumption 198
+                oxygen 10111 (23), co2 01010 (10), life support rating 230
+            "}
+        );
+        assert!(DiagnosticReport::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_criteria_tie_breaking() {
+        assert_eq!(oxygen_criterion(7, 5), Keep::Ones);
+        assert_eq!(oxygen_criterion(5, 7), Keep::Zeros);
+        assert_eq!(oxygen_criterion(2, 2), Keep::Ones);
+        assert_eq!(co2_criterion(7, 5), Keep::Zeros);
+        assert_eq!(co2_criterion(5, 7), Keep::Ones);
+        assert_eq!(co2_criterion(2, 2), Keep::Zeros);
+        assert_eq!(co2_criterion(1, 0), Keep::Zeros);
+    }
+
+    #[test]
+    fn test_bit_filter() {
+        let (dir, file) = example_file();
+        let input: Vec<String> = stream_items_from_file(file).unwrap().collect();
+        assert_eq!(bit_filter(input.clone(), oxygen_criterion).unwrap(), 23);
+        assert_eq!(bit_filter(input.clone(), co2_criterion).unwrap(), 10);
+        // Most common bit again, but ties keep the zeros this time: 10110 and 10111 tie on the last bit
+        let ties_to_zero = |ones, zeros| {
+            if ones > zeros {
+                Keep::Ones
+            } else {
+                Keep::Zeros
+            }
+        };
+        assert_eq!(bit_filter(input.clone(), ties_to_zero).unwrap(), 22);
+        assert_eq!(bit_filter(input, |_, _| Keep::Ones).unwrap(), 30);
+        drop(dir);
+
+        // Criteria that keep a bit nobody has or duplicates leave no unique binary
+        let binaries = vec!["00".to_string(), "01".to_string()];
+        assert!(bit_filter(binaries, |_, _| Keep::Ones).is_err());
+        let binaries = vec!["01".to_string(), "01".to_string()];
+        assert!(bit_filter(binaries, oxygen_criterion).is_err());
+    }
+}
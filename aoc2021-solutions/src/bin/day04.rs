@@ -0,0 +1,501 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    iter::{self, Enumerate},
+    num::ParseIntError,
+    ops::{Index, IndexMut},
+};
+
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{try_stream_blocks_from_file, Block};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use regex::Regex;
+
+fn get_draws(line: &str) -> Result<Vec<usize>, ParseIntError> {
+    line.split(',').map(|s| s.parse::<usize>()).collect()
+}
+
+struct BingoField {
+    content: Vec<(usize, bool)>,
+    width: usize,
+}
+
+impl TryFrom<Vec<String>> for BingoField {
+    type Error = ParseIntError;
+
+    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
+        let delim_regex = Regex::new(r"\s+").unwrap();
+
+        let width = delim_regex.split(&value[0]).count();
+        let content = value
+            .iter()
+            .flat_map(|line| {
+                delim_regex
+                    .split(line)
+                    .filter(|p| !p.is_empty())
+                    .map(|s| s.parse::<usize>())
+            })
+            .collect::<Result<Vec<usize>, _>>()?
+            .into_iter()
+            .map(|v| (v, false))
+            .collect();
+
+        Ok(BingoField { content, width })
+    }
+}
+
+impl Index<(usize, usize)> for BingoField {
+    type Output = (usize, bool);
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (x, y) = index;
+        &self.content[y * self.width + x]
+    }
+}
+
+impl IndexMut<(usize, usize)> for BingoField {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        let (x, y) = index;
+        &mut self.content[y * self.width + x]
+    }
+}
+
+impl BingoField {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.content.len() / self.width
+    }
+
+    fn base_score(&self) -> usize {
+        self.content.iter().filter(|(_, m)| !m).map(|t| t.0).sum()
+    }
+
+    /// Marks a cell and checks whether this completed its row or column.
+    fn mark_cell(&mut self, cell: usize) -> bool {
+        self.content[cell].1 = true;
+        let (x, y) = (cell % self.width, cell / self.width);
+        (0..self.height()).all(|y| self[(x, y)].1) || (0..self.width()).all(|x| self[(x, y)].1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Win {
+    board: usize,
+    /// Index of the draw that completed the board
+    draw: usize,
+    score: usize,
+}
+
+struct BingoGame {
+    boards: Vec<BingoField>,
+    won: Vec<bool>,
+    remaining: usize,
+    /// All (board, cell) positions of each number
+    positions: HashMap<usize, Vec<(usize, usize)>>,
+}
+
+impl BingoGame {
+    fn new(boards: Vec<BingoField>) -> Self {
+        let mut positions = HashMap::<usize, Vec<(usize, usize)>>::new();
+        for (board_idx, board) in boards.iter().enumerate() {
+            for (cell, (number, _)) in board.content.iter().enumerate() {
+                positions
+                    .entry(*number)
+                    .or_default()
+                    .push((board_idx, cell));
+            }
+        }
+        Self {
+            won: vec![false; boards.len()],
+            remaining: boards.len(),
+            boards,
+            positions,
+        }
+    }
+
+    fn draw(&mut self, draw_idx: usize, number: usize, wins: &mut VecDeque<Win>) {
+        for &(board_idx, cell) in self.positions.get(&number).into_iter().flatten() {
+            if !self.won[board_idx] && self.boards[board_idx].mark_cell(cell) {
+                self.won[board_idx] = true;
+                self.remaining -= 1;
+                wins.push_back(Win {
+                    board: board_idx,
+                    draw: draw_idx,
+                    score: self.boards[board_idx].base_score() * number,
+                });
+            }
+        }
+    }
+
+    /// Plays all draws and groups the wins by the draw that completed the boards, in the order of the draws.
+    fn play_rounds<I: IntoIterator<Item = usize>>(&mut self, draws: I) -> Vec<Vec<Win>> {
+        let mut rounds: Vec<Vec<Win>> = Vec::new();
+        for win in self.play(draws) {
+            match rounds.last_mut() {
+                Some(round) if round[0].draw == win.draw => round.push(win),
+                _ => rounds.push(vec![win]),
+            }
+        }
+        rounds
+    }
+
+    /// The wins of the first winning draw, no numbers after that draw are drawn.
+    fn first_round<I: IntoIterator<Item = usize>>(&mut self, draws: I) -> Vec<Win> {
+        let mut wins = self.play(draws);
+        match wins.next() {
+            // The other boards completed by the same draw are already pending
+            Some(first) => iter::once(first).chain(wins.pending.drain(..)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Plays the draws in a single pass over all boards, yielding every board once it has won.
+    /// Boards winning on the same draw are yielded in their input order, no further numbers are
+    /// drawn once all boards have won.
+    fn play<I: IntoIterator<Item = usize>>(&mut self, draws: I) -> Wins<'_, I::IntoIter> {
+        Wins {
+            game: self,
+            draws: draws.into_iter().enumerate(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+struct Wins<'a, I> {
+    game: &'a mut BingoGame,
+    draws: Enumerate<I>,
+    pending: VecDeque<Win>,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Wins<'_, I> {
+    type Item = Win;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && self.game.remaining > 0 {
+            let (draw_idx, number) = self.draws.next()?;
+            self.game.draw(draw_idx, number, &mut self.pending);
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Decides which of several boards winning on the same draw counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TieBreak {
+    HighestScore,
+    LowestScore,
+    FirstBoard,
+    LastBoard,
+}
+
+impl TieBreak {
+    /// `None` for an empty round.
+    fn pick(&self, round: &[Win]) -> Option<Win> {
+        let iter = round.iter().copied();
+        match self {
+            TieBreak::HighestScore => iter.max_by_key(|win| win.score),
+            TieBreak::LowestScore => iter.min_by_key(|win| win.score),
+            TieBreak::FirstBoard => iter.min_by_key(|win| win.board),
+            TieBreak::LastBoard => iter.max_by_key(|win| win.board),
+        }
+    }
+
+    /// `--tie-break POLICY` replaces the policies of both parts, e.g. `--tie-break first`.
+    fn from_args(args: &[String]) -> Result<Option<TieBreak>> {
+        match args.iter().position(|arg| arg == "--tie-break") {
+            Some(i) => match args.get(i + 1).map(String::as_str) {
+                Some("highest") => Ok(Some(TieBreak::HighestScore)),
+                Some("lowest") => Ok(Some(TieBreak::LowestScore)),
+                Some("first") => Ok(Some(TieBreak::FirstBoard)),
+                Some("last") => Ok(Some(TieBreak::LastBoard)),
+                policy => Err(anyhow!(
+                    "Unknown tie-break policy {:?}, expected highest, lowest, first or last",
+                    policy
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            TieBreak::HighestScore => "the highest score",
+            TieBreak::LowestScore => "the lowest score",
+            TieBreak::FirstBoard => "the first board",
+            TieBreak::LastBoard => "the last board",
+        }
+    }
+}
+
+// Among boards winning on the same draw, part 1 takes the highest and part 2 the lowest score
+const FIRST_WIN_POLICY: TieBreak = TieBreak::HighestScore;
+const LAST_WIN_POLICY: TieBreak = TieBreak::LowestScore;
+
+fn read_game<I: Input>(input: I) -> Result<(BingoGame, Vec<usize>)> {
+    let mut blocks = try_stream_blocks_from_file(input)?;
+    let draws = get_draws(
+        &blocks
+            .next()
+            .ok_or_else(|| anyhow!("No draws in input"))??
+            .lines[0],
+    )?;
+    let boards = blocks
+        .map(|block| block.and_then(Block::parse::<BingoField>))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((BingoGame::new(boards), draws))
+}
+
+fn first_win<I: Input>(input: I, policy: TieBreak) -> Result<Win> {
+    let (mut game, draws) = read_game(input)?;
+    policy
+        .pick(&game.first_round(draws))
+        .ok_or_else(|| anyhow!("No board wins"))
+}
+
+fn last_win<I: Input>(input: I, policy: TieBreak) -> Result<Win> {
+    let (mut game, draws) = read_game(input)?;
+    let rounds = game.play_rounds(draws);
+    rounds
+        .last()
+        .and_then(|round| policy.pick(round))
+        .ok_or_else(|| anyhow!("No board wins"))
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    Ok(first_win(input, FIRST_WIN_POLICY)?.score)
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    Ok(last_win(input, LAST_WIN_POLICY)?.score)
+}
+
+/// Every win in the order of the draws, with a warning where the first or last win is a tie.
+fn explain<I: Input>(input: I) -> Result<String> {
+    let (mut game, draws) = read_game(input)?;
+    let rounds = game.play_rounds(draws.iter().copied());
+    let mut lines = Vec::new();
+    for (i, round) in rounds.iter().enumerate() {
+        for win in round {
+            lines.push(format!(
+                "draw {} ({}): board {} wins with score {}",
+                win.draw + 1,
+                draws[win.draw],
+                win.board,
+                win.score
+            ));
+        }
+        let mut positions = Vec::new();
+        if i == 0 {
+            positions.push(("first", FIRST_WIN_POLICY));
+        }
+        if i == rounds.len() - 1 {
+            positions.push(("last", LAST_WIN_POLICY));
+        }
+        for (position, policy) in positions.into_iter().filter(|_| round.len() > 1) {
+            let picked = policy.pick(round).unwrap();
+            lines.push(format!(
+                "warning: boards {} tie for the {} win, {} counts: board {} with score {}",
+                round.iter().map(|win| win.board).join(", "),
+                position,
+                policy.describe(),
+                picked.board,
+                picked.score
+            ));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+const INPUT: &str = "input/day04.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--explain") {
+        println!("{}", explain(input)?);
+    }
+    if let Some(policy) = TieBreak::from_args(&args)? {
+        println!("Ties are broken by {}", policy.describe());
+        println!("Answer for part 1: {}", first_win(input, policy)?.score);
+        println!("Answer for part 2: {}", last_win(input, policy)?.score);
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use aoc2021::{stream_file_blocks, test_helpers::create_line_file};
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use crate::get_draws;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+            22 13 17 11  0
+            8  2 23  4 24
+            21  9 14 16  7
+            6 10  3 18  5
+            1 12 20 15 19
+
+            3 15  0  2 22
+            9 18 13 17  5
+            19  8  7 25 23
+            20 11 10 24  4
+            14 21 16 12  6
+
+            14 21 17 24  4
+            10 16 15  9 19
+            18  8 23 26 20
+            22 11 13  6  5
+            2  0 12  3  7
+            "}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_read_draws() {
+        let (dir, file) = example_file();
+        let first = &stream_file_blocks(file).unwrap().next().unwrap()[0];
+        assert_eq!(
+            get_draws(first).unwrap(),
+            vec![
+                7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18, 20, 8,
+                19, 3, 26, 1
+            ]
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_read_bingo() {
+        let (dir, file) = example_file();
+        let bingo_str = stream_file_blocks(file).unwrap().nth(1).unwrap();
+        let bingo = BingoField::try_from(bingo_str).unwrap();
+        assert_eq!(
+            bingo.content.iter().map(|(n, _)| *n).collect::<Vec<_>>(),
+            vec![
+                22, 13, 17, 11, 0, 8, 2, 23, 4, 24, 21, 9, 14, 16, 7, 6, 10, 3, 18, 5, 1, 12, 20,
+                15, 19
+            ]
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_score_bingo() {
+        let (dir, file) = example_file();
+        let mut blocks = stream_file_blocks(file).unwrap();
+        let draws = get_draws(&blocks.next().unwrap()[0]).unwrap();
+        let bingo_str = blocks.nth(2).unwrap();
+        let mut game = BingoGame::new(vec![BingoField::try_from(bingo_str).unwrap()]);
+        assert_eq!(
+            game.play(draws).collect::<Vec<_>>(),
+            vec![Win {
+                board: 0,
+                draw: 11,
+                score: 4512
+            }]
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_play() {
+        let (dir, file) = example_file();
+        let (mut game, draws) = read_game(file).unwrap();
+        let wins = game.play(draws.iter().copied()).collect::<Vec<_>>();
+        assert_eq!(
+            wins.iter()
+                .map(|win| (win.board, win.draw))
+                .collect::<Vec<_>>(),
+            vec![(2, 11), (0, 13), (1, 14)]
+        );
+        assert_eq!(wins[2].score, 1924);
+        // All boards have won, further draws change nothing
+        assert_eq!(game.play(draws).next(), None);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_ties() {
+        let lines = [indoc! {"
+            1,2,3,4
+
+            1 2
+            5 6
+
+            2 1
+            7 8
+
+            3 4
+            9 9
+
+            4 3
+            9 9
+            "}];
+        let (dir, file) = create_line_file(lines.iter(), None);
+        let (mut game, draws) = read_game(&file).unwrap();
+        let rounds = game.play_rounds(draws);
+        let boards = |round: &Vec<Win>| round.iter().map(|win| (win.board, win.draw)).collect_vec();
+        assert_eq!(
+            rounds.iter().map(boards).collect_vec(),
+            vec![vec![(0, 1), (1, 1)], vec![(2, 3), (3, 3)]]
+        );
+        // The first round is known without drawing any further numbers
+        let (mut game, draws) = read_game(&file).unwrap();
+        let first_round = game.first_round(
+            draws[..2]
+                .iter()
+                .copied()
+                .chain(iter::from_fn(|| panic!("Drew past the first win"))),
+        );
+        assert_eq!(first_round, rounds[0]);
+        assert_eq!(first_win(&file, TieBreak::HighestScore).unwrap().score, 30);
+        assert_eq!(first_win(&file, TieBreak::LowestScore).unwrap().score, 22);
+        assert_eq!(first_win(&file, TieBreak::LastBoard).unwrap().board, 1);
+        assert_eq!(last_win(&file, TieBreak::FirstBoard).unwrap().board, 2);
+        assert_eq!(part1(&file).unwrap(), 30);
+        assert_eq!(part2(&file).unwrap(), 72);
+        assert_eq!(TieBreak::HighestScore.pick(&[]), None);
+        let args = |args: &[&str]| {
+            TieBreak::from_args(&args.iter().map(|arg| arg.to_string()).collect_vec())
+        };
+        assert_eq!(
+            args(&["--tie-break", "last"]).unwrap(),
+            Some(TieBreak::LastBoard)
+        );
+        assert_eq!(args(&["--explain"]).unwrap(), None);
+        assert!(args(&["--tie-break"]).is_err());
+        assert!(args(&["--tie-break", "random"]).is_err());
+
+        let explanation = explain(&file).unwrap();
+        assert_eq!(
+            explanation.lines().collect_vec(),
+            vec![
+                "draw 2 (2): board 0 wins with score 22",
+                "draw 2 (2): board 1 wins with score 30",
+                "warning: boards 0, 1 tie for the first win, the highest score counts: board 1 with score 30",
+                "draw 4 (4): board 2 wins with score 72",
+                "draw 4 (4): board 3 wins with score 72",
+                "warning: boards 2, 3 tie for the last win, the lowest score counts: board 2 with score 72",
+            ]
+        );
+        drop(dir);
+    }
+}
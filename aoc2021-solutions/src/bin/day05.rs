@@ -0,0 +1,471 @@
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{
+    bidirange::bidi_range,
+    error::InputError,
+    lenient,
+    parse::extract_ints,
+    try_stream_items_from_file,
+    vec2d::{IVec2D, NumVecParsingError, UVec2D},
+};
+use aoc2021::{Input, InputSource};
+use itertools::iproduct;
+use std::{collections::HashMap, str::FromStr};
+
+#[derive(Debug, PartialEq)]
+struct Line {
+    start: UVec2D,
+    end: UVec2D,
+}
+
+impl Line {
+    fn is_cardinal(&self) -> bool {
+        // A single point counts as cardinal, it is covered by part 1 already
+        self.start.x() == self.end.x() || self.start.y() == self.end.y()
+    }
+
+    fn iter_points(&self) -> Box<dyn Iterator<Item = UVec2D>> {
+        // Parsing made sure that both ends fit into an IVec2D
+        let start = IVec2D::try_from(self.start).unwrap();
+        let end = IVec2D::try_from(self.end).unwrap();
+        let x = bidi_range(start.x(), end.x());
+        let y = bidi_range(start.y(), end.y());
+        // All points lie between both ends, so they can't be negative
+        let to_point = |(x, y)| UVec2D::try_from(IVec2D::new(x, y)).unwrap();
+        if self.is_cardinal() {
+            Box::new(iproduct!(x, y).map(to_point))
+        } else {
+            Box::new(x.zip(y).map(to_point))
+        }
+    }
+}
+
+impl FromStr for Line {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x1, y1, x2, y2] = extract_ints(s).map_err(|e| match e {
+            NumVecParsingError::MissingValue => InputError::new(s, "start or end point is missing"),
+            e => InputError::new(s, format!("could not parse point: {}", e)),
+        })?;
+        let line = Line {
+            start: UVec2D::new(x1, y1),
+            end: UVec2D::new(x2, y2),
+        };
+        for point in [line.start, line.end] {
+            IVec2D::try_from(point)
+                .map_err(|e| InputError::new(s, format!("coordinate out of range: {}", e)))?;
+        }
+        Ok(line)
+    }
+}
+
+/// Which vents take part in the overlap count, `--lines cardinal|diagonal|all`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineFilter {
+    Cardinal,
+    Diagonal,
+    All,
+}
+
+impl LineFilter {
+    fn accepts(self, line: &Line) -> bool {
+        match self {
+            LineFilter::Cardinal => line.is_cardinal(),
+            LineFilter::Diagonal => !line.is_cardinal(),
+            LineFilter::All => true,
+        }
+    }
+}
+
+impl FromStr for LineFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cardinal" => Ok(LineFilter::Cardinal),
+            "diagonal" => Ok(LineFilter::Diagonal),
+            "all" => Ok(LineFilter::All),
+            _ => Err(anyhow!(
+                "Unknown line filter {:?}, expected cardinal, diagonal or all",
+                s
+            )),
+        }
+    }
+}
+
+/// The puzzle counts the points where at least two lines overlap.
+const MIN_OVERLAP: usize = 2;
+
+fn mark_overlaps<'a, F>(
+    lines: impl Iterator<Item = &'a Line>,
+    filter: F,
+) -> impl IntoIterator<Item = (UVec2D, usize)>
+where
+    F: Fn(&Line) -> bool,
+{
+    let mut map = HashMap::<UVec2D, usize>::new();
+    lines
+        .filter(|l| filter(l))
+        .flat_map(|l| l.iter_points())
+        .for_each(|p| *map.entry(p).or_insert(0) += 1);
+    map
+}
+
+/// Counts in a map of the covered points only, without the `parallel` feature and for the areas
+/// that are too large for the dense grids of `count_overlaps_parallel`.
+fn count_overlaps_hashed<F>(lines: &[Line], filter: F, min_lines: usize) -> usize
+where
+    F: Fn(&Line) -> bool,
+{
+    let overlaps = mark_overlaps(lines.iter(), filter);
+    overlaps
+        .into_iter()
+        .map(|t| t.1)
+        .filter(|c| *c >= min_lines)
+        .count()
+}
+
+/// The largest dense grid `count_overlaps_parallel` allocates per thread, in cells of one byte.
+#[cfg(feature = "parallel")]
+const MAX_GRID_CELLS: usize = 1 << 26;
+
+/// Rasterizes the lines on all cores, every thread into its own dense grid.
+/// The grids only count up to `min_lines`, since we just need to know where enough lines overlap.
+/// Vents spread over more than `MAX_GRID_CELLS` are counted by `count_overlaps_hashed` instead.
+#[cfg(feature = "parallel")]
+fn count_overlaps_parallel<F>(lines: &[Line], filter: F, min_lines: usize) -> usize
+where
+    F: Fn(&Line) -> bool + Sync,
+{
+    use rayon::prelude::*;
+
+    let cap = match u8::try_from(min_lines) {
+        Ok(cap) => cap,
+        Err(_) => return count_overlaps_hashed(lines, filter, min_lines),
+    };
+    let width = lines
+        .iter()
+        .map(|l| l.start.x().max(l.end.x()).saturating_add(1))
+        .max()
+        .unwrap_or(0);
+    let height = lines
+        .iter()
+        .map(|l| l.start.y().max(l.end.y()).saturating_add(1))
+        .max()
+        .unwrap_or(0);
+    match width.checked_mul(height) {
+        Some(cells) if cells <= MAX_GRID_CELLS => (),
+        _ => return count_overlaps_hashed(lines, filter, min_lines),
+    }
+    let empty_grid = || vec![0u8; width * height];
+    let grid = lines
+        .par_iter()
+        .filter(|line| filter(line))
+        .fold(empty_grid, |mut grid, line| {
+            for point in line.iter_points() {
+                let count = &mut grid[point.x() + point.y() * width];
+                *count = count.saturating_add(1).min(cap);
+            }
+            grid
+        })
+        .reduce(empty_grid, |mut merged, grid| {
+            for (count, other) in merged.iter_mut().zip(grid) {
+                *count = count.saturating_add(other).min(cap);
+            }
+            merged
+        });
+    grid.iter()
+        .filter(|count| usize::from(**count) >= min_lines)
+        .count()
+}
+
+/// The number of points covered by at least `min_lines` of the lines that pass the filter.
+#[cfg(feature = "parallel")]
+fn count_overlaps<F>(lines: &[Line], filter: F, min_lines: usize) -> usize
+where
+    F: Fn(&Line) -> bool + Sync,
+{
+    count_overlaps_parallel(lines, filter, min_lines)
+}
+
+/// The number of points covered by at least `min_lines` of the lines that pass the filter.
+#[cfg(not(feature = "parallel"))]
+fn count_overlaps<F>(lines: &[Line], filter: F, min_lines: usize) -> usize
+where
+    F: Fn(&Line) -> bool + Sync,
+{
+    count_overlaps_hashed(lines, filter, min_lines)
+}
+
+/// `--lines FILTER` and `--min-overlap N` for variants of the puzzle, `None` if neither is given.
+/// The filter defaults to all lines and the overlap to two lines.
+fn requested_variant(args: &[String]) -> Result<Option<(LineFilter, usize)>> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .map(|i| {
+                args.get(i + 1)
+                    .ok_or_else(|| anyhow!("{} needs a value", flag))
+            })
+            .transpose()
+    };
+    let filter = value("--lines")?;
+    let min_lines = value("--min-overlap")?;
+    if filter.is_none() && min_lines.is_none() {
+        return Ok(None);
+    }
+    let filter = filter.map_or(Ok(LineFilter::All), |filter| filter.parse())?;
+    let min_lines = min_lines.map_or(Ok(MIN_OVERLAP), |n| n.parse())?;
+    if min_lines == 0 {
+        return Err(anyhow!("--min-overlap needs at least one line"));
+    }
+    Ok(Some((filter, min_lines)))
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let lines = try_stream_items_from_file::<_, Line>(input)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(count_overlaps(&lines, Line::is_cardinal, MIN_OVERLAP))
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let lines = try_stream_items_from_file::<_, Line>(input)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(count_overlaps(&lines, |_| true, MIN_OVERLAP))
+}
+
+const INPUT: &str = "input/day05.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((filter, min_lines)) = requested_variant(&args)? {
+        let lines = try_stream_items_from_file::<_, Line>(input)?.collect::<Result<Vec<_>, _>>()?;
+        println!(
+            "Points covered by at least {} {:?} lines: {}",
+            min_lines,
+            filter,
+            count_overlaps(&lines, |line| filter.accepts(line), min_lines)
+        );
+        return Ok(());
+    }
+    if lenient::requested() {
+        let lines = lenient::report(lenient::read_items::<_, Line>(input)?);
+        let cardinal = |line: &Line| LineFilter::Cardinal.accepts(line);
+        println!(
+            "Answer for part 1: {}",
+            count_overlaps(&lines, cardinal, MIN_OVERLAP)
+        );
+        println!(
+            "Answer for part 2: {}",
+            count_overlaps(&lines, |_| true, MIN_OVERLAP)
+        );
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::{stream_items_from_file, test_helpers::create_line_file};
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                0,9 -> 5,9
+                8,0 -> 0,8
+                9,4 -> 3,4
+                2,2 -> 2,1
+                7,0 -> 7,4
+                6,4 -> 2,0
+                0,9 -> 2,9
+                3,4 -> 1,4
+                0,0 -> 8,8
+                5,5 -> 8,2
+            "}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_line_conversion() {
+        let (dir, file) = example_file();
+        let first = stream_items_from_file::<_, Line>(file)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert_eq!(
+            first,
+            Line {
+                start: UVec2D::new(0, 9),
+                end: UVec2D::new(5, 9)
+            }
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_line_filters() {
+        let (dir, file) = example_file();
+        let lines = stream_items_from_file::<_, Line>(file)
+            .unwrap()
+            .collect::<Vec<_>>();
+        drop(dir);
+        let count = |filter: &str, min_lines| {
+            let filter: LineFilter = filter.parse().unwrap();
+            count_overlaps(&lines, |line| filter.accepts(line), min_lines)
+        };
+        assert_eq!(count("cardinal", 2), 5);
+        assert_eq!(count("all", 2), 12);
+        assert_eq!(count("diagonal", 2), 4);
+        assert_eq!(count("all", 3), 2);
+        assert_eq!(count("all", 1), 39);
+        assert_eq!(count_overlaps(&lines, |line| line.start.x() == 0, 2), 3);
+        assert!("sideways".parse::<LineFilter>().is_err());
+
+        let point = "8,4 -> 8,4".parse::<Line>().unwrap();
+        assert!(point.is_cardinal());
+        assert_eq!(point.iter_points().collect::<Vec<_>>(), vec![UVec2D::new(8, 4)]);
+        let with_point = lines.into_iter().chain([point]).collect::<Vec<_>>();
+        let cardinal = |line: &Line| LineFilter::Cardinal.accepts(line);
+        assert_eq!(count_overlaps(&with_point, cardinal, 2), 6);
+        let diagonal = |line: &Line| LineFilter::Diagonal.accepts(line);
+        assert_eq!(count_overlaps(&with_point, diagonal, 2), 4);
+
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(requested_variant(&args(&[])).unwrap(), None);
+        assert_eq!(
+            requested_variant(&args(&["--lines", "diagonal"])).unwrap(),
+            Some((LineFilter::Diagonal, 2))
+        );
+        assert_eq!(
+            requested_variant(&args(&["--min-overlap", "3"])).unwrap(),
+            Some((LineFilter::All, 3))
+        );
+        assert!(requested_variant(&args(&["--min-overlap", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_vec_conversions() {
+        assert_eq!(
+            UVec2D::try_from(IVec2D::new(3, 4)).unwrap(),
+            UVec2D::new(3, 4)
+        );
+        assert!(UVec2D::try_from(IVec2D::new(3, -4)).is_err());
+        assert_eq!(
+            IVec2D::try_from(UVec2D::new(3, 4)).unwrap(),
+            IVec2D::new(3, 4)
+        );
+        assert!(IVec2D::try_from(UVec2D::new(usize::MAX, 0)).is_err());
+        assert_eq!(
+            UVec2D::new(3, 4).checked_add_signed(IVec2D::new(-3, 2)),
+            Some(UVec2D::new(0, 6))
+        );
+        assert_eq!(
+            UVec2D::new(3, 4).checked_add_signed(IVec2D::new(-4, 0)),
+            None
+        );
+        assert!(format!("{},0 -> 0,0", usize::MAX).parse::<Line>().is_err());
+    }
+
+    #[test]
+    fn test_bidi_range() {
+        use aoc2021::bidirange::bidi_range_of;
+        use itertools::Itertools;
+
+        assert_eq!(bidi_range(3, 0).collect_vec(), vec![3, 2, 1, 0]);
+        assert_eq!(bidi_range(-1, 1).collect_vec(), vec![-1, 0, 1]);
+        assert_eq!(bidi_range(5, 5).collect_vec(), vec![5]);
+        assert_eq!(bidi_range_of(0u8, 10, 3).collect_vec(), vec![0, 3, 6, 9]);
+        assert_eq!(bidi_range_of(2u8, 0, 1).collect_vec(), vec![2, 1, 0]);
+        assert_eq!(bidi_range_of(253u8, 255, 2).collect_vec(), vec![253, 255]);
+        assert_eq!(bidi_range_of('e', 'a', 2).collect::<String>(), "eca");
+
+        let floats = bidi_range_of(0.0, 1.0, 0.1).collect_vec();
+        assert_eq!(floats.len(), 11);
+        assert_eq!(floats.last(), Some(&1.0));
+        let floats = bidi_range_of(1.0f32, -1.0, 0.3).collect_vec();
+        assert_eq!(floats.len(), 7);
+        assert!(floats.iter().all(|f| *f >= -1.0));
+    }
+
+    /// Random horizontal, vertical and diagonal vents within a `size` x `size` area.
+    #[cfg(feature = "parallel")]
+    fn synthetic_vents(count: usize, size: usize, mut seed: u64) -> Vec<Line> {
+        let mut next = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound as u64) as usize
+        };
+        (0..count)
+            .map(|_| {
+                let start = IVec2D::new(next(size) as isize, next(size) as isize);
+                let direction = [(1, 0), (0, 1), (1, 1), (1, -1)][next(4)];
+                let direction = IVec2D::new(direction.0, direction.1);
+                // Shorten the line instead of clamping its end, so diagonals stay diagonal
+                let room = |pos: isize, d: isize| match d {
+                    1 => size as isize - 1 - pos,
+                    -1 => pos,
+                    _ => isize::MAX,
+                };
+                let length = (next(size / 4) as isize + 1)
+                    .min(room(start.x(), direction.x()))
+                    .min(room(start.y(), direction.y()));
+                Line {
+                    start: UVec2D::try_from(start).unwrap(),
+                    end: UVec2D::try_from(start + direction * length).unwrap(),
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_overlaps() {
+        let lines = synthetic_vents(500, 200, 0x0505);
+        for filter in [LineFilter::Cardinal, LineFilter::Diagonal, LineFilter::All] {
+            for min_lines in [1, 2, 3, 300] {
+                let filter = |line: &Line| filter.accepts(line);
+                assert_eq!(
+                    count_overlaps_parallel(&lines, filter, min_lines),
+                    count_overlaps_hashed(&lines, filter, min_lines)
+                );
+            }
+        }
+        assert_eq!(count_overlaps_parallel(&[], |_| true, 2), 0);
+
+        // Far too large for a dense grid per thread
+        let far = "0,0 -> 3,3\n1000000000,5 -> 1000000000,0\n1000000000,2 -> 999999998,2"
+            .lines()
+            .map(|line| line.parse::<Line>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(count_overlaps_parallel(&far, |_| true, 2), 1);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[ignore]
+    fn bench_parallel_overlaps() {
+        let lines = synthetic_vents(20_000, 2_000, 0x2021);
+        let now = std::time::Instant::now();
+        let hashed = count_overlaps_hashed(&lines, |_| true, MIN_OVERLAP);
+        let hashed_time = now.elapsed();
+        let now = std::time::Instant::now();
+        let parallel = count_overlaps_parallel(&lines, |_| true, MIN_OVERLAP);
+        let parallel_time = now.elapsed();
+        println!(
+            "{} overlaps, hashed {:?}, parallel {:?}",
+            hashed, hashed_time, parallel_time
+        );
+        assert_eq!(hashed, parallel);
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+#[cfg(any(test, not(feature = "fastpaths")))]
+use aoc2021::simulation::iterate_n;
+use aoc2021::stream_csv_items;
+use itertools::Itertools;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+};
+
+type Population = [usize; 9];
+
+trait PopulationSim {
+    fn step(&mut self);
+    fn population_size(&self) -> usize;
+}
+
+fn parse_population<R: Read>(input: R) -> Result<Population> {
+    let mut output = Population::default();
+    for individual in stream_csv_items::<_, usize>(input) {
+        let individual = individual?;
+        *output
+            .get_mut(individual)
+            .ok_or_else(|| anyhow!("Invalid timer {}", individual))? += 1;
+    }
+    Ok(output)
+}
+
+impl PopulationSim for Population {
+    fn step(&mut self) {
+        let spawns = self[0];
+        for age in 1..=8 {
+            self[age-1] = self[age];
+        }
+        self[6] += spawns;
+        self[8] = spawns;
+    }
+
+    fn population_size(&self) -> usize {
+        self.iter().sum()
+    }
+}
+
+#[cfg(any(test, not(feature = "fastpaths")))]
+fn run_simulation(population: &mut impl PopulationSim, steps: usize) -> usize{
+    let (population, _) = iterate_n(population, steps, |population, _| {
+        population.step();
+        population
+    });
+    population.population_size()
+}
+
+/// The population on every day from day 0 up to and including `days`
+fn simulate_series(mut population: Population, days: usize) -> impl Iterator<Item = Population> {
+    std::iter::once(population).chain((0..days).map(move |_| {
+        population.step();
+        population
+    }))
+}
+
+fn write_series_csv<W: Write>(
+    series: impl Iterator<Item = Population>,
+    mut out: W,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "day,total,{}",
+        (0..9).map(|timer| format!("timer{}", timer)).join(",")
+    )?;
+    for (day, population) in series.enumerate() {
+        writeln!(
+            out,
+            "{},{},{}",
+            day,
+            population.population_size(),
+            population.iter().join(",")
+        )?;
+    }
+    Ok(())
+}
+
+/// The population size after `days`.
+#[cfg(not(feature = "fastpaths"))]
+fn simulate(mut population: Population, days: usize) -> usize {
+    run_simulation(&mut population, days)
+}
+
+/// The population size after `days`, moving the start of the buckets instead of shifting them.
+#[cfg(feature = "fastpaths")]
+fn simulate(population: Population, days: usize) -> usize {
+    let buckets = population.map(|count| count as u64);
+    aoc2021::fastpaths::simulate_lanternfish(buckets, days) as usize
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    Ok(simulate(parse_population(input.open()?)?, 80))
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    Ok(simulate(parse_population(input.open()?)?, 256))
+}
+
+const INPUT: &str = "input/day06.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--dump-series", out] | ["--dump-series", out, "--days", _] => {
+            let days = args
+                .get(3)
+                .map(|days| days.parse())
+                .transpose()?
+                .unwrap_or(256);
+            let population = parse_population(input.open()?)?;
+            write_series_csv(
+                simulate_series(population, days),
+                BufWriter::new(File::create(out)?),
+            )?;
+            return Ok(());
+        }
+        _ => (),
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(["3,4,3,1,2"].iter(), None)
+    }
+
+    #[test]
+    fn test_simulation() {
+        let (dir, file) = example_file();
+        let mut population = parse_population(File::open(file).unwrap()).unwrap();
+        assert_eq!(run_simulation(&mut population, 18), 26);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_series() {
+        let (dir, file) = example_file();
+        let population = parse_population(File::open(file).unwrap()).unwrap();
+        let series = simulate_series(population, 18).collect::<Vec<_>>();
+        assert_eq!(series.len(), 19);
+        assert_eq!(series[0], population);
+        assert_eq!(series[1], [1, 1, 2, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(series[18].population_size(), 26);
+
+        let mut csv = Vec::new();
+        write_series_csv(simulate_series(population, 2), &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "day,total,timer0,timer1,timer2,timer3,timer4,timer5,timer6,timer7,timer8\n\
+             0,5,0,1,1,2,1,0,0,0,0\n\
+             1,5,1,1,2,1,0,0,0,0,0\n\
+             2,6,1,2,1,0,0,0,1,0,1\n"
+        );
+        drop(dir);
+    }
+
+    #[cfg(feature = "fastpaths")]
+    #[test]
+    fn test_fastpath_matches() {
+        use aoc2021::fastpaths::simulate_lanternfish;
+
+        let (dir, file) = example_file();
+        let population = parse_population(File::open(file).unwrap()).unwrap();
+        let buckets = population.map(|count| count as u64);
+        for days in [0, 1, 9, 18, 80, 256] {
+            let mut reference = population;
+            assert_eq!(
+                simulate_lanternfish(buckets, days),
+                run_simulation(&mut reference, days) as u64
+            );
+        }
+        drop(dir);
+    }
+}
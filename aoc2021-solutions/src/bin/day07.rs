@@ -0,0 +1,337 @@
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::stats::{self, Histogram};
+use aoc2021::stream_csv_items;
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    num::ParseIntError,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// The crabs grouped by position as `(position, count)` pairs sorted by position, so the costs
+/// are computed once per distinct position instead of once per crab.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Fleet(Vec<(usize, usize)>);
+
+impl Fleet {
+    /// Merges the weights of repeated positions and drops positions without crabs.
+    fn from_weights<I: IntoIterator<Item = (usize, usize)>>(weights: I) -> Self {
+        let mut counts = BTreeMap::new();
+        for (position, count) in weights {
+            if count > 0 {
+                *counts.entry(position).or_insert(0) += count;
+            }
+        }
+        Fleet(counts.into_iter().collect())
+    }
+
+    fn size(&self) -> usize {
+        self.0.iter().map(|&(_, count)| count).sum()
+    }
+
+    /// Every crab on its own, in the order of the positions.
+    fn crabs(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0
+            .iter()
+            .flat_map(|&(position, count)| std::iter::repeat_n(position, count))
+    }
+
+    fn fuel_to<F>(&self, target: usize, mut fuel_conversion: F) -> usize
+    where
+        F: FnMut(usize) -> usize,
+    {
+        self.0
+            .iter()
+            .map(|&(position, count)| count * fuel_conversion(abs_diff(position, target)))
+            .sum()
+    }
+
+    /// The position with at most half of the crabs on either side, the cheapest target with
+    /// constant fuel per step.
+    fn weighted_median(&self) -> Option<usize> {
+        let half = self.size().div_ceil(2);
+        let mut seen = 0;
+        self.0.iter().find_map(|&(position, count)| {
+            seen += count;
+            (seen >= half).then_some(position)
+        })
+    }
+
+    /// The cheapest target position and its fuel without scanning all positions.
+    /// With constant fuel per step that's the median, with growing fuel per step the optimum of the
+    /// continuous cost lies within half a step of the mean, so only the positions around it are tried.
+    fn optimal_alignment(&self, growing_fuel: bool) -> Option<(usize, usize)> {
+        if !growing_fuel {
+            let target = self.weighted_median()?;
+            return Some((target, self.fuel_to(target, |d| d)));
+        }
+        let (min, max) = (self.0.first()?.0, self.0.last()?.0);
+        let weighted_sum: usize = self
+            .0
+            .iter()
+            .map(|&(position, count)| position * count)
+            .sum();
+        let mean = weighted_sum / self.size();
+        (mean.saturating_sub(1).max(min)..=(mean + 2).min(max))
+            .map(|target| (target, self.fuel_to(target, gauss_fuel_conversion)))
+            .min_by_key(|&(_, fuel)| fuel)
+    }
+}
+
+/// A single crab's position or `POSITION*COUNT` for many crabs at the same position.
+struct Crabs {
+    position: usize,
+    count: usize,
+}
+
+impl FromStr for Crabs {
+    type Err = ParseIntError;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        Ok(match token.split_once('*') {
+            Some((position, count)) => Crabs {
+                position: position.parse()?,
+                count: count.parse()?,
+            },
+            None => Crabs {
+                position: token.parse()?,
+                count: 1,
+            },
+        })
+    }
+}
+
+/// Counts the crabs per position while reading, without holding every single crab in memory.
+fn parse_fleet<R: Read>(input: R) -> Result<Fleet> {
+    let weights = stream_csv_items(input).map_ok(|crabs: Crabs| (crabs.position, crabs.count));
+    Ok(itertools::process_results(weights, |weights| {
+        Fleet::from_weights(weights)
+    })?)
+}
+
+fn abs_diff(a: usize, b: usize) -> usize {
+    a.abs_diff(b)
+}
+
+fn gauss_fuel_conversion(raw: usize) -> usize {
+    raw * (raw + 1) / 2
+}
+
+// A simple structure mapping a final alignment position to the total amount of fuel
+// It might also be viable to only consider actually existing starting positions for
+// better space efficiency, but this was easier to implement.
+struct PositionFuelMap(Vec<usize>, usize);
+
+impl Index<usize> for PositionFuelMap {
+    type Output = usize;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index - self.1]
+    }
+}
+
+impl IndexMut<usize> for PositionFuelMap {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index - self.1]
+    }
+}
+
+fn calc_distances<F>(fleet: &Fleet, mut fuel_conversion: F) -> PositionFuelMap
+where
+    F: FnMut(usize) -> usize,
+{
+    let (min, max) = stats::minmax(fleet.0.iter().map(|&(position, _)| position)).unwrap();
+    let mut output = PositionFuelMap(vec![0; max - min + 1], min);
+    for &(crab_position, count) in &fleet.0 {
+        for target_position in min..=max {
+            output[target_position] +=
+                count * fuel_conversion(abs_diff(crab_position, target_position));
+        }
+    }
+
+    output
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let fleet = parse_fleet(input.open()?)?;
+    let (_, fuel) = fleet
+        .optimal_alignment(false)
+        .ok_or_else(|| anyhow!("There are no crabs"))?;
+    Ok(fuel)
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let fleet = parse_fleet(input.open()?)?;
+    let (_, fuel) = fleet
+        .optimal_alignment(true)
+        .ok_or_else(|| anyhow!("There are no crabs"))?;
+    Ok(fuel)
+}
+
+/// The cheapest position to align at by scanning all positions and the fuel each crab needs to get there.
+fn best_alignment<F>(fleet: &Fleet, mut fuel_conversion: F) -> (usize, Vec<usize>)
+where
+    F: FnMut(usize) -> usize,
+{
+    let distances = calc_distances(fleet, &mut fuel_conversion);
+    let (offset, _) = distances
+        .0
+        .iter()
+        .enumerate()
+        .min_by_key(|&(_, fuel)| fuel)
+        .unwrap();
+    let target = offset + distances.1;
+    let fuel = fleet
+        .crabs()
+        .map(|crab| fuel_conversion(abs_diff(crab, target)))
+        .collect();
+    (target, fuel)
+}
+
+/// How the fuel is distributed over the crabs at the best position of each part, for `--explain`.
+fn explain<I: Input>(input: I) -> Result<String> {
+    let crabs = parse_fleet(input.open()?)?;
+    let mut explanation = String::new();
+    for (part, fuel_conversion) in [
+        (1, (|d| d) as fn(usize) -> usize),
+        (2, gauss_fuel_conversion),
+    ] {
+        let (target, fuel) = best_alignment(&crabs, fuel_conversion);
+        explanation += &format!(
+            "Part {}: align at {} using {} fuel, median per crab {}, 90th percentile {}\n",
+            part,
+            target,
+            fuel.iter().sum::<usize>(),
+            stats::percentile(&fuel, 50.0).unwrap(),
+            stats::percentile(&fuel, 90.0).unwrap()
+        );
+        explanation += &Histogram::of(&fuel, 10).render(40);
+    }
+    Ok(explanation)
+}
+
+const INPUT: &str = "input/day07.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if std::env::args().skip(1).any(|arg| arg == "--explain") {
+        print!("{}", explain(input)?);
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(["16,1,2,0,4,2,7,1,2,14"].iter(), None)
+    }
+
+    #[test]
+    fn test_parse() {
+        let (dir, file) = example_file();
+        let crabs = parse_fleet(file.open().unwrap()).unwrap();
+        assert_eq!(
+            crabs,
+            Fleet(vec![
+                (0, 1),
+                (1, 2),
+                (2, 3),
+                (4, 1),
+                (7, 1),
+                (14, 1),
+                (16, 1)
+            ])
+        );
+        assert_eq!(
+            parse_fleet("0,1*2,\n2*3, 4,7,14,16,\n".as_bytes()).unwrap(),
+            crabs
+        );
+        assert_eq!(crabs.size(), 10);
+        assert!(parse_fleet("1,2*x".as_bytes()).is_err());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_distances_p1() {
+        let (dir, file) = example_file();
+        let crabs = parse_fleet(file.open().unwrap()).unwrap();
+        let distances = calc_distances(&crabs, |d| d);
+        assert_eq!(distances[2], 37);
+        assert_eq!(distances[1], 41);
+        assert_eq!(distances[3], 39);
+        assert_eq!(distances[10], 71);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_best_alignment() {
+        let (dir, file) = example_file();
+        let crabs = parse_fleet(file.open().unwrap()).unwrap();
+        let (target, fuel) = best_alignment(&crabs, |d| d);
+        assert_eq!(target, 2);
+        assert_eq!(fuel, vec![2, 1, 1, 0, 0, 0, 2, 5, 12, 14]);
+        let (target, fuel) = best_alignment(&crabs, gauss_fuel_conversion);
+        assert_eq!((target, fuel.iter().sum::<usize>()), (5, 168));
+
+        let explanation = explain(&file).unwrap();
+        let lines = explanation.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "Part 1: align at 2 using 37 fuel, median per crab 1, 90th percentile 12"
+        );
+        assert_eq!(
+            &lines[1..3],
+            [
+                "  0..1 | ######################################## 5",
+                "  2..3 | ################                         2",
+            ]
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_weighted_fleets() {
+        let fleet = Fleet::from_weights([16, 1, 2, 0, 4, 2, 7, 1, 2, 14].map(|crab| (crab, 1)));
+        assert_eq!(fleet.optimal_alignment(false), Some((2, 37)));
+        assert_eq!(fleet.optimal_alignment(true), Some((5, 168)));
+        assert_eq!(Fleet::default().optimal_alignment(true), None);
+
+        let weighted = Fleet::from_weights([(3, 2), (0, 0), (10, 1), (3, 1)]);
+        assert_eq!(weighted, Fleet(vec![(3, 3), (10, 1)]));
+        assert_eq!(weighted.crabs().collect::<Vec<_>>(), vec![3, 3, 3, 10]);
+        assert_eq!(weighted.optimal_alignment(false), Some((3, 7)));
+
+        // A large synthetic fleet with few distinct positions, the analytic optimum has to match
+        // the exhaustive scan over all positions
+        let mut seed = 0x0707u64;
+        let fleet = Fleet::from_weights((0..2000).map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            ((seed % 1000) as usize, (seed >> 32) as usize % 1_000_000)
+        }));
+        assert!(fleet.size() > 100_000_000);
+        for (growing_fuel, fuel_conversion) in [
+            (false, (|d| d) as fn(usize) -> usize),
+            (true, gauss_fuel_conversion),
+        ] {
+            let scanned = calc_distances(&fleet, fuel_conversion);
+            let (target, fuel) = fleet.optimal_alignment(growing_fuel).unwrap();
+            assert_eq!(fuel, *scanned.0.iter().min().unwrap());
+            assert_eq!(scanned[target], fuel);
+        }
+    }
+}
@@ -0,0 +1,600 @@
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::{stats, stream_items_from_file};
+use itertools::Itertools;
+use std::{
+    ops::{BitAnd, RangeInclusive, Sub},
+    str::FromStr,
+};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone)]
+struct SignalPattern([bool; 7]);
+
+#[derive(Error, Debug)]
+enum SignalPatternStrError {
+    #[error("invalid character in signal: {0}")]
+    InvalidCharacter(u8),
+}
+
+impl FromStr for SignalPattern {
+    type Err = SignalPatternStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::default();
+        for signal in s.as_bytes() {
+            *result
+                .0
+                .get_mut((signal - b'a') as usize)
+                .ok_or(SignalPatternStrError::InvalidCharacter(*signal))? = true;
+        }
+        Ok(result)
+    }
+}
+
+impl BitAnd for &SignalPattern {
+    type Output = SignalPattern;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut output = SignalPattern::default();
+        for (i, v) in self
+            .0
+            .iter()
+            .zip(rhs.0.iter())
+            .map(|(&l, &r)| l && r)
+            .enumerate()
+        {
+            output.0[i] = v;
+        }
+        output
+    }
+}
+
+impl Sub for &SignalPattern {
+    type Output = SignalPattern;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut output = SignalPattern::default();
+        for (i, v) in self
+            .0
+            .iter()
+            .zip(rhs.0.iter())
+            .map(|(&l, &r)| l && !r)
+            .enumerate()
+        {
+            output.0[i] = v;
+        }
+        output
+    }
+}
+
+#[derive(Debug, Default)]
+struct BaseStore {
+    one: SignalPattern,
+    four: SignalPattern,
+    seven: SignalPattern,
+    eight: SignalPattern,
+}
+
+impl BaseStore {
+    fn from_vec(input: &[SignalPattern]) -> BaseStore {
+        let mut output = BaseStore::default();
+        for pattern in input {
+            match pattern.identify_simple() {
+                Some(1) => output.one = pattern.clone(),
+                Some(4) => output.four = pattern.clone(),
+                Some(7) => output.seven = pattern.clone(),
+                Some(8) => output.eight = pattern.clone(),
+                _ => (),
+            }
+        }
+
+        output
+    }
+}
+
+impl SignalPattern {
+    /// The pattern as a bit set, bit `i` stands for wire `'a' + i`.
+    fn mask(&self) -> u8 {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, &on)| on)
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    fn count(&self) -> usize {
+        self.0.iter().filter(|&&s| s).count()
+    }
+
+    fn identify_simple(&self) -> Option<usize> {
+        match self.count() {
+            2 => Some(1),
+            3 => Some(7),
+            4 => Some(4),
+            7 => Some(8),
+            _ => None,
+        }
+    }
+
+    fn identify_deduce(&self, base: &BaseStore) -> usize {
+        self.identify_simple()
+            .unwrap_or_else(|| match self.count() {
+                6 => match (self & &base.one).count() {
+                    2 => match (self & &base.four).count() {
+                        4 => 9,
+                        3 => 0,
+                        _ => panic!("Invalid intersection"),
+                    },
+                    1 => 6,
+                    _ => panic!("Invalid intersection"),
+                },
+                5 => match (self & &(&base.four - &base.one)).count() {
+                    2 => 5,
+                    1 => match (self & &base.one).count() {
+                        1 => 2,
+                        2 => 3,
+                        _ => panic!("Invalid intersection")
+                    },
+                    _ => panic!("Invalid intersection"),
+                },
+                v => panic!("Unexpected count: {}", v)
+            })
+    }
+}
+
+fn parse_line(line: impl AsRef<str>) -> (Vec<SignalPattern>, Vec<SignalPattern>) {
+    let mut patterns = line.as_ref().split('|').map(|s| {
+        s.split(' ')
+            .filter(|s| !s.is_empty())
+            .map(|signal| signal.parse::<SignalPattern>())
+            .collect::<Result<_, _>>()
+            .expect("Error in pattern")
+    });
+    (
+        patterns.next().expect("Missing patterns"),
+        patterns.next().expect("Missing examples"),
+    )
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    Ok(stream_items_from_file(input)?
+        .map(|l: String| parse_line(l))
+        .map(|(_, example)| example.iter().filter_map(|p| p.identify_simple()).count())
+        .sum())
+}
+
+fn decode_line(examples: &[SignalPattern], output: &[SignalPattern]) -> usize {
+    decode_digits(examples, output)
+        .into_iter()
+        .reduce(|acc, v| (acc * 10) + v)
+        .expect("Empty output is not allowed")
+}
+
+fn decode_digits(examples: &[SignalPattern], output: &[SignalPattern]) -> Vec<usize> {
+    let base = BaseStore::from_vec(examples);
+    output
+        .iter()
+        .map(|pattern| pattern.identify_deduce(&base))
+        .collect()
+}
+
+/// Lit segments of every digit as bit sets, bit `i` stands for segment `'a' + i` of the intact display.
+const DIGIT_SEGMENTS: [u8; 10] = [
+    0b1110111, 0b0100100, 0b1011101, 0b1101101, 0b0101110, 0b1101011, 0b1111011, 0b0100101,
+    0b1111111, 0b1101111,
+];
+
+const ALL_SEGMENTS: u8 = 0b1111111;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+enum WiringError {
+    #[error("no wiring matches the patterns")]
+    NoWiring,
+    #[error("the patterns allow {0} different wirings")]
+    Ambiguous(usize),
+}
+
+/// Maps every wire to the segment it is connected to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Wiring([u8; 7]);
+
+impl Wiring {
+    fn segments(&self, mask: u8) -> u8 {
+        (0..7)
+            .filter(|wire| mask & 1 << wire != 0)
+            .fold(0, |segments, wire| segments | self.0[wire])
+    }
+
+    fn digit(&self, pattern: &SignalPattern) -> Option<usize> {
+        let segments = self.segments(pattern.mask());
+        DIGIT_SEGMENTS.iter().position(|&digit| digit == segments)
+    }
+}
+
+/// Narrows down the candidate segments of every wire until nothing changes anymore:
+/// The wires of a pattern must light one of the digits with the same number of segments,
+/// and the wires outside of it must not light any segment of that digit.
+fn propagate(candidates: &mut [u8; 7], patterns: &[u8]) -> Result<(), WiringError> {
+    loop {
+        let before = *candidates;
+        for &pattern in patterns {
+            let (inside, outside) = DIGIT_SEGMENTS
+                .iter()
+                .filter(|digit| digit.count_ones() == pattern.count_ones())
+                .filter(|&&digit| {
+                    (0..7).all(|wire| {
+                        let allowed = if pattern & 1 << wire != 0 {
+                            digit
+                        } else {
+                            ALL_SEGMENTS & !digit
+                        };
+                        candidates[wire] & allowed != 0
+                    })
+                })
+                .fold((0, 0), |(inside, outside), &digit| {
+                    (inside | digit, outside | (ALL_SEGMENTS & !digit))
+                });
+            for (wire, candidate) in candidates.iter_mut().enumerate() {
+                *candidate &= if pattern & 1 << wire != 0 {
+                    inside
+                } else {
+                    outside
+                };
+            }
+        }
+        // A segment that is certain for one wire can't belong to any other wire
+        for wire in 0..7 {
+            if candidates[wire].count_ones() == 1 {
+                let segment = candidates[wire];
+                for (other, candidate) in candidates.iter_mut().enumerate() {
+                    if other != wire {
+                        *candidate &= !segment;
+                    }
+                }
+            }
+        }
+        if candidates.contains(&0) {
+            return Err(WiringError::NoWiring);
+        }
+        if *candidates == before {
+            return Ok(());
+        }
+    }
+}
+
+/// Tries the remaining candidates of the wires from `wire` on and collects all consistent wirings.
+fn search_wirings(
+    candidates: &[u8; 7],
+    patterns: &[u8],
+    wiring: &mut Wiring,
+    wire: usize,
+    used: u8,
+    found: &mut Vec<Wiring>,
+) {
+    if wire == 7 {
+        if patterns
+            .iter()
+            .all(|&pattern| DIGIT_SEGMENTS.contains(&wiring.segments(pattern)))
+        {
+            found.push(*wiring);
+        }
+        return;
+    }
+    for segment in (0..7).map(|i| 1 << i) {
+        if candidates[wire] & segment != 0 && used & segment == 0 {
+            wiring.0[wire] = segment;
+            search_wirings(
+                candidates,
+                patterns,
+                wiring,
+                wire + 1,
+                used | segment,
+                found,
+            );
+        }
+    }
+}
+
+/// Finds the only wiring that turns every pattern into a digit.
+/// Unlike the deduction this needs neither all ten digits nor any particular ones among the patterns.
+fn solve_wiring(patterns: &[SignalPattern]) -> Result<Wiring, WiringError> {
+    let patterns = patterns
+        .iter()
+        .map(SignalPattern::mask)
+        .unique()
+        .collect_vec();
+    let mut candidates = [ALL_SEGMENTS; 7];
+    propagate(&mut candidates, &patterns)?;
+
+    let mut found = Vec::new();
+    search_wirings(
+        &candidates,
+        &patterns,
+        &mut Wiring([0; 7]),
+        0,
+        0,
+        &mut found,
+    );
+    match found[..] {
+        [] => Err(WiringError::NoWiring),
+        [wiring] => Ok(wiring),
+        _ => Err(WiringError::Ambiguous(found.len())),
+    }
+}
+
+/// Decodes the output using the patterns of both the examples and the output itself.
+fn decode_line_propagated(
+    examples: &[SignalPattern],
+    output: &[SignalPattern],
+) -> Result<usize, WiringError> {
+    Ok(decode_digits_propagated(examples, output)?
+        .into_iter()
+        .fold(0, |acc, v| acc * 10 + v))
+}
+
+fn decode_digits_propagated(
+    examples: &[SignalPattern],
+    output: &[SignalPattern],
+) -> Result<Vec<usize>, WiringError> {
+    let all_patterns = examples.iter().chain(output).cloned().collect_vec();
+    let wiring = solve_wiring(&all_patterns)?;
+    Ok(output
+        .iter()
+        .map(|pattern| {
+            wiring
+                .digit(pattern)
+                .expect("The wiring covers all patterns")
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    /// Tells the digits apart by their intersections with 1, 4, 7 and 8
+    Deduce,
+    /// Constraint propagation over the possible segments of each wire
+    Propagate,
+}
+
+impl FromStr for Algorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deduce" => Ok(Algorithm::Deduce),
+            "propagate" => Ok(Algorithm::Propagate),
+            _ => bail!("Unknown algorithm {}, use deduce or propagate", s),
+        }
+    }
+}
+
+fn solve_part2<I: Input>(input: I, algorithm: Algorithm) -> Result<usize> {
+    stream_items_from_file(input)?
+        .map(|l: String| parse_line(l))
+        .map(|(ex, pat)| match algorithm {
+            Algorithm::Deduce => Ok(decode_line(&ex, &pat)),
+            Algorithm::Propagate => Ok(decode_line_propagated(&ex, &pat)?),
+        })
+        .sum()
+}
+
+/// The decoded output of a single display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DecodedDisplay {
+    /// Counted from 1 like the lines of the input
+    line: usize,
+    digits: Vec<usize>,
+    value: usize,
+    /// Whether the value lies within the range the caller expected
+    in_range: bool,
+}
+
+/// The decoded displays of an input together with how often each digit appears in their outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutputAnalysis {
+    displays: Vec<DecodedDisplay>,
+    digit_counts: [usize; 10],
+}
+
+impl OutputAnalysis {
+    fn sum(&self) -> usize {
+        self.displays.iter().map(|display| display.value).sum()
+    }
+
+    fn out_of_range(&self) -> impl Iterator<Item = &DecodedDisplay> {
+        self.displays.iter().filter(|display| !display.in_range)
+    }
+
+    fn report(&self) -> String {
+        let bars = (0..10)
+            .map(|digit| (digit.to_string(), self.digit_counts[digit]))
+            .collect_vec();
+        let flagged = self
+            .out_of_range()
+            .map(|display| format!("line {}: {:04}\n", display.line, display.value))
+            .collect::<String>();
+        format!(
+            concat!(
+                "Sum of the outputs: {}\nDigits in the outputs\n{}",
+                "{} displays outside of the expected range\n{}"
+            ),
+            self.sum(),
+            stats::bar_chart(&bars, 40),
+            self.out_of_range().count(),
+            flagged
+        )
+    }
+}
+
+/// Decodes every display and flags the ones whose value isn't within `expected`.
+fn analyze_outputs<I: Input>(
+    input: I,
+    algorithm: Algorithm,
+    expected: &RangeInclusive<usize>,
+) -> Result<OutputAnalysis> {
+    let mut digit_counts = [0; 10];
+    let displays = stream_items_from_file(input)?
+        .map(|l: String| parse_line(l))
+        .enumerate()
+        .map(|(i, (ex, pat))| {
+            let digits = match algorithm {
+                Algorithm::Deduce => decode_digits(&ex, &pat),
+                Algorithm::Propagate => decode_digits_propagated(&ex, &pat)?,
+            };
+            digits.iter().for_each(|&digit| digit_counts[digit] += 1);
+            let value = digits.iter().fold(0, |acc, v| acc * 10 + v);
+            Ok(DecodedDisplay {
+                line: i + 1,
+                digits,
+                value,
+                in_range: expected.contains(&value),
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(OutputAnalysis {
+        displays,
+        digit_counts,
+    })
+}
+
+/// Parses a range of expected display values like `1000-9999`.
+fn parse_expected(s: &str) -> Result<RangeInclusive<usize>> {
+    match s.split_once('-') {
+        Some((min, max)) => Ok(min.parse()?..=max.parse()?),
+        None => bail!("Expected a range like 1000-9999 but got {}", s),
+    }
+}
+
+/// The `--algo` and `--analyze` flags, every flag needs a value.
+fn parse_args<I: IntoIterator<Item = String>>(
+    args: I,
+) -> Result<(Algorithm, Option<RangeInclusive<usize>>)> {
+    let mut algorithm = Algorithm::Deduce;
+    let mut expected = None;
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", flag));
+        match flag.as_str() {
+            "--algo" => algorithm = value()?.parse()?,
+            "--analyze" => expected = Some(parse_expected(&value()?)?),
+            _ => bail!("Unknown argument {}", flag),
+        }
+    }
+    Ok((algorithm, expected))
+}
+
+const INPUT: &str = "input/day08.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let (algorithm, expected) = parse_args(std::env::args().skip(1))?;
+    if let Some(expected) = expected {
+        print!("{}", analyze_outputs(input, algorithm, &expected)?.report());
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!(
+        "Answer for part 2: {}",
+        measure("part 2", || solve_part2(input, algorithm))?
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+            edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+            fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+            fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+            aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+            fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+            dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+            bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+            egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+            gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_propagation() {
+        let (dir, file) = example_file();
+        assert_eq!(solve_part2(file, Algorithm::Propagate).unwrap(), 61229);
+        drop(dir);
+
+        let patterns =
+            |s: &str| -> Vec<SignalPattern> { s.split(' ').map(|p| p.parse().unwrap()).collect() };
+        // Without 1 and 4 among the examples the deduction has nothing to work with
+        let examples = patterns("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb cagedb");
+        let output = patterns("cdfeb fcadb cdfeb cdbaf");
+        assert_eq!(decode_line_propagated(&examples, &output), Ok(5353));
+
+        assert_eq!(
+            decode_line_propagated(&patterns("ab"), &patterns("ab")),
+            Err(WiringError::Ambiguous(240))
+        );
+        assert_eq!(
+            decode_line_propagated(&patterns("abcdef"), &patterns("a")),
+            Err(WiringError::NoWiring)
+        );
+        assert!("magic".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_output_analysis() {
+        let (dir, file) = example_file();
+        for algorithm in [Algorithm::Deduce, Algorithm::Propagate] {
+            let analysis = analyze_outputs(&file, algorithm, &(1000..=8999)).unwrap();
+            assert_eq!(analysis.sum(), 61229);
+            assert_eq!(analysis.displays.len(), 10);
+            assert_eq!(
+                analysis.displays[0],
+                DecodedDisplay {
+                    line: 1,
+                    digits: vec![8, 3, 9, 4],
+                    value: 8394,
+                    in_range: true,
+                }
+            );
+            assert_eq!(analysis.digit_counts.iter().sum::<usize>(), 40);
+            assert_eq!(analysis.digit_counts[1], 8);
+            assert_eq!(
+                analysis.out_of_range().map(|d| (d.line, d.value)).collect_vec(),
+                vec![(2, 9781), (4, 9361)]
+            );
+            let report = analysis.report();
+            assert!(report.starts_with("Sum of the outputs: 61229\nDigits in the outputs\n0 | "));
+            assert!(report.contains("2 displays outside of the expected range\nline 2: 9781\n"));
+        }
+        assert_eq!(parse_expected("0-9999").unwrap(), 0..=9999);
+        assert!(parse_expected("9999").is_err());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(args(&[]).unwrap(), (Algorithm::Deduce, None));
+        assert_eq!(
+            args(&["--analyze", "1-9", "--algo", "propagate"]).unwrap(),
+            (Algorithm::Propagate, Some(1..=9))
+        );
+        assert_eq!(args(&["--algo"]).unwrap_err().to_string(), "--algo needs a value");
+        assert!(args(&["--algo", "deduce", "--analyze"]).is_err());
+        assert!(args(&["--artifacts", "out"]).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::stats::{self, Histogram};
+use aoc2021::{field2d::Field2D, stream_grid_from_file};
+use std::collections::HashSet;
+
+#[derive(Debug)]
+struct Heightmap(Field2D<u32>);
+
+impl Heightmap {
+    fn read<I: Input>(input: I) -> Result<Self> {
+        let field = stream_grid_from_file(input, |c| c.to_digit(10).ok_or("not a digit"))?;
+        Ok(Heightmap(field))
+    }
+
+    fn is_low_point(&self, x: usize, y: usize) -> bool {
+        let v = self.0[(x, y)];
+        self.0.neighbors(x, y).all(|pos| self.0[pos] > v)
+    }
+
+    fn search_low_points<'a>(&'a self) -> impl Iterator<Item = (usize, usize)> + 'a {
+        (0..self.0.width())
+            .flat_map(move |x| {
+                (0..self.0.height()).filter_map(move |y| {
+                    if self.is_low_point(x, y) {
+                        Some((x, y))
+                    } else {
+                        None
+                    }
+                })
+            })
+    }
+
+    fn basin_size(&self, x: usize, y: usize) -> usize {
+        let mut to_visit = vec![(x, y)];
+        let mut visited = HashSet::from([(x, y)]);
+
+        while let Some((cx, cy)) = to_visit.pop() {
+            for neighbor in self.0.neighbors_filtered(cx, cy, |&height| height < 9) {
+                if visited.insert(neighbor) {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+
+        visited.len()
+    }
+
+    fn basin_sizes(&self) -> Vec<usize> {
+        self.search_low_points().map(|(x, y)| self.basin_size(x, y)).collect()
+    }
+}
+
+fn part1<I: Input>(input: I) -> Result<u32> {
+    let map = Heightmap::read(input)?;
+    Ok(map.search_low_points().map(|(x, y)| map.0[(x, y)] + 1).sum())
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let map = Heightmap::read(input)?;
+    Ok(stats::largest(map.basin_sizes(), 3).iter().product())
+}
+
+/// The distribution of the basin sizes, for `--explain`.
+fn explain<I: Input>(input: I) -> Result<String> {
+    let map = Heightmap::read(input)?;
+    let sizes = map.basin_sizes();
+    let (smallest, largest) = stats::minmax(sizes.iter().copied()).unwrap_or_default();
+    Ok(format!(
+        "{} basins of {} to {} locations, median {}, largest three {:?}\n{}",
+        sizes.len(),
+        smallest,
+        largest,
+        stats::percentile(&sizes, 50.0).unwrap_or_default(),
+        stats::largest(sizes.iter().copied(), 3),
+        Histogram::of(&sizes, 10).render(40)
+    ))
+}
+
+const INPUT: &str = "input/day09.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if std::env::args().skip(1).any(|arg| arg == "--explain") {
+        print!("{}", explain(input)?);
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, path::Path};
+
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use itertools::Itertools;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            2199943210
+            3987894921
+            9856789892
+            8767896789
+            9899965678"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_lowpoints() {
+        let (dir, file) = example_file();
+        let map = Heightmap::read(file).unwrap();
+        let lowpoints: HashSet<_> = map.search_low_points().collect();
+        assert_eq!(
+            lowpoints,
+            HashSet::from_iter(vec![(1, 0), (9, 0), (2, 2), (6, 4)].into_iter())
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_basins() {
+        let (dir, file) = example_file();
+        let map = Heightmap::read(file).unwrap();
+        drop(dir);
+        assert_eq!(map.basin_size(1, 0), 3);
+        assert_eq!(map.basin_size(9, 0), 9);
+        assert_eq!(map.basin_size(2, 2), 14);
+        assert_eq!(map.basin_size(6, 4), 9);
+        assert_eq!(stats::largest(map.basin_sizes(), 3), vec![14, 9, 9]);
+    }
+
+    #[test]
+    fn test_explain() {
+        let (dir, file) = example_file();
+        let explanation = explain(&file).unwrap();
+        drop(dir);
+        let lines = explanation.lines().collect_vec();
+        assert_eq!(
+            lines[0],
+            "4 basins of 3 to 14 locations, median 9, largest three [14, 9, 9]"
+        );
+        assert_eq!(lines.len(), 8);
+        assert!(lines[1].starts_with("  2..3 | ####################"));
+        assert!(lines[4].starts_with("  8..9 | ########################################"));
+    }
+
+    #[test]
+    fn test_neighborhoods() {
+        let field = Field2D::parse(["123", "456", "789"].iter(), |row| {
+            row.chars().map(|c| c.to_digit(10).unwrap()).collect_vec()
+        })
+        .unwrap();
+        let sorted = |neighbors: Vec<(usize, usize)>| neighbors.into_iter().sorted().collect_vec();
+
+        assert_eq!(sorted(field.neighbors(0, 0).collect()), vec![(0, 1), (1, 0)]);
+        assert_eq!(field.neighbors(1, 1).count(), 4);
+        assert_eq!(field.neighbors(2, 1).count(), 3);
+        assert_eq!(
+            sorted(field.neighbors_diag(0, 0).collect()),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+        assert_eq!(field.neighbors_diag(1, 1).count(), 8);
+        assert_eq!(field.neighbors_diag(1, 2).count(), 5);
+
+        // The filtered versions see the same positions, only the predicate decides
+        for (x, y) in (0..3).cartesian_product(0..3) {
+            assert!(field.neighbors_filtered(x, y, |_| true).eq(field.neighbors(x, y)));
+            assert!(field
+                .neighbors_diag_filtered(x, y, |_| true)
+                .eq(field.neighbors_diag(x, y)));
+        }
+        assert_eq!(
+            sorted(field.neighbors_filtered(1, 1, |&v| v % 2 == 0).collect()),
+            vec![(0, 1), (1, 0), (1, 2), (2, 1)]
+        );
+        assert_eq!(
+            sorted(field.neighbors_diag_filtered(1, 1, |&v| v > 6).collect()),
+            vec![(0, 2), (1, 2), (2, 2)]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::syntax::{Alphabet, DelimiterId, SyntaxError, Verdict};
+use aoc2021::{Input, InputSource};
+use itertools::{process_results, Itertools};
+use std::fs::File;
+use std::io::{prelude::*, BufReader, BufWriter};
+
+/// Checks the lines one at a time, so the memory needed does not grow with the size of the file.
+fn check_file<I: Input>(
+    alphabet: &Alphabet,
+    input: I,
+) -> Result<impl Iterator<Item = Result<Verdict>> + '_> {
+    let reader = BufReader::new(input.open()?);
+    Ok(alphabet.check_reader(reader).map(|verdict| Ok(verdict??)))
+}
+
+fn get_all_syntax_errors(
+    verdicts: impl IntoIterator<Item = Verdict>,
+) -> impl Iterator<Item = SyntaxError> {
+    verdicts.into_iter().filter_map(|verdict| match verdict {
+        Verdict::Corrupted(error) => Some(error),
+        _ => None,
+    })
+}
+
+fn get_all_incomplete_lines(
+    verdicts: impl IntoIterator<Item = Verdict>,
+) -> impl Iterator<Item = Vec<DelimiterId>> {
+    verdicts.into_iter().filter_map(|verdict| match verdict {
+        Verdict::Incomplete(missing) => Some(missing),
+        _ => None,
+    })
+}
+
+fn part1<I: Input>(input: I) -> Result<u64> {
+    let alphabet = Alphabet::default();
+    let verdicts = check_file(&alphabet, input)?;
+    process_results(verdicts, |verdicts| {
+        get_all_syntax_errors(verdicts)
+            .map(|e| alphabet.score_error(&e))
+            .sum()
+    })
+}
+
+fn part2<I: Input>(input: I) -> Result<u64> {
+    let alphabet = Alphabet::default();
+    let verdicts = check_file(&alphabet, input)?;
+    let mut scores = process_results(verdicts, |verdicts| {
+        get_all_incomplete_lines(verdicts)
+            .map(|missing| alphabet.score_completion(&missing))
+            .collect_vec()
+    })?;
+    scores.sort();
+    Ok(scores[scores.len() / 2])
+}
+
+/// Separates a corrupted line from the description of its error in a repaired file.
+const CORRUPTED_MARK: &str = "  # corrupted: ";
+
+/// The line with the delimiters it misses appended. Corrupted lines can't be fixed by appending,
+/// they are kept and flagged with their first error instead.
+fn repair_line(alphabet: &Alphabet, line: &str) -> Result<(Verdict, String)> {
+    let verdict = alphabet.check(line)?;
+    let repaired = match &verdict {
+        Verdict::Valid => line.to_string(),
+        Verdict::Incomplete(missing) => format!("{}{}", line, alphabet.completion(missing)),
+        Verdict::Corrupted(error) => {
+            let found = alphabet.pair(error.found).close;
+            let reason = match error.expected {
+                Some(expected) => format!(
+                    "expected {} but found {}",
+                    alphabet.pair(expected).close,
+                    found
+                ),
+                None => format!("found {} without an open delimiter", found),
+            };
+            format!("{}{}{}", line, CORRUPTED_MARK, reason)
+        }
+    };
+    Ok((verdict, repaired))
+}
+
+/// How many lines `repair` kept, completed and flagged.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RepairSummary {
+    valid: usize,
+    completed: usize,
+    corrupted: usize,
+}
+
+/// Writes every line of the input to `output`, repaired as far as possible by `repair_line`.
+fn repair<I: Input, W: Write>(
+    alphabet: &Alphabet,
+    input: I,
+    mut output: W,
+) -> Result<RepairSummary> {
+    let mut summary = RepairSummary::default();
+    for line in BufReader::new(input.open()?).lines() {
+        let (verdict, repaired) = repair_line(alphabet, &line?)?;
+        match verdict {
+            Verdict::Valid => summary.valid += 1,
+            Verdict::Incomplete(_) => summary.completed += 1,
+            Verdict::Corrupted(_) => summary.corrupted += 1,
+        }
+        writeln!(output, "{}", repaired)?;
+    }
+    output.flush()?;
+    Ok(summary)
+}
+
+const INPUT: &str = "input/day10.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let ["--fix", output] = args.iter().map(String::as_str).collect_vec()[..] {
+        let file = BufWriter::new(File::create(output)?);
+        let summary = repair(&Alphabet::default(), input, file)?;
+        println!(
+            "{} lines completed, {} already complete, {} corrupted lines flagged",
+            summary.completed, summary.valid, summary.corrupted
+        );
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::syntax::{AlphabetError, DelimiterPair, TokenizeError};
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use itertools::Itertools;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                [({(<(())[]>[[{[]{<()<>>
+                [(()[<>])]({[<{<<[]>>(
+                {([(<{}[<>[]}>{[]{[(<()>
+                (((({<>}<{<{<>}{[]{[]{}
+                [[<[([]))<([[{}[[()]]]
+                [{[{({}]{}}([{[{{{}}([]
+                {<[[]]>}<{[{[{[]{()[[[]
+                [<(<(<(<{}))><([]([]()
+                <{([([[(<>()){}]>(<<{{
+                <{([{{}}[<[[[<>{}]]]>[]]"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn example_verdicts(alphabet: &Alphabet) -> Vec<Verdict> {
+        let (dir, file) = example_file();
+        let verdicts = check_file(alphabet, file)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        drop(dir);
+        verdicts
+    }
+
+    #[test]
+    fn test_syntax_checker() {
+        let alphabet = Alphabet::default();
+        let errors = get_all_syntax_errors(example_verdicts(&alphabet))
+            .map(|e| {
+                (
+                    alphabet.pair(e.found).close,
+                    e.expected.map(|d| alphabet.pair(d).close),
+                )
+            })
+            .collect_vec();
+
+        assert_eq!(
+            errors,
+            vec![
+                ('}', Some(']')),
+                (')', Some(']')),
+                (']', Some(')')),
+                (')', Some('>')),
+                ('>', Some(']')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_completion() {
+        let alphabet = Alphabet::default();
+        let incomplete = get_all_incomplete_lines(example_verdicts(&alphabet)).collect_vec();
+        let scores = incomplete
+            .iter()
+            .map(|missing| alphabet.score_completion(missing))
+            .collect_vec();
+        assert_eq!(scores, vec![288957, 5566, 1480781, 995444, 294]);
+        assert_eq!(alphabet.completion(&incomplete[0]), "}}]])})]");
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let alphabet = Alphabet::new(vec![
+            DelimiterPair::new('/', '\\', 10, 1),
+            DelimiterPair::new('b', 'e', 20, 2),
+        ])
+        .unwrap()
+        .with_completion_base(3);
+        assert_eq!(alphabet.check("b/\\e").unwrap(), Verdict::Valid);
+        assert_eq!(
+            alphabet.check("b/").unwrap(),
+            Verdict::Incomplete(vec![1, 0])
+        );
+        assert_eq!(alphabet.score_completion(&[1, 0]), 5);
+        match alphabet.check("b/e").unwrap() {
+            Verdict::Corrupted(error) => assert_eq!(alphabet.score_error(&error), 20),
+            v => panic!("Unexpected verdict {:?}", v),
+        }
+        assert_eq!(alphabet.check("b(e"), Err(TokenizeError::InvalidChar('(')));
+        assert_eq!(
+            Alphabet::new(vec![DelimiterPair::new('|', '|', 1, 1)]).unwrap_err(),
+            AlphabetError::DuplicateChar('|')
+        );
+    }
+
+    #[test]
+    fn test_repair() {
+        let alphabet = Alphabet::default();
+        let (dir, file) = example_file();
+        let mut output = Vec::new();
+        let summary = repair(&alphabet, &file, &mut output).unwrap();
+        drop(dir);
+        assert_eq!(
+            summary,
+            RepairSummary {
+                valid: 0,
+                completed: 5,
+                corrupted: 5
+            }
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect_vec();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], "[({(<(())[]>[[{[]{<()<>>}}]])})]");
+        assert_eq!(
+            lines[2],
+            "{([(<{}[<>[]}>{[]{[(<()>  # corrupted: expected ] but found }"
+        );
+        let (flagged, fixed): (Vec<_>, Vec<_>) =
+            lines.iter().partition(|line| line.contains(CORRUPTED_MARK));
+        assert_eq!(flagged.len(), 5);
+        for line in fixed {
+            assert_eq!(alphabet.check(line).unwrap(), Verdict::Valid, "{}", line);
+        }
+        assert_eq!(
+            repair_line(&alphabet, "()>").unwrap().1,
+            "()>  # corrupted: found > without an open delimiter"
+        );
+        assert!(repair_line(&alphabet, "(x").is_err());
+    }
+
+    #[test]
+    fn test_check_reader() {
+        let alphabet = Alphabet::default();
+        let input: &[u8] = b"([])\r\n(]\n(\xff)\n<<";
+        let verdicts = alphabet
+            .check_reader(input)
+            .map(Result::unwrap)
+            .collect_vec();
+        assert_eq!(verdicts.len(), 4);
+        assert_eq!(verdicts[0], Ok(Verdict::Valid));
+        assert!(matches!(verdicts[1], Ok(Verdict::Corrupted(_))));
+        assert_eq!(verdicts[2], Err(TokenizeError::InvalidChar('\u{fffd}')));
+        assert_eq!(verdicts[3], Ok(Verdict::Incomplete(vec![3, 3])));
+
+        // A corrupted line is rejected before the invalid character behind the error is seen
+        assert!(matches!(
+            alphabet.check_bytes(b"(]x"),
+            Ok(Verdict::Corrupted(_))
+        ));
+
+        // Lines are checked one at a time, so huge inputs don't need to fit into memory
+        use std::io::Read;
+        let huge = std::io::repeat(b'(').take(1_000_000).chain(&b"\n[]"[..]);
+        let verdicts = alphabet
+            .check_reader(std::io::BufReader::new(huge))
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .collect_vec();
+        assert!(matches!(&verdicts[0], Verdict::Incomplete(open) if open.len() == 1_000_000));
+        assert_eq!(verdicts[1], Verdict::Valid);
+    }
+}
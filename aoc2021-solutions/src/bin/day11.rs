@@ -1,22 +1,21 @@
 use anyhow::Result;
-use aoc2021::{field2d::Field2D, stream_items_from_file};
-use itertools::Itertools;
-use std::{collections::HashSet, path::Path};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{
+    field2d::Field2D,
+    simulation::{iterate_n, iterate_until_within, DidNotConverge, Limits},
+    stream_grid_from_file,
+};
+use aoc2021::{Input, InputSource};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 struct OctopusEnergies(Field2D<u32>);
 
 impl OctopusEnergies {
-    fn parse(input: impl Iterator<Item = String>) -> Self {
-        OctopusEnergies(
-            Field2D::parse(input, |line| {
-                line.chars()
-                    .map(|c| c.to_digit(10).expect("Invalid input char"))
-                    .collect_vec()
-                    .into_iter()
-            })
-            .unwrap(),
-        )
+    fn read<I: Input>(input: I) -> Result<Self> {
+        Ok(OctopusEnergies(stream_grid_from_file(input, |c| {
+            c.to_digit(10).ok_or("not a digit")
+        })?))
     }
 
     fn step(&mut self) -> usize {
@@ -48,37 +47,37 @@ impl OctopusEnergies {
     }
 
     fn simulate(&mut self, nsteps: usize) -> usize {
-        (0..nsteps).map(|_| self.step()).sum()
+        iterate_n(0, nsteps, |flashes, _| flashes + self.step()).0
     }
 
-    fn find_sync(&mut self) -> usize {
+    fn find_sync(&mut self, limits: &Limits) -> Result<usize, DidNotConverge> {
         let field_size = self.0.len();
-        // Run an infinite simulation and stop as soon as all octopuses flash
-        std::iter::repeat_with(|| self.step())
-            .enumerate()
-            .filter(|(_, flashes)| *flashes == field_size)
-            .next()
-            .unwrap()
-            .0
-            + 1
+        // Run the simulation until all octopuses flash at once
+        iterate_until_within(0, |_| self.step(), |&flashes| flashes == field_size, limits)
+            .map(|(_, steps)| steps)
     }
 }
 
-fn part1<P: AsRef<Path>>(input: P) -> Result<usize> {
-    let mut energies = OctopusEnergies::parse(stream_items_from_file(input)?);
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let mut energies = OctopusEnergies::read(input)?;
     Ok(energies.simulate(100))
 }
 
-fn part2<P: AsRef<Path>>(input: P) -> Result<usize> {
-    let mut energies = OctopusEnergies::parse(stream_items_from_file(input)?);
-    Ok(energies.find_sync())
+fn find_sync<I: Input>(input: I, limits: &Limits) -> Result<usize> {
+    let mut energies = OctopusEnergies::read(input)?;
+    Ok(energies.find_sync(limits)?)
 }
 
 const INPUT: &str = "input/day11.txt";
 
 fn main() -> Result<()> {
-    println!("Answer for part 1: {}", part1(INPUT)?);
-    println!("Answer for part 2: {}", part2(INPUT)?);
+    let input = &InputSource::detect(INPUT)?;
+    let limits = Limits::from_args(std::env::args().skip(1))?;
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!(
+        "Answer for part 2: {}",
+        measure("part 2", || find_sync(input, &limits))?
+    );
     Ok(())
 }
 
@@ -86,7 +85,7 @@ fn main() -> Result<()> {
 mod tests {
     use std::path::Path;
 
-    use aoc2021::{stream_items_from_file, test_helpers::create_line_file};
+    use aoc2021::test_helpers::create_line_file;
     use indoc::indoc;
     use tempfile::TempDir;
 
@@ -113,23 +112,16 @@ mod tests {
     #[test]
     fn test_short_sim() {
         let (dir, file) = example_file();
-        let lines = stream_items_from_file(file).unwrap();
-        let mut energies = OctopusEnergies::parse(lines);
+        let mut energies = OctopusEnergies::read(file).unwrap();
         assert_eq!(energies.simulate(10), 204);
         drop(dir);
     }
 
     #[test]
-    fn test_part1() {
-        let (dir, file) = example_file();
-        assert_eq!(part1(file).unwrap(), 1656);
-        drop(dir);
-    }
-
-    #[test]
-    fn test_part2() {
+    fn test_sync_limit() {
         let (dir, file) = example_file();
-        assert_eq!(part2(file).unwrap(), 195);
+        assert!(find_sync(&file, &Limits::max_steps(194)).is_err());
+        assert_eq!(find_sync(&file, &Limits::max_steps(195)).unwrap(), 195);
         drop(dir);
     }
 }
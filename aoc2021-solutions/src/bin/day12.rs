@@ -0,0 +1,630 @@
+use anyhow::{bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::stats::{self, Stats};
+use aoc2021::{repl, repl::Repl, stream_items_from_file};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    str::FromStr,
+};
+
+/// An undirected graph whose nodes are interned, they are stored once and referred to by their index.
+#[derive(Debug)]
+struct Graph<T> {
+    nodes: Vec<T>,
+    node_lookup: HashMap<T, usize>,
+    adjacencies: Vec<HashSet<usize>>,
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self {
+            nodes: Default::default(),
+            node_lookup: Default::default(),
+            adjacencies: Default::default(),
+        }
+    }
+}
+
+impl<T> Graph<T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn insert_node(&mut self, node: T) -> usize {
+        match self.node_lookup.get(&node) {
+            Some(&v) => v,
+            None => {
+                let v = self.nodes.len();
+                self.nodes.push(node.clone());
+                self.node_lookup.insert(node, v);
+                self.adjacencies.push(Default::default());
+                v
+            }
+        }
+    }
+
+    fn connect(&mut self, a: T, b: T) -> (usize, usize) {
+        let av = self.insert_node(a);
+        let bv = self.insert_node(b);
+        self.adjacencies[av].insert(bv);
+        self.adjacencies[bv].insert(av);
+        (av, bv)
+    }
+
+    fn get_node_value(&self, index: usize) -> Option<&T> {
+        self.nodes.get(index)
+    }
+
+    fn get_node_index(&self, node: &T) -> Option<usize> {
+        self.node_lookup.get(node).copied()
+    }
+
+    fn get_neighbors(&self, node: usize) -> Option<&HashSet<usize>> {
+        self.adjacencies.get(node)
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum Cave {
+    SmallCave(String),
+    BigCave(String),
+}
+
+impl FromStr for Cave {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.chars()
+            .next()
+            .map(|c| c.is_uppercase())
+            .unwrap_or_default()
+        {
+            Ok(Self::BigCave(s.to_string()))
+        } else {
+            Ok(Self::SmallCave(s.to_string()))
+        }
+    }
+}
+
+impl Cave {
+    fn is_small(&self) -> bool {
+        match self {
+            Cave::SmallCave(_) => true,
+            Cave::BigCave(_) => false,
+        }
+    }
+}
+
+impl Display for Cave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cave::SmallCave(name) | Cave::BigCave(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CaveSystem(Graph<Cave>, HashSet<usize>);
+
+impl CaveSystem {
+    fn parse(input: impl Iterator<Item = String>) -> Self {
+        let mut connections = Graph::<Cave>::default();
+        let mut small_caves = HashSet::<usize>::new();
+
+        for line in input {
+            let (left, right) = line
+                .split('-')
+                .map(|name| Cave::from_str(name).unwrap())
+                .collect_tuple()
+                .unwrap();
+            let (left_small, right_small) = (left.is_small(), right.is_small());
+            let (left_idx, right_idx) = connections.connect(left, right);
+            if left_small {
+                small_caves.insert(left_idx);
+            }
+            if right_small {
+                small_caves.insert(right_idx);
+            }
+        }
+
+        CaveSystem(connections, small_caves)
+    }
+
+    /// Prints a path of cave indices like the puzzle does, e.g. `start,A,b,end`.
+    fn path_to_string(&self, path: &[usize]) -> String {
+        path.iter()
+            .map(|&cave| self.0.get_node_value(cave).unwrap())
+            .join(",")
+    }
+
+    /// Enumerates the paths one by one and calls `on_path` for each of them.
+    fn dfs_search<F: FnMut(&[usize])>(
+        &self,
+        cur_path: &mut Vec<usize>,
+        visited_small_nodes: &mut HashSet<usize>,
+        target: usize,
+        double: bool,
+        start: usize,
+        on_path: &mut F,
+    ) -> usize {
+        let cur = *cur_path.last().unwrap();
+        let mut paths = 0;
+        for neighbor in self.0.get_neighbors(cur).unwrap() {
+            if *neighbor == target {
+                paths += 1;
+                cur_path.push(target);
+                on_path(cur_path);
+                cur_path.pop();
+            } else {
+                let second_small = visited_small_nodes.contains(neighbor);
+                if !second_small || (!double && *neighbor != start) {
+                    if self.1.contains(neighbor) {
+                        visited_small_nodes.insert(*neighbor);
+                    }
+                    cur_path.push(*neighbor);
+                    paths += self.dfs_search(
+                        cur_path,
+                        visited_small_nodes,
+                        target,
+                        double || second_small,
+                        start,
+                        on_path,
+                    );
+                    cur_path.pop();
+                    if !second_small {
+                        visited_small_nodes.remove(neighbor);
+                    }
+                }
+            }
+        }
+
+        paths
+    }
+
+    // Number of paths from `cur` to `target`, the visited small caves are tracked as their bits in `visited_small`
+    fn count_paths_from(
+        &self,
+        cur: usize,
+        visited_small: u64,
+        double: bool,
+        (start, target): (usize, usize),
+        small_bits: &HashMap<usize, u64>,
+        cache: &mut HashMap<(usize, u64, bool), usize>,
+    ) -> usize {
+        if let Some(&paths) = cache.get(&(cur, visited_small, double)) {
+            return paths;
+        }
+        let mut paths = 0;
+        for &neighbor in self.0.get_neighbors(cur).unwrap() {
+            if neighbor == target {
+                paths += 1;
+                continue;
+            }
+            let (visited_small, double) = match small_bits.get(&neighbor) {
+                None => (visited_small, double),
+                Some(&bit) if visited_small & bit == 0 => (visited_small | bit, double),
+                Some(_) if !double && neighbor != start => (visited_small, true),
+                Some(_) => continue,
+            };
+            paths += self.count_paths_from(
+                neighbor,
+                visited_small,
+                double,
+                (start, target),
+                small_bits,
+                cache,
+            );
+        }
+        cache.insert((cur, visited_small, double), paths);
+        paths
+    }
+
+    /// Same as enumerating the paths with `visit_paths`, but caches the number of paths for every combination of
+    /// current cave, visited small caves and whether the double visit has been used up.
+    /// Only the small caves get a bit, with more than 64 of them the paths are enumerated instead.
+    fn count_paths(&self, from: &Cave, to: &Cave, allow_double: bool) -> usize {
+        if self.1.len() > 64 {
+            return self.visit_paths(from, to, allow_double, |_| ());
+        }
+        let small_bits: HashMap<usize, u64> = self
+            .1
+            .iter()
+            .sorted()
+            .enumerate()
+            .map(|(i, &cave)| (cave, 1 << i))
+            .collect();
+        let start = self.0.get_node_index(from).unwrap();
+        let end = self.0.get_node_index(to).unwrap();
+        self.count_paths_from(
+            start,
+            small_bits.get(&start).copied().unwrap_or(0),
+            !allow_double,
+            (start, end),
+            &small_bits,
+            &mut HashMap::new(),
+        )
+    }
+
+    /// All paths in the notation of the puzzle, sorted alphabetically.
+    fn list_paths(&self, from: &Cave, to: &Cave, allow_double: bool) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.visit_paths(from, to, allow_double, |path| {
+            paths.push(self.path_to_string(path))
+        });
+        paths.sort();
+        paths
+    }
+
+    fn visit_paths<F: FnMut(&[usize])>(
+        &self,
+        from: &Cave,
+        to: &Cave,
+        allow_double: bool,
+        mut on_path: F,
+    ) -> usize {
+        let start = self.0.get_node_index(from).unwrap();
+        let end = self.0.get_node_index(to).unwrap();
+        let mut start_path = vec![start];
+        let mut visited_small_nodes = HashSet::new();
+        // A big cave can be passed again like any other big cave, even if the paths start there
+        if self.1.contains(&start) {
+            visited_small_nodes.insert(start);
+        }
+
+        self.dfs_search(
+            &mut start_path,
+            &mut visited_small_nodes,
+            end,
+            !allow_double,
+            start,
+            &mut on_path,
+        )
+    }
+}
+
+impl Repl for CaveSystem {
+    fn eval(&mut self, command: &str) -> Result<String> {
+        let words = command.split_whitespace().collect_vec();
+        match words[..] {
+            [command @ ("paths" | "list"), "from", from, "to", to, ref rest @ ..] => {
+                let allow_double = match rest {
+                    [] => false,
+                    ["twice"] => true,
+                    _ => bail!("Unexpected arguments {:?}", rest),
+                };
+                let from = Cave::from_str(from).unwrap();
+                let to = Cave::from_str(to).unwrap();
+                for cave in [&from, &to] {
+                    if self.0.get_node_index(cave).is_none() {
+                        bail!("Unknown cave {}", cave);
+                    }
+                }
+                if command == "list" {
+                    Ok(self.list_paths(&from, &to, allow_double).join("\n"))
+                } else {
+                    Ok(self.count_paths(&from, &to, allow_double).to_string())
+                }
+            }
+            _ => bail!("Unknown command {}", command),
+        }
+    }
+
+    fn help(&self) -> String {
+        [
+            "paths from <A> to <B> [twice]: count the paths between two caves, \
+             optionally allowing a single small cave to be visited twice",
+            "list from <A> to <B> [twice]: print these paths",
+        ]
+        .join("\n")
+    }
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let cave_system = CaveSystem::parse(stream_items_from_file(input)?);
+    Ok(cave_system.count_paths(
+        &Cave::SmallCave("start".to_string()),
+        &Cave::SmallCave("end".to_string()),
+        false,
+    ))
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let cave_system = CaveSystem::parse(stream_items_from_file(input)?);
+    Ok(cave_system.count_paths(
+        &Cave::SmallCave("start".to_string()),
+        &Cave::SmallCave("end".to_string()),
+        true,
+    ))
+}
+
+impl Stats for CaveSystem {
+    fn stats(&self) -> Vec<(String, String)> {
+        let CaveSystem(graph, small_caves) = self;
+        let degrees = graph.adjacencies.iter().map(HashSet::len).counts();
+        let edges = graph.adjacencies.iter().map(HashSet::len).sum::<usize>() / 2;
+        vec![
+            ("caves".to_string(), graph.nodes.len().to_string()),
+            ("small caves".to_string(), small_caves.len().to_string()),
+            ("connections".to_string(), edges.to_string()),
+            (
+                "caves by degree".to_string(),
+                degrees
+                    .iter()
+                    .sorted()
+                    .map(|(degree, count)| format!("{}: {}", degree, count))
+                    .join(", "),
+            ),
+        ]
+    }
+}
+
+const INPUT: &str = "input/day12.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if repl::requested() {
+        let mut cave_system = CaveSystem::parse(stream_items_from_file(input)?);
+        return Ok(repl::run(&mut cave_system)?);
+    }
+    if stats::requested() {
+        print!(
+            "{}",
+            stats::render(&CaveSystem::parse(stream_items_from_file(input)?))
+        );
+        return Ok(());
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    impl CaveSystem {
+        /// Enumerates every path, the reference for `count_paths`.
+        fn find_all_paths(&self, from: &Cave, to: &Cave, allow_double: bool) -> usize {
+            self.visit_paths(from, to, allow_double, |_| ())
+        }
+    }
+
+    fn example_file1() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                start-A
+                start-b
+                A-c
+                A-b
+                b-d
+                A-end
+                b-end"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn example_file2() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                dc-end
+                HN-start
+                start-kj
+                dc-start
+                dc-HN
+                LN-dc
+                HN-end
+                kj-sa
+                kj-HN
+                kj-dc"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn example_file3() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                fs-end
+                he-DX
+                fs-he
+                start-DX
+                pj-DX
+                end-zg
+                zg-sl
+                zg-pj
+                pj-he
+                RW-he
+                fs-DX
+                pj-RW
+                zg-RW
+                start-pj
+                he-WI
+                zg-he
+                pj-fs
+                start-RW"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn start_end() -> (Cave, Cave) {
+        (
+            Cave::SmallCave("start".to_string()),
+            Cave::SmallCave("end".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_memoized_matches_dfs() {
+        let (start, end) = start_end();
+        let (dir1, file1) = example_file1();
+        let (dir2, file2) = example_file2();
+        let (dir3, file3) = example_file3();
+        for file in [file1.as_ref(), file2.as_ref(), file3.as_ref()] {
+            let cave_system = CaveSystem::parse(stream_items_from_file(file).unwrap());
+            for allow_double in [false, true] {
+                assert_eq!(
+                    cave_system.count_paths(&start, &end, allow_double),
+                    cave_system.find_all_paths(&start, &end, allow_double)
+                );
+            }
+        }
+        drop(dir1);
+        drop(dir2);
+        drop(dir3);
+    }
+
+    #[test]
+    fn test_big_cave_start() {
+        let (_, end) = start_end();
+        let (dir, file) = example_file2();
+        let mut cave_system = CaveSystem::parse(stream_items_from_file(file).unwrap());
+        drop(dir);
+        let big_caves = cave_system
+            .0
+            .nodes
+            .iter()
+            .filter(|cave| !cave.is_small())
+            .cloned()
+            .collect_vec();
+        assert_eq!(big_caves.len(), 2);
+        for from in &big_caves {
+            for allow_double in [false, true] {
+                assert_eq!(
+                    cave_system.count_paths(from, &end, allow_double),
+                    cave_system.find_all_paths(from, &end, allow_double)
+                );
+            }
+        }
+        // The paths may pass the big start cave again, e.g. HN,kj,HN,end
+        let listed = cave_system.eval("list from HN to end").unwrap();
+        assert!(listed.lines().contains(&"HN,kj,HN,end"));
+        assert_eq!(
+            cave_system.eval("paths from HN to end").unwrap(),
+            listed.lines().count().to_string()
+        );
+    }
+
+    #[test]
+    fn test_many_small_caves() {
+        // More small caves than bits in the mask of `count_paths`
+        let names = ["start".to_string()]
+            .into_iter()
+            .chain((0..70).map(|i| format!("s{}", i)))
+            .chain(["end".to_string()])
+            .collect_vec();
+        let cave_system = CaveSystem::parse(
+            names
+                .iter()
+                .tuple_windows()
+                .map(|(a, b)| format!("{}-{}", a, b)),
+        );
+        let (start, end) = start_end();
+        assert_eq!(cave_system.count_paths(&start, &end, false), 1);
+        assert_eq!(cave_system.count_paths(&start, &end, true), 1);
+    }
+
+    // Every small cave is connected to every big cave and to its successor
+    fn dense_cave_system(small: usize, big: usize) -> CaveSystem {
+        let small_names = ["start".to_string(), "end".to_string()]
+            .into_iter()
+            .chain((0..small).map(|i| format!("s{}", i)))
+            .collect_vec();
+        let big_names = (0..big).map(|i| format!("B{}", i)).collect_vec();
+        let lines = small_names
+            .iter()
+            .cartesian_product(big_names.iter())
+            .map(|(s, b)| format!("{}-{}", s, b))
+            .chain(
+                small_names
+                    .iter()
+                    .tuple_windows()
+                    .map(|(a, b)| format!("{}-{}", a, b)),
+            );
+        CaveSystem::parse(lines)
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_dense_cave_system() {
+        let (start, end) = start_end();
+        let cave_system = dense_cave_system(6, 2);
+        for allow_double in [false, true] {
+            let now = std::time::Instant::now();
+            let memoized = cave_system.count_paths(&start, &end, allow_double);
+            let memoized_time = now.elapsed();
+            let now = std::time::Instant::now();
+            let enumerated = cave_system.find_all_paths(&start, &end, allow_double);
+            let enumerated_time = now.elapsed();
+            println!(
+                "{} paths (double visit: {}), memoized {:?}, dfs {:?}",
+                memoized, allow_double, memoized_time, enumerated_time
+            );
+            assert_eq!(memoized, enumerated);
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let (dir, file) = example_file1();
+        let cave_system = CaveSystem::parse(stream_items_from_file(file).unwrap());
+        drop(dir);
+
+        assert_eq!(
+            stats::render(&cave_system),
+            indoc! {"
+                caves           : 6
+                small caves     : 5
+                connections     : 7
+                caves by degree : 1: 2, 2: 2, 4: 2
+            "}
+        );
+    }
+
+    #[test]
+    fn test_repl() {
+        let (dir, file) = example_file1();
+        let mut cave_system = CaveSystem::parse(stream_items_from_file(file).unwrap());
+        drop(dir);
+
+        assert_eq!(cave_system.eval("paths from start to end").unwrap(), "10");
+        assert_eq!(
+            cave_system.eval("paths from start to end twice").unwrap(),
+            "36"
+        );
+        assert!(cave_system.eval("paths from start to nowhere").is_err());
+        assert_eq!(
+            cave_system.eval("list from start to end").unwrap(),
+            indoc! {"
+                start,A,b,A,c,A,end
+                start,A,b,A,end
+                start,A,b,end
+                start,A,c,A,b,A,end
+                start,A,c,A,b,end
+                start,A,c,A,end
+                start,A,end
+                start,b,A,c,A,end
+                start,b,A,end
+                start,b,end"}
+        );
+        assert_eq!(
+            cave_system
+                .eval("list from start to end twice")
+                .unwrap()
+                .lines()
+                .count(),
+            36
+        );
+        assert!(cave_system.eval("paths").is_err());
+    }
+}
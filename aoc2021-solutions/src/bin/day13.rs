@@ -0,0 +1,344 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::{
+    artifacts::{self, Artifacts},
+    rle_field::RleField,
+    stream_items_from_file,
+    transform2d::{Axis, Fold},
+    vec2d::Vec2D,
+};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use regex::Regex;
+use std::collections::HashSet;
+
+type Dots = HashSet<Vec2D<usize>>;
+type Folds = Vec<Fold>;
+
+fn parse_input(input: impl Iterator<Item = String>) -> Result<(Dots, Folds)> {
+    let fold_re = Regex::new(r"^fold along (\w)=(\d+)$").expect("Regex syntax failure");
+
+    let mut dots = Dots::new();
+    let mut folds = Folds::new();
+
+    for line in input.filter(|l| !l.is_empty()) {
+        if let Some(m) = fold_re.captures(&line) {
+            let fold_pos = m.get(2).unwrap().as_str().parse::<usize>()?;
+            let fold = match m.get(1).unwrap().as_str() {
+                "x" => Fold::along_x(fold_pos),
+                "y" => Fold::along_y(fold_pos),
+                _ => anyhow::bail!("Invalid fold descriptor {}", line),
+            };
+            folds.push(fold);
+        } else {
+            dots.insert(line.parse::<_>()?);
+        }
+    }
+
+    Ok((dots, folds))
+}
+
+fn execute_fold(dots: Dots, fold: &Fold) -> Dots {
+    fold.apply_all(&dots)
+}
+
+/// The positions `from..=to` along one axis end up at `sign * p + offset` after all folds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Piece {
+    from: i64,
+    to: i64,
+    sign: i64,
+    offset: i64,
+}
+
+impl Piece {
+    fn map(&self, pos: i64) -> i64 {
+        self.sign * pos + self.offset
+    }
+
+    /// The part of the piece whose image lies within `lo..=hi`.
+    fn with_image_in(&self, lo: i64, hi: i64) -> Option<Piece> {
+        let (from, to) = if self.sign > 0 {
+            (lo - self.offset, hi.saturating_sub(self.offset))
+        } else {
+            (self.offset - hi, self.offset - lo)
+        };
+        let from = from.max(self.from);
+        let to = to.min(self.to);
+        (from <= to).then_some(Piece { from, to, ..*self })
+    }
+}
+
+/// All folds along one axis composed into a piecewise reflection, positions outside of the pieces get lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AxisMap(Vec<Piece>);
+
+impl AxisMap {
+    fn identity() -> Self {
+        AxisMap(vec![Piece {
+            from: 0,
+            to: i64::MAX,
+            sign: 1,
+            offset: 0,
+        }])
+    }
+
+    fn fold(self, line: i64) -> Self {
+        let mut pieces: Vec<Piece> = Vec::with_capacity(self.0.len() * 2);
+        for piece in self.0 {
+            let kept = piece.with_image_in(0, line);
+            // Everything behind the line gets mirrored, unless it would end up in front of the paper
+            let mirrored = piece.with_image_in(line + 1, 2 * line).map(|piece| Piece {
+                sign: -piece.sign,
+                offset: 2 * line - piece.offset,
+                ..piece
+            });
+            let mut parts = kept.into_iter().chain(mirrored).collect_vec();
+            parts.sort_by_key(|part| part.from);
+            for part in parts {
+                match pieces.last_mut() {
+                    Some(last)
+                        if last.to + 1 == part.from
+                            && (last.sign, last.offset) == (part.sign, part.offset) =>
+                    {
+                        last.to = part.to
+                    }
+                    _ => pieces.push(part),
+                }
+            }
+        }
+        AxisMap(pieces)
+    }
+
+    fn get(&self, pos: usize) -> Option<usize> {
+        let pos = pos as i64;
+        let index = self.0.partition_point(|piece| piece.to < pos);
+        let piece = self.0.get(index).filter(|piece| piece.from <= pos)?;
+        Some(piece.map(pos) as usize)
+    }
+}
+
+/// Where every dot ends up after a whole sequence of folds, without folding the paper step by step.
+#[derive(Debug, Clone)]
+struct FoldMap {
+    x: AxisMap,
+    y: AxisMap,
+}
+
+impl FoldMap {
+    fn new(folds: &[Fold]) -> Self {
+        folds.iter().fold(
+            FoldMap {
+                x: AxisMap::identity(),
+                y: AxisMap::identity(),
+            },
+            |map, fold| match fold.axis {
+                Axis::X => FoldMap {
+                    x: map.x.fold(fold.line as i64),
+                    ..map
+                },
+                Axis::Y => FoldMap {
+                    y: map.y.fold(fold.line as i64),
+                    ..map
+                },
+            },
+        )
+    }
+
+    fn apply(&self, dot: &Vec2D<usize>) -> Option<Vec2D<usize>> {
+        Some(Vec2D::new(self.x.get(dot.x())?, self.y.get(dot.y())?))
+    }
+
+    fn apply_all(&self, dots: &Dots) -> Dots {
+        dots.iter().filter_map(|dot| self.apply(dot)).collect()
+    }
+}
+
+fn render_dots(dots: &Dots) -> String {
+    let width = dots.iter().map(|dot| dot.x()).max().unwrap() + 1;
+    let height = dots.iter().map(|dot| dot.y()).max().unwrap() + 1;
+
+    let paper = RleField::from_points(width, height, dots.iter().map(|dot| (dot.x(), dot.y())));
+
+    (0..height)
+        .map(|y| {
+            paper
+                .row(y)
+                .into_iter()
+                .map(|dot| if dot { 'x' } else { ' ' })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// The paper after all folds, before the letters are read from it.
+struct FoldedPaper(Dots);
+
+impl Artifacts for FoldedPaper {
+    fn artifacts(&self) -> Vec<(String, String)> {
+        vec![("paper.txt".to_string(), render_dots(&self.0))]
+    }
+}
+
+fn fold_all<I: Input>(input: I) -> Result<FoldedPaper> {
+    let (dots, folds) = parse_input(stream_items_from_file(input)?)?;
+    Ok(FoldedPaper(FoldMap::new(&folds).apply_all(&dots)))
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let (dots, folds) = parse_input(stream_items_from_file(input)?)?;
+    let dots = execute_fold(dots, folds.first().unwrap());
+    Ok(dots.len())
+}
+
+fn part2<I: Input>(input: I) -> Result<String> {
+    Ok(render_dots(&fold_all(input)?.0))
+}
+
+const INPUT: &str = "input/day13.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if artifacts::requested_dir().is_some() {
+        artifacts::write_if_requested("day13", &fold_all(input)?)?;
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!(
+        "Answer for part 2:\n{}",
+        measure("part 2", || part2(input))?
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::{assert_completes_within, assert_snapshot, create_line_file};
+    use indoc::indoc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                6,10
+                0,14
+                9,10
+                0,3
+                10,4
+                4,11
+                6,0
+                6,12
+                4,1
+                0,13
+                10,12
+                3,4
+                3,0
+                8,4
+                1,10
+                2,14
+                8,10
+                9,0
+                
+                fold along y=7
+                fold along x=5"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_part2() {
+        let (dir, file) = example_file();
+        assert_snapshot("day13_part2", &part2(file).unwrap());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_artifacts() {
+        let (dir, file) = example_file();
+        let out = TempDir::new().unwrap();
+        let written = artifacts::write(out.path(), "day13", &fold_all(&file).unwrap()).unwrap();
+        assert_eq!(written, vec![out.path().join("day13").join("paper.txt")]);
+        assert_eq!(
+            std::fs::read_to_string(&written[0]).unwrap(),
+            part2(&file).unwrap()
+        );
+        assert_eq!(
+            artifacts::dir_from_args(["--artifacts", "out"].map(String::from)),
+            Some("out".into())
+        );
+        assert_eq!(
+            artifacts::dir_from_args(["--artifacts"].map(String::from)),
+            None
+        );
+        drop(dir);
+    }
+
+    fn fold_iteratively(dots: Dots, folds: &[Fold]) -> Dots {
+        folds.iter().fold(dots, execute_fold)
+    }
+
+    #[test]
+    fn test_composed_folds() {
+        let (dir, file) = example_file();
+        let (dots, folds) = parse_input(stream_items_from_file(&file).unwrap()).unwrap();
+        drop(dir);
+        let map = FoldMap::new(&folds);
+        assert_eq!(map.apply_all(&dots), fold_iteratively(dots.clone(), &folds));
+        assert_eq!(
+            FoldMap::new(&folds[..1]).apply_all(&dots).len(),
+            fold_iteratively(dots.clone(), &folds[..1]).len()
+        );
+        // Like with `execute_fold`, dots on a fold line stay where they are
+        assert_eq!(map.y.get(7), Some(7));
+        assert_eq!(map.y.get(14), Some(0));
+        assert_eq!(map.y.get(15), None);
+
+        // Uneven folds push dots beyond the edge of the paper, repeated folds shift the pieces around
+        let dots: Dots = (0..40)
+            .flat_map(|x| (0..40).map(move |y| Vec2D::new(x, y * 3 % 41)))
+            .collect();
+        let folds = [30, 3, 25, 2, 17, 9, 11, 4, 7, 1, 5, 2, 3, 1]
+            .iter()
+            .enumerate()
+            .map(|(i, &line)| {
+                if i % 3 == 0 {
+                    Fold::along_y(line)
+                } else {
+                    Fold::along_x(line)
+                }
+            })
+            .collect_vec();
+        for n in 0..=folds.len() {
+            assert_eq!(
+                FoldMap::new(&folds[..n]).apply_all(&dots),
+                fold_iteratively(dots.clone(), &folds[..n]),
+                "after {} folds",
+                n
+            );
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn perf_many_folds() {
+        let dots: Dots = (0..1_000_000)
+            .map(|i: usize| Vec2D::new(i % 4099, i.wrapping_mul(2654435761) % 4099))
+            .collect();
+        let folds = (0..2000)
+            .map(|i| match i % 2 {
+                0 => Fold::along_x(4000 - i),
+                _ => Fold::along_y(4000 - i),
+            })
+            .collect_vec();
+        let composed =
+            assert_completes_within("day13 composed folds", Duration::from_secs(2), || {
+                FoldMap::new(&folds).apply_all(&dots)
+            });
+        assert_eq!(composed, fold_iteratively(dots, &folds));
+    }
+}
@@ -0,0 +1,358 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::artifacts::{self, Artifacts};
+use aoc2021::{stats, stream_items_from_file};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::{cmp::Reverse, collections::HashMap, fmt::Display};
+
+type ElementCounts = HashMap<char, usize>;
+type ElementPairCounts = HashMap<(char, char), usize>;
+type PairInsertionRules = HashMap<(char, char), char>;
+
+fn parse_input(
+    mut input: impl Iterator<Item = String>,
+) -> (ElementCounts, ElementPairCounts, PairInsertionRules) {
+    let polymer_template = input.next().unwrap();
+
+    let element_counts =
+        polymer_template
+            .chars()
+            .fold(ElementCounts::new(), |mut counts, element| {
+                *counts.entry(element).or_insert(0) += 1;
+                counts
+            });
+    let element_pair_counts = polymer_template.chars().tuple_windows().fold(
+        ElementPairCounts::new(),
+        |mut counts, pair| {
+            *counts.entry(pair).or_insert(0) += 1;
+            counts
+        },
+    );
+
+    let rules: PairInsertionRules = input
+        .filter_map(|line| {
+            line.split(" -> ")
+                .map(|part| part.to_string())
+                .collect_tuple::<(_, _)>()
+        })
+        .map(|(pair, produce)| {
+            (
+                pair.chars().collect_tuple().unwrap(),
+                produce.chars().next().unwrap(),
+            )
+        })
+        .collect();
+
+    (element_counts, element_pair_counts, rules)
+}
+
+// Only the reference for the composed rules in the tests
+#[cfg_attr(not(test), allow(dead_code))]
+fn execute_rules(
+    counts: &mut ElementCounts,
+    pairs: ElementPairCounts,
+    rules: &PairInsertionRules,
+) -> ElementPairCounts {
+    let mut new_pairs = ElementPairCounts::new();
+    for (pair, count) in pairs.into_iter() {
+        if rules.contains_key(&pair) {
+            let insert = rules[&pair];
+            *counts.entry(insert).or_insert(0) += count;
+            *new_pairs.entry((pair.0, insert)).or_insert(0) += count;
+            *new_pairs.entry((insert, pair.1)).or_insert(0) += count;
+        } else {
+            new_pairs.insert(pair, count);
+        }
+    }
+
+    new_pairs
+}
+
+/// What a single pair grows into after some steps.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Expansion {
+    pairs: ElementPairCounts,
+    /// The elements inserted between the two elements of the pair
+    inserted: ElementCounts,
+}
+
+/// The pair insertion rules applied `steps` times in a row, as a table from every pair to
+/// what it grows into. Pairs without an entry stay as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ComposedRules {
+    steps: usize,
+    table: HashMap<(char, char), Expansion>,
+}
+
+impl ComposedRules {
+    fn single_step(rules: &PairInsertionRules) -> Self {
+        let table = rules
+            .iter()
+            .map(|(&pair, &insert)| {
+                let mut pairs = ElementPairCounts::new();
+                *pairs.entry((pair.0, insert)).or_insert(0) += 1;
+                *pairs.entry((insert, pair.1)).or_insert(0) += 1;
+                let inserted = ElementCounts::from([(insert, 1)]);
+                (pair, Expansion { pairs, inserted })
+            })
+            .collect();
+        ComposedRules { steps: 1, table }
+    }
+
+    /// The rules of `self` followed by the ones of `next`.
+    fn then(&self, next: &ComposedRules) -> Self {
+        let table = self
+            .table
+            .iter()
+            .map(|(&pair, expansion)| {
+                let mut composed = expansion.clone();
+                composed.pairs.clear();
+                for (inner, &count) in &expansion.pairs {
+                    match next.table.get(inner) {
+                        Some(next_expansion) => {
+                            for (&outer, &outer_count) in &next_expansion.pairs {
+                                *composed.pairs.entry(outer).or_insert(0) += count * outer_count;
+                            }
+                            for (&element, &inserted) in &next_expansion.inserted {
+                                *composed.inserted.entry(element).or_insert(0) += count * inserted;
+                            }
+                        }
+                        None => *composed.pairs.entry(*inner).or_insert(0) += count,
+                    }
+                }
+                (pair, composed)
+            })
+            // Pairs that `self` keeps as they are only grow in the `next` steps
+            .chain(
+                next.table
+                    .iter()
+                    .filter(|(pair, _)| !self.table.contains_key(pair))
+                    .map(|(&pair, expansion)| (pair, expansion.clone())),
+            )
+            .collect();
+        ComposedRules {
+            steps: self.steps + next.steps,
+            table,
+        }
+    }
+
+    /// The rules applied `steps` times, found by squaring the rules in O(log steps) compositions.
+    fn power(rules: &PairInsertionRules, steps: usize) -> Self {
+        let mut composed: Option<ComposedRules> = None;
+        let mut square = ComposedRules::single_step(rules);
+        let mut remaining = steps;
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                composed = Some(match composed {
+                    Some(composed) => composed.then(&square),
+                    None => square.clone(),
+                });
+            }
+            remaining /= 2;
+            if remaining > 0 {
+                square = square.then(&square);
+            }
+        }
+        composed.unwrap_or_default()
+    }
+
+    /// Grows the pairs of a polymer with the given element counts.
+    fn apply(&self, counts: &ElementCounts, pairs: &ElementPairCounts) -> ElementCounts {
+        let mut counts = counts.clone();
+        for (pair, &count) in pairs {
+            if let Some(expansion) = self.table.get(pair) {
+                for (&element, &inserted) in &expansion.inserted {
+                    *counts.entry(element).or_insert(0) += count * inserted;
+                }
+            }
+        }
+        counts
+    }
+}
+
+fn format_counts<K: Display>(counts: impl Iterator<Item = (K, usize)>) -> String {
+    counts
+        .map(|(key, count)| format!("{}={}", key, count))
+        .sorted()
+        .join(" ")
+}
+
+/// The table with one line per pair, `NN -> pairs CN=1 NC=1, inserts C=1`.
+impl Artifacts for ComposedRules {
+    fn artifacts(&self) -> Vec<(String, String)> {
+        let table = self
+            .table
+            .iter()
+            .sorted_by_key(|(pair, _)| **pair)
+            .map(|((first, second), expansion)| {
+                format!(
+                    "{}{} -> pairs {}, inserts {}\n",
+                    first,
+                    second,
+                    format_counts(
+                        expansion
+                            .pairs
+                            .iter()
+                            .map(|((a, b), &count)| (format!("{}{}", a, b), count))
+                    ),
+                    format_counts(expansion.inserted.iter().map(|(e, &count)| (e, count))),
+                )
+            })
+            .collect();
+        vec![(format!("rules_{}_steps.txt", self.steps), table)]
+    }
+}
+
+fn grow<I: Input>(input: I, steps: usize) -> Result<ElementCounts> {
+    let (counts, pairs, rules) = parse_input(stream_items_from_file(input)?);
+    Ok(ComposedRules::power(&rules, steps).apply(&counts, &pairs))
+}
+
+fn spread(counts: &ElementCounts) -> usize {
+    let (min, max) = stats::minmax(counts.values().copied()).unwrap();
+    max - min
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    Ok(spread(&grow(input, 10)?))
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    Ok(spread(&grow(input, 40)?))
+}
+
+/// The element counts after both parts, most common first, for `--explain`.
+fn explain<I: Input>(input: I) -> Result<String> {
+    // Both parts grow the template from the start, so it is read only once
+    let input = InputSource::reader(input.open()?)?;
+    let mut explanation = String::new();
+    for steps in [10, 40] {
+        let counts = grow(&input, steps)?;
+        let bars = counts
+            .iter()
+            .sorted_by_key(|&(element, count)| (Reverse(*count), *element))
+            .map(|(element, count)| (element.to_string(), *count))
+            .collect_vec();
+        explanation += &format!(
+            "After {} steps, most minus least common: {}\n",
+            steps,
+            spread(&counts)
+        );
+        explanation += &stats::bar_chart(&bars, 40);
+    }
+    Ok(explanation)
+}
+
+const INPUT: &str = "input/day14.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if std::env::args().skip(1).any(|arg| arg == "--explain") {
+        print!("{}", explain(input)?);
+    }
+    if artifacts::requested_dir().is_some() {
+        let (_, _, rules) = parse_input(stream_items_from_file(input)?);
+        for steps in [10, 40] {
+            artifacts::write_if_requested("day14", &ComposedRules::power(&rules, steps))?;
+        }
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::{simulation::iterate_n, test_helpers::create_line_file};
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                NNCB
+
+                CH -> B
+                HH -> N
+                CB -> H
+                NH -> C
+                HB -> C
+                HC -> B
+                HN -> C
+                NN -> C
+                BH -> H
+                NC -> B
+                NB -> B
+                BN -> B
+                BB -> N
+                BC -> B
+                CC -> N
+                CN -> C"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_explain() {
+        let (dir, file) = example_file();
+        let counts = grow(&file, 10).unwrap();
+        assert_eq!((counts[&'B'], counts[&'H']), (1749, 161));
+        assert_eq!(spread(&counts), 1588);
+
+        let explanation = explain(&file).unwrap();
+        let lines = explanation.lines().collect_vec();
+        assert_eq!(lines[0], "After 10 steps, most minus least common: 1588");
+        assert!(lines[1].starts_with("B | ########################################"));
+        assert!(lines[1].ends_with(" 1749"));
+        assert_eq!(
+            lines[5],
+            "After 40 steps, most minus least common: 2188189693529"
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_composed_rules() {
+        let (dir, file) = example_file();
+        let (counts, pairs, rules) = parse_input(stream_items_from_file(&file).unwrap());
+        for steps in [0, 1, 2, 5, 10, 13] {
+            let mut iterated = counts.clone();
+            let (iterated_pairs, _) = iterate_n(pairs.clone(), steps, |pairs, _| {
+                execute_rules(&mut iterated, pairs, &rules)
+            });
+            let composed = ComposedRules::power(&rules, steps);
+            assert_eq!(composed.apply(&counts, &pairs), iterated, "{} steps", steps);
+            // The pairs follow from the table as well, not only the element counts
+            let mut composed_pairs = ElementPairCounts::new();
+            for (pair, &count) in &pairs {
+                match composed.table.get(pair) {
+                    Some(expansion) => expansion.pairs.iter().for_each(|(&p, &c)| {
+                        *composed_pairs.entry(p).or_insert(0) += count * c;
+                    }),
+                    None => *composed_pairs.entry(*pair).or_insert(0) += count,
+                }
+            }
+            assert_eq!(composed_pairs, iterated_pairs, "{} steps", steps);
+        }
+        let single = ComposedRules::single_step(&rules);
+        assert_eq!(
+            single.then(&single.then(&single)),
+            ComposedRules::power(&rules, 3)
+        );
+
+        let (name, table) = ComposedRules::power(&rules, 2).artifacts().swap_remove(0);
+        assert_eq!(name, "rules_2_steps.txt");
+        assert_eq!(table.lines().count(), 16);
+        // NN -> NCN -> NBCCN
+        assert!(table
+            .lines()
+            .contains(&"NN -> pairs BC=1 CC=1 CN=1 NB=1, inserts B=1 C=2"));
+        drop(dir);
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::search::{astar, render_on_field, weighted_astar, SearchObserver, SearchRecord};
+use aoc2021::{field2d::Field2D, stream_grid_from_file};
+use itertools::Itertools;
+use std::{collections::BinaryHeap, cmp::Reverse};
+
+type RiskField = Field2D<u32>;
+
+fn read_risk_field<I: Input>(input: I) -> Result<RiskField> {
+    Ok(stream_grid_from_file(input, |c| c.to_digit(10).ok_or("not a digit"))?)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PathFindEntry { 
+    score: u32,
+    node: (usize,usize),
+}
+
+impl PartialOrd for PathFindEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathFindEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score
+            .cmp(&other.score)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+type Pos = (usize, usize);
+
+fn cheapest_path(field: &RiskField, from: Pos, to: Pos) -> Option<u32> {
+    search_path(field, from, to, &mut ()).map(|(risk, _)| risk)
+}
+
+/// A euclidean distance to `to` as the heuristic, this works since every move costs at least 1 risk.
+fn euclidean_to(to: Pos) -> impl Fn(&Pos) -> u32 {
+    move |pos| ((to.0.abs_diff(pos.0).pow(2) + to.1.abs_diff(pos.1).pow(2)) as f32).sqrt() as u32
+}
+
+/// A* path search that reports its progress to `observer` and returns the lowest risk together with the path.
+fn search_path<O: SearchObserver<Pos>>(field: &RiskField, from: Pos, to: Pos, observer: &mut O) -> Option<(u32, Vec<Pos>)> {
+    astar(
+        from,
+        |&(x, y)| field.neighbors(x, y).map(|pos| (pos, field[pos])),
+        euclidean_to(to),
+        |&pos| pos == to,
+        observer,
+    )
+}
+
+/// Weighted A* with the heuristic scaled by `weight`, rounded to hundredths. The risk of the returned path
+/// is at most `weight` times the lowest risk, in exchange the search usually expands far fewer cells.
+fn weighted_search_path<O: SearchObserver<Pos>>(
+    field: &RiskField,
+    from: Pos,
+    to: Pos,
+    weight: f64,
+    observer: &mut O,
+) -> Option<(u32, Vec<Pos>)> {
+    weighted_astar(
+        from,
+        |&(x, y)| field.neighbors(x, y).map(|pos| (pos, field[pos])),
+        euclidean_to(to),
+        ((weight * 100.0).round() as u32, 100),
+        |&pos| pos == to,
+        observer,
+    )
+}
+
+fn search_record(field: &RiskField, from: Pos, to: Pos) -> SearchRecord<Pos> {
+    let mut record = SearchRecord::default();
+    search_path(field, from, to, &mut record);
+    record
+}
+
+/// The risk of the path a weighted search finds, with the number of cells it expanded.
+fn weighted_search(field: &RiskField, from: Pos, to: Pos, weight: f64) -> (u32, usize) {
+    let mut record = SearchRecord::default();
+    let (risk, _) = weighted_search_path(field, from, to, weight, &mut record).expect("Every cell of the field is reachable");
+    (risk, record.expansions.len())
+}
+
+fn path_find(field: &RiskField) -> Option<u32> {
+    cheapest_path(field, (0, 0), (field.width() - 1, field.height() - 1))
+}
+
+/// Lowest total risk from `from` to every position of the field, computed with Dijkstra's algorithm.
+fn distance_field(field: &RiskField, from: Pos) -> Field2D<u32> {
+    let mut distances = Field2D::new_empty(field.width(), field.height());
+    distances.iter_mut().for_each(|d| *d = u32::MAX);
+    let mut open_nodes = BinaryHeap::new();
+
+    distances[from] = 0;
+    open_nodes.push(Reverse(PathFindEntry {score: 0, node: from}));
+
+    while let Some(Reverse(current)) = open_nodes.pop() {
+        if current.score > distances[current.node] {
+            continue;
+        }
+        for neighbor in field.neighbors(current.node.0, current.node.1) {
+            let cand_score = current.score + field[neighbor];
+            if cand_score < distances[neighbor] {
+                distances[neighbor] = cand_score;
+                open_nodes.push(Reverse(PathFindEntry { score: cand_score, node: neighbor}));
+            }
+        }
+    }
+
+    distances
+}
+
+fn render_heatmap(distances: &Field2D<u32>) -> String {
+    const SHADES: &[u8] = b" .:-=+*#%@";
+    let max = distances.iter().copied().filter(|&d| d != u32::MAX).max().unwrap_or(0).max(1) as usize;
+    (0..distances.height())
+        .map(|y| {
+            (0..distances.width())
+                .map(|x| match distances[(x, y)] {
+                    u32::MAX => '?',
+                    d => SHADES[d as usize * (SHADES.len() - 1) / max] as char,
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+fn part1<I: Input>(input: I) -> Result<u32> {
+    let field = read_risk_field(input)?;
+    let min_risk = path_find(&field).unwrap();
+    Ok(min_risk)
+}
+
+fn quintuple_field(input: &RiskField) -> RiskField {
+    let mut new_field = RiskField::new_empty(input.width() * 5, input.height() * 5);
+    (0..5).cartesian_product(0..5).for_each(|(field_x,field_y)| {
+        let (offset_x, offset_y) = (field_x * input.width(), field_y * input.height()); 
+        (0..input.width()).cartesian_product(0..input.height()).for_each(|(ox,oy)| {
+            new_field[(offset_x + ox, offset_y + oy)] = (input[(ox,oy)] + field_x as u32 + field_y as u32 - 1) % 9 + 1;
+        });
+    });
+
+    new_field
+}
+
+fn part2<I: Input>(input: I) -> Result<u32> {
+    let field = quintuple_field(&read_risk_field(input)?);
+    let min_risk = path_find(&field).unwrap();
+    Ok(min_risk)
+}
+
+const INPUT: &str = "input/day15.txt";
+
+fn parse_pos(s: &str) -> Result<Pos> {
+    let (x, y) = s.split_once(',').ok_or_else(|| anyhow!("Expected a position like 3,4 but got {}", s))?;
+    Ok((x.parse()?, y.parse()?))
+}
+
+/// Answers queries of the form `[--full] [--from X,Y] [--to X,Y]... [--heatmap] [--search] [--heuristic-weight W [--verify]]`
+/// `--search` shows the cells the A* search expanded for each goal, with its frontier and the path on top.
+/// `--heuristic-weight` searches with weighted A* instead, `--verify` compares its result with the optimal search.
+fn run_queries(input: &InputSource, args: &[String]) -> Result<()> {
+    let mut field = read_risk_field(input)?;
+    let mut from = (0, 0);
+    let mut goals = Vec::new();
+    let mut heatmap = false;
+    let mut search = false;
+    let mut weight = None;
+    let mut verify = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--full" => field = quintuple_field(&field),
+            "--from" => from = parse_pos(args.next().ok_or_else(|| anyhow!("--from needs a position"))?)?,
+            "--to" => goals.push(parse_pos(args.next().ok_or_else(|| anyhow!("--to needs a position"))?)?),
+            "--heatmap" => heatmap = true,
+            "--search" => search = true,
+            "--heuristic-weight" => {
+                let w: f64 = args.next().ok_or_else(|| anyhow!("--heuristic-weight needs a weight"))?.parse()?;
+                if !(w >= 1.0 && w.is_finite()) {
+                    return Err(anyhow!("The heuristic weight must be at least 1 but is {}", w));
+                }
+                weight = Some(w);
+            }
+            "--verify" => verify = true,
+            other => return Err(anyhow!("Unknown argument {}", other)),
+        }
+    }
+    if goals.is_empty() {
+        goals.push((field.width() - 1, field.height() - 1));
+    }
+    if let Some(&pos) = goals.iter().chain([&from]).find(|&&(x, y)| x >= field.width() || y >= field.height()) {
+        return Err(anyhow!("Position {:?} is outside of the {}x{} field", pos, field.width(), field.height()));
+    }
+
+    if let Some(weight) = weight {
+        for &goal in &goals {
+            let (risk, expansions) = weighted_search(&field, from, goal, weight);
+            println!("Weighted A* ({}) from {:?} to {:?}: risk {}, {} expansions", weight, from, goal, risk, expansions);
+            if verify {
+                let (optimal, optimal_expansions) = weighted_search(&field, from, goal, 1.0);
+                println!(
+                    "Optimal A* from {:?} to {:?}: risk {}, {} expansions, the weighted path is {:.1}% more risky",
+                    from, goal, optimal, optimal_expansions,
+                    (risk - optimal) as f64 * 100.0 / optimal.max(1) as f64
+                );
+            }
+        }
+        return Ok(());
+    }
+    if verify {
+        return Err(anyhow!("--verify needs a --heuristic-weight"));
+    }
+
+    let distances = distance_field(&field, from);
+    for &goal in &goals {
+        println!("Lowest risk from {:?} to {:?}: {}", from, goal, distances[goal]);
+    }
+    if heatmap {
+        println!("{}", render_heatmap(&distances));
+    }
+    if search {
+        for &goal in &goals {
+            let record = search_record(&field, from, goal);
+            println!("Search from {:?} to {:?}: {} expansions, {} left open", from, goal, record.expansions.len(), record.frontier().len());
+            println!("{}", render_on_field(&field, &record, |&risk| char::from_digit(risk, 10).unwrap_or('?')));
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return run_queries(input, &args);
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            1163751742
+            1381373672
+            2136511328
+            3694931569
+            7463417111
+            1319128137
+            1359912421
+            3125421639
+            1293138521
+            2311944581"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_path_find_entry_order() {
+        let entry = |score, node| PathFindEntry { score, node };
+        // `Ord` and `PartialOrd` agree and order by score first, the heap pops the cheapest entry
+        assert!(entry(3, (0, 0)) < entry(5, (0, 0)));
+        assert!(entry(3, (0, 1)) > entry(3, (0, 0)));
+        assert_eq!(
+            entry(3, (2, 0)).partial_cmp(&entry(4, (0, 0))),
+            Some(entry(3, (2, 0)).cmp(&entry(4, (0, 0))))
+        );
+        let mut heap: BinaryHeap<_> = [entry(7, (1, 1)), entry(2, (5, 5)), entry(4, (0, 3))]
+            .into_iter()
+            .map(Reverse)
+            .collect();
+        assert_eq!(heap.pop().map(|Reverse(entry)| entry.score), Some(2));
+        assert_eq!(heap.pop().map(|Reverse(entry)| entry.score), Some(4));
+    }
+
+    #[test]
+    fn test_custom_start_and_goal() {
+        let (dir, file) = example_file();
+        let field = read_risk_field(file).unwrap();
+        assert_eq!(cheapest_path(&field, (0, 0), (9, 9)), Some(40));
+        assert_eq!(cheapest_path(&field, (9, 9), (9, 9)), Some(0));
+        assert_eq!(cheapest_path(&field, (9, 9), (8, 9)), Some(8));
+        assert_eq!(cheapest_path(&field, (9, 0), (0, 9)), Some(distance_field(&field, (9, 0))[(0, 9)]));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_search_record() {
+        let (dir, file) = example_file();
+        let field = read_risk_field(file).unwrap();
+        let record = search_record(&field, (0, 0), (9, 9));
+        let path = record.path.clone().unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(9, 9)));
+        assert!(path.iter().tuple_windows().all(|(a, b)| a.0.abs_diff(b.0) + a.1.abs_diff(b.1) == 1));
+        assert_eq!(path.iter().skip(1).map(|&pos| field[pos]).sum::<u32>(), 40);
+        assert_eq!(record.expansions.first(), Some(&(0, 0)));
+        assert_eq!(record.expansions.last(), Some(&(9, 9)));
+        assert!(record.expansions.len() <= 100);
+        assert!(record.frontier().iter().all(|pos| !record.expansions.contains(pos)));
+
+        let rendered = render_on_field(&field, &record, |&risk| char::from_digit(risk, 10).unwrap());
+        assert_eq!(rendered.lines().count(), 10);
+        assert_eq!(rendered.matches("\x1b[1;97m").count(), path.len());
+        assert!(rendered.starts_with("\x1b[1;97m1"));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let (dir, file) = example_file();
+        let field = read_risk_field(file).unwrap();
+        let distances = distance_field(&field, (0, 0));
+        assert_eq!(distances[(0, 0)], 0);
+        assert_eq!(distances[(1, 0)], 1);
+        assert_eq!(distances[(0, 1)], 1);
+        assert_eq!(distances[(9, 9)], 40);
+        for (x, y) in (0..10).cartesian_product(0..10) {
+            assert_eq!(Some(distances[(x, y)]), cheapest_path(&field, (0, 0), (x, y)));
+        }
+
+        let heatmap = render_heatmap(&distances);
+        assert_eq!(heatmap.lines().count(), 10);
+        assert!(heatmap.starts_with(' '));
+        assert!(heatmap.contains('@'));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_weighted_search() {
+        let (dir, file) = example_file();
+        let field = read_risk_field(file).unwrap();
+        let full = quintuple_field(&field);
+        let goal = (full.width() - 1, full.height() - 1);
+        assert_eq!(weighted_search(&full, (0, 0), goal, 1.0).0, 315);
+
+        for weight in [1.5, 3.0, 5.0, 8.0] {
+            let (risk, _) = weighted_search(&full, (0, 0), goal, weight);
+            assert!(risk >= 315 && risk as f64 <= 315.0 * weight);
+        }
+        // The euclidean heuristic underestimates a lot on these risks, only large weights make a difference
+        let (risk, expansions) = weighted_search(&full, (0, 0), goal, 8.0);
+        assert!(risk > 315);
+        assert!(expansions < full.width() * full.height() / 10);
+
+        let mut record = SearchRecord::default();
+        let (risk, path) = weighted_search_path(&field, (0, 0), (9, 9), 3.0, &mut record).unwrap();
+        assert_eq!(path.iter().skip(1).map(|&pos| field[pos]).sum::<u32>(), risk);
+        assert_eq!(record.path, Some(path));
+        drop(dir);
+    }
+}
@@ -0,0 +1,928 @@
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{
+    bits::{self, Encoding},
+    repl,
+    repl::Repl,
+    stats, stream_items_from_file,
+    walk::{self, Visit, Visitor, Walk},
+};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+fn truncated() -> anyhow::Error {
+    anyhow!("Truncated transmission")
+}
+
+fn read_bit_triple(input: &mut impl Iterator<Item = bool>) -> Result<[bool; 3]> {
+    let tuple = input.next_tuple().ok_or_else(truncated);
+    tuple.map(|(v1, v2, v3)| [v1, v2, v3])
+}
+
+fn read_bit_quintuple(input: &mut impl Iterator<Item = bool>) -> Result<[bool; 5]> {
+    let tuple = input.next_tuple().ok_or_else(truncated);
+    tuple.map(|(v1, v2, v3, v4, v5)| [v1, v2, v3, v4, v5])
+}
+
+fn read_n_bits(input: &mut impl Iterator<Item = bool>, n: usize) -> Result<Vec<bool>> {
+    (0..n).map(|_| input.next().ok_or_else(truncated)).collect()
+}
+
+/// `value` followed by the lowest `count` bits of `bits`, an error if that needs more than 64 bits.
+fn append_bits(value: u64, bits: u64, count: u32) -> Result<u64> {
+    if value.leading_zeros() < count {
+        bail!("A literal is longer than 64 bits");
+    }
+    Ok(value << count | bits)
+}
+
+fn convert_literal(input: &[bool]) -> Result<u64> {
+    input
+        .iter()
+        .try_fold(0, |value, &bit| append_bits(value, bit as u64, 1))
+}
+
+#[derive(Debug)]
+struct Header {
+    version: u64,
+    typ: u64,
+}
+
+fn parse_header(input: &mut impl Iterator<Item = bool>) -> Result<(usize, Header)> {
+    let version = convert_literal(&read_bit_triple(input)?)?;
+    let typ = convert_literal(&read_bit_triple(input)?)?;
+    Ok((6, Header { version, typ }))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PacketContents {
+    Literal(u64),
+    Operator(u64, Vec<Packet>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Packet {
+    version: u64,
+    contents: PacketContents,
+}
+
+fn parse_packet(input: &mut impl Iterator<Item = bool>) -> Result<(usize, Packet)> {
+    let (header_len, header) = parse_header(input)?;
+    let (len, contents) = match header.typ {
+        4 => {
+            let mut full_bits = Vec::new();
+            loop {
+                let bits = read_bit_quintuple(input)?;
+                full_bits.extend_from_slice(&bits[1..]);
+                if !bits[0] {
+                    break;
+                }
+            }
+            (
+                full_bits.len() + full_bits.len() / 4 + header_len,
+                PacketContents::Literal(convert_literal(&full_bits)?),
+            )
+        }
+        _ => {
+            let mut children = Vec::new();
+            let length_type_id = input.next().ok_or_else(truncated)?;
+            let mut read_bits = 0;
+            if !length_type_id {
+                // Length type ID is 0, so we get 15 bits for the number of sub-packets
+                let total_subpacket_bits = convert_literal(&read_n_bits(input, 15)?)? as usize;
+                while read_bits < total_subpacket_bits {
+                    let (subpacket_bits, packet) = parse_packet(input)?;
+                    children.push(packet);
+                    read_bits += subpacket_bits;
+                }
+                read_bits += 15;
+            } else {
+                // Length type ID is 1, so we get 11 bits for the number of bits in the sub packets
+                let total_subpackets = convert_literal(&read_n_bits(input, 11)?)?;
+                for _ in 0..total_subpackets {
+                    let (subpacket_bits, packet) = parse_packet(input)?;
+                    children.push(packet);
+                    read_bits += subpacket_bits;
+                }
+                read_bits += 11;
+            }
+            (
+                read_bits + 1 + header_len,
+                PacketContents::Operator(header.typ, children),
+            )
+        }
+    };
+    Ok((
+        len,
+        Packet {
+            version: header.version,
+            contents,
+        },
+    ))
+}
+
+/// Computes the value of an operator, `None` if the result does not fit into a u64.
+type OperatorFn = Box<dyn Fn(&[u64]) -> Option<u64>>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+enum EvalError {
+    #[error("Unknown operator type {0}")]
+    UnknownOperator(u64),
+    #[error("Operator {name} needs {required} operands, got {got}")]
+    MissingOperands {
+        name: String,
+        required: usize,
+        got: usize,
+    },
+    #[error("Operator {0} overflowed")]
+    Overflow(String),
+}
+
+struct OperatorDef {
+    name: String,
+    /// The exact number of operands, `None` if the operator accepts any non-zero number of operands
+    arity: Option<usize>,
+    apply: OperatorFn,
+}
+
+/// Maps packet type IDs to the operators they represent.
+struct OperatorTable {
+    operators: HashMap<u64, OperatorDef>,
+}
+
+impl Default for OperatorTable {
+    fn default() -> Self {
+        let mut table = OperatorTable {
+            operators: HashMap::new(),
+        };
+        table.register(0, "sum", None, |v| {
+            v.iter().try_fold(0u64, |acc, &x| acc.checked_add(x))
+        });
+        table.register(1, "product", None, |v| {
+            v.iter().try_fold(1u64, |acc, &x| acc.checked_mul(x))
+        });
+        table.register(2, "min", None, |v| v.iter().min().copied());
+        table.register(3, "max", None, |v| v.iter().max().copied());
+        table.register(5, "gt", Some(2), |v| Some((v[0] > v[1]) as u64));
+        table.register(6, "lt", Some(2), |v| Some((v[0] < v[1]) as u64));
+        table.register(7, "eq", Some(2), |v| Some((v[0] == v[1]) as u64));
+        table
+    }
+}
+
+impl OperatorTable {
+    fn register<F>(&mut self, typ: u64, name: &str, arity: Option<usize>, apply: F)
+    where
+        F: Fn(&[u64]) -> Option<u64> + 'static,
+    {
+        self.operators.insert(
+            typ,
+            OperatorDef {
+                name: name.to_string(),
+                arity,
+                apply: Box::new(apply),
+            },
+        );
+    }
+
+    fn get(&self, typ: u64) -> Result<&OperatorDef, EvalError> {
+        self.operators
+            .get(&typ)
+            .ok_or(EvalError::UnknownOperator(typ))
+    }
+
+    /// Evaluates a packet. Surplus operands are ignored, use `validate` to reject them.
+    fn evaluate(&self, packet: &Packet) -> Result<u64, EvalError> {
+        match &packet.contents {
+            PacketContents::Literal(v) => Ok(*v),
+            PacketContents::Operator(typ, children) => {
+                self.get(*typ)?;
+                let operands = children
+                    .iter()
+                    .map(|child| self.evaluate(child))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.apply(*typ, &operands)
+            }
+        }
+    }
+
+    /// Applies the operator `typ` to the values of its sub-packets.
+    fn apply(&self, typ: u64, operands: &[u64]) -> Result<u64, EvalError> {
+        let op = self.get(typ)?;
+        let required = op.arity.unwrap_or(1);
+        if operands.len() < required {
+            return Err(EvalError::MissingOperands {
+                name: op.name.clone(),
+                required,
+                got: operands.len(),
+            });
+        }
+        let operands = match op.arity {
+            Some(arity) => &operands[..arity],
+            None => operands,
+        };
+        (op.apply)(operands).ok_or_else(|| EvalError::Overflow(op.name.clone()))
+    }
+
+    /// Checks that all operator types are known and get the exact number of operands they need.
+    fn validate(&self, packet: &Packet) -> Result<()> {
+        let mut validator = Validator {
+            table: self,
+            error: None,
+        };
+        walk::walk(packet, &mut validator);
+        validator.error.map_or(Ok(()), Err)
+    }
+}
+
+/// Stops at the first operator that `OperatorTable::validate` rejects.
+struct Validator<'a> {
+    table: &'a OperatorTable,
+    error: Option<anyhow::Error>,
+}
+
+impl Validator<'_> {
+    fn check(&self, packet: &Packet) -> Result<()> {
+        if let PacketContents::Operator(typ, children) = &packet.contents {
+            let op = self.table.get(*typ)?;
+            match op.arity {
+                Some(arity) if arity != children.len() => bail!(
+                    "Operator {} needs exactly {} operands, got {}",
+                    op.name,
+                    arity,
+                    children.len()
+                ),
+                None if children.is_empty() => bail!("Operator {} has no operands", op.name),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Visitor<Packet> for Validator<'_> {
+    fn enter(&mut self, packet: &Packet, _depth: usize) -> Visit {
+        match self.check(packet) {
+            Ok(()) => Visit::Continue,
+            Err(e) => {
+                self.error = Some(e);
+                Visit::Stop
+            }
+        }
+    }
+}
+
+/// Aggregates packets bottom-up while they are decoded, so no `Packet` tree is built.
+/// An operator is opened when its header is read, gets the values of its sub-packets one by one and is closed after the last one.
+trait PacketFold {
+    type Value;
+    type Partial;
+
+    fn literal(&mut self, version: u64, value: u64) -> Result<Self::Value>;
+    fn open(&mut self, version: u64, typ: u64) -> Result<Self::Partial>;
+    fn add(&mut self, partial: &mut Self::Partial, child: Self::Value) -> Result<()>;
+    fn close(&mut self, partial: Self::Partial) -> Result<Self::Value>;
+}
+
+struct VersionSum;
+
+impl PacketFold for VersionSum {
+    type Value = u64;
+    type Partial = u64;
+
+    fn literal(&mut self, version: u64, _value: u64) -> Result<u64> {
+        Ok(version)
+    }
+
+    fn open(&mut self, version: u64, _typ: u64) -> Result<u64> {
+        Ok(version)
+    }
+
+    fn add(&mut self, partial: &mut u64, child: u64) -> Result<()> {
+        *partial += child;
+        Ok(())
+    }
+
+    fn close(&mut self, partial: u64) -> Result<u64> {
+        Ok(partial)
+    }
+}
+
+/// Evaluates the transmission like `OperatorTable::evaluate`, only the operands of the open operators are kept.
+struct Evaluator<'a>(&'a OperatorTable);
+
+impl PacketFold for Evaluator<'_> {
+    type Value = u64;
+    type Partial = (u64, Vec<u64>);
+
+    fn literal(&mut self, _version: u64, value: u64) -> Result<u64> {
+        Ok(value)
+    }
+
+    fn open(&mut self, _version: u64, typ: u64) -> Result<(u64, Vec<u64>)> {
+        self.0.get(typ)?;
+        Ok((typ, Vec::new()))
+    }
+
+    fn add(&mut self, (_, operands): &mut (u64, Vec<u64>), child: u64) -> Result<()> {
+        operands.push(child);
+        Ok(())
+    }
+
+    fn close(&mut self, (typ, operands): (u64, Vec<u64>)) -> Result<u64> {
+        Ok(self.0.apply(typ, &operands)?)
+    }
+}
+
+/// Counts the bits it hands out, the sub-packet lengths are given in bits.
+struct BitReader<I> {
+    bits: I,
+    position: usize,
+}
+
+impl<I: Iterator<Item = bool>> BitReader<I> {
+    fn bit(&mut self) -> Result<bool> {
+        self.position += 1;
+        self.bits.next().ok_or_else(truncated)
+    }
+
+    fn number(&mut self, bits: usize) -> Result<u64> {
+        (0..bits).try_fold(0, |value, _| append_bits(value, self.bit()? as u64, 1))
+    }
+}
+
+/// The sub-packets an open operator still waits for.
+enum Remaining {
+    UntilPosition(usize),
+    Packets(u64),
+}
+
+/// Decodes the outermost packet of `bits` and folds it with `folder`.
+/// Nesting is tracked on an explicit stack that only holds the open operators, so deep transmissions can't overflow the call stack.
+fn fold_packet<F: PacketFold>(
+    bits: impl Iterator<Item = bool>,
+    folder: &mut F,
+) -> Result<F::Value> {
+    let mut reader = BitReader { bits, position: 0 };
+    let mut open: Vec<(F::Partial, Remaining)> = Vec::new();
+    loop {
+        let version = reader.number(3)?;
+        let typ = reader.number(3)?;
+        let mut value = if typ == 4 {
+            let mut literal = 0u64;
+            loop {
+                let more = reader.bit()?;
+                literal = append_bits(literal, reader.number(4)?, 4)?;
+                if !more {
+                    break;
+                }
+            }
+            folder.literal(version, literal)?
+        } else {
+            let partial = folder.open(version, typ)?;
+            let remaining = if reader.bit()? {
+                Remaining::Packets(reader.number(11)?)
+            } else {
+                let length = reader.number(15)? as usize;
+                Remaining::UntilPosition(reader.position + length)
+            };
+            match remaining {
+                Remaining::Packets(0) => folder.close(partial)?,
+                Remaining::UntilPosition(end) if end == reader.position => folder.close(partial)?,
+                remaining => {
+                    open.push((partial, remaining));
+                    continue;
+                }
+            }
+        };
+        // Hand the finished packet to its parent and close all operators that are complete now
+        loop {
+            let Some((partial, remaining)) = open.last_mut() else {
+                return Ok(value);
+            };
+            folder.add(partial, value)?;
+            let complete = match remaining {
+                Remaining::UntilPosition(end) => reader.position >= *end,
+                Remaining::Packets(count) => {
+                    *count -= 1;
+                    *count == 0
+                }
+            };
+            if !complete {
+                break;
+            }
+            let (partial, _) = open.pop().unwrap();
+            value = folder.close(partial)?;
+        }
+    }
+}
+
+/// Decodes a transmission. In strict mode the packets are validated against the operator table
+/// and the padding after the outermost packet must consist of zeros only.
+fn decode_bits(bits: Vec<bool>, table: &OperatorTable, strict: bool) -> Result<Packet> {
+    let mut iter = bits.into_iter();
+    let (_, packet) = parse_packet(&mut iter)?;
+    if strict {
+        if iter.any(|bit| bit) {
+            bail!("Non-zero padding bits after the outermost packet");
+        }
+        table.validate(&packet)?;
+    }
+    Ok(packet)
+}
+
+fn read_hex<I: Input>(input: I) -> Result<Vec<bool>> {
+    let hex: String = stream_items_from_file(input)?
+        .next()
+        .ok_or_else(|| anyhow!("Empty transmission"))?;
+    Ok(bits::parse_hex(&hex)?)
+}
+
+fn read_packet<I: Input>(input: I, strict: bool) -> Result<Packet> {
+    decode_bits(read_hex(input)?, &OperatorTable::default(), strict)
+}
+
+impl Walk for Packet {
+    fn for_each_child<F: FnMut(&Self) -> bool>(&self, f: F) -> bool {
+        match &self.contents {
+            PacketContents::Literal(_) => true,
+            PacketContents::Operator(_, children) => children.iter().all(f),
+        }
+    }
+}
+
+impl Packet {
+    fn render_tree(&self, table: &OperatorTable) -> String {
+        let mut renderer = TreeRenderer {
+            table,
+            out: String::new(),
+        };
+        walk::walk(self, &mut renderer);
+        renderer.out
+    }
+}
+
+/// One line per packet, sub-packets indented below their operator.
+struct TreeRenderer<'a> {
+    table: &'a OperatorTable,
+    out: String,
+}
+
+impl Visitor<Packet> for TreeRenderer<'_> {
+    fn enter(&mut self, packet: &Packet, depth: usize) -> Visit {
+        let indent = "  ".repeat(depth);
+        match &packet.contents {
+            PacketContents::Literal(v) => self
+                .out
+                .push_str(&format!("{}literal {} (v{})\n", indent, v, packet.version)),
+            PacketContents::Operator(op, _) => {
+                let name = self.table.get(*op).map_or("unknown", |op| &op.name);
+                let value = self
+                    .table
+                    .evaluate(packet)
+                    .map_or_else(|e| format!("<{}>", e), |v| v.to_string());
+                self.out.push_str(&format!(
+                    "{}{} = {} (v{})\n",
+                    indent, name, value, packet.version
+                ));
+            }
+        }
+        Visit::Continue
+    }
+}
+
+/// The shape of a transmission, for `--explain`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TransmissionStats {
+    /// Packet counts by type ID, literals have type 4
+    types: BTreeMap<u64, usize>,
+    max_depth: usize,
+    literals: Vec<u64>,
+}
+
+impl Visitor<Packet> for TransmissionStats {
+    fn enter(&mut self, packet: &Packet, depth: usize) -> Visit {
+        let typ = match &packet.contents {
+            PacketContents::Literal(value) => {
+                self.literals.push(*value);
+                4
+            }
+            PacketContents::Operator(typ, _) => *typ,
+        };
+        *self.types.entry(typ).or_default() += 1;
+        self.max_depth = self.max_depth.max(depth);
+        Visit::Continue
+    }
+}
+
+impl TransmissionStats {
+    fn of(packet: &Packet) -> Self {
+        let mut stats = TransmissionStats::default();
+        walk::walk(packet, &mut stats);
+        stats
+    }
+
+    fn packets(&self) -> usize {
+        self.types.values().sum()
+    }
+
+    /// The packet counts with the operator names of `table`.
+    fn type_counts(&self, table: &OperatorTable) -> Vec<(String, usize)> {
+        self.types
+            .iter()
+            .map(|(&typ, &count)| {
+                let name = match typ {
+                    4 => "literal".to_string(),
+                    _ => table
+                        .get(typ)
+                        .map_or_else(|_| format!("type {}", typ), |op| op.name.clone()),
+                };
+                (name, count)
+            })
+            .collect()
+    }
+
+    /// How many literals need how many bits.
+    fn literal_widths(&self) -> Vec<(String, usize)> {
+        let mut widths = BTreeMap::new();
+        for value in &self.literals {
+            *widths.entry(u64::BITS - value.leading_zeros()).or_default() += 1;
+        }
+        widths
+            .into_iter()
+            .map(|(width, count)| (format!("{} bits", width), count))
+            .collect()
+    }
+}
+
+/// Packet counts by type, the nesting depth, the distribution of the literal values and
+/// how many bits of the transmission are padding after the outermost packet.
+fn explain(bits: Vec<bool>, table: &OperatorTable) -> Result<String> {
+    let total = bits.len();
+    let (used, packet) = parse_packet(&mut bits.into_iter())?;
+    let stats = TransmissionStats::of(&packet);
+    let (smallest, largest) = stats::minmax(stats.literals.iter().copied()).unwrap_or_default();
+    Ok(format!(
+        "{} packets, nested {} deep\n{}\
+         {} literals from {} to {}, median {}\n{}\
+         {} of {} bits used, {} bits of padding\n",
+        stats.packets(),
+        stats.max_depth,
+        stats::bar_chart(&stats.type_counts(table), 40),
+        stats.literals.len(),
+        smallest,
+        largest,
+        stats::percentile(&stats.literals, 50.0).unwrap_or_default(),
+        stats::bar_chart(&stats.literal_widths(), 40),
+        used,
+        total,
+        total - used
+    ))
+}
+
+struct PacketExplorer {
+    packet: Packet,
+    table: OperatorTable,
+}
+
+impl Repl for PacketExplorer {
+    fn eval(&mut self, command: &str) -> Result<String> {
+        match command {
+            "eval" => Ok(self.table.evaluate(&self.packet)?.to_string()),
+            "validate" => {
+                self.table.validate(&self.packet)?;
+                Ok("ok".to_string())
+            }
+            "tree" => Ok(self.packet.render_tree(&self.table).trim_end().to_string()),
+            _ => bail!("Unknown command {}", command),
+        }
+    }
+
+    fn help(&self) -> String {
+        "eval: evaluate the outermost packet\n\
+         validate: check operand counts of all operators\n\
+         tree: show the packet hierarchy"
+            .to_string()
+    }
+}
+
+fn part1<I: Input>(input: I) -> Result<u64> {
+    fold_packet(read_hex(input)?.into_iter(), &mut VersionSum)
+}
+
+fn part2<I: Input>(input: I) -> Result<u64> {
+    let table = OperatorTable::default();
+    fold_packet(read_hex(input)?.into_iter(), &mut Evaluator(&table))
+        .map_err(|e| anyhow!("Could not evaluate the transmission: {}", e))
+}
+
+const INPUT: &str = "input/day16.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if repl::requested() {
+        let mut explorer = PacketExplorer {
+            packet: read_packet(input, false)?,
+            table: OperatorTable::default(),
+        };
+        return Ok(repl::run(&mut explorer)?);
+    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let ["--decode", encoding, path] = args.iter().map(String::as_str).collect_vec()[..] {
+        // Folded instead of decoded, transmissions given this way may be too deep or large for a tree
+        let table = OperatorTable::default();
+        let bits = bits::read_bits(path, encoding.parse::<Encoding>()?)?;
+        let value = fold_packet(bits.iter().copied(), &mut Evaluator(&table))?;
+        println!("Value: {}", value);
+        println!(
+            "Version sum: {}",
+            fold_packet(bits.into_iter(), &mut VersionSum)?
+        );
+        return Ok(());
+    }
+    if args.iter().any(|arg| arg == "--explain") {
+        print!("{}", explain(read_hex(input)?, &OperatorTable::default())?);
+    }
+    if args.iter().any(|arg| arg == "--strict") {
+        read_packet(input, true)?;
+        println!("Transmission passed strict validation");
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file5() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(["C200B40A82"].iter(), None)
+    }
+
+    fn literal(value: u64) -> Packet {
+        Packet {
+            version: 0,
+            contents: PacketContents::Literal(value),
+        }
+    }
+
+    fn decode_packet(hex: &str, table: &OperatorTable, strict: bool) -> Result<Packet> {
+        decode_bits(bits::parse_hex(hex)?, table, strict)
+    }
+
+    fn operator(typ: u64, children: Vec<Packet>) -> Packet {
+        Packet {
+            version: 0,
+            contents: PacketContents::Operator(typ, children),
+        }
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let table = OperatorTable::default();
+        assert_eq!(decode_packet("D2FE28", &table, true).unwrap().version, 6);
+        assert!(decode_packet("D2FE29", &table, false).is_ok());
+        assert!(decode_packet("D2FE29", &table, true).is_err());
+        let packet = decode_packet("C200B40A82", &table, true).unwrap();
+        assert_eq!(table.evaluate(&packet).unwrap(), 3);
+
+        let too_many_operands = operator(5, vec![literal(1), literal(2), literal(3)]);
+        assert_eq!(table.evaluate(&too_many_operands).unwrap(), 0);
+        assert!(table.validate(&too_many_operands).is_err());
+        assert!(table.evaluate(&operator(6, vec![literal(1)])).is_err());
+        assert!(table.validate(&operator(0, vec![])).is_err());
+
+        let (dir, file) = example_file5();
+        assert_eq!(read_packet(&file, true).unwrap(), packet);
+        drop(dir);
+        // An empty transmission is an error in strict mode as well
+        let (dir, file) = create_line_file(std::iter::empty::<&str>(), None);
+        assert!(read_packet(&file, true).is_err());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        let mut table = OperatorTable::default();
+        let packet = operator(9, vec![operator(0, vec![literal(20), literal(1)])]);
+        assert!(table.evaluate(&packet).is_err());
+        assert!(table.validate(&packet).is_err());
+
+        table.register(9, "double", Some(1), |v| v[0].checked_mul(2));
+        assert!(table.validate(&packet).is_ok());
+        assert_eq!(table.evaluate(&packet).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let table = OperatorTable::default();
+        assert_eq!(
+            table.evaluate(&operator(4711, vec![literal(1)])),
+            Err(EvalError::UnknownOperator(4711))
+        );
+        assert_eq!(
+            table.evaluate(&operator(2, vec![])),
+            Err(EvalError::MissingOperands {
+                name: "min".to_string(),
+                required: 1,
+                got: 0
+            })
+        );
+        let huge = literal(u64::MAX / 2 + 1);
+        assert_eq!(
+            table.evaluate(&operator(1, vec![huge.clone(), literal(2)])),
+            Err(EvalError::Overflow("product".to_string()))
+        );
+        assert_eq!(
+            table.evaluate(&operator(0, vec![huge.clone(), huge.clone()])),
+            Err(EvalError::Overflow("sum".to_string()))
+        );
+        // Overflows only matter if they happen, comparisons of large values are fine
+        assert_eq!(table.evaluate(&operator(5, vec![huge, literal(2)])), Ok(1));
+    }
+
+    #[test]
+    fn test_tolerant_encodings() {
+        let table = OperatorTable::default();
+        let expected = decode_packet("9C0141080250320F1802104A08", &table, true).unwrap();
+        assert_eq!(
+            decode_packet("9c0141080250320f1802104a08\n", &table, true).unwrap(),
+            expected
+        );
+        assert_eq!(
+            decode_packet(" 9C01 4108\t0250 320F 1802 104A 08 ", &table, true).unwrap(),
+            expected
+        );
+        assert!(decode_packet("9C0141080250320F1802104A0G", &table, false).is_err());
+
+        let base64 = bits::parse_base64("nAFBCAJQMg8YAhBKCA==").unwrap();
+        assert_eq!(
+            base64,
+            bits::parse_hex("9C0141080250320F1802104A08").unwrap()
+        );
+        assert_eq!(decode_bits(base64, &table, true).unwrap(), expected);
+        assert_eq!(
+            bits::parse_base64("nAFBCAJQMg8YAhBKCA").unwrap().len(),
+            13 * 8
+        );
+        assert!(bits::parse_base64("nAFBC").is_err());
+        assert!(bits::parse_base64("nA-B").is_err());
+
+        let raw = [
+            0x9C, 0x01, 0x41, 0x08, 0x02, 0x50, 0x32, 0x0F, 0x18, 0x02, 0x10, 0x4A, 0x08,
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transmission.bin");
+        std::fs::write(&path, raw).unwrap();
+        let packet = decode_bits(bits::read_bits(&path, Encoding::Raw).unwrap(), &table, true);
+        assert_eq!(packet.unwrap(), expected);
+        assert_eq!("RAW".parse::<Encoding>().unwrap(), Encoding::Raw);
+        drop(dir);
+    }
+
+    /// The tree based reference for the fold.
+    fn sum_versions(packet: Packet) -> u64 {
+        let mut sum = 0;
+        let mut stack = Vec::new();
+        stack.push(packet);
+
+        while let Some(packet) = stack.pop() {
+            sum += packet.version;
+            match packet.contents {
+                PacketContents::Literal(_) => (),
+                PacketContents::Operator(_, mut children) => stack.append(&mut children),
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_streaming_fold() {
+        let table = OperatorTable::default();
+        for hex in [
+            "8A004A801A8002F478",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "9C0141080250320F1802104A08",
+            "D2FE28",
+        ] {
+            let bits = bits::parse_hex(hex).unwrap();
+            let packet = decode_bits(bits.clone(), &table, false).unwrap();
+            let versions = fold_packet(bits.iter().copied(), &mut VersionSum).unwrap();
+            assert_eq!(versions, sum_versions(packet.clone()), "{}", hex);
+            let value = fold_packet(bits.into_iter(), &mut Evaluator(&table)).unwrap();
+            assert_eq!(value, table.evaluate(&packet).unwrap(), "{}", hex);
+        }
+        let truncated = bits::parse_hex("D2FE").unwrap();
+        assert!(fold_packet(truncated.into_iter(), &mut VersionSum).is_err());
+        let uses_eq = bits::parse_hex("9C0141080250320F1802104A08").unwrap();
+        let mut table_without_eq = OperatorTable::default();
+        table_without_eq.operators.remove(&7);
+        assert!(fold_packet(uses_eq.into_iter(), &mut Evaluator(&table_without_eq)).is_err());
+
+        // 16 groups of 4 bits are the longest literal that fits, a 17th one can't be dropped silently
+        let literal = |groups: usize| {
+            let mut bits = vec![false, false, false, true, false, false];
+            for group in 0..groups {
+                bits.push(group + 1 < groups);
+                bits.extend([true, false, false, true]);
+            }
+            bits
+        };
+        assert_eq!(
+            fold_packet(literal(16).into_iter(), &mut Evaluator(&table)).unwrap(),
+            0x9999_9999_9999_9999
+        );
+        assert!(fold_packet(literal(17).into_iter(), &mut Evaluator(&table))
+            .unwrap_err()
+            .to_string()
+            .contains("longer than 64 bits"));
+        // The tree decoder agrees on both
+        let packet = decode_bits(literal(16), &table, false).unwrap();
+        assert_eq!(
+            packet.contents,
+            PacketContents::Literal(0x9999_9999_9999_9999)
+        );
+        assert!(decode_bits(literal(17), &table, false).is_err());
+        assert_eq!(convert_literal(&[true; 64]).unwrap(), u64::MAX);
+        assert!(convert_literal(&[true; 65]).is_err());
+
+        // Far too deep for the recursive parser: sums of a single operand, nested 100000 times
+        let push = |bits: &mut Vec<bool>, value: u64, count: usize| {
+            bits.extend((0..count).rev().map(|bit| value >> bit & 1 == 1))
+        };
+        let depth = 100_000;
+        let mut deep = Vec::new();
+        for _ in 0..depth {
+            push(&mut deep, 7, 3);
+            push(&mut deep, 0, 3);
+            push(&mut deep, 1, 1);
+            push(&mut deep, 1, 11);
+        }
+        push(&mut deep, 1, 3);
+        push(&mut deep, 4, 3);
+        push(&mut deep, 0b01010, 5);
+        let versions = fold_packet(deep.iter().copied(), &mut VersionSum).unwrap();
+        assert_eq!(versions, 7 * depth + 1);
+        assert_eq!(
+            fold_packet(deep.into_iter(), &mut Evaluator(&table)).unwrap(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let table = OperatorTable::default();
+        let bits = bits::parse_hex("9C0141080250320F1802104A08").unwrap();
+        let packet = decode_bits(bits.clone(), &table, false).unwrap();
+        let stats = TransmissionStats::of(&packet);
+        assert_eq!(stats.packets(), 7);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.literals, vec![1, 3, 2, 2]);
+        assert_eq!(
+            stats.type_counts(&table),
+            vec![
+                ("sum".to_string(), 1),
+                ("product".to_string(), 1),
+                ("literal".to_string(), 4),
+                ("eq".to_string(), 1)
+            ]
+        );
+
+        let explanation = explain(bits, &table).unwrap();
+        let lines = explanation.lines().collect_vec();
+        assert_eq!(lines[0], "7 packets, nested 2 deep");
+        assert!(explanation.contains("4 literals from 1 to 3, median 2"));
+        assert!(explanation.ends_with("102 of 104 bits used, 2 bits of padding\n"));
+        assert!(explain(bits::parse_hex("D2FE").unwrap(), &table).is_err());
+    }
+
+    #[test]
+    fn test_repl() {
+        let (dir, file) = example_file5();
+        let mut explorer = PacketExplorer {
+            packet: read_packet(file, false).unwrap(),
+            table: OperatorTable::default(),
+        };
+        drop(dir);
+
+        let mut output = Vec::new();
+        repl::run_with(&mut explorer, "eval\ntree\nfoo\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect_vec();
+        assert_eq!(lines[0], "> 3");
+        assert!(lines[1].starts_with("> sum = 3"));
+        assert!(lines[2].starts_with("  literal 1"));
+        assert!(lines[3].starts_with("  literal 2"));
+        assert!(lines[4].starts_with("> Error: Unknown command foo"));
+    }
+}
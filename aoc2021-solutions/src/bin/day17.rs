@@ -0,0 +1,336 @@
+use anyhow::anyhow;
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::{parse::extract_ints, stream_items_from_file};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    io::{self, Write},
+};
+
+#[derive(Debug)]
+struct TargetArea {
+    x_area: (i32, i32),
+    y_area: (i32, i32),
+}
+
+fn parse_input(input: &str) -> Result<TargetArea> {
+    // Don't bother checking the fluff around the numbers, just grab the numbers and go
+    let [x0, x1, y0, y1] = extract_ints(input)?;
+
+    Ok(TargetArea {
+        x_area: (x0, x1),
+        y_area: (y0, y1),
+    })
+}
+
+trait VelocityLogic {
+    fn step_velocity(vel: i32) -> i32;
+}
+
+struct YVelocityLogic();
+
+impl VelocityLogic for YVelocityLogic {
+    fn step_velocity(vel: i32) -> i32 {
+        vel - 1
+    }
+}
+
+struct XVelocityLogic();
+
+impl VelocityLogic for XVelocityLogic {
+    fn step_velocity(vel: i32) -> i32 {
+        vel - vel.signum()
+    }
+}
+
+fn check_area_hit<L: VelocityLogic>(target_range: &(i32, i32), mut velocity: i32) -> bool {
+    let mut pos = 0;
+    let init_cmp = (pos.cmp(&target_range.0), pos.cmp(&target_range.1));
+
+    loop {
+        let cmp = (pos.cmp(&target_range.0), pos.cmp(&target_range.1));
+        if cmp.0 != cmp.1 {
+            return true;
+        } else if cmp != init_cmp {
+            return false;
+        } else {
+            pos += velocity;
+            let new_velocity = L::step_velocity(velocity);
+            if new_velocity == velocity && new_velocity == 0 {
+                return false;
+            }
+            velocity = new_velocity;
+        }
+    }
+}
+
+fn find_max_velocity_y(target_range: &(i32, i32)) -> i32 {
+    // Using this velocity, we will have target_range.0 velocity on our 0-crossing, allowing us to do a single step to the end of the target range from there
+    -target_range.0 - 1
+}
+
+fn get_y_range(target_range: &(i32, i32)) -> Vec<i32> {
+    let min = target_range.0; // Fastest downwards shot we can do is immediately reaching the target region
+    let max = find_max_velocity_y(target_range);
+    (min..=max)
+        .filter(|&vel| check_area_hit::<YVelocityLogic>(target_range, vel))
+        .collect()
+}
+
+// Find an approximate minimal value for the x velocity that will get us to the given target value (Using the inverse of the Gauss formula)
+fn find_x_velocity_approx(target: i32) -> i32 {
+    (((2 * target) as f64 + 0.25).sqrt() - 0.5).floor() as i32
+}
+
+fn get_x_range(target_range: &(i32, i32)) -> Vec<i32> {
+    let min = find_x_velocity_approx(target_range.0);
+    let max = target_range.1; // Fastest we can do is a single step to the end of the target range
+
+    // Filter for values that actually end up hitting the target range
+    (min..=max)
+        .filter(|&vel| check_area_hit::<XVelocityLogic>(target_range, vel))
+        .collect()
+}
+
+fn find_max_height(velocity: i32) -> i32 {
+    if velocity < 0 {
+        0
+    } else {
+        (velocity * (velocity + 1)) / 2
+    }
+}
+
+/// The positions a probe passes through, starting with the first step after the launch.
+struct Trajectory {
+    pos: (i32, i32),
+    velocity: (i32, i32),
+}
+
+impl Trajectory {
+    fn new(velocity: (i32, i32)) -> Self {
+        Self {
+            pos: (0, 0),
+            velocity,
+        }
+    }
+}
+
+impl Iterator for Trajectory {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pos.0 += self.velocity.0;
+        self.pos.1 += self.velocity.1;
+        self.velocity = (
+            XVelocityLogic::step_velocity(self.velocity.0),
+            YVelocityLogic::step_velocity(self.velocity.1),
+        );
+        Some(self.pos)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shot {
+    velocity: (i32, i32),
+    /// Number of steps until the probe is within the target for the first time
+    steps: usize,
+    peak: i32,
+}
+
+fn simulate(velocity: (i32, i32), target: &TargetArea) -> Option<Shot> {
+    let mut peak = 0;
+    for (step, pos) in Trajectory::new(velocity).enumerate() {
+        if pos.0 > target.x_area.1 || pos.1 < target.y_area.0 {
+            return None;
+        }
+        peak = peak.max(pos.1);
+        // We haven't overshot the outer bounds of our target yet; did we cross the lower bounds?
+        if pos.0 >= target.x_area.0 && pos.1 <= target.y_area.1 {
+            return Some(Shot {
+                velocity,
+                steps: step + 1,
+                peak,
+            });
+        }
+    }
+    unreachable!("Trajectories are infinite")
+}
+
+fn check_hit(velocity: (i32, i32), target: &TargetArea) -> bool {
+    simulate(velocity, target).is_some()
+}
+
+fn valid_shots(target: &TargetArea) -> Vec<Shot> {
+    let yrange = get_y_range(&target.y_area);
+    get_x_range(&target.x_area)
+        .into_iter()
+        .cartesian_product(yrange)
+        .filter_map(|velocity| simulate(velocity, target))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ShotStatistics {
+    shots: usize,
+    /// Number of shots per step count until landing
+    steps: BTreeMap<usize, usize>,
+    min_peak: i32,
+    max_peak: i32,
+    mean_peak: f64,
+}
+
+impl ShotStatistics {
+    fn new(shots: &[Shot]) -> Option<Self> {
+        Some(Self {
+            shots: shots.len(),
+            steps: shots
+                .iter()
+                .map(|shot| shot.steps)
+                .counts()
+                .into_iter()
+                .collect(),
+            min_peak: shots.iter().map(|shot| shot.peak).min()?,
+            max_peak: shots.iter().map(|shot| shot.peak).max()?,
+            mean_peak: shots.iter().map(|shot| shot.peak as f64).sum::<f64>() / shots.len() as f64,
+        })
+    }
+}
+
+impl Display for ShotStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Valid shots: {}", self.shots)?;
+        writeln!(
+            f,
+            "Peak height: min {}, max {}, mean {:.2}",
+            self.min_peak, self.max_peak, self.mean_peak
+        )?;
+        write!(f, "Steps until landing:")?;
+        for (steps, count) in self.steps.iter() {
+            write!(f, "\n{:>5}: {}", steps, count)?;
+        }
+        Ok(())
+    }
+}
+
+fn fraction_peaking_above(shots: &[Shot], height: i32) -> f64 {
+    shots.iter().filter(|shot| shot.peak > height).count() as f64 / shots.len() as f64
+}
+
+fn write_csv<W: Write>(shots: &[Shot], mut out: W) -> io::Result<()> {
+    writeln!(out, "vx,vy,steps,peak")?;
+    for shot in shots {
+        writeln!(
+            out,
+            "{},{},{},{}",
+            shot.velocity.0, shot.velocity.1, shot.steps, shot.peak
+        )?;
+    }
+    Ok(())
+}
+
+fn read_target<I: Input>(input: I) -> Result<TargetArea> {
+    parse_input(
+        &stream_items_from_file::<_, String>(input)?
+            .next()
+            .ok_or(anyhow!("No input"))?,
+    )
+}
+
+fn part1<I: Input>(input: I) -> Result<i32> {
+    let target = read_target(input)?;
+    let max_v = find_max_velocity_y(&target.y_area);
+    Ok(find_max_height(max_v))
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let target = read_target(input)?;
+    let xrange = get_x_range(&target.x_area);
+    let yrange = get_y_range(&target.y_area);
+
+    Ok(xrange
+        .iter()
+        .flat_map(|&xvel| {
+            let target = &target;
+            yrange
+                .iter()
+                .filter(move |&&yvel| check_hit((xvel, yvel), target))
+                .map(move |&yvel| (xvel, yvel))
+        })
+        .count())
+}
+
+const INPUT: &str = "input/day17.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--csv"] => {
+            let shots = valid_shots(&read_target(input)?);
+            write_csv(&shots, io::stdout().lock())?;
+            return Ok(());
+        }
+        ["--stats"] => {
+            let shots = valid_shots(&read_target(input)?);
+            let stats = ShotStatistics::new(&shots).ok_or(anyhow!("No valid shots"))?;
+            println!("{}", stats);
+            return Ok(());
+        }
+        ["--above", height] => {
+            let shots = valid_shots(&read_target(input)?);
+            let fraction = fraction_peaking_above(&shots, height.parse()?);
+            println!(
+                "{:.2}% of valid shots peak above {}",
+                fraction * 100.0,
+                height
+            );
+            return Ok(());
+        }
+        _ => (),
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate() {
+        let target = parse_input("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!(
+            simulate((7, 2), &target),
+            Some(Shot {
+                velocity: (7, 2),
+                steps: 7,
+                peak: 3
+            })
+        );
+        assert_eq!(simulate((17, -4), &target), None);
+        assert_eq!(simulate((6, 9), &target).unwrap().peak, 45);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let target = parse_input("target area: x=20..30, y=-10..-5").unwrap();
+        let shots = valid_shots(&target);
+        let stats = ShotStatistics::new(&shots).unwrap();
+        assert_eq!(stats.shots, 112);
+        assert_eq!(stats.steps.values().sum::<usize>(), 112);
+        assert_eq!(stats.min_peak, 0);
+        assert_eq!(stats.max_peak, 45);
+        assert_eq!(fraction_peaking_above(&shots, 45), 0.0);
+        assert_eq!(fraction_peaking_above(&shots, -1), 1.0);
+
+        let mut csv = Vec::new();
+        write_csv(&shots, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 113);
+        assert_eq!(csv.lines().next(), Some("vx,vy,steps,peak"));
+    }
+}
@@ -0,0 +1,595 @@
+use anyhow::anyhow;
+use anyhow::{bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::stream_items_from_file;
+use aoc2021::walk::{self, Visit, Visitor, Walk};
+use itertools::Itertools;
+use std::fmt::{Debug, Display};
+use std::{cell::RefCell, iter::Peekable, rc::Rc, str::FromStr};
+
+// Walkable SnailFishExpr tree
+
+#[derive(Debug)]
+enum SnailFishExpr {
+    Constant(usize),
+    Pair(Rc<RefCell<SnailFishExpr>>, Rc<RefCell<SnailFishExpr>>),
+}
+
+impl SnailFishExpr {
+    fn const_value(&self) -> Option<usize> {
+        match self {
+            SnailFishExpr::Constant(v) => Some(*v),
+            SnailFishExpr::Pair(_, _) => None,
+        }
+    }
+
+    fn pair(left: SnailFishExpr, right: SnailFishExpr) -> Self {
+        Self::Pair(Rc::new(RefCell::new(left)), Rc::new(RefCell::new(right)))
+    }
+
+    fn simple_pair(left: usize, right: usize) -> Self {
+        Self::pair(
+            SnailFishExpr::Constant(left),
+            SnailFishExpr::Constant(right),
+        )
+    }
+
+    fn magnitude(&self) -> usize {
+        match self {
+            SnailFishExpr::Constant(v) => *v,
+            SnailFishExpr::Pair(left, right) => {
+                3 * left.borrow().magnitude() + 2 * right.borrow().magnitude()
+            }
+        }
+    }
+
+    fn leaf_sum(&self) -> usize {
+        let mut sum = LeafSum(0);
+        walk::walk(self, &mut sum);
+        sum.0
+    }
+
+    /// Whether no pair is nested inside four pairs and no regular number is 10 or greater.
+    fn is_reduced(&self) -> bool {
+        walk::find(self, |node, depth| match node {
+            SnailFishExpr::Constant(v) => *v >= 10,
+            SnailFishExpr::Pair(_, _) => depth >= 4,
+        })
+        .is_none()
+    }
+
+    fn deep_copy(&self) -> Self {
+        match self {
+            SnailFishExpr::Constant(v) => Self::Constant(*v),
+            SnailFishExpr::Pair(left, right) => Self::pair(left.borrow().deep_copy(), right.borrow().deep_copy()),
+        }
+    }
+}
+
+impl Walk for SnailFishExpr {
+    fn for_each_child<F: FnMut(&Self) -> bool>(&self, mut f: F) -> bool {
+        match self {
+            SnailFishExpr::Constant(_) => true,
+            SnailFishExpr::Pair(left, right) => f(&left.borrow()) && f(&right.borrow()),
+        }
+    }
+}
+
+struct LeafSum(usize);
+
+impl Visitor<SnailFishExpr> for LeafSum {
+    fn enter(&mut self, node: &SnailFishExpr, _depth: usize) -> Visit {
+        if let SnailFishExpr::Constant(v) = node {
+            self.0 += v;
+        }
+        Visit::Continue
+    }
+}
+
+impl Display for SnailFishExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnailFishExpr::Constant(v) => write!(f, "{}", v),
+            SnailFishExpr::Pair(left, right) => write!(f, "[{},{}]", left.borrow(), right.borrow()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SnailFishCursorImpl {
+    current: Rc<RefCell<SnailFishExpr>>,
+    parent: Option<Rc<SnailFishCursorImpl>>,
+}
+
+trait SnailFishCursor
+where
+    Self: Sized,
+{
+    fn left(&self) -> Option<Self>;
+    fn right(&self) -> Option<Self>;
+    fn depth(&self) -> usize;
+    fn parent(&self) -> Option<Self>;
+    fn get_const_value(&self) -> Option<usize>;
+    fn set_value(&self, value: usize);
+    fn replace_node(&self, node: SnailFishExpr);
+    fn is_value_pair(&self) -> bool;
+    fn same(&self, other: &Self) -> bool;
+}
+
+trait AsCursor {
+    fn as_cursor(&self) -> SnailFishCursorImpl;
+}
+
+impl AsCursor for Rc<RefCell<SnailFishExpr>> {
+    fn as_cursor(&self) -> SnailFishCursorImpl {
+        SnailFishCursorImpl {
+            parent: None,
+            current: self.clone(),
+        }
+    }
+}
+
+fn descend(
+    cursor: &Rc<SnailFishCursorImpl>,
+    child: &Rc<RefCell<SnailFishExpr>>,
+) -> Rc<SnailFishCursorImpl> {
+    Rc::new(SnailFishCursorImpl {
+        current: child.clone(),
+        parent: Some(cursor.clone()),
+    })
+}
+
+impl SnailFishCursor for Rc<SnailFishCursorImpl> {
+    fn left(&self) -> Option<Self> {
+        match &*self.current.borrow() {
+            SnailFishExpr::Constant(_) => None,
+            SnailFishExpr::Pair(left, _) => Some(descend(self, left)),
+        }
+    }
+
+    fn right(&self) -> Option<Self> {
+        match &*self.current.borrow() {
+            SnailFishExpr::Constant(_) => None,
+            SnailFishExpr::Pair(_, right) => Some(descend(self, right)),
+        }
+    }
+
+    fn depth(&self) -> usize {
+        1 + self.parent.as_ref().map(|p| p.depth()).unwrap_or(0)
+    }
+
+    fn parent(&self) -> Option<Self> {
+        self.parent.clone()
+    }
+
+    fn get_const_value(&self) -> Option<usize> {
+        self.current.as_ref().borrow().const_value()
+    }
+
+    fn set_value(&self, value: usize) {
+        self.current.replace(SnailFishExpr::Constant(value));
+    }
+
+    fn replace_node(&self, node: SnailFishExpr) {
+        self.current.replace(node);
+    }
+
+    fn is_value_pair(&self) -> bool {
+        self.left()
+            .and_then(|node| node.get_const_value().map(|_| true))
+            .unwrap_or(false)
+            || self
+                .right()
+                .and_then(|node| node.get_const_value().map(|_| true))
+                .unwrap_or(false)
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.current, &other.current)
+    }
+}
+
+fn find_left_neighbor_const<T: SnailFishCursor>(mut cursor: T) -> Option<T> {
+    loop {
+        let new_cursor = cursor.parent()?;
+        if let Some(left) = new_cursor.left() {
+            if !left.same(&cursor) {
+                cursor = left;
+                while let Some(right) = cursor.right() {
+                    cursor = right;
+                }
+                return Some(cursor);
+            }
+        }
+        cursor = new_cursor;
+    }
+}
+
+fn find_right_neighbor_const<T: SnailFishCursor>(mut cursor: T) -> Option<T> {
+    loop {
+        let new_cursor = cursor.parent()?;
+        if let Some(right) = new_cursor.right() {
+            if !right.same(&cursor) {
+                cursor = right;
+                while let Some(left) = cursor.left() {
+                    cursor = left;
+                }
+                return Some(cursor);
+            }
+        }
+        cursor = new_cursor;
+    }
+}
+
+fn explode(cursor: impl SnailFishCursor + Clone + Debug) {
+    let left_value = cursor
+        .left()
+        .expect("Explode must not be called on leafs")
+        .get_const_value()
+        .expect("Explode must only be called on simple pairs");
+    let right_value = cursor
+        .right()
+        .expect("Explode must not be called on leafs")
+        .get_const_value()
+        .expect("Explode must only be called on simple pairs");
+
+    if let Some(node) = find_left_neighbor_const(cursor.clone()) {
+        let old_value = node
+            .get_const_value()
+            .expect("Find left neighbor must return a constant");
+        node.set_value(old_value + left_value);
+    }
+    if let Some(node) = find_right_neighbor_const(cursor.clone()) {
+        let old_value = node
+            .get_const_value()
+            .expect("Find left neighbor must return a constant");
+        node.set_value(old_value + right_value);
+    }
+
+    cursor.set_value(0);
+}
+
+fn split(cursor: impl SnailFishCursor + Clone) {
+    let value = cursor
+        .get_const_value()
+        .expect("Can only split const value");
+    cursor.replace_node(SnailFishExpr::simple_pair(value / 2, value.div_ceil(2)));
+}
+
+/// A single action of the reduction, `depth` counts the pairs the node is nested in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReductionAction {
+    Explode { pair: (usize, usize), depth: usize },
+    Split { value: usize, depth: usize },
+}
+
+impl Display for ReductionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReductionAction::Explode { pair, depth } => {
+                write!(f, "explode [{},{}] at depth {}", pair.0, pair.1, depth)
+            }
+            ReductionAction::Split { value, depth } => {
+                write!(f, "split {} at depth {}", value, depth)
+            }
+        }
+    }
+}
+
+fn reduce_step_explode(root: impl SnailFishCursor + Clone + Debug) -> Option<ReductionAction> {
+    if root.depth() == 5 && root.is_value_pair() {
+        let left = root.left().and_then(|node| node.get_const_value()).unwrap();
+        let right = root.right().and_then(|node| node.get_const_value()).unwrap();
+        let action = ReductionAction::Explode {
+            pair: (left, right),
+            depth: root.depth() - 1,
+        };
+        explode(root);
+        Some(action)
+    } else {
+        root.left().and_then(reduce_step_explode)
+            .or_else(|| root.right().and_then(reduce_step_explode))
+    }
+}
+
+fn reduce_step_split(root: impl SnailFishCursor + Clone + Debug) -> Option<ReductionAction> {
+    match root.get_const_value() {
+        Some(value) if value >= 10 => {
+            let action = ReductionAction::Split { value, depth: root.depth() - 1 };
+            split(root);
+            Some(action)
+        }
+        _ => root.left().and_then(reduce_step_split)
+            .or_else(|| root.right().and_then(reduce_step_split)),
+    }
+}
+
+/// Reduces the number and hands every action to `on_step` right after it was applied.
+fn reduce_with<F>(root: impl SnailFishCursor + Clone + Debug, mut on_step: F)
+where
+    F: FnMut(&ReductionAction),
+{
+    while let Some(action) =
+        reduce_step_explode(root.clone()).or_else(|| reduce_step_split(root.clone()))
+    {
+        on_step(&action);
+    }
+}
+
+fn reduce(root: impl SnailFishCursor + Clone + Debug) {
+    reduce_with(root, |_| ())
+}
+
+/// Reduces the number and records every action together with the number right after it.
+fn reduce_traced(expr: &Rc<RefCell<SnailFishExpr>>) -> Vec<(ReductionAction, String)> {
+    let mut trace = Vec::new();
+    reduce_with(Rc::new(expr.as_cursor()), |action| {
+        trace.push((action.clone(), expr.borrow().to_string()))
+    });
+    trace
+}
+
+// Snailfish Expr parser
+fn consume(iter: &mut impl Iterator<Item = char>, expected: char) -> Result<()> {
+    let next = iter
+        .next()
+        .ok_or(anyhow!("Unexpected end of input, wanted: '{}'", expected))?;
+    if next != expected {
+        bail!("Unexpected input (Got '{}', expected '{}')", next, expected);
+    }
+    Ok(())
+}
+
+fn parse_snailfish(iter: &mut Peekable<impl Iterator<Item = char>>) -> Result<SnailFishExpr> {
+    match iter.peek().ok_or(anyhow!("Empty input!"))? {
+        '[' => {
+            iter.next();
+            let left = parse_snailfish(iter)?;
+            consume(iter, ',')?;
+            let right = parse_snailfish(iter)?;
+            consume(iter, ']')?;
+            Ok(SnailFishExpr::Pair(
+                Rc::new(RefCell::new(left)),
+                Rc::new(RefCell::new(right)),
+            ))
+        }
+        c if c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(digit) = iter.next_if(|c| c.is_ascii_digit()) {
+                number.push(digit);
+            }
+            Ok(SnailFishExpr::Constant(number.parse()?))
+        }
+        c => bail!("Unexpected char '{}'", c),
+    }
+}
+
+impl FromStr for SnailFishExpr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_snailfish(&mut s.chars().peekable())
+    }
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let mut expressions = stream_items_from_file::<_, SnailFishExpr>(input)?;
+    let mut sum = Rc::new(RefCell::new(expressions.next().unwrap()));
+    reduce(Rc::new(sum.as_cursor()));
+    for expression in expressions {
+        let expr = Rc::new(RefCell::new(expression));
+        reduce(Rc::new(expr.as_cursor()));
+
+        sum = Rc::new(RefCell::new(SnailFishExpr::Pair(sum, expr)));
+        reduce(Rc::new(sum.as_cursor()));
+    }
+    let magnitude = sum.borrow().magnitude();
+    Ok(magnitude)
+}
+
+// Weights of the leaves of a full tree of depth 4, sorted descending.
+// A reduced number has no leaf deeper than that and splitting a leaf never lowers the magnitude.
+const LEAF_WEIGHTS: [usize; 16] = [81, 54, 54, 54, 54, 36, 36, 36, 36, 36, 36, 24, 24, 24, 24, 16];
+
+/// Upper bound for the magnitude of a reduced number with the given leaf sum.
+/// Explosions can only drop values and splits keep the sum, so the sum of two numbers can be bounded before reducing it.
+fn magnitude_upper_bound(leaf_sum: usize) -> usize {
+    let mut remaining = leaf_sum;
+    LEAF_WEIGHTS
+        .iter()
+        .map(|weight| {
+            let value = remaining.min(9);
+            remaining -= value;
+            weight * value
+        })
+        .sum()
+}
+
+/// Finds the largest magnitude of the sum of any two (reduced) expressions.
+/// Also returns the number of reductions that were needed.
+fn max_magnitude_of_sums(expressions: &[Rc<RefCell<SnailFishExpr>>], prune: bool) -> (usize, usize) {
+    let sums = expressions.iter().map(|e| e.borrow().leaf_sum()).collect_vec();
+    // Just assume that adding the same number twice is also allowed...
+    let mut candidates = (0..expressions.len())
+        .cartesian_product(0..expressions.len())
+        .map(|(a, b)| (magnitude_upper_bound(sums[a] + sums[b]), a, b))
+        .collect_vec();
+    // Trying the most promising pairs first makes the bound cut off early
+    candidates.sort_unstable_by(|l, r| r.cmp(l));
+
+    let mut best = 0;
+    let mut reductions = 0;
+    for (bound, a, b) in candidates {
+        if prune && bound <= best {
+            break;
+        }
+        let sum = Rc::new(RefCell::new(SnailFishExpr::pair(
+            expressions[a].borrow().deep_copy(),
+            expressions[b].borrow().deep_copy(),
+        )));
+        reduce(Rc::new(sum.as_cursor()));
+        reductions += 1;
+        best = best.max(sum.borrow().magnitude());
+    }
+    (best, reductions)
+}
+
+fn read_reduced<I: Input>(input: I) -> Result<Vec<Rc<RefCell<SnailFishExpr>>>> {
+    let expressions = stream_items_from_file::<_, SnailFishExpr>(input)?.map(|e| Rc::new(RefCell::new(e))).collect_vec();
+    // Assuming that every number needs to be reduced first
+    expressions.iter().filter(|ex| !ex.borrow().is_reduced()).for_each(|ex| {
+        reduce(Rc::new(ex.as_cursor()));
+    });
+    Ok(expressions)
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let expressions = read_reduced(input)?;
+    Ok(max_magnitude_of_sums(&expressions, true).0)
+}
+
+/// The additions of part 1 with every reduction step, in the style of the puzzle's worked examples.
+fn explain<I: Input>(input: I) -> Result<String> {
+    let mut lines = Vec::new();
+    let mut sum: Option<Rc<RefCell<SnailFishExpr>>> = None;
+    for expression in stream_items_from_file::<_, SnailFishExpr>(input)? {
+        let expr = Rc::new(RefCell::new(expression));
+        reduce(Rc::new(expr.as_cursor()));
+        let next = match sum {
+            None => expr,
+            Some(sum) => {
+                lines.push(format!("  {}\n+ {}", sum.borrow(), expr.borrow()));
+                let next = Rc::new(RefCell::new(SnailFishExpr::Pair(sum, expr)));
+                lines.push(format!("after addition: {}", next.borrow()));
+                for (action, result) in reduce_traced(&next) {
+                    lines.push(format!("after {}: {}", action, result));
+                }
+                next
+            }
+        };
+        sum = Some(next);
+    }
+    if let Some(sum) = sum {
+        lines.push(format!("= {} with magnitude {}", sum.borrow(), sum.borrow().magnitude()));
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+const INPUT: &str = "input/day18.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if std::env::args().skip(1).any(|arg| arg == "--explain") {
+        print!("{}", explain(input)?);
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                [[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]
+                [7,[[[3,7],[4,3]],[[6,3],[8,8]]]]
+                [[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]
+                [[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]
+                [7,[5,[[3,8],[1,4]]]]
+                [[2,[2,2]],[8,[8,1]]]
+                [2,9]
+                [1,[[[9,3],9],[[9,0],[0,7]]]]
+                [[[5,[7,4]],7],1]
+                [[[[4,2],2],6],[8,7]]"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn example_file1() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                [[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+                [[[5,[2,8]],4],[5,[[9,9],0]]]
+                [6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+                [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+                [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+                [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+                [[[[5,4],[7,7]],8],[[8,3],8]]
+                [[9,3],[[9,9],[6,[4,9]]]]
+                [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+                [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]"}]
+            .iter(),
+            None,
+        )
+    }
+
+    /// Checks that pruning keeps the answer and returns the reductions with and without it.
+    fn check_pruning<P: AsRef<Path>>(file: P) -> (usize, usize) {
+        let expressions = read_reduced(file).unwrap();
+        let (pruned, pruned_reductions) = max_magnitude_of_sums(&expressions, true);
+        let (full, full_reductions) = max_magnitude_of_sums(&expressions, false);
+        assert_eq!(pruned, full);
+        assert_eq!(full_reductions, expressions.len() * expressions.len());
+        (pruned_reductions, full_reductions)
+    }
+
+    #[test]
+    fn test_reduction_trace() {
+        let expr = Rc::new(RefCell::new(
+            "[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]".parse::<SnailFishExpr>().unwrap(),
+        ));
+        assert!(!expr.borrow().is_reduced());
+        assert_eq!(walk::shape(&*expr.borrow()).depth, 5);
+        assert_eq!(expr.borrow().leaf_sum(), 45);
+        let trace = reduce_traced(&expr)
+            .into_iter()
+            .map(|(action, result)| format!("after {}: {}", action, result))
+            .collect_vec();
+        assert_eq!(
+            trace,
+            [
+                "after explode [4,3] at depth 4: [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "after explode [8,4] at depth 4: [[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "after split 15 at depth 3: [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "after split 13 at depth 4: [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "after explode [6,7] at depth 4: [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+        assert!(reduce_traced(&expr).is_empty());
+        assert!(expr.borrow().is_reduced());
+        assert!(!"[[1,2],10]".parse::<SnailFishExpr>().unwrap().is_reduced());
+
+        let (dir, file) = create_line_file(["[[[[4,3],4],4],[7,[[8,4],9]]]", "[1,1]"].iter(), None);
+        let explanation = explain(&file).unwrap();
+        drop(dir);
+        let lines = explanation.lines().collect_vec();
+        assert_eq!(lines[2], "after addition: [[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        assert_eq!(lines[8], "= [[[[0,7],4],[[7,8],[6,0]]],[8,1]] with magnitude 1384");
+    }
+
+    #[test]
+    fn test_pruning() {
+        let (dir, file) = example_file();
+        // The bound has to actually cut off pairs on the homework example
+        let (pruned, full) = check_pruning(file);
+        assert!(pruned < full, "{} of {} reductions with pruning", pruned, full);
+        drop(dir);
+        let (dir, file) = example_file1();
+        let (pruned, full) = check_pruning(file);
+        assert!(pruned <= full);
+        drop(dir);
+        assert_eq!(magnitude_upper_bound(0), 0);
+        assert_eq!(magnitude_upper_bound(1000), 9 * 625);
+    }
+}
@@ -0,0 +1,1261 @@
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::artifacts::{self, Artifacts};
+use aoc2021::geometry::{Cuboid, Interval, KdTree, Point, Transform};
+use aoc2021::graph::DotGraph;
+use aoc2021::stats::{self, Stats};
+use aoc2021::vecn::Vec2D;
+use aoc2021::solution::{self, Solution};
+use aoc2021::{repl, repl::Repl, stream_typed_blocks_from_file};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+type Vec3D = Point;
+
+/// Which orientations a scanner can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Any of the 24 axis-aligned orientations in space
+    Spatial,
+    /// Only rotations within the x/y plane, the z coordinates of all readings are expected to be 0
+    Planar,
+}
+
+/// Parameters of the scanner alignment, the defaults are the ones of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Alignment {
+    /// Number of beacons two scanners need to share before we consider them aligned
+    overlap_threshold: usize,
+    mode: Mode,
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Alignment {
+            overlap_threshold: 12,
+            mode: Mode::Spatial,
+        }
+    }
+}
+
+impl Alignment {
+    /// Reads `--threshold N` and `--planar` from the command line arguments.
+    fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut alignment = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--threshold" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("--threshold needs a value"))?;
+                    alignment.overlap_threshold = value.parse()?;
+                }
+                "--planar" => alignment.mode = Mode::Planar,
+                _ => (),
+            }
+        }
+        Ok(alignment)
+    }
+
+    fn rotations(&self) -> &'static [Transform] {
+        match self.mode {
+            Mode::Spatial => Transform::cardinal_rotations(),
+            Mode::Planar => Transform::planar_rotations(),
+        }
+    }
+}
+
+fn find_transformation(
+    baseline: &HashSet<Vec3D>,
+    to_match: &HashSet<Vec3D>,
+    alignment: &Alignment,
+) -> Option<(Transform, Vec3D)> {
+    for transform in alignment.rotations() {
+        let mut distance_counts: HashMap<Vec3D, usize> = HashMap::new();
+        to_match
+            .iter()
+            .map(|relative_beacon| transform * relative_beacon)
+            .cartesian_product(baseline.iter())
+            .map(|(candidate, baseline)| baseline - &candidate)
+            .for_each(|dist| *distance_counts.entry(dist).or_insert(0) += 1);
+
+        // With a low threshold several offsets can qualify, the one shared by most beacons wins
+        // and ties go to the smallest offset, so the result doesn't depend on the hash order
+        let best = distance_counts
+            .into_iter()
+            .filter(|&(_, count)| count >= alignment.overlap_threshold)
+            .max_by_key(|&(offset, count)| (count, Reverse(offset)));
+        if let Some((offset, _)) = best {
+            return Some((transform.clone(), offset));
+        }
+    }
+    None
+}
+
+/// Assembles the full beacon map in the coordinate system of the first scanner, sorted by the
+/// coordinates. Also returns the transformation from each scanner's coordinates into that system.
+/// The remaining scanners are tried in the order of their index in every round, so the result is
+/// the same on every run. Fails if some scanners can't be aligned with the overlap threshold.
+fn assemble_map(
+    relative_positions: Vec<HashSet<Vec3D>>,
+    alignment: &Alignment,
+) -> Result<(Vec<Vec3D>, Vec<Transform>)> {
+    if relative_positions.is_empty() {
+        bail!("There are no scanner readings to assemble");
+    }
+    let scanners = relative_positions.len();
+    let mut transforms = vec![None; scanners];
+    let mut remaining = relative_positions.into_iter().enumerate().collect_vec();
+    // Initial Baseline is what the first scanner sees
+    let (_, mut map) = remaining.remove(0);
+    transforms[0] = Some(Transform::identity());
+    let mut to_remove: Vec<usize> = Vec::new();
+    while !remaining.is_empty() {
+        for (i, (scanner, scanner_result)) in remaining.iter().enumerate() {
+            if let Some((rotation, offset)) = find_transformation(&map, scanner_result, alignment) {
+                let transform = &Transform::translation(offset) * &rotation;
+                map.extend(
+                    scanner_result
+                        .iter()
+                        .map(|rel_beacon| &transform * rel_beacon),
+                );
+                to_remove.push(i);
+
+                transforms[*scanner] = Some(transform);
+            }
+        }
+        if to_remove.is_empty() {
+            bail!(
+                "{} of the {} scanners don't overlap the map in at least {} beacons",
+                remaining.len(),
+                scanners,
+                alignment.overlap_threshold
+            );
+        }
+        while let Some(i) = to_remove.pop() {
+            remaining.remove(i);
+        }
+    }
+    Ok((
+        map.into_iter().sorted().collect(),
+        transforms.into_iter().map(Option::unwrap).collect(),
+    ))
+}
+
+/// The assembled map together with the scanner positions, both in the coordinates of the first scanner.
+struct BeaconMap {
+    beacons: Vec<Vec3D>,
+    transforms: Vec<Transform>,
+}
+
+impl Artifacts for BeaconMap {
+    fn artifacts(&self) -> Vec<(String, String)> {
+        let beacons = self
+            .beacons
+            .iter()
+            .map(|b| format!("{},{},{}\n", b.x(), b.y(), b.z()))
+            .collect();
+        let scanners = self
+            .transforms
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let p = t.translation;
+                format!("{},{},{},{}\n", i, p.x(), p.y(), p.z())
+            })
+            .collect();
+        vec![
+            ("beacons.csv".to_string(), beacons),
+            ("scanners.csv".to_string(), scanners),
+        ]
+    }
+}
+
+/// Expresses an assembled map relative to the position and orientation of the given scanner,
+/// sorted by the new coordinates.
+fn map_relative_to<'a>(
+    map: impl IntoIterator<Item = &'a Vec3D>,
+    transforms: &[Transform],
+    scanner: usize,
+) -> Vec<Vec3D> {
+    let to_scanner = transforms[scanner].inverse();
+    map.into_iter()
+        .map(|beacon| &to_scanner * beacon)
+        .sorted()
+        .collect()
+}
+
+/// Counts how many of a scanner's beacons coincide with the map after applying the transform.
+fn count_overlap(map: &KdTree, beacons: &HashSet<Vec3D>, transform: &Transform) -> usize {
+    beacons
+        .iter()
+        .filter(|beacon| map.contains(&(transform * *beacon)))
+        .count()
+}
+
+/// An edge between two scanners that share at least the overlap threshold of beacons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Overlap {
+    scanners: (usize, usize),
+    shared_beacons: usize,
+}
+
+/// Determines which scanners overlap once all of them are aligned.
+fn overlap_graph(
+    relative_positions: &[HashSet<Vec3D>],
+    transforms: &[Transform],
+    alignment: &Alignment,
+) -> Vec<Overlap> {
+    let trees = relative_positions
+        .iter()
+        .zip(transforms.iter())
+        .map(|(beacons, transform)| beacons.iter().map(|b| transform * b).collect::<KdTree>())
+        .collect_vec();
+
+    (0..relative_positions.len())
+        .tuple_combinations()
+        .filter_map(|(a, b)| {
+            let shared_beacons = count_overlap(&trees[a], &relative_positions[b], &transforms[b]);
+            (shared_beacons >= alignment.overlap_threshold).then_some(Overlap {
+                scanners: (a, b),
+                shared_beacons,
+            })
+        })
+        .collect()
+}
+
+fn overlap_dot(overlaps: &[Overlap]) -> DotGraph {
+    let mut graph = DotGraph::undirected("scanners");
+    for overlap in overlaps {
+        graph.edge(
+            overlap.scanners.0,
+            overlap.scanners.1,
+            Some(&overlap.shared_beacons.to_string()),
+        );
+    }
+    graph
+}
+
+/// Finds the beacon closest to each of the scanners, `None` for an empty map.
+fn closest_beacons(map: &KdTree, scanners: &[Vec3D]) -> Option<Vec<Vec3D>> {
+    scanners
+        .iter()
+        .map(|scanner| map.nearest(scanner).copied())
+        .collect()
+}
+
+/// How far a scanner can see in the puzzle, used as the default radius of `--explain`.
+const SCANNER_RANGE: i64 = 1000;
+
+/// Queries against an assembled map, the beacons are kept in a KD-tree for the radius lookups.
+struct MapQueries {
+    beacons: KdTree,
+    scanners: Vec<Vec3D>,
+    transforms: Vec<Transform>,
+}
+
+impl MapQueries {
+    fn new(map: &BeaconMap) -> Self {
+        MapQueries {
+            beacons: map.beacons.iter().cloned().collect(),
+            scanners: map.transforms.iter().map(|t| t.translation).collect(),
+            transforms: map.transforms.clone(),
+        }
+    }
+
+    /// The whole map in the coordinates of a scanner, as it would report the beacons.
+    fn relative_to_scanner(&self, scanner: usize) -> Result<Vec<Vec3D>> {
+        if scanner >= self.transforms.len() {
+            bail!("There is no scanner {}", scanner);
+        }
+        Ok(map_relative_to(self.beacons.iter(), &self.transforms, scanner))
+    }
+
+    /// All beacons within the euclidean `radius` of `position`, sorted by their coordinates.
+    fn visible_from(&self, position: &Vec3D, radius: i64) -> Vec<Vec3D> {
+        self.beacons
+            .within_radius(position, radius)
+            .into_iter()
+            .cloned()
+            .sorted()
+            .collect()
+    }
+
+    fn visible_from_scanner(&self, scanner: usize, radius: i64) -> Result<Vec<Vec3D>> {
+        let position = self
+            .scanners
+            .get(scanner)
+            .ok_or_else(|| anyhow!("There is no scanner {}", scanner))?;
+        Ok(self.visible_from(position, radius))
+    }
+
+    /// The manhattan distance of every pair of scanners, closest pairs first.
+    fn scanner_distances(&self) -> Vec<((usize, usize), i64)> {
+        (0..self.scanners.len())
+            .tuple_combinations()
+            .map(|(a, b)| {
+                let distance = (self.scanners[b] - self.scanners[a]).manhattan_norm();
+                ((a, b), distance)
+            })
+            .sorted_by_key(|&(pair, distance)| (distance, pair))
+            .collect()
+    }
+
+    /// The smallest box containing all beacons, `None` for an empty map.
+    fn bounding_box(&self) -> Option<Cuboid> {
+        let (x, y, z) = (0..3)
+            .map(|axis| {
+                let (min, max) = self
+                    .beacons
+                    .iter()
+                    .map(|b| b[axis])
+                    .minmax()
+                    .into_option()?;
+                Some(Interval(min, max))
+            })
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .collect_tuple()?;
+        Some(Cuboid::from_intervals(&x, &y, &z))
+    }
+
+    /// A summary of the map for `--explain`.
+    fn explain(&self) -> String {
+        let mut lines = vec![format!(
+            "{} beacons seen by {} scanners",
+            self.beacons.len(),
+            self.scanners.len()
+        )];
+        if let Some(bounds) = self.bounding_box() {
+            lines.push(format!("Bounding box: {}", bounds));
+        }
+        for (scanner, position) in self.scanners.iter().enumerate() {
+            lines.push(format!(
+                "Scanner {} at {}: {} beacons within {}",
+                scanner,
+                position,
+                self.visible_from(position, SCANNER_RANGE).len(),
+                SCANNER_RANGE
+            ));
+        }
+        let distances = self.scanner_distances();
+        if let (Some(closest), Some(farthest)) = (distances.first(), distances.last()) {
+            lines.push(format!(
+                "Closest scanners: {} and {} at {}",
+                closest.0 .0, closest.0 .1, closest.1
+            ));
+            lines.push(format!(
+                "Farthest scanners: {} and {} at {}",
+                farthest.0 .0, farthest.0 .1, farthest.1
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl Repl for MapQueries {
+    fn eval(&mut self, command: &str) -> Result<String> {
+        let words = command.split_whitespace().collect_vec();
+        let format_beacons = |beacons: Vec<Vec3D>| beacons.iter().map(Vec3D::to_string).join("\n");
+        match words[..] {
+            ["visible", "scanner", scanner, radius] => Ok(format_beacons(
+                self.visible_from_scanner(scanner.parse()?, radius.parse()?)?,
+            )),
+            ["visible", position, radius] => {
+                let position = position
+                    .parse::<Vec3D>()
+                    .map_err(|e| anyhow!("Invalid position {}: {:?}", position, e))?;
+                Ok(format_beacons(
+                    self.visible_from(&position, radius.parse()?),
+                ))
+            }
+            ["relative", "scanner", scanner] => Ok(format_beacons(
+                self.relative_to_scanner(scanner.parse()?)?,
+            )),
+            ["closest"] => Ok(closest_beacons(&self.beacons, &self.scanners)
+                .ok_or_else(|| anyhow!("The map has no beacons"))?
+                .iter()
+                .enumerate()
+                .map(|(scanner, beacon)| format!("{}: {}", scanner, beacon))
+                .join("\n")),
+            ["distances"] => Ok(self
+                .scanner_distances()
+                .iter()
+                .map(|((a, b), distance)| format!("{} {}: {}", a, b, distance))
+                .join("\n")),
+            ["bounds"] => Ok(self
+                .bounding_box()
+                .map_or_else(|| "empty map".to_string(), |bounds| bounds.to_string())),
+            _ => bail!("Unknown command {}", command),
+        }
+    }
+
+    fn help(&self) -> String {
+        "visible X,Y,Z R: beacons within radius R of a position\n\
+         visible scanner N R: beacons within radius R of scanner N\n\
+         relative scanner N: all beacons in the coordinates of scanner N\n\
+         closest: the beacon closest to every scanner\n\
+         distances: manhattan distances of all scanner pairs, closest first\n\
+         bounds: bounding box of all beacons"
+            .to_string()
+    }
+}
+
+/// The beacons as reported by each scanner, before any alignment.
+struct ScannerReadings(Vec<HashSet<Vec3D>>);
+
+impl Stats for ScannerReadings {
+    fn stats(&self) -> Vec<(String, String)> {
+        let counts = self.0.iter().map(HashSet::len).collect_vec();
+        let mut stats = vec![
+            ("scanners".to_string(), counts.len().to_string()),
+            (
+                "readings".to_string(),
+                counts.iter().sum::<usize>().to_string(),
+            ),
+        ];
+        if let Some((min, max)) = counts.iter().minmax().into_option() {
+            stats.push((
+                "beacons per scanner".to_string(),
+                format!("{}..{}", min, max),
+            ));
+        }
+        stats.extend(
+            counts
+                .iter()
+                .enumerate()
+                .map(|(scanner, count)| (format!("scanner {}", scanner), count.to_string())),
+        );
+        stats
+    }
+}
+
+/// Parses the beacon lines below a `--- scanner N ---` header.
+fn scanner_readings<F>(lines: Vec<String>, parse_beacon: F) -> Result<HashSet<Vec3D>>
+where
+    F: Fn(&str) -> Result<Vec3D>,
+{
+    let (header, beacons) = lines
+        .split_first()
+        .ok_or_else(|| anyhow!("Empty scanner block"))?;
+    if !header.starts_with("--- scanner") {
+        bail!("Expected a scanner header, found {:?}", header);
+    }
+    beacons
+        .iter()
+        .map(|line| parse_beacon(line).map_err(|e| anyhow!("Invalid beacon {:?}: {}", line, e)))
+        .collect()
+}
+
+/// The beacons one scanner reports, relative to the scanner.
+struct Scan(HashSet<Vec3D>);
+
+impl TryFrom<Vec<String>> for Scan {
+    type Error = anyhow::Error;
+
+    fn try_from(lines: Vec<String>) -> Result<Self> {
+        scanner_readings(lines, |line| Ok(line.parse::<Vec3D>()?)).map(Scan)
+    }
+}
+
+/// A scan with two coordinates per beacon, for use with `Mode::Planar`.
+struct PlanarScan(HashSet<Vec3D>);
+
+impl TryFrom<Vec<String>> for PlanarScan {
+    type Error = anyhow::Error;
+
+    fn try_from(lines: Vec<String>) -> Result<Self> {
+        scanner_readings(lines, |line| {
+            let beacon = line.parse::<Vec2D<i64>>()?;
+            Ok(Vec3D::new(beacon.x(), beacon.y(), 0))
+        })
+        .map(PlanarScan)
+    }
+}
+
+fn parse_beacon_positions<I: Input>(input: I) -> Result<Vec<HashSet<Vec3D>>> {
+    Ok(stream_typed_blocks_from_file(input)?
+        .map_ok(|Scan(beacons)| beacons)
+        .collect::<Result<_, _>>()?)
+}
+
+fn parse_planar_beacon_positions<I: Input>(input: I) -> Result<Vec<HashSet<Vec3D>>> {
+    Ok(stream_typed_blocks_from_file(input)?
+        .map_ok(|PlanarScan(beacons)| beacons)
+        .collect::<Result<_, _>>()?)
+}
+
+fn read_scanners<I: Input>(input: I, alignment: &Alignment) -> Result<Vec<HashSet<Vec3D>>> {
+    match alignment.mode {
+        Mode::Spatial => parse_beacon_positions(input),
+        Mode::Planar => parse_planar_beacon_positions(input),
+    }
+}
+
+fn max_scanner_distance(transforms: &[Transform]) -> i64 {
+    let scanners = transforms.iter().map(|t| t.translation).collect_vec();
+    scanners
+        .iter()
+        .cartesian_product(scanners.iter())
+        .map(|(v1, v2)| (v2 - v1).manhattan_norm())
+        .max()
+        .unwrap()
+}
+
+impl BeaconMap {
+    /// Assembles the map once, which is by far the most expensive step, everything else is
+    /// derived from it.
+    fn assemble(scans: Vec<HashSet<Vec3D>>, alignment: &Alignment) -> Result<Self> {
+        let (beacons, transforms) = assemble_map(scans, alignment)?;
+        Ok(BeaconMap {
+            beacons,
+            transforms,
+        })
+    }
+
+    /// The number of beacons and the largest distance between two scanners.
+    fn answers(&self) -> (usize, i64) {
+        (self.beacons.len(), max_scanner_distance(&self.transforms))
+    }
+}
+
+/// Day 19 with the alignment given on the command line.
+struct BeaconScanners(Alignment);
+
+impl Solution for BeaconScanners {
+    type Parsed = Vec<HashSet<Vec3D>>;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn parse<I: Input>(&self, input: I) -> Result<Self::Parsed> {
+        read_scanners(input, &self.0)
+    }
+
+    fn part1(&self, scans: &Self::Parsed) -> Result<usize> {
+        Ok(BeaconMap::assemble(scans.clone(), &self.0)?.beacons.len())
+    }
+
+    fn part2(&self, scans: &Self::Parsed) -> Result<i64> {
+        let map = BeaconMap::assemble(scans.clone(), &self.0)?;
+        Ok(max_scanner_distance(&map.transforms))
+    }
+
+    /// Both parts need the assembled map, so it is only assembled once.
+    fn solve_both(&self, scans: &Self::Parsed) -> Result<(usize, i64)> {
+        Ok(BeaconMap::assemble(scans.clone(), &self.0)?.answers())
+    }
+}
+
+const INPUT: &str = "input/day19.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let day = BeaconScanners(Alignment::from_args(std::env::args().skip(1))?);
+    let alignment = day.0;
+    let scans = day.parse(input)?;
+    if stats::requested() {
+        print!("{}", stats::render(&ScannerReadings(scans)));
+        return Ok(());
+    }
+    if std::env::args().skip(1).any(|arg| arg == "--dot") {
+        let (_, transforms) = assemble_map(scans.clone(), &alignment)?;
+        println!(
+            "{}",
+            overlap_dot(&overlap_graph(&scans, &transforms, &alignment))
+        );
+        return Ok(());
+    }
+    let explain = std::env::args().skip(1).any(|arg| arg == "--explain");
+    if !(repl::requested() || explain || artifacts::requested_dir().is_some()) {
+        solution::print_answers(solution::solve(&day, &scans)?);
+        return Ok(());
+    }
+    // Everything else is derived from the same map as the answers
+    let map = measure("both parts", || BeaconMap::assemble(scans, &alignment))?;
+    if repl::requested() || explain {
+        let mut queries = MapQueries::new(&map);
+        if repl::requested() {
+            return Ok(repl::run(&mut queries)?);
+        }
+        println!("{}", queries.explain());
+    }
+    artifacts::write_if_requested("day19", &map)?;
+    solution::print_answers(map.answers());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::str::FromStr;
+
+    use aoc2021::test_helpers::{assert_completes_within, assert_snapshot, create_line_file};
+    use indoc::indoc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                --- scanner 0 ---
+                404,-588,-901
+                528,-643,409
+                -838,591,734
+                390,-675,-793
+                -537,-823,-458
+                -485,-357,347
+                -345,-311,381
+                -661,-816,-575
+                -876,649,763
+                -618,-824,-621
+                553,345,-567
+                474,580,667
+                -447,-329,318
+                -584,868,-557
+                544,-627,-890
+                564,392,-477
+                455,729,728
+                -892,524,684
+                -689,845,-530
+                423,-701,434
+                7,-33,-71
+                630,319,-379
+                443,580,662
+                -789,900,-551
+                459,-707,401
+                
+                --- scanner 1 ---
+                686,422,578
+                605,423,415
+                515,917,-361
+                -336,658,858
+                95,138,22
+                -476,619,847
+                -340,-569,-846
+                567,-361,727
+                -460,603,-452
+                669,-402,600
+                729,430,532
+                -500,-761,534
+                -322,571,750
+                -466,-666,-811
+                -429,-592,574
+                -355,545,-477
+                703,-491,-529
+                -328,-685,520
+                413,935,-424
+                -391,539,-444
+                586,-435,557
+                -364,-763,-893
+                807,-499,-711
+                755,-354,-619
+                553,889,-390
+                
+                --- scanner 2 ---
+                649,640,665
+                682,-795,504
+                -784,533,-524
+                -644,584,-595
+                -588,-843,648
+                -30,6,44
+                -674,560,763
+                500,723,-460
+                609,671,-379
+                -555,-800,653
+                -675,-892,-343
+                697,-426,-610
+                578,704,681
+                493,664,-388
+                -671,-858,530
+                -667,343,800
+                571,-461,-707
+                -138,-166,112
+                -889,563,-600
+                646,-828,498
+                640,759,510
+                -630,509,768
+                -681,-892,-333
+                673,-379,-804
+                -742,-814,-386
+                577,-820,562
+                
+                --- scanner 3 ---
+                -589,542,597
+                605,-692,669
+                -500,565,-823
+                -660,373,557
+                -458,-679,-417
+                -488,449,543
+                -626,468,-788
+                338,-750,-386
+                528,-832,-391
+                562,-778,733
+                -938,-730,414
+                543,643,-506
+                -524,371,-870
+                407,773,750
+                -104,29,83
+                378,-903,-323
+                -778,-728,485
+                426,699,580
+                -438,-605,-362
+                -469,-447,-387
+                509,732,623
+                647,635,-688
+                -868,-804,481
+                614,-800,639
+                595,780,-596
+                
+                --- scanner 4 ---
+                727,592,562
+                -293,-554,779
+                441,611,-461
+                -714,465,-776
+                -743,427,-804
+                -660,-479,-426
+                832,-632,460
+                927,-485,-438
+                408,393,-506
+                466,436,-512
+                110,16,151
+                -258,-428,682
+                -393,719,612
+                -211,-452,876
+                808,-476,-593
+                -575,615,604
+                -485,667,467
+                -680,325,-822
+                -627,-443,-432
+                872,-547,-609
+                833,512,582
+                807,604,487
+                839,-516,451
+                891,-625,532
+                -652,-548,-490
+                30,-46,-14
+                "}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn example_beacons() -> HashSet<Vec3D> {
+        let input: &str = indoc! {"
+            -892,524,684
+            -876,649,763
+            -838,591,734
+            -789,900,-551
+            -739,-1745,668
+            -706,-3180,-659
+            -697,-3072,-689
+            -689,845,-530
+            -687,-1600,576
+            -661,-816,-575
+            -654,-3158,-753
+            -635,-1737,486
+            -631,-672,1502
+            -624,-1620,1868
+            -620,-3212,371
+            -618,-824,-621
+            -612,-1695,1788
+            -601,-1648,-643
+            -584,868,-557
+            -537,-823,-458
+            -532,-1715,1894
+            -518,-1681,-600
+            -499,-1607,-770
+            -485,-357,347
+            -470,-3283,303
+            -456,-621,1527
+            -447,-329,318
+            -430,-3130,366
+            -413,-627,1469
+            -345,-311,381
+            -36,-1284,1171
+            -27,-1108,-65
+            7,-33,-71
+            12,-2351,-103
+            26,-1119,1091
+            346,-2985,342
+            366,-3059,397
+            377,-2827,367
+            390,-675,-793
+            396,-1931,-563
+            404,-588,-901
+            408,-1815,803
+            423,-701,434
+            432,-2009,850
+            443,580,662
+            455,729,728
+            456,-540,1869
+            459,-707,401
+            465,-695,1988
+            474,580,667
+            496,-1584,1900
+            497,-1838,-617
+            527,-524,1933
+            528,-643,409
+            534,-1912,768
+            544,-627,-890
+            553,345,-567
+            564,392,-477
+            568,-2007,-577
+            605,-1665,1952
+            612,-1593,1893
+            630,319,-379
+            686,-3108,-505
+            776,-3184,-501
+            846,-3110,-434
+            1135,-1161,1235
+            1243,-1093,1063
+            1660,-552,429
+            1693,-557,386
+            1735,-437,1738
+            1749,-1800,1813
+            1772,-405,1572
+            1776,-675,371
+            1779,-442,1789
+            1780,-1548,337
+            1786,-1538,337
+            1847,-1591,415
+            1889,-1729,1762
+            1994,-1805,1792"
+        };
+        input
+            .lines()
+            .map(Vec3D::from_str)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_card_transforms() {
+        assert_eq!(
+            Transform::cardinal_rotations()
+                .iter()
+                .cloned()
+                .collect::<HashSet<_>>()
+                .len(),
+            24
+        );
+    }
+
+    #[test]
+    fn test_correlation_checks() {
+        let (dir, file) = example_file();
+        let scanner_results = parse_beacon_positions(file).unwrap();
+        let (map, _) = assemble_map(scanner_results, &Alignment::default()).unwrap();
+
+        let superset = example_beacons();
+        assert!(map.iter().cloned().collect::<HashSet<_>>() == superset);
+        assert!(map.windows(2).all(|pair| pair[0] < pair[1]));
+
+        drop(dir);
+    }
+
+    #[test]
+    fn test_deterministic_assembly() {
+        let (dir, file) = example_file();
+        let scanner_results = parse_beacon_positions(file).unwrap();
+        drop(dir);
+        // A low threshold makes several offsets qualify for most scanner pairs
+        for overlap_threshold in [12, 3] {
+            let alignment = Alignment {
+                overlap_threshold,
+                ..Alignment::default()
+            };
+            let dumps = (0..5)
+                .map(|_| {
+                    let (beacons, transforms) =
+                        assemble_map(scanner_results.clone(), &alignment).unwrap();
+                    BeaconMap {
+                        beacons,
+                        transforms,
+                    }
+                    .artifacts()
+                })
+                .collect_vec();
+            assert!(dumps.iter().all(|dump| *dump == dumps[0]));
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let (dir, file) = example_file();
+        let readings = ScannerReadings(parse_beacon_positions(file).unwrap());
+        drop(dir);
+
+        let stats = readings.stats();
+        assert_eq!(stats[0], ("scanners".to_string(), "5".to_string()));
+        assert_eq!(stats[1], ("readings".to_string(), "127".to_string()));
+        assert_eq!(
+            stats[2],
+            ("beacons per scanner".to_string(), "25..26".to_string())
+        );
+        assert_eq!(stats.len(), 8);
+    }
+
+    #[test]
+    fn test_count_overlap() {
+        let (dir, file) = example_file();
+        let scanner_results = parse_beacon_positions(file).unwrap();
+        let (_, transforms) =
+            assemble_map(scanner_results.clone(), &Alignment::default()).unwrap();
+
+        // Scanners 0 and 1 share exactly 12 beacons, as do scanners 1 and 4
+        let scanner0: KdTree = scanner_results[0].iter().cloned().collect();
+        assert_eq!(
+            count_overlap(&scanner0, &scanner_results[1], &transforms[1]),
+            12
+        );
+        let scanner1: KdTree = scanner_results[1]
+            .iter()
+            .map(|b| &transforms[1] * b)
+            .collect();
+        assert_eq!(
+            count_overlap(&scanner1, &scanner_results[4], &transforms[4]),
+            12
+        );
+
+        drop(dir);
+    }
+
+    #[test]
+    fn test_overlap_graph() {
+        let (dir, file) = example_file();
+        let scanner_results = parse_beacon_positions(file).unwrap();
+        let (_, transforms) =
+            assemble_map(scanner_results.clone(), &Alignment::default()).unwrap();
+        let overlaps = overlap_graph(&scanner_results, &transforms, &Alignment::default());
+
+        assert_eq!(
+            overlaps.iter().map(|o| o.scanners).collect_vec(),
+            vec![(0, 1), (1, 3), (1, 4), (2, 4)]
+        );
+        assert!(overlaps.iter().all(|o| o.shared_beacons == 12));
+        assert_snapshot("day19_overlaps", &overlap_dot(&overlaps).to_string());
+
+        drop(dir);
+    }
+
+    #[test]
+    fn test_transform_inverse() {
+        let offset = Vec3D::new(68, -1246, -43);
+        for rotation in Transform::cardinal_rotations() {
+            let transform = &Transform::translation(offset) * rotation;
+            assert_eq!(&transform * &transform.inverse(), Transform::identity());
+            assert_eq!(&transform.inverse() * &transform, Transform::identity());
+
+            let cuboid =
+                Cuboid::from_intervals(&Interval(-3, 5), &Interval(0, 1), &Interval(7, 20));
+            let moved = transform.apply_cuboid(&cuboid);
+            assert_eq!(moved.volume(), cuboid.volume());
+            assert_eq!(transform.inverse().apply_cuboid(&moved), cuboid);
+        }
+    }
+
+    #[test]
+    fn test_map_relative_to() {
+        let (dir, file) = example_file();
+        let scanner_results = parse_beacon_positions(file).unwrap();
+        let (map, transforms) =
+            assemble_map(scanner_results.clone(), &Alignment::default()).unwrap();
+
+        assert_eq!(map_relative_to(&map, &transforms, 0), map);
+        let dumped = BeaconMap {
+            beacons: map.clone(),
+            transforms: transforms.clone(),
+        }
+        .artifacts();
+        assert_eq!(dumped[0].1.lines().count(), 79);
+        assert!(dumped[1].1.contains("\n1,68,-1246,-43\n"));
+        for (scanner, beacons) in scanner_results.iter().enumerate() {
+            let relative_map = map_relative_to(&map, &transforms, scanner);
+            assert_eq!(relative_map.len(), map.len());
+            assert!(beacons
+                .iter()
+                .all(|b| relative_map.binary_search(b).is_ok()));
+        }
+
+        drop(dir);
+    }
+
+    #[test]
+    fn test_kd_tree() {
+        let beacons = example_beacons();
+        let tree: KdTree = beacons.iter().cloned().collect();
+        assert_eq!(tree.len(), beacons.len());
+
+        for target in [
+            Vec3D::new(0, 0, 0),
+            Vec3D::new(1800, -1500, 400),
+            Vec3D::new(-850, 600, 700),
+        ] {
+            let expected = beacons
+                .iter()
+                .min_by_key(|b| (*b - &target).norm_squared())
+                .unwrap();
+            assert_eq!(tree.nearest(&target), Some(expected));
+
+            let mut in_radius = tree.within_radius(&target, 500);
+            in_radius.sort();
+            let mut expected = beacons
+                .iter()
+                .filter(|b| (*b - &target).norm_squared() <= 500 * 500)
+                .collect_vec();
+            expected.sort();
+            assert_eq!(in_radius, expected);
+        }
+        assert!(beacons.iter().all(|b| tree.contains(b)));
+        assert!(!tree.contains(&Vec3D::new(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_closest_beacons() {
+        let (dir, file) = example_file();
+        let (map, transforms) =
+            assemble_map(parse_beacon_positions(file).unwrap(), &Alignment::default()).unwrap();
+        let scanners = transforms.iter().map(|t| t.translation).collect_vec();
+        let closest = closest_beacons(&map.iter().cloned().collect(), &scanners).unwrap();
+
+        assert_eq!(closest.len(), transforms.len());
+        for (beacon, transform) in closest.iter().zip(transforms.iter()) {
+            let dist = (beacon - &transform.translation).norm_squared();
+            assert!(map
+                .iter()
+                .all(|b| (b - &transform.translation).norm_squared() >= dist));
+        }
+
+        drop(dir);
+    }
+
+    #[test]
+    fn test_map_queries() {
+        let (dir, file) = example_file();
+        let (beacons, transforms) =
+            assemble_map(parse_beacon_positions(file).unwrap(), &Alignment::default()).unwrap();
+        drop(dir);
+        let mut queries = MapQueries::new(&BeaconMap {
+            beacons: beacons.clone(),
+            transforms,
+        });
+
+        let visible = queries.visible_from_scanner(1, SCANNER_RANGE).unwrap();
+        let expected = beacons
+            .iter()
+            .filter(|b| (*b - &Vec3D::new(68, -1246, -43)).norm_squared() <= 1000 * 1000)
+            .cloned()
+            .sorted()
+            .collect_vec();
+        assert_eq!(visible, expected);
+        assert!(queries.visible_from_scanner(5, 10).is_err());
+
+        let distances = queries.scanner_distances();
+        assert_eq!(distances.len(), 10);
+        assert!(distances.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(distances.last().unwrap().1, 3621);
+
+        let bounds = queries.bounding_box().unwrap();
+        assert!(beacons
+            .iter()
+            .all(|b| bounds.contains(&Cuboid { from: *b, to: *b })));
+        assert_eq!(queries.eval("bounds").unwrap(), bounds.to_string());
+        assert_eq!(
+            queries.eval("visible 68,-1246,-43 1000").unwrap(),
+            queries.eval("visible scanner 1 1000").unwrap()
+        );
+        assert_eq!(queries.eval("distances").unwrap().lines().count(), 10);
+        assert_eq!(
+            queries.eval("relative scanner 0").unwrap(),
+            beacons.iter().sorted().join("\n")
+        );
+        assert_eq!(queries.relative_to_scanner(1).unwrap().len(), beacons.len());
+        assert!(queries.eval("relative scanner 5").is_err());
+        assert_eq!(queries.eval("closest").unwrap().lines().count(), 5);
+        assert!(queries.eval("hull").is_err());
+        assert!(queries
+            .explain()
+            .contains("Farthest scanners: 2 and 3 at 3621"));
+    }
+
+    /// Xorshift generator, good enough to scatter synthetic beacons reproducibly.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next_in(&mut self, from: i64, to: i64) -> i64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            from + (self.0 % (to - from + 1) as u64) as i64
+        }
+    }
+
+    /// What scanners at the given poses report: every beacon within `radius` on all axes, in the scanner's own coordinates.
+    /// With `max_readings` a scanner only reports that many of its closest beacons.
+    fn synthetic_readings(
+        beacons: &[Vec3D],
+        poses: &[Transform],
+        radius: i64,
+        max_readings: Option<usize>,
+    ) -> Vec<HashSet<Vec3D>> {
+        poses
+            .iter()
+            .map(|pose| {
+                let to_scanner = pose.inverse();
+                beacons
+                    .iter()
+                    .map(|beacon| &to_scanner * beacon)
+                    .filter(|reading| reading.chebyshev_distance(&Vec3D::default()) <= radius)
+                    .sorted_by_key(|reading| reading.norm_squared())
+                    .take(max_readings.unwrap_or(usize::MAX))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Generates readings for the beacons and poses and checks that they are assembled into the original map.
+    fn check_reconstruction(
+        beacons: &[Vec3D],
+        poses: &[Transform],
+        radius: i64,
+        max_readings: Option<usize>,
+    ) {
+        let readings = synthetic_readings(beacons, poses, radius, max_readings);
+        // The map is built in the coordinates of the first scanner
+        let to_first = poses[0].inverse();
+        let expected_map: HashSet<_> = readings
+            .iter()
+            .zip(poses)
+            .flat_map(|(scanner, pose)| {
+                let transform = &to_first * pose;
+                scanner.iter().map(move |reading| &transform * reading)
+            })
+            .collect();
+
+        let (map, transforms) = assemble_map(readings, &Alignment::default()).unwrap();
+        assert_eq!(map, expected_map.into_iter().sorted().collect_vec());
+        for (transform, pose) in transforms.iter().zip(poses) {
+            assert_eq!(*transform, &to_first * pose);
+        }
+    }
+
+    #[test]
+    fn test_synthetic_scanners() {
+        let mut rng = XorShift(0x2021_0019);
+        let beacons = (0..150)
+            .map(|_| {
+                Vec3D::new(
+                    rng.next_in(-1000, 3000),
+                    rng.next_in(-1000, 1000),
+                    rng.next_in(-1000, 1000),
+                )
+            })
+            .collect_vec();
+        let rotations = Transform::cardinal_rotations();
+        let poses = [
+            (Vec3D::new(0, 0, 0), 5),
+            (Vec3D::new(900, 40, -30), 0),
+            (Vec3D::new(1800, -60, 20), 17),
+            (Vec3D::new(2100, 50, 10), 23),
+        ]
+        .map(|(position, rotation)| &Transform::translation(position) * &rotations[rotation]);
+
+        check_reconstruction(&beacons, &poses, 1000, None);
+        check_reconstruction(&beacons, &poses, 1000, Some(50));
+    }
+
+    #[test]
+    fn test_planar_alignment() {
+        // Scanner 1 sits at 5,-2 and is rotated by 90 degrees, 4 of the beacons are visible to both
+        let (dir, file) = create_line_file(
+            [indoc! {"
+                --- scanner 0 ---
+                0,2
+                4,1
+                3,3
+                -1,-1
+                7,0
+
+                --- scanner 1 ---
+                3,1
+                5,2
+                2,-2
+                4,5
+                6,6"}]
+            .iter(),
+            None,
+        );
+        let alignment =
+            Alignment::from_args(["--planar", "--threshold", "4"].map(String::from)).unwrap();
+        let scanner_results = read_scanners(&file, &alignment).unwrap();
+        let (map, transforms) = assemble_map(scanner_results.clone(), &alignment).unwrap();
+        assert_eq!(map.len(), 6);
+        assert_eq!(transforms[1].translation, Vec3D::new(5, -2, 0));
+        assert!(map.contains(&Vec3D::new(-1, -1, 0)));
+        assert!(map.contains(&Vec3D::new(-1, 4, 0)));
+
+        let overlaps = overlap_graph(&scanner_results, &transforms, &alignment);
+        assert_eq!(
+            overlaps,
+            vec![Overlap {
+                scanners: (0, 1),
+                shared_beacons: 4
+            }]
+        );
+        let stricter = Alignment {
+            overlap_threshold: 5,
+            ..alignment
+        };
+        assert!(overlap_graph(&scanner_results, &transforms, &stricter).is_empty());
+        assert_eq!(
+            BeaconScanners(alignment).solve_both(&scanner_results).unwrap(),
+            (6, 7)
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_unaligned_scanners() {
+        let (dir, file) = example_file();
+        let alignment = Alignment::from_args(["--threshold", "13"].map(String::from)).unwrap();
+        let day = BeaconScanners(alignment);
+        let error = day.solve_both(&day.parse(&file).unwrap()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "4 of the 5 scanners don't overlap the map in at least 13 beacons"
+        );
+        assert!(assemble_map(Vec::new(), &Alignment::default()).is_err());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_answers() {
+        let (dir, file) = example_file();
+        let day = BeaconScanners(Alignment::default());
+        let scans = day.parse(file).unwrap();
+        drop(dir);
+        assert_eq!(day.solve_both(&scans).unwrap(), (79, 3621));
+        // The combined path has to give the same answers as the parts on their own
+        assert_eq!(day.part1(&scans).unwrap(), 79);
+        assert_eq!(day.part2(&scans).unwrap(), 3621);
+    }
+
+    #[test]
+    #[ignore]
+    fn perf_example() {
+        let (dir, file) = example_file();
+        let day = BeaconScanners(Alignment::default());
+        let answers = assert_completes_within("day19 both parts", Duration::from_secs(5), || {
+            solution::solve(&day, &day.parse(&file).unwrap()).unwrap()
+        });
+        assert_eq!(answers, (79, 3621));
+        drop(dir);
+    }
+}
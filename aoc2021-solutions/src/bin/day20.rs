@@ -0,0 +1,411 @@
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::checkpoint::{self, Checkpoints};
+use aoc2021::report::{Reporter, Verbosity};
+use aoc2021::{
+    field2d::Field2D, parse_grid, rle_field::RleField, simulation::iterate_n,
+    stream_items_from_file,
+};
+use aoc2021::{Input, InputSource};
+use std::ops::Range;
+
+fn grow<T: Clone + Default>(input: &Field2D<T>, amount: usize) -> Field2D<T> {
+    let mut res = Field2D::new_empty(input.width() + 2 * amount, input.height() + 2 * amount);
+    for x in 0..input.width() {
+        for y in 0..input.height() {
+            res[(x + amount, y + amount)] = input[(x, y)].clone();
+        }
+    }
+    res
+}
+
+fn translate_string_repr(input: String) -> Vec<bool> {
+    input.chars().map(|c| c == '#').collect()
+}
+
+fn parse_pixel(c: char) -> Result<bool> {
+    match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(anyhow!("expected '#' or '.'")),
+    }
+}
+
+/// What the infinite dark background around the image does during the enhancement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Background {
+    /// Dark pixels surrounded by dark pixels stay dark
+    Stable,
+    /// The background lights up in every odd step and goes dark again in every even one
+    Blinking,
+    /// The background lights up and stays lit, so infinitely many pixels are lit from the first step on
+    Growing,
+}
+
+impl Background {
+    /// Looks at the entries for an all dark (0) and an all lit (511) neighborhood.
+    fn classify(replacement_table: &[bool]) -> Result<Background> {
+        if replacement_table.len() != 512 {
+            bail!(
+                "The enhancement table needs 512 entries, but it has {}",
+                replacement_table.len()
+            );
+        }
+        Ok(match (replacement_table[0], replacement_table[511]) {
+            (false, _) => Background::Stable,
+            (true, false) => Background::Blinking,
+            (true, true) => Background::Growing,
+        })
+    }
+
+    /// Rejects step counts after which the background is lit, the number of lit pixels would be infinite then.
+    fn check_steps(self, steps: usize) -> Result<()> {
+        match self {
+            Background::Stable => Ok(()),
+            Background::Blinking if steps.is_multiple_of(2) => Ok(()),
+            Background::Blinking => bail!(
+                "The background blinks (table entry 0 is '#' and entry 511 is '.'), \
+                 after {} steps infinitely many pixels are lit",
+                steps
+            ),
+            Background::Growing if steps == 0 => Ok(()),
+            Background::Growing => bail!(
+                "Table entries 0 and 511 are both '#', so the infinite background stays lit \
+                 and the number of lit pixels is infinite"
+            ),
+        }
+    }
+}
+
+/// Reads the enhancement table and the image and makes sure the answer after `steps` steps is finite.
+fn read_puzzle<I: Input>(input: I, steps: usize) -> Result<(Vec<bool>, Field2D<bool>)> {
+    let mut lines = stream_items_from_file::<_, String>(input)?;
+    let replacement_table = translate_string_repr(lines.next().unwrap_or_default());
+    Background::classify(&replacement_table)?.check_steps(steps)?;
+    lines.next();
+    Ok((replacement_table, read_input_field(lines)?))
+}
+
+/// Reads the image, which starts after the enhancement table and a blank line.
+fn read_input_field(input: impl Iterator<Item = String>) -> Result<Field2D<bool>> {
+    let field = parse_grid(input, 3, parse_pixel)?;
+    Ok(grow(&field, 2))
+}
+
+fn step_field(old_field: &Field2D<bool>, replacement_table: &[bool]) -> Field2D<bool> {
+    let mut new_field = Field2D::new_empty(old_field.width() + 4, old_field.height() + 4);
+    for x in 1..old_field.width() - 1 {
+        for y in 1..old_field.height() - 1 {
+            let lookup = (0..3)
+                .flat_map(|ny| (0..3).map(move |nx| old_field[(x - 1 + nx, y - 1 + ny)]))
+                .fold(0, |sum, bit| (sum * 2) + if bit { 1 } else { 0 });
+            new_field[(x + 2, y + 2)] = replacement_table[lookup];
+        }
+    }
+
+    new_field
+}
+
+fn render_field(field: &Field2D<bool>) -> String {
+    let mut result = String::with_capacity((field.width() + 1) * field.height());
+    for y in 0..field.height() {
+        for x in 0..field.width() {
+            result.push(if field[(x, y)] { '#' } else { '.' });
+        }
+        result.push('\n');
+    }
+    result
+}
+
+fn visualize_field(field: &Field2D<bool>, reporter: &Reporter) {
+    if reporter.enabled(Verbosity::Normal) {
+        reporter.info(render_field(field).trim_end());
+    }
+}
+
+fn simulate(field: Field2D<bool>, replacement_table: Vec<bool>, steps: usize) -> Field2D<bool> {
+    iterate_n(field, steps, |field, i| {
+        let mut field = step_field(&field, &replacement_table);
+        // This is a hack to get proper simulations of the infinite fields even if index 0 of the replacement table is not `false`.
+        // This still requires that index 511 in the replacement is `false`, which `Background::classify` checks!
+        // Basically, the step function will always create a new 2-wide ring of `false` values around the entire image,
+        // and this ring must be completely lit up if we are on an even step...
+        if i % 2 == 0 && replacement_table[0] {
+            let w = field.width();
+            let h = field.height();
+            for x in 0..w {
+                for y in 0..3 {
+                    field[(x, y)] = true;
+                    field[(x, h - 1 - y)] = true;
+                }
+            }
+            for x in 0..3 {
+                for y in 0..h {
+                    field[(x, y)] = true;
+                    field[(w - 1 - x, y)] = true;
+                }
+            }
+        }
+        field
+    })
+    .0
+}
+
+/// Same as `step_field`, but the image stays run-length encoded and only the three input rows of the
+/// current output row are decoded. `fill_border` lights up the new ring of the image, see `simulate`.
+fn step_rle(old_field: &RleField, replacement_table: &[bool], fill_border: bool) -> RleField {
+    let (width, height) = (old_field.width() + 4, old_field.height() + 4);
+    let mut new_field = RleField::new_empty(width, 0);
+    for y in 0..height {
+        let mut row = vec![false; width];
+        let inner = (3..height - 3).contains(&y);
+        if inner {
+            let rows = [y - 3, y - 2, y - 1].map(|old_y| old_field.row(old_y));
+            for (x, value) in row.iter_mut().enumerate().take(width - 3).skip(3) {
+                let lookup = rows
+                    .iter()
+                    .flat_map(|old_row| &old_row[x - 3..x])
+                    .fold(0, |sum, &bit| (sum * 2) + if bit { 1 } else { 0 });
+                *value = replacement_table[lookup];
+            }
+        }
+        if fill_border {
+            if inner {
+                row[..3].fill(true);
+                row[width - 3..].fill(true);
+            } else {
+                row.fill(true);
+            }
+        }
+        new_field.push_row(row);
+    }
+    new_field
+}
+
+/// Runs the steps of the given range on an image that already went through the earlier ones,
+/// `after_step` receives the number of steps done so far and the image after each step.
+fn simulate_rle_between<F>(
+    field: RleField,
+    replacement_table: &[bool],
+    steps: Range<usize>,
+    mut after_step: F,
+) -> RleField
+where
+    F: FnMut(usize, &RleField),
+{
+    let first = steps.start;
+    iterate_n(field, steps.len(), |field, i| {
+        let step = first + i;
+        let field = step_rle(
+            &field,
+            replacement_table,
+            step.is_multiple_of(2) && replacement_table[0],
+        );
+        after_step(step + 1, &field);
+        field
+    })
+    .0
+}
+
+/// Runs the enhancement for `steps` steps on a run-length encoded image, which keeps long runs cheap.
+/// With checkpoints the image is saved every few steps and a resumed run continues from the saved one.
+fn lit_after<I: Input>(
+    input: I,
+    steps: usize,
+    checkpoints: Option<&Checkpoints>,
+    reporter: &Reporter,
+) -> Result<usize> {
+    let (replacement_table, field) = read_puzzle(input, steps)?;
+    let (done, field) = match checkpoints.map(|c| c.restore(DAY)).transpose()?.flatten() {
+        Some((done, state)) => (done, checkpoint::rle_field_from_json(&state)?),
+        None => (0, RleField::from(&field)),
+    };
+    if done > steps {
+        bail!("The checkpoint is already at step {} of {}", done, steps);
+    }
+    if done > 0 {
+        reporter.info(format!("Resuming after step {}", done));
+    }
+    let mut saved = Ok(());
+    let field = simulate_rle_between(field, &replacement_table, done..steps, |done, field| {
+        if let Some(checkpoints) = checkpoints.filter(|c| c.due(done) && saved.is_ok()) {
+            saved = checkpoints.save(DAY, done, checkpoint::rle_field_to_json(field));
+        }
+    });
+    saved?;
+    reporter.info(format!(
+        "{}x{} image stored in {} run boundaries",
+        field.width(),
+        field.height(),
+        field.run_boundaries()
+    ));
+    Ok(field.count_set())
+}
+
+fn part1<I: Input>(input: I, reporter: &Reporter) -> Result<usize> {
+    let (replacement_table, mut field) = read_puzzle(input, 2)?;
+
+    field = simulate(field, replacement_table, 2);
+
+    visualize_field(&field, reporter);
+
+    let lit_pixels = field.into_iter().filter(|&x| x).count();
+
+    Ok(lit_pixels)
+}
+
+fn part2<I: Input>(input: I, reporter: &Reporter) -> Result<usize> {
+    let (replacement_table, mut field) = read_puzzle(input, 50)?;
+
+    field = simulate(field, replacement_table, 50);
+
+    visualize_field(&field, reporter);
+
+    let lit_pixels = field.into_iter().filter(|&x| x).count();
+
+    Ok(lit_pixels)
+}
+
+const INPUT: &str = "input/day20.txt";
+const DAY: &str = "day20";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let (reporter, args) = Reporter::from_args(std::env::args().skip(1))?;
+    let (checkpoints, args) = Checkpoints::from_args(args)?;
+    if let [flag, steps] = args.as_slice() {
+        if flag == "--steps" {
+            let steps = steps.parse()?;
+            println!(
+                "Lit pixels after {} steps: {}",
+                steps,
+                lit_after(input, steps, checkpoints.as_ref(), &reporter)?
+            );
+            return Ok(());
+        }
+    }
+    println!(
+        "Answer for part 1: {}",
+        measure("part 1", || part1(input, &reporter))?
+    );
+    println!(
+        "Answer for part 2: {}",
+        measure("part 2", || part2(input, &reporter))?
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::{assert_snapshot, create_line_file};
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            ..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+            
+            #..#.
+            #....
+            ##..#
+            ..#..
+            ..###"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_image_after_two_steps() {
+        let (dir, file) = example_file();
+        let mut lines = stream_items_from_file::<_, String>(file).unwrap();
+        let replacement_table = translate_string_repr(lines.next().unwrap());
+        lines.next();
+        let field = simulate(read_input_field(lines).unwrap(), replacement_table, 2);
+        assert_snapshot("day20_step2", &render_field(&field));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_rle_simulation() {
+        let (dir, file) = example_file();
+        let mut lines = stream_items_from_file::<_, String>(&file).unwrap();
+        let mut replacement_table = translate_string_repr(lines.next().unwrap());
+        lines.next();
+        let field = read_input_field(lines).unwrap();
+        let rle = RleField::from(&field);
+        assert_eq!(Field2D::from(&rle), field);
+
+        // The second table flips the infinite background every step
+        let mut flipping = replacement_table.clone();
+        flipping[0] = true;
+        flipping[511] = false;
+        for table in [&mut replacement_table, &mut flipping] {
+            for steps in [1, 2, 7] {
+                let dense = simulate(field.clone(), table.clone(), steps);
+                let sparse = simulate_rle_between(rle.clone(), table, 0..steps, |_, _| ());
+                assert_eq!(sparse, RleField::from(&dense));
+                assert_eq!(sparse.count_set(), dense.iter().filter(|&&x| x).count());
+                assert!(sparse.run_boundaries() < dense.len());
+            }
+        }
+        assert_eq!(
+            lit_after(&file, 50, None, &Reporter::default()).unwrap(),
+            3351
+        );
+
+        // The second run continues from the image saved after step 14
+        let checkpoints = Checkpoints {
+            file: dir.path().join("day20.json"),
+            every: 7,
+            resume: true,
+        };
+        let reporter = Reporter::default();
+        assert_eq!(
+            lit_after(&file, 14, Some(&checkpoints), &reporter).unwrap(),
+            350
+        );
+        assert_eq!(checkpoints.restore(DAY).unwrap().unwrap().0, 14);
+        assert_eq!(
+            lit_after(&file, 50, Some(&checkpoints), &reporter).unwrap(),
+            3351
+        );
+        assert!(lit_after(&file, 20, Some(&checkpoints), &reporter).is_err());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_background_analysis() {
+        let (dir, file) = example_file();
+        let mut lines = stream_items_from_file::<_, String>(&file).unwrap();
+        let mut table = translate_string_repr(lines.next().unwrap());
+        assert_eq!(Background::classify(&table).unwrap(), Background::Stable);
+        assert!(read_puzzle(&file, 3).is_ok());
+
+        table[0] = true;
+        table[511] = false;
+        let blinking = Background::classify(&table).unwrap();
+        assert_eq!(blinking, Background::Blinking);
+        assert!(blinking.check_steps(2).is_ok());
+        assert!(blinking.check_steps(3).is_err());
+
+        table[511] = true;
+        let growing = Background::classify(&table).unwrap();
+        assert_eq!(growing, Background::Growing);
+        let error = growing.check_steps(2).unwrap_err().to_string();
+        assert!(error.contains("infinite"), "{}", error);
+
+        assert!(Background::classify(&table[..100]).is_err());
+        drop(dir);
+
+        let lines = ["#".repeat(512), String::new(), "#.".to_string()];
+        let (dir, file) = create_line_file(lines.iter(), None);
+        assert!(part1(&file, &Reporter::default()).is_err());
+        drop(dir);
+    }
+}
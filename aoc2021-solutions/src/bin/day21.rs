@@ -0,0 +1,418 @@
+use anyhow::anyhow;
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::{graph::DotGraph, parse::extract_all_ints, stream_items_from_file};
+use aoc2021::{Input, InputSource};
+use cached::proc_macro::cached;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+trait Die {
+    fn roll(&mut self) -> usize;
+}
+
+struct PracticeDie {
+    counter: usize,
+    limit: usize,
+}
+
+impl PracticeDie {
+    fn new(limit: usize) -> Self {
+        PracticeDie { counter: 0, limit }
+    }
+}
+
+impl Die for PracticeDie {
+    fn roll(&mut self) -> usize {
+        self.counter += 1;
+        let res = self.counter;
+        self.counter %= self.limit;
+        res
+    }
+}
+
+fn game(
+    mut die: impl Die,
+    score_limit: usize,
+    starting_positions: (usize, usize),
+) -> (usize, usize) {
+    let mut player1_pos = starting_positions.0;
+    let mut player2_pos = starting_positions.1;
+    let mut player1_score = 0;
+    let mut player2_score = 0;
+    let mut throws = 0;
+    loop {
+        let fields: usize = (0..3).map(|_| die.roll()).sum();
+        player1_pos = ((player1_pos + fields - 1) % 10) + 1;
+        player1_score += player1_pos;
+        throws += 3;
+        if player1_score >= score_limit {
+            return (player2_score, throws);
+        }
+
+        let fields: usize = (0..3).map(|_| die.roll()).sum();
+        player2_pos = ((player2_pos + fields - 1) % 10) + 1;
+        player2_score += player2_pos;
+        throws += 3;
+        if player2_score >= score_limit {
+            return (player1_score, throws);
+        }
+    }
+}
+
+fn extract_starting_position(line: &str) -> Result<usize> {
+    extract_all_ints(line)?
+        .pop()
+        .ok_or(anyhow!("No number in line"))
+}
+
+fn part1<I: Input>(input: I) -> Result<usize> {
+    let starting_positions: Vec<usize> = stream_items_from_file::<_, String>(input)?
+        .map(|line| extract_starting_position(&line))
+        .collect::<Result<_>>()?;
+    let die = PracticeDie::new(100);
+    let (loosing_score, throws) = game(die, 1000, (starting_positions[0], starting_positions[1]));
+    Ok(loosing_score * throws)
+}
+
+fn get_dice_combinations(sides: usize) -> HashMap<usize, usize> {
+    let mut res = HashMap::new();
+    for one in 1..=sides {
+        for two in 1..=sides {
+            for three in 1..=sides {
+                *res.entry(one + two + three).or_insert(0) += 1;
+            }
+        }
+    }
+    res
+}
+
+lazy_static! {
+    static ref DIRAC_DIE_COMBINATIONS: HashMap<usize, usize> = get_dice_combinations(3);
+}
+
+#[cached]
+fn dirac_game(
+    p1move: bool,
+    p1pos: usize,
+    p2pos: usize,
+    p1score: usize,
+    p2score: usize,
+) -> (usize, usize) {
+    let moving_player_pos = if p1move { p1pos } else { p2pos };
+    let moving_player_score = if p1move { p1score } else { p2score };
+
+    let mut result = (0, 0);
+    for (steps, options) in DIRAC_DIE_COMBINATIONS.iter() {
+        let new_pos = ((moving_player_pos + steps - 1) % 10) + 1;
+        let new_score = moving_player_score + new_pos;
+        if new_score >= 21 {
+            if p1move {
+                result.0 += options;
+            } else {
+                result.1 += options;
+            }
+        } else {
+            let sub = if p1move {
+                dirac_game(false, new_pos, p2pos, new_score, p2score)
+            } else {
+                dirac_game(true, p1pos, new_pos, p1score, new_score)
+            };
+            result.0 += options * sub.0;
+            result.1 += options * sub.1;
+        }
+    }
+    result
+}
+
+/// A state of the Dirac dice game, `p1move` tells whose turn it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GameState {
+    p1move: bool,
+    positions: (usize, usize),
+    scores: (usize, usize),
+}
+
+impl GameState {
+    fn new(starting_positions: (usize, usize)) -> Self {
+        GameState {
+            p1move: true,
+            positions: starting_positions,
+            scores: (0, 0),
+        }
+    }
+
+    /// Universes in which player 1 and 2 win from here on, taken from the memoized solver.
+    fn wins(&self) -> (usize, usize) {
+        dirac_game(
+            self.p1move,
+            self.positions.0,
+            self.positions.1,
+            self.scores.0,
+            self.scores.1,
+        )
+    }
+
+    /// Moves the current player by `steps`, `None` if that wins the game.
+    fn after_roll(&self, steps: usize) -> Option<GameState> {
+        let mut next = *self;
+        let (pos, score) = if self.p1move {
+            (&mut next.positions.0, &mut next.scores.0)
+        } else {
+            (&mut next.positions.1, &mut next.scores.1)
+        };
+        *pos = ((*pos + steps - 1) % 10) + 1;
+        *score += *pos;
+        if *score >= 21 {
+            return None;
+        }
+        next.p1move = !self.p1move;
+        Some(next)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "player {} to move, positions {}/{}, scores {}/{}",
+            if self.p1move { 1 } else { 2 },
+            self.positions.0,
+            self.positions.1,
+            self.scores.0,
+            self.scores.1
+        )
+    }
+}
+
+/// The first turns of the Dirac dice game, every node knows in how many universes each player wins from there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GameTree {
+    state: GameState,
+    wins: (usize, usize),
+    /// One branch per sum of the three rolls, `None` if the tree was cut off here
+    branches: Option<Vec<Branch>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Branch {
+    roll: usize,
+    /// Number of ways the three rolls add up to `roll`
+    universes: usize,
+    /// `None` if the moving player wins with this roll
+    next: Option<GameTree>,
+}
+
+impl GameTree {
+    fn build(state: GameState, depth: usize) -> Self {
+        let branches = (depth > 0).then(|| {
+            DIRAC_DIE_COMBINATIONS
+                .iter()
+                .map(|(&roll, &universes)| Branch {
+                    roll,
+                    universes,
+                    next: state
+                        .after_roll(roll)
+                        .map(|next| GameTree::build(next, depth - 1)),
+                })
+                .sorted_by_key(|branch| branch.roll)
+                .collect()
+        });
+        GameTree {
+            state,
+            wins: state.wins(),
+            branches,
+        }
+    }
+
+    fn to_dot(&self) -> DotGraph {
+        fn add(tree: &GameTree, graph: &mut DotGraph, next_id: &mut usize) -> usize {
+            let id = *next_id;
+            *next_id += 1;
+            let total = (tree.wins.0 + tree.wins.1).max(1);
+            let label = format!(
+                "{}\\nplayer 1 wins {} ({}%)\\nplayer 2 wins {} ({}%){}",
+                tree.state.describe(),
+                tree.wins.0,
+                tree.wins.0 * 100 / total,
+                tree.wins.1,
+                tree.wins.1 * 100 / total,
+                if tree.branches.is_none() {
+                    "\\n..."
+                } else {
+                    ""
+                }
+            );
+            graph.node(id, Some(&label));
+            for branch in tree.branches.iter().flatten() {
+                let child = match &branch.next {
+                    Some(next) => add(next, graph, next_id),
+                    None => {
+                        let leaf = *next_id;
+                        *next_id += 1;
+                        let winner = if tree.state.p1move { 1 } else { 2 };
+                        graph.node(leaf, Some(&format!("player {} wins", winner)));
+                        leaf
+                    }
+                };
+                let label = format!("roll {} (x{})", branch.roll, branch.universes);
+                graph.edge(id, child, Some(&label));
+            }
+            id
+        }
+
+        let mut graph = DotGraph::directed("dirac");
+        add(self, &mut graph, &mut 0);
+        graph
+    }
+
+    fn to_json(&self) -> String {
+        let state = &self.state;
+        let mut json = format!(
+            "{{\"mover\":{},\"positions\":[{},{}],\"scores\":[{},{}],\"wins\":[{},{}]",
+            if state.p1move { 1 } else { 2 },
+            state.positions.0,
+            state.positions.1,
+            state.scores.0,
+            state.scores.1,
+            self.wins.0,
+            self.wins.1
+        );
+        match &self.branches {
+            Some(branches) => {
+                let branches = branches
+                    .iter()
+                    .map(|branch| {
+                        let outcome = match &branch.next {
+                            Some(next) => format!("\"next\":{}", next.to_json()),
+                            None => format!("\"winner\":{}", if state.p1move { 1 } else { 2 }),
+                        };
+                        format!(
+                            "{{\"roll\":{},\"universes\":{},{}}}",
+                            branch.roll, branch.universes, outcome
+                        )
+                    })
+                    .join(",");
+                json.push_str(&format!(",\"branches\":[{}]}}", branches));
+            }
+            None => json.push_str(",\"truncated\":true}"),
+        }
+        json
+    }
+}
+
+/// Prints the game tree for `--tree dot|json [--depth N]`, the default depth is 2 turns.
+fn export_tree(starting_positions: (usize, usize), args: &[String]) -> Result<()> {
+    let mut format = None;
+    let mut depth = 2;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+        match arg.as_str() {
+            "--tree" => format = Some(value()?.clone()),
+            "--depth" => depth = value()?.parse()?,
+            other => return Err(anyhow!("Unknown argument {}", other)),
+        }
+    }
+    let tree = GameTree::build(GameState::new(starting_positions), depth);
+    match format.as_deref() {
+        Some("dot") => println!("{}", tree.to_dot()),
+        Some("json") => println!("{}", tree.to_json()),
+        _ => return Err(anyhow!("Pass --tree dot or --tree json")),
+    }
+    Ok(())
+}
+
+fn read_starting_positions<I: Input>(input: I) -> Result<(usize, usize)> {
+    let starting_positions: Vec<usize> = stream_items_from_file::<_, String>(input)?
+        .map(|line| extract_starting_position(&line))
+        .collect::<Result<_>>()?;
+    match starting_positions[..] {
+        [p1, p2] => Ok((p1, p2)),
+        _ => Err(anyhow!("Expected two starting positions")),
+    }
+}
+
+fn part2<I: Input>(input: I) -> Result<usize> {
+    let results = GameState::new(read_starting_positions(input)?).wins();
+    Ok([results.0, results.1].into_iter().max().unwrap())
+}
+
+const INPUT: &str = "input/day21.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // Other flags, like the ones the runner passes on, leave the day to solve the puzzle
+    if args.iter().any(|arg| arg == "--tree") {
+        return export_tree(read_starting_positions(input)?, &args);
+    }
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2021::test_helpers::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_game_tree() {
+        let tree = GameTree::build(GameState::new((4, 8)), 2);
+        assert_eq!(tree.wins, (444356092776315, 341960390180808));
+        let branches = tree.branches.as_ref().unwrap();
+        assert_eq!(
+            branches.iter().map(|branch| branch.roll).collect_vec(),
+            (3..=9).collect_vec()
+        );
+        assert_eq!(
+            branches
+                .iter()
+                .map(|branch| branch.universes)
+                .sum::<usize>(),
+            27
+        );
+        for tree in branches.iter().filter_map(|branch| branch.next.as_ref()) {
+            let sum = tree.branches.iter().flatten().fold((0, 0), |sum, branch| {
+                let wins = branch.next.as_ref().unwrap().wins;
+                (
+                    sum.0 + branch.universes * wins.0,
+                    sum.1 + branch.universes * wins.1,
+                )
+            });
+            assert_eq!(sum, tree.wins);
+            assert!(tree.branches.iter().flatten().all(|branch| branch
+                .next
+                .as_ref()
+                .unwrap()
+                .branches
+                .is_none()));
+        }
+
+        // Close to the end, some rolls win the game right away
+        let state = GameState {
+            p1move: false,
+            positions: (1, 7),
+            scores: (3, 15),
+        };
+        let tree = GameTree::build(state, 1);
+        let winning: Vec<usize> = tree
+            .branches
+            .iter()
+            .flatten()
+            .filter(|branch| branch.next.is_none())
+            .map(|branch| branch.roll)
+            .collect();
+        assert_eq!(winning, vec![3, 9]);
+
+        assert_snapshot(
+            "day21_tree",
+            &GameTree::build(GameState::new((4, 8)), 1)
+                .to_dot()
+                .to_string(),
+        );
+        let json = GameTree::build(GameState::new((4, 8)), 1).to_json();
+        assert!(json.starts_with(r#"{"mover":1,"positions":[4,8],"scores":[0,0],"wins":[444356092776315,341960390180808],"branches":[{"roll":3,"universes":1,"next":{"mover":2,"positions":[7,8]"#));
+        assert_eq!(json.matches(r#""truncated":true"#).count(), 7);
+    }
+}
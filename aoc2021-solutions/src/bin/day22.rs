@@ -0,0 +1,1010 @@
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::artifacts::{self, Artifacts};
+use aoc2021::error::InputError;
+use aoc2021::geometry::{total_volume, Cuboid, Interval, Point, VolumeOverflow};
+use aoc2021::repl::{self, Repl};
+use aoc2021::solution::{self, Solution};
+use aoc2021::stats::{self, Stats};
+use aoc2021::{for_each_input_line, lenient};
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use std::cmp::Reverse;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Action {
+    On,
+    Off,
+}
+
+/// Parses a range like `x=-20..26` of the given axis, errors point into `descriptor`.
+fn parse_interval(descriptor: &str, range: &str, axis: &str) -> Result<Interval, InputError> {
+    let bounds = range
+        .strip_prefix(axis)
+        .and_then(|range| range.strip_prefix('='))
+        .and_then(|bounds| bounds.split_once(".."))
+        .ok_or_else(|| {
+            let reason = format!("expected a range like {}=-3..7", axis);
+            InputError::in_text(descriptor, range, reason)
+        })?;
+    let bound = |token: &str| {
+        token
+            .parse()
+            .map_err(|e| InputError::in_text(descriptor, token, format!("invalid bound, {}", e)))
+    };
+    Ok(Interval(bound(bounds.0)?, bound(bounds.1)?))
+}
+
+/// Parses the `ranges` part of `descriptor`, e.g. `x=1..2,y=3..4,z=5..6`.
+fn parse_cuboid(descriptor: &str, ranges: &str) -> Result<Cuboid, InputError> {
+    // Missing ranges are reported at the end of the line
+    let end = &descriptor[descriptor.len()..];
+    let mut ranges = ranges.split(',');
+    let mut next_interval = |axis| parse_interval(descriptor, ranges.next().unwrap_or(end), axis);
+    let xi = next_interval("x")?;
+    let yi = next_interval("y")?;
+    let zi = next_interval("z")?;
+    Ok(Cuboid::from_intervals(&xi, &yi, &zi))
+}
+
+fn parse_action(descriptor: &str) -> Result<(Action, Cuboid), InputError> {
+    let (word, ranges) = descriptor
+        .split_once(' ')
+        .unwrap_or((descriptor, &descriptor[descriptor.len()..]));
+    let action = match word {
+        "on" => Action::On,
+        "off" => Action::Off,
+        _ => return Err(InputError::in_text(descriptor, word, "expected on or off")),
+    };
+    Ok((action, parse_cuboid(descriptor, ranges)?))
+}
+
+// The inputs for this day are rather long, so we avoid allocating a String for every line
+// and with the `fastpaths` feature map the input file into memory instead of copying it
+fn read_actions<I: Input>(input: I) -> Result<Vec<(Action, Cuboid)>> {
+    let mut actions = Vec::new();
+    let mut line_number = 0;
+    let mut error = None;
+    for_each_input_line(&input, |line| {
+        line_number += 1;
+        if error.is_none() {
+            match parse_action(line) {
+                Ok(action) => actions.push(action),
+                Err(e) => error = Some(e.at_line(line_number)),
+            }
+        }
+    })?;
+    match (error, input.name()) {
+        (Some(e), Some(name)) => Err(e.in_file(name).into()),
+        (Some(e), None) => Err(e.into()),
+        (None, _) => Ok(actions),
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct OptimizationReport {
+    shadowed: usize,
+    merged: usize,
+}
+
+impl OptimizationReport {
+    fn eliminated(&self) -> usize {
+        self.shadowed + self.merged
+    }
+}
+
+/// Returns the cuboid covering exactly `a` and `b` if there is one.
+/// That is the case if they agree on two axes and overlap or touch on the remaining one.
+fn merge_cuboids(a: &Cuboid, b: &Cuboid) -> Option<Cuboid> {
+    let a_intervals = [a.x_interval(), a.y_interval(), a.z_interval()];
+    let b_intervals = [b.x_interval(), b.y_interval(), b.z_interval()];
+    let differing = (0..3)
+        .filter(|&axis| a_intervals[axis] != b_intervals[axis])
+        .collect_vec();
+    match differing[..] {
+        [] => Some(a.clone()),
+        [axis] => {
+            let (ai, bi) = (&a_intervals[axis], &b_intervals[axis]);
+            if ai.0 <= bi.1 + 1 && bi.0 <= ai.1 + 1 {
+                let mut merged = a_intervals.clone();
+                merged[axis] = Interval(ai.0.min(bi.0), ai.1.max(bi.1));
+                Some(Cuboid::from_intervals(&merged[0], &merged[1], &merged[2]))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Shrinks the instruction list without changing the final reactor state:
+/// - Instructions that are completely covered by a later instruction have no effect and are dropped.
+/// - An instruction can be moved in front of all earlier instructions it does not intersect with,
+///   so if that makes it adjacent to an instruction of the same kind that it can be merged with, we merge them.
+fn optimize_actions(actions: Vec<(Action, Cuboid)>) -> (Vec<(Action, Cuboid)>, OptimizationReport) {
+    let mut report = OptimizationReport::default();
+
+    let mut unshadowed = Vec::with_capacity(actions.len());
+    for (i, (action, cuboid)) in actions.iter().enumerate() {
+        if actions[i + 1..]
+            .iter()
+            .any(|(_, later)| later.contains(cuboid))
+        {
+            report.shadowed += 1;
+        } else {
+            unshadowed.push((*action, cuboid.clone()));
+        }
+    }
+
+    let mut optimized: Vec<(Action, Cuboid)> = Vec::with_capacity(unshadowed.len());
+    'actions: for (action, cuboid) in unshadowed {
+        for target in (0..optimized.len()).rev() {
+            let (target_action, target_cuboid) = &optimized[target];
+            if *target_action == action {
+                if let Some(merged) = merge_cuboids(target_cuboid, &cuboid) {
+                    optimized[target].1 = merged;
+                    report.merged += 1;
+                    continue 'actions;
+                }
+            }
+            if target_cuboid.intersects(&cuboid) {
+                break;
+            }
+        }
+        optimized.push((action, cuboid));
+    }
+
+    (optimized, report)
+}
+
+fn execute_action(mut cuboids: Vec<Cuboid>, action: Action, new_cuboid: &Cuboid) -> Vec<Cuboid> {
+    match action {
+        Action::On => {
+            let mut resulting_cuboids = vec![new_cuboid.clone()];
+            for old_cuboid in cuboids.iter() {
+                resulting_cuboids = execute_action(resulting_cuboids, Action::Off, old_cuboid)
+            }
+            cuboids.append(&mut resulting_cuboids);
+            cuboids
+        }
+        Action::Off => {
+            let mut resulting_cuboids = Vec::with_capacity(cuboids.len());
+            for cuboid in cuboids {
+                if !cuboid.intersects(new_cuboid) {
+                    resulting_cuboids.push(cuboid);
+                } else {
+                    resulting_cuboids.append(&mut (&cuboid - new_cuboid));
+                }
+            }
+            resulting_cuboids
+        }
+    }
+}
+
+/// Number of instructions sampled for the growth chart of `--explain`.
+const GROWTH_SAMPLES: usize = 20;
+
+/// The length of the cuboid list after each instruction. Inputs whose instructions cut the active
+/// cuboids into many fragments make `execute_action` slow, this shows where that happens.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Growth(Vec<usize>);
+
+impl Growth {
+    /// Executes the instructions like `reboot` while recording the growth.
+    fn record(actions: &[(Action, Cuboid)]) -> (Vec<Cuboid>, Self) {
+        let mut counts = Vec::with_capacity(actions.len());
+        let cuboids = actions
+            .iter()
+            .fold(Vec::new(), |acc, (action, new_cuboid)| {
+                let cuboids = execute_action(acc, *action, new_cuboid);
+                counts.push(cuboids.len());
+                cuboids
+            });
+        (cuboids, Growth(counts))
+    }
+
+    /// The first instruction after which the list was longest and its length then.
+    fn peak(&self) -> Option<(usize, usize)> {
+        self.0
+            .iter()
+            .enumerate()
+            .max_by_key(|&(index, &count)| (count, Reverse(index)))
+            .map(|(index, &count)| (index + 1, count))
+    }
+
+    /// The instruction that added the most cuboids and how many it added.
+    fn largest_step(&self) -> Option<(usize, usize)> {
+        let before = std::iter::once(0).chain(self.0.iter().copied());
+        self.0
+            .iter()
+            .zip(before)
+            .enumerate()
+            .map(|(index, (&after, before))| (index + 1, after.saturating_sub(before)))
+            .max_by_key(|&(instruction, added)| (added, Reverse(instruction)))
+    }
+
+    fn to_csv(&self) -> String {
+        std::iter::once("instruction,cuboids\n".to_string())
+            .chain(
+                self.0
+                    .iter()
+                    .enumerate()
+                    .map(|(index, count)| format!("{},{}\n", index + 1, count)),
+            )
+            .collect()
+    }
+
+    fn explain(&self) -> String {
+        let (Some((peak_at, peak)), Some((step_at, added))) = (self.peak(), self.largest_step())
+        else {
+            return "No instructions\n".to_string();
+        };
+        let mut explanation = format!(
+            "{} cuboids after {} instructions, at most {} after instruction {}, \
+             instruction {} added the most ({})\n",
+            self.0[self.0.len() - 1],
+            self.0.len(),
+            peak,
+            peak_at,
+            step_at,
+            added
+        );
+        let stride = self.0.len().div_ceil(GROWTH_SAMPLES);
+        let samples = (stride - 1..self.0.len())
+            .step_by(stride)
+            .map(|index| (format!("after {}", index + 1), self.0[index]))
+            .collect_vec();
+        explanation += &stats::bar_chart(&samples, 40);
+        explanation
+    }
+}
+
+/// A piece of the reactor that is switched on by instruction `turned_on_by`.
+/// It is active after `from` instructions were executed, until `until` instructions were executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fragment {
+    cuboid: Cuboid,
+    turned_on_by: usize,
+    from: usize,
+    until: Option<usize>,
+}
+
+impl Fragment {
+    fn active_after(&self, instructions: usize) -> bool {
+        self.from <= instructions && self.until.is_none_or(|until| until > instructions)
+    }
+}
+
+/// Every fragment that was ever active, so the reactor can be inspected at any point of the reboot.
+/// Instructions are numbered from 1 like the lines of the input, after instruction 0 nothing is on.
+#[derive(Debug, Default)]
+struct History {
+    fragments: Vec<Fragment>,
+    active: Vec<usize>,
+    instructions: usize,
+}
+
+impl History {
+    fn record(actions: &[(Action, Cuboid)]) -> Self {
+        let mut history = History::default();
+        for (action, cuboid) in actions {
+            history.execute(*action, cuboid);
+        }
+        history
+    }
+
+    /// Unlike `execute_action`, switching on carves the new cuboid out of the active fragments,
+    /// so every cube is attributed to the last instruction that turned it on.
+    fn execute(&mut self, action: Action, new_cuboid: &Cuboid) {
+        self.instructions += 1;
+        let instruction = self.instructions;
+        let mut active = Vec::with_capacity(self.active.len());
+        for index in std::mem::take(&mut self.active) {
+            if !self.fragments[index].cuboid.intersects(new_cuboid) {
+                active.push(index);
+                continue;
+            }
+            self.fragments[index].until = Some(instruction);
+            let turned_on_by = self.fragments[index].turned_on_by;
+            for cuboid in &self.fragments[index].cuboid - new_cuboid {
+                active.push(self.fragments.len());
+                self.fragments.push(Fragment {
+                    cuboid,
+                    turned_on_by,
+                    from: instruction,
+                    until: None,
+                });
+            }
+        }
+        if action == Action::On {
+            active.push(self.fragments.len());
+            self.fragments.push(Fragment {
+                cuboid: new_cuboid.clone(),
+                turned_on_by: instruction,
+                from: instruction,
+                until: None,
+            });
+        }
+        self.active = active;
+    }
+
+    fn fragments_after(&self, instructions: usize) -> impl Iterator<Item = &Fragment> {
+        self.fragments
+            .iter()
+            .filter(move |fragment| fragment.active_after(instructions))
+    }
+
+    fn volume_after(&self, instructions: usize) -> Result<u128, VolumeOverflow> {
+        let cuboids = self
+            .fragments_after(instructions)
+            .map(|fragment| fragment.cuboid.clone())
+            .collect_vec();
+        total_volume(&cuboids)
+    }
+
+    /// The instruction that last turned the cube on, `None` if it is off after `instructions`.
+    fn turned_on_by(&self, cube: &Point, instructions: usize) -> Option<usize> {
+        let contains = |cuboid: &Cuboid| {
+            cuboid.x_interval().contains(cube.x())
+                && cuboid.y_interval().contains(cube.y())
+                && cuboid.z_interval().contains(cube.z())
+        };
+        self.fragments_after(instructions)
+            .find(|fragment| contains(&fragment.cuboid))
+            .map(|fragment| fragment.turned_on_by)
+    }
+}
+
+#[allow(dead_code)]
+fn scadviz(input: &Vec<Cuboid>) {
+    for cuboid in input {
+        println!(
+            "translate([{},{},{}])",
+            cuboid.from.x() * 10,
+            cuboid.from.y() * 10,
+            cuboid.from.z() * 10
+        );
+        println!(
+            "cube([{},{},{}]);",
+            cuboid.x_interval().len() * 10,
+            cuboid.y_interval().len() * 10,
+            cuboid.z_interval().len() * 10
+        );
+    }
+}
+
+struct ReactorExplorer {
+    cuboids: Vec<Cuboid>,
+    growth: Option<Growth>,
+    history: Option<History>,
+}
+
+impl ReactorExplorer {
+    fn new(actions: &[(Action, Cuboid)]) -> Self {
+        let (cuboids, growth) = Growth::record(actions);
+        ReactorExplorer {
+            cuboids,
+            growth: Some(growth),
+            history: None,
+        }
+    }
+
+    fn load<I: Input>(input: I) -> Result<Self> {
+        Ok(Self::new(&read_actions(input)?))
+    }
+
+    /// Like `load`, but keeps the full history of the reboot for the temporal queries.
+    fn load_with_history<I: Input>(input: I) -> Result<Self> {
+        let history = History::record(&read_actions(input)?);
+        let cuboids = history
+            .fragments_after(history.instructions)
+            .map(|fragment| fragment.cuboid.clone())
+            .collect();
+        Ok(ReactorExplorer {
+            cuboids,
+            growth: None,
+            history: Some(history),
+        })
+    }
+
+    fn history(&self) -> Result<&History> {
+        self.history
+            .as_ref()
+            .ok_or_else(|| anyhow!("No history recorded, start with --history"))
+    }
+
+    fn total_volume(&self) -> Result<u128, VolumeOverflow> {
+        total_volume(&self.cuboids)
+    }
+
+    fn volume_within(&self, region: &Cuboid) -> Result<u128, VolumeOverflow> {
+        let clipped = self
+            .cuboids
+            .iter()
+            .filter_map(|cuboid| cuboid.clipped_to(region))
+            .collect_vec();
+        total_volume(&clipped)
+    }
+}
+
+impl Artifacts for ReactorExplorer {
+    fn artifacts(&self) -> Vec<(String, String)> {
+        let mut artifacts = vec![(
+            "cuboids.txt".to_string(),
+            self.cuboids
+                .iter()
+                .map(|cuboid| format!("{}\n", cuboid))
+                .collect(),
+        )];
+        if let Some(growth) = &self.growth {
+            artifacts.push(("growth.csv".to_string(), growth.to_csv()));
+        }
+        artifacts
+    }
+}
+
+impl Repl for ReactorExplorer {
+    fn eval(&mut self, command: &str) -> Result<String> {
+        match command.split_once(' ') {
+            Some(("volume", region)) => {
+                let region = parse_cuboid(region, region)?;
+                Ok(self.volume_within(&region)?.to_string())
+            }
+            Some(("after", instructions)) => Ok(self
+                .history()?
+                .volume_after(instructions.parse()?)?
+                .to_string()),
+            Some(("who", query)) => {
+                let history = self.history()?;
+                let (cube, instructions) = match query.split_once(' ') {
+                    Some((cube, instructions)) => (cube, instructions.parse()?),
+                    None => (query, history.instructions),
+                };
+                let cube = cube
+                    .parse::<Point>()
+                    .map_err(|e| anyhow!("Invalid cube {}: {:?}", cube, e))?;
+                Ok(match history.turned_on_by(&cube, instructions) {
+                    Some(instruction) => format!("instruction {}", instruction),
+                    None => "off".to_string(),
+                })
+            }
+            None if command == "total" => Ok(self.total_volume()?.to_string()),
+            _ => bail!("Unknown command {}", command),
+        }
+    }
+
+    fn help(&self) -> String {
+        [
+            "volume x=<a>..<b>,y=<a>..<b>,z=<a>..<b>: count the active cubes in a region",
+            "total: count all active cubes",
+            "after <k>: count the active cubes after the first k instructions (needs --history)",
+            "who <x>,<y>,<z> [k]: the instruction that last turned a cube on (needs --history)",
+        ]
+        .join("\n")
+    }
+}
+
+/// The reboot steps as read from the input, before any of them is executed.
+struct RebootSteps(Vec<(Action, Cuboid)>);
+
+impl Stats for RebootSteps {
+    fn stats(&self) -> Vec<(String, String)> {
+        let count = |wanted| {
+            self.0
+                .iter()
+                .filter(|(action, _)| *action == wanted)
+                .count()
+        };
+        let mut stats = vec![
+            ("on instructions".to_string(), count(Action::On).to_string()),
+            (
+                "off instructions".to_string(),
+                count(Action::Off).to_string(),
+            ),
+        ];
+        for (axis, interval) in [
+            ("x", Cuboid::x_interval as fn(&Cuboid) -> Interval),
+            ("y", Cuboid::y_interval),
+            ("z", Cuboid::z_interval),
+        ] {
+            let from = self.0.iter().map(|(_, c)| interval(c).0).min();
+            let to = self.0.iter().map(|(_, c)| interval(c).1).max();
+            if let (Some(from), Some(to)) = (from, to) {
+                stats.push((format!("{} range", axis), Interval(from, to).to_string()));
+            }
+        }
+        stats
+    }
+}
+
+/// Runs the steps clipped to the initialization region.
+fn initialize(actions: Vec<(Action, Cuboid)>) -> Result<u128, VolumeOverflow> {
+    let init_interval = Interval(-50, 50);
+    let init_region = Cuboid::from_intervals(&init_interval, &init_interval, &init_interval);
+    let cuboids = actions
+        .into_iter()
+        .filter_map(|(action, cuboid)| Some((action, cuboid.clipped_to(&init_region)?)))
+        .fold(Vec::new(), |acc, (action, new_cuboid)| {
+            execute_action(acc, action, &new_cuboid)
+        });
+
+    // scadviz(&cuboids);
+
+    total_volume(&cuboids)
+}
+
+fn reboot(actions: Vec<(Action, Cuboid)>) -> Result<u128, VolumeOverflow> {
+    let cuboids = actions
+        .into_iter()
+        .fold(Vec::new(), |acc, (action, new_cuboid)| {
+            execute_action(acc, action, &new_cuboid)
+        });
+
+    // scadviz(&cuboids);
+
+    total_volume(&cuboids)
+}
+
+/// Day 22, both parts run the reboot steps, part 1 only within the initialization region.
+struct Reboot;
+
+impl Solution for Reboot {
+    type Parsed = Vec<(Action, Cuboid)>;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse<I: Input>(&self, input: I) -> Result<Self::Parsed> {
+        read_actions(input)
+    }
+
+    fn part1(&self, actions: &Self::Parsed) -> Result<u128> {
+        Ok(initialize(actions.clone())?)
+    }
+
+    fn part2(&self, actions: &Self::Parsed) -> Result<u128> {
+        Ok(reboot(actions.clone())?)
+    }
+}
+
+const INPUT: &str = "input/day22.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    if repl::requested() {
+        let mut explorer = if std::env::args().skip(1).any(|arg| arg == "--history") {
+            ReactorExplorer::load_with_history(input)?
+        } else {
+            ReactorExplorer::load(input)?
+        };
+        return Ok(repl::run(&mut explorer)?);
+    }
+    if lenient::requested() {
+        let actions = lenient::report(lenient::read_with(input, parse_action)?);
+        solution::print_answers(solution::solve(&Reboot, &actions)?);
+        return Ok(());
+    }
+    let actions = Reboot.parse(input)?;
+    if stats::requested() {
+        print!("{}", stats::render(&RebootSteps(actions)));
+        return Ok(());
+    }
+    let explain = std::env::args().skip(1).any(|arg| arg == "--explain");
+    let optimize = std::env::args().skip(1).any(|arg| arg == "--optimize");
+    // The artifacts and the explanation are taken from the same run of the steps
+    if explain || artifacts::requested_dir().is_some() {
+        let explorer = ReactorExplorer::new(&actions);
+        artifacts::write_if_requested("day22", &explorer)?;
+        if let Some(growth) = explorer.growth.as_ref().filter(|_| explain) {
+            print!("{}", growth.explain());
+        }
+    }
+    if explain && optimize {
+        let (optimized, _) = optimize_actions(actions.clone());
+        print!("Optimized: {}", Growth::record(&optimized).1.explain());
+    }
+    if !optimize {
+        solution::print_answers(solution::solve(&Reboot, &actions)?);
+        return Ok(());
+    }
+    // Part 1 clips the instructions to the initialization region, which the optimizer is not aware of
+    println!("Answer for part 1: {}", measure("part 1", || Reboot.part1(&actions))?);
+    let total = actions.len();
+    let (actions, report) = optimize_actions(actions);
+    println!(
+        "Optimizer eliminated {} of {} instructions ({} shadowed, {} merged)",
+        report.eliminated(),
+        total,
+        report.shadowed,
+        report.merged
+    );
+    println!("Answer for part 2: {}", reboot(actions)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::{assert_completes_within, create_line_file};
+    use indoc::indoc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                on x=-20..26,y=-36..17,z=-47..7
+                on x=-20..33,y=-21..23,z=-26..28
+                on x=-22..28,y=-29..23,z=-38..16
+                on x=-46..7,y=-6..46,z=-50..-1
+                on x=-49..1,y=-3..46,z=-24..28
+                on x=2..47,y=-22..22,z=-23..27
+                on x=-27..23,y=-28..26,z=-21..29
+                on x=-39..5,y=-6..47,z=-3..44
+                on x=-30..21,y=-8..43,z=-13..34
+                on x=-22..26,y=-27..20,z=-29..19
+                off x=-48..-32,y=26..41,z=-47..-37
+                on x=-12..35,y=6..50,z=-50..-2
+                off x=-48..-32,y=-32..-16,z=-15..-5
+                on x=-18..26,y=-33..15,z=-7..46
+                off x=-40..-22,y=-38..-28,z=23..41
+                on x=-16..35,y=-41..10,z=-47..6
+                off x=-32..-23,y=11..30,z=-14..3
+                on x=-49..-5,y=-3..45,z=-29..18
+                off x=18..30,y=-20..-8,z=-3..13
+                on x=-41..9,y=-7..43,z=-33..15
+                on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
+                on x=967..23432,y=45373..81175,z=27513..53682"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn example_file_xlarge() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            on x=-5..47,y=-31..22,z=-19..33
+            on x=-44..5,y=-27..21,z=-14..35
+            on x=-49..-1,y=-11..42,z=-10..38
+            on x=-20..34,y=-40..6,z=-44..1
+            off x=26..39,y=40..50,z=-2..11
+            on x=-41..5,y=-41..6,z=-36..8
+            off x=-43..-33,y=-45..-28,z=7..25
+            on x=-33..15,y=-32..19,z=-34..11
+            off x=35..47,y=-46..-34,z=-11..5
+            on x=-14..36,y=-6..44,z=-16..29
+            on x=-57795..-6158,y=29564..72030,z=20435..90618
+            on x=36731..105352,y=-21140..28532,z=16094..90401
+            on x=30999..107136,y=-53464..15513,z=8553..71215
+            on x=13528..83982,y=-99403..-27377,z=-24141..23996
+            on x=-72682..-12347,y=18159..111354,z=7391..80950
+            on x=-1060..80757,y=-65301..-20884,z=-103788..-16709
+            on x=-83015..-9461,y=-72160..-8347,z=-81239..-26856
+            on x=-52752..22273,y=-49450..9096,z=54442..119054
+            on x=-29982..40483,y=-108474..-28371,z=-24328..38471
+            on x=-4958..62750,y=40422..118853,z=-7672..65583
+            on x=55694..108686,y=-43367..46958,z=-26781..48729
+            on x=-98497..-18186,y=-63569..3412,z=1232..88485
+            on x=-726..56291,y=-62629..13224,z=18033..85226
+            on x=-110886..-34664,y=-81338..-8658,z=8914..63723
+            on x=-55829..24974,y=-16897..54165,z=-121762..-28058
+            on x=-65152..-11147,y=22489..91432,z=-58782..1780
+            on x=-120100..-32970,y=-46592..27473,z=-11695..61039
+            on x=-18631..37533,y=-124565..-50804,z=-35667..28308
+            on x=-57817..18248,y=49321..117703,z=5745..55881
+            on x=14781..98692,y=-1341..70827,z=15753..70151
+            on x=-34419..55919,y=-19626..40991,z=39015..114138
+            on x=-60785..11593,y=-56135..2999,z=-95368..-26915
+            on x=-32178..58085,y=17647..101866,z=-91405..-8878
+            on x=-53655..12091,y=50097..105568,z=-75335..-4862
+            on x=-111166..-40997,y=-71714..2688,z=5609..50954
+            on x=-16602..70118,y=-98693..-44401,z=5197..76897
+            on x=16383..101554,y=4615..83635,z=-44907..18747
+            off x=-95822..-15171,y=-19987..48940,z=10804..104439
+            on x=-89813..-14614,y=16069..88491,z=-3297..45228
+            on x=41075..99376,y=-20427..49978,z=-52012..13762
+            on x=-21330..50085,y=-17944..62733,z=-112280..-30197
+            on x=-16478..35915,y=36008..118594,z=-7885..47086
+            off x=-98156..-27851,y=-49952..43171,z=-99005..-8456
+            off x=2032..69770,y=-71013..4824,z=7471..94418
+            on x=43670..120875,y=-42068..12382,z=-24787..38892
+            off x=37514..111226,y=-45862..25743,z=-16714..54663
+            off x=25699..97951,y=-30668..59918,z=-15349..69697
+            off x=-44271..17935,y=-9516..60759,z=49131..112598
+            on x=-61695..-5813,y=40978..94975,z=8655..80240
+            off x=-101086..-9439,y=-7088..67543,z=33935..83858
+            off x=18020..114017,y=-48931..32606,z=21474..89843
+            off x=-77139..10506,y=-89994..-18797,z=-80..59318
+            off x=8476..79288,y=-75520..11602,z=-96624..-24783
+            on x=-47488..-1262,y=24338..100707,z=16292..72967
+            off x=-84341..13987,y=2429..92914,z=-90671..-1318
+            off x=-37810..49457,y=-71013..-7894,z=-105357..-13188
+            off x=-27365..46395,y=31009..98017,z=15428..76570
+            off x=-70369..-16548,y=22648..78696,z=-1892..86821
+            on x=-53470..21291,y=-120233..-33476,z=-44150..38147
+            off x=-93533..-4276,y=-16170..68771,z=-104985..-24507"}]
+            .iter(),
+            None,
+        )
+    }
+
+    // Instructions reaching beyond the initialization region still count with the part inside it
+    #[test]
+    fn test_clipped_instructions() {
+        let (dir, file) = create_line_file(
+            [
+                "on x=40..60,y=-60..60,z=0..0",
+                "off x=45..100,y=0..100,z=-100..100",
+            ]
+            .iter(),
+            None,
+        );
+        let actions = Reboot.parse(file).unwrap();
+        assert_eq!(Reboot.part1(&actions).unwrap(), 11 * 101 - 6 * 51);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_extreme_coordinates() {
+        let min = i64::MIN;
+        let max = i64::MAX;
+        let whole_line = Interval(min, max);
+        let line = Cuboid::from_intervals(&whole_line, &Interval(0, 0), &Interval(0, 0));
+        assert_eq!(line.volume(), Ok(1 << 64));
+
+        // Each side has 2^63 + 1 cubes, together that's far beyond i64 but still fits into u128
+        let half = Interval(min / 2, max / 2 + 1);
+        let slab = Cuboid::from_intervals(&half, &half, &Interval(-1, 1));
+        assert_eq!(slab.volume(), Ok(3 * ((1 << 63) + 1) * ((1 << 63) + 1)));
+
+        let plane = Cuboid::from_intervals(&whole_line, &whole_line, &Interval(0, 0));
+        assert_eq!(plane.volume(), Err(VolumeOverflow));
+        assert_eq!(total_volume([&slab, &slab, &slab]), Err(VolumeOverflow));
+
+        let reactor = ReactorExplorer {
+            cuboids: [
+                (Action::On, line.clone()),
+                (
+                    Action::Off,
+                    Cuboid::from_intervals(&Interval(min, -1), &Interval(0, 0), &Interval(0, 0)),
+                ),
+                (
+                    Action::On,
+                    Cuboid::from_intervals(
+                        &Interval(max, max),
+                        &Interval(min, max),
+                        &Interval(0, 0),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .fold(Vec::new(), |acc, (action, cuboid)| {
+                execute_action(acc, action, &cuboid)
+            }),
+            growth: None,
+            history: None,
+        };
+        // The line keeps its non-negative half, the crossing line shares one cube with it
+        assert_eq!(reactor.total_volume(), Ok((1 << 63) + (1 << 64) - 1));
+
+        let lines = [
+            format!("on x={}..{},y={}..{},z=0..1", min, max, min, max),
+            "off x=0..0,y=0..0,z=0..0".to_string(),
+        ];
+        let (dir, file) = create_line_file(lines.iter(), None);
+        let actions = Reboot.parse(&file).unwrap();
+        assert!(Reboot.part2(&actions).is_err());
+        assert_eq!(Reboot.part1(&actions).unwrap(), 101 * 101 * 2 - 1);
+        drop(dir);
+
+        let (dir, file) =
+            create_line_file(["on x=1..2,y=1..2,z=1..2", "on x=1..2,y=1..2"].iter(), None);
+        let e = Reboot.parse(&file).unwrap_err().downcast::<InputError>().unwrap();
+        assert_eq!(e.path, Some(file.as_ref().display().to_string()));
+        assert_eq!((e.line, e.column), (Some(2), Some(17)));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let (action, cuboid) =
+            parse_action("off x=-54112..-39298,y=-85059..-49293,z=-27449..7877").unwrap();
+        assert_eq!(action, Action::Off);
+        assert_eq!(cuboid.x_interval(), Interval(-54112, -39298));
+        assert_eq!(cuboid.y_interval(), Interval(-85059, -49293));
+        assert_eq!(cuboid.z_interval(), Interval(-27449, 7877));
+        assert!(parse_action("on x=10..12,y=10..12").is_err());
+        assert_eq!(
+            parse_action("on x=10..12,y=1O..12,z=3..4")
+                .unwrap_err()
+                .to_string(),
+            "column 15: invalid bound, invalid digit found in string at \"1O\""
+        );
+        assert_eq!(
+            parse_action("on x=10..12,y=10..12")
+                .unwrap_err()
+                .to_string(),
+            "column 21: expected a range like z=-3..7"
+        );
+        assert_eq!(
+            parse_action("of x=1..2,y=1..2,z=1..2").unwrap_err().column,
+            Some(1)
+        );
+        assert_eq!("x=-5..-3".parse::<Interval>().unwrap(), Interval(-5, -3));
+    }
+
+    #[test]
+    fn test_optimizer() {
+        let actions = [
+            "on x=0..3,y=0..3,z=0..3",
+            "off x=10..12,y=10..12,z=10..12",
+            "on x=4..6,y=0..3,z=0..3",
+            "on x=1..2,y=1..2,z=1..2",
+            "off x=-1..4,y=-1..4,z=-1..4",
+            "on x=0..1,y=0..1,z=0..1",
+            "on x=0..1,y=0..1,z=2..3",
+        ]
+        .iter()
+        .map(|line| parse_action(line).unwrap())
+        .collect_vec();
+        let expected = reboot(actions.clone());
+
+        let (optimized, report) = optimize_actions(actions);
+        assert_eq!(
+            report,
+            OptimizationReport {
+                shadowed: 2,
+                merged: 1
+            }
+        );
+        assert_eq!(optimized.len(), 4);
+        assert_eq!(reboot(optimized), expected);
+
+        let (dir, file) = example_file_xlarge();
+        let actions = read_actions(file).unwrap();
+        drop(dir);
+        let (optimized, _) = optimize_actions(actions.clone());
+        assert_eq!(reboot(optimized), reboot(actions));
+    }
+
+    #[test]
+    fn test_stats() {
+        let (dir, file) = create_line_file(
+            [indoc! {"
+                on x=10..12,y=10..12,z=10..12
+                on x=11..13,y=11..13,z=11..13
+                off x=9..11,y=9..11,z=9..11
+                on x=10..10,y=10..10,z=10..10"}]
+            .iter(),
+            None,
+        );
+        let steps = RebootSteps(read_actions(file).unwrap());
+        drop(dir);
+
+        assert_eq!(
+            stats::render(&steps),
+            indoc! {"
+                on instructions  : 3
+                off instructions : 1
+                x range          : 9..13
+                y range          : 9..13
+                z range          : 9..13
+            "}
+        );
+        assert_eq!(stats::render(&RebootSteps(Vec::new())).lines().count(), 2);
+    }
+
+    #[test]
+    fn test_repl() {
+        let (dir, file) = example_file();
+        let mut explorer = ReactorExplorer::load(file).unwrap();
+        drop(dir);
+
+        assert_eq!(
+            explorer
+                .eval("volume x=-50..50,y=-50..50,z=-50..50")
+                .unwrap(),
+            "590784"
+        );
+        assert_eq!(explorer.eval("volume x=0..0,y=0..0,z=0..0").unwrap(), "1");
+        assert!(explorer.eval("volume x=0..0").is_err());
+        assert!(explorer.eval("area").is_err());
+        assert_eq!(explorer.eval("total").unwrap(), "39769202357779");
+
+        // The dumped cuboids are disjoint, so their volumes add up to the number of active cubes
+        let (_, cuboids) = explorer.artifacts().swap_remove(0);
+        let dumped = cuboids
+            .lines()
+            .map(|line| parse_cuboid(line, line).unwrap())
+            .collect_vec();
+        assert_eq!(total_volume(&dumped), explorer.total_volume());
+    }
+
+    #[test]
+    fn test_growth() {
+        let (dir, file) = create_line_file(
+            [indoc! {"
+                on x=10..12,y=10..12,z=10..12
+                on x=11..13,y=11..13,z=11..13
+                off x=9..11,y=9..11,z=9..11
+                on x=10..10,y=10..10,z=10..10"}]
+            .iter(),
+            None,
+        );
+        let actions = read_actions(file).unwrap();
+        drop(dir);
+
+        let (cuboids, growth) = Growth::record(&actions);
+        assert_eq!(cuboids.len(), growth.0[3]);
+        assert_eq!(growth.0, vec![1, 4, 6, 7]);
+        assert_eq!(growth.peak(), Some((4, 7)));
+        assert_eq!(growth.largest_step(), Some((2, 3)));
+        assert_eq!(growth.to_csv(), "instruction,cuboids\n1,1\n2,4\n3,6\n4,7\n");
+        let explanation = growth.explain();
+        assert!(explanation
+            .starts_with("7 cuboids after 4 instructions, at most 7 after instruction 4"));
+        assert_eq!(explanation.lines().count(), 5);
+        assert_eq!(Growth::default().explain(), "No instructions\n");
+    }
+
+    #[test]
+    fn test_history() {
+        let (dir, file) = create_line_file(
+            [indoc! {"
+                on x=10..12,y=10..12,z=10..12
+                on x=11..13,y=11..13,z=11..13
+                off x=9..11,y=9..11,z=9..11
+                on x=10..10,y=10..10,z=10..10"}]
+            .iter(),
+            None,
+        );
+        let history = History::record(&read_actions(&file).unwrap());
+        let volumes = (0..=4)
+            .map(|k| history.volume_after(k).unwrap())
+            .collect_vec();
+        assert_eq!(volumes, [0, 27, 46, 38, 39]);
+        assert_eq!(history.turned_on_by(&Point::new(10, 10, 10), 4), Some(4));
+        assert_eq!(history.turned_on_by(&Point::new(10, 10, 10), 3), None);
+        assert_eq!(history.turned_on_by(&Point::new(12, 12, 12), 4), Some(2));
+        assert_eq!(history.turned_on_by(&Point::new(12, 12, 12), 1), Some(1));
+        assert_eq!(history.turned_on_by(&Point::new(10, 12, 12), 4), Some(1));
+        assert_eq!(history.turned_on_by(&Point::new(11, 11, 11), 4), None);
+
+        let mut explorer = ReactorExplorer::load_with_history(&file).unwrap();
+        assert_eq!(explorer.eval("after 2").unwrap(), "46");
+        assert_eq!(explorer.eval("who 12,12,12").unwrap(), "instruction 2");
+        assert_eq!(explorer.eval("who 10,10,10 3").unwrap(), "off");
+        assert_eq!(explorer.eval("total").unwrap(), "39");
+        assert!(ReactorExplorer::load(&file)
+            .unwrap()
+            .eval("after 2")
+            .is_err());
+        drop(dir);
+
+        let (dir, file) = example_file();
+        let actions = read_actions(&file).unwrap();
+        drop(dir);
+        let history = History::record(&actions);
+        for k in [0, 5, 11, 20, actions.len()] {
+            assert_eq!(
+                history.volume_after(k),
+                reboot(actions[..k].to_vec()),
+                "after {} instructions",
+                k
+            );
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn perf_example() {
+        let (dir, file) = example_file_xlarge();
+        let answer = assert_completes_within("day22 part 2", Duration::from_secs(2), || {
+            Reboot.part2(&Reboot.parse(&file).unwrap()).unwrap()
+        });
+        assert_eq!(answer, 2758514936282235);
+        drop(dir);
+    }
+}
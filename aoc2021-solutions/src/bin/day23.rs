@@ -0,0 +1,1375 @@
+use anyhow::{anyhow, bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::artifacts::{self, Artifacts};
+use aoc2021::repl::{self, Repl};
+#[cfg(test)]
+use aoc2021::search::bfs_states;
+use aoc2021::search::{SearchObserver, SearchRecord};
+use aoc2021::stream_items_from_file;
+use aoc2021::{Input, InputSource};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::Display,
+    path::Path,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Token {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Token {
+    fn target_room(&self) -> RoomId {
+        match self {
+            Token::A => RoomId(0),
+            Token::B => RoomId(1),
+            Token::C => RoomId(2),
+            Token::D => RoomId(3),
+        }
+    }
+}
+
+/// The parts of the puzzle's rules that variants of it change: the energy a single step costs each type of
+/// amphipod and whether they may stop on the hallway spaces right outside of the rooms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rules {
+    /// Indexed by the amphipod's target room, i.e. in the order A, B, C, D
+    step_costs: [usize; 4],
+    stop_outside_rooms: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            step_costs: [1, 10, 100, 1000],
+            stop_outside_rooms: false,
+        }
+    }
+}
+
+impl Rules {
+    fn step_cost(&self, token: Token) -> usize {
+        self.step_costs[token.target_room().0]
+    }
+
+    /// Reads rules like the following, anything that isn't given keeps the value of the puzzle's own rules.
+    /// ```toml
+    /// stop_outside_rooms = true
+    ///
+    /// [step_costs]
+    /// B = 20
+    /// ```
+    fn parse(content: &str) -> Result<Rules> {
+        let mut rules = Rules::default();
+        let table = content.parse::<toml::Value>()?;
+        let table = table
+            .as_table()
+            .ok_or_else(|| anyhow!("Expected a table of rules"))?;
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("stop_outside_rooms", toml::Value::Boolean(allowed)) => {
+                    rules.stop_outside_rooms = *allowed
+                }
+                ("step_costs", toml::Value::Table(costs)) => {
+                    for (token, cost) in costs {
+                        let room = RoomId::ALL
+                            .into_iter()
+                            .find(|room| room.token().to_string() == *token)
+                            .ok_or_else(|| anyhow!("There is no amphipod type {}", token))?;
+                        rules.step_costs[room.0] =
+                            cost.as_integer().filter(|&cost| cost > 0).ok_or_else(|| {
+                                anyhow!("The step cost of {} must be positive", token)
+                            })? as usize;
+                    }
+                }
+                _ => bail!("Unknown rule {} = {}", key, value),
+            }
+        }
+        Ok(rules)
+    }
+
+    fn load<P: AsRef<Path>>(path: P) -> Result<Rules> {
+        Rules::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// One of the four side rooms, counted from the left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RoomId(usize);
+
+impl RoomId {
+    const ALL: [RoomId; 4] = [RoomId(0), RoomId(1), RoomId(2), RoomId(3)];
+
+    /// The hallway column in front of the room, amphipods can't stop there.
+    fn door(self) -> usize {
+        2 + 2 * self.0
+    }
+
+    /// The type of amphipod that belongs into this room.
+    fn token(self) -> Token {
+        [Token::A, Token::B, Token::C, Token::D][self.0]
+    }
+}
+
+/// A hallway column an amphipod can stop in, i.e. any column from 0 to 10 except for the doors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct HallwayPos(usize);
+
+impl HallwayPos {
+    const ALL: [HallwayPos; 7] = [
+        HallwayPos(0),
+        HallwayPos(1),
+        HallwayPos(3),
+        HallwayPos(5),
+        HallwayPos(7),
+        HallwayPos(9),
+        HallwayPos(10),
+    ];
+    /// The spaces right outside of the rooms, amphipods only stop there if the `Rules` allow it.
+    const DOORS: [HallwayPos; 4] = [HallwayPos(2), HallwayPos(4), HallwayPos(6), HallwayPos(8)];
+    /// The orders in which moves out of and into the rooms are generated, the search breaks ties by them.
+    const LEAVE_ORDER: [HallwayPos; 7] = [
+        HallwayPos(1),
+        HallwayPos(0),
+        HallwayPos(9),
+        HallwayPos(10),
+        HallwayPos(3),
+        HallwayPos(5),
+        HallwayPos(7),
+    ];
+    const ENTER_ORDER: [HallwayPos; 7] = [
+        HallwayPos(3),
+        HallwayPos(5),
+        HallwayPos(7),
+        HallwayPos(1),
+        HallwayPos(0),
+        HallwayPos(9),
+        HallwayPos(10),
+    ];
+
+    fn new(column: usize) -> Option<HallwayPos> {
+        Self::ALL.into_iter().find(|pos| pos.0 == column)
+    }
+
+    /// Every position an amphipod could stand on under `rules`.
+    fn allowed(rules: &Rules) -> impl Iterator<Item = HallwayPos> {
+        let doors = if rules.stop_outside_rooms {
+            &Self::DOORS[..]
+        } else {
+            &[]
+        };
+        Self::ALL.into_iter().chain(doors.iter().copied())
+    }
+
+    fn column(self) -> usize {
+        self.0
+    }
+
+    /// Steps along the hallway between this position and the door of `room`.
+    fn distance_to(self, room: RoomId) -> usize {
+        self.0.abs_diff(room.door())
+    }
+
+    /// The positions an amphipod passes on its way between here and the door of `room`.
+    fn between(self, room: RoomId) -> impl Iterator<Item = HallwayPos> {
+        let (low, high) = (self.0.min(room.door()), self.0.max(room.door()));
+        Self::ALL
+            .into_iter()
+            .filter(move |pos| low < pos.0 && pos.0 < high)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+struct GameState {
+    room_size: usize,
+    rooms: [Vec<Token>; 4],
+    hallway_spaces: [Option<Token>; 3],
+    hallway_storage: [[Option<Token>; 2]; 2],
+    doorways: [Option<Token>; 4],
+}
+
+impl GameState {
+    fn new_empty(room_size: usize) -> GameState {
+        GameState {
+            room_size,
+            rooms: Default::default(),
+            hallway_spaces: Default::default(),
+            hallway_storage: Default::default(),
+            doorways: Default::default(),
+        }
+    }
+
+    fn new_finished(room_size: usize) -> GameState {
+        let mut empty = GameState::new_empty(room_size);
+        for room in RoomId::ALL {
+            *empty.room_mut(room) = vec![room.token(); room_size];
+        }
+        empty
+    }
+
+    fn room(&self, room: RoomId) -> &Vec<Token> {
+        &self.rooms[room.0]
+    }
+
+    fn room_mut(&mut self, room: RoomId) -> &mut Vec<Token> {
+        &mut self.rooms[room.0]
+    }
+
+    fn hallway(&self, pos: HallwayPos) -> Option<Token> {
+        match pos.column() {
+            0 => self.hallway_storage[0][1],
+            1 => self.hallway_storage[0][0],
+            column @ (2 | 4 | 6 | 8) => self.doorways[column / 2 - 1],
+            9 => self.hallway_storage[1][0],
+            10 => self.hallway_storage[1][1],
+            column => self.hallway_spaces[(column - 3) / 2],
+        }
+    }
+
+    fn hallway_mut(&mut self, pos: HallwayPos) -> &mut Option<Token> {
+        match pos.column() {
+            0 => &mut self.hallway_storage[0][1],
+            1 => &mut self.hallway_storage[0][0],
+            column @ (2 | 4 | 6 | 8) => &mut self.doorways[column / 2 - 1],
+            9 => &mut self.hallway_storage[1][0],
+            10 => &mut self.hallway_storage[1][1],
+            column => &mut self.hallway_spaces[(column - 3) / 2],
+        }
+    }
+
+    /// Steps from the top amphipod of the room to the door.
+    fn room_exit_cost(&self, room: RoomId) -> usize {
+        self.room_size - self.room(room).len() + 1
+    }
+
+    /// Steps from the door to the free space on top of the room.
+    fn room_enter_cost(&self, room: RoomId) -> usize {
+        self.room_size - self.room(room).len()
+    }
+
+    /// Whether an amphipod could walk between `pos` and the door of `room`, ignoring `pos` itself.
+    /// The doors themselves are only ever occupied if the rules allow amphipods to stop there.
+    fn path_is_free(&self, pos: HallwayPos, room: RoomId) -> bool {
+        let (low, high) = (pos.0.min(room.door()), pos.0.max(room.door()));
+        pos.between(room).all(|pos| self.hallway(pos).is_none())
+            && HallwayPos::DOORS
+                .into_iter()
+                .filter(|door| low <= door.0 && door.0 <= high && *door != pos)
+                .all(|door| self.hallway(door).is_none())
+    }
+
+    /// Rooms can only be entered if they have space and contain no amphipods of other types.
+    fn can_enter(&self, room: RoomId) -> bool {
+        self.room(room).len() < self.room_size
+            && self.room(room).iter().all(|t| t.target_room() == room)
+    }
+
+    /// Lower bound for the cost of sorting the burrow: every amphipod that still has to move
+    /// walks the hallway distance to its room and takes at least one step into it.
+    fn min_remaining_cost(&self, rules: &Rules) -> usize {
+        let to_room = |token: &Token, column: usize| {
+            (column.abs_diff(token.target_room().door()) + 1) * rules.step_cost(*token)
+        };
+
+        let hallway = HallwayPos::allowed(rules)
+            .filter_map(|pos| self.hallway(pos).map(|token| to_room(&token, pos.column())))
+            .sum::<usize>();
+
+        let rooms = RoomId::ALL
+            .into_iter()
+            .flat_map(|room_id| {
+                let room = self.room(room_id);
+                // Everything above the first misplaced amphipod has to leave the room
+                let first_misplaced = room
+                    .iter()
+                    .position(|t| t.target_room() != room_id)
+                    .unwrap_or(room.len());
+                room.iter()
+                    .enumerate()
+                    .skip(first_misplaced)
+                    .map(move |(height, token)| {
+                        let exit = (self.room_size - height) * rules.step_cost(*token);
+                        if token.target_room() == room_id {
+                            // Step out to the side and back in
+                            exit + 3 * rules.step_cost(*token)
+                        } else {
+                            exit + to_room(token, room_id.door())
+                        }
+                    })
+            })
+            .sum::<usize>();
+
+        hallway + rooms
+    }
+
+    fn generate_next_states(&self, rules: &Rules) -> Vec<(usize, GameState)> {
+        let doors = if rules.stop_outside_rooms {
+            &HallwayPos::DOORS[..]
+        } else {
+            &[]
+        };
+        let mut states = Vec::new();
+        for room in RoomId::ALL {
+            if self.room(room).iter().all(|t| t.target_room() == room) {
+                // This room is either empty or in a properly sorted state, no need to do anything now
+                continue;
+            }
+            let token = *self.room(room).last().unwrap();
+            // Move the top amphipod to any free hallway position it can reach
+            for pos in HallwayPos::LEAVE_ORDER.iter().chain(doors).copied() {
+                if self.hallway(pos).is_none() && self.path_is_free(pos, room) {
+                    let mut new_state = self.clone();
+                    new_state.room_mut(room).pop();
+                    *new_state.hallway_mut(pos) = Some(token);
+                    let cost = self.room_exit_cost(room) + pos.distance_to(room);
+                    states.push((cost * rules.step_cost(token), new_state));
+                }
+            }
+        }
+
+        for pos in HallwayPos::ENTER_ORDER.iter().chain(doors).copied() {
+            if let Some(token) = self.hallway(pos) {
+                let room = token.target_room();
+                if self.can_enter(room) && self.path_is_free(pos, room) {
+                    let mut new_state = self.clone();
+                    new_state.hallway_mut(pos).take();
+                    new_state.room_mut(room).push(token);
+                    let cost = pos.distance_to(room) + self.room_enter_cost(room);
+                    states.push((cost * rules.step_cost(token), new_state));
+                }
+            }
+        }
+        states
+    }
+}
+
+/// Bits per cell of a `PackedState`, 0 for an empty cell and the room number plus 1 for an amphipod.
+const CELL_BITS: usize = 4;
+const HALLWAY_CELLS: usize = 11;
+/// The deepest rooms a `PackedState` can hold, 11 hallway cells and 16 room cells take 108 bits.
+const MAX_ROOM_SIZE: usize = 4;
+
+/// A `GameState` packed into a single integer, so the search can hash, compare and copy states
+/// without touching the heap. The hallway columns take the lowest cells, followed by the rooms
+/// from left to right with their cells from the bottom up, the room size is stored above them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PackedState(u128);
+
+impl PackedState {
+    const ROOM_SIZE_SHIFT: usize = (HALLWAY_CELLS + 4 * MAX_ROOM_SIZE) * CELL_BITS;
+
+    fn cell(self, index: usize) -> Option<Token> {
+        match (self.0 >> (index * CELL_BITS)) & 0xf {
+            0 => None,
+            code => Some(RoomId(code as usize - 1).token()),
+        }
+    }
+
+    fn room_size(self) -> usize {
+        (self.0 >> Self::ROOM_SIZE_SHIFT) as usize
+    }
+
+    fn unpack(self) -> GameState {
+        let room_size = self.room_size();
+        let mut state = GameState::new_empty(room_size);
+        for column in 0..HALLWAY_CELLS {
+            *state.hallway_mut(HallwayPos(column)) = self.cell(column);
+        }
+        for room in RoomId::ALL {
+            let first = HALLWAY_CELLS + room.0 * MAX_ROOM_SIZE;
+            *state.room_mut(room) = (first..first + room_size)
+                .map_while(|index| self.cell(index))
+                .collect();
+        }
+        state
+    }
+}
+
+impl From<&GameState> for PackedState {
+    fn from(state: &GameState) -> Self {
+        assert!(
+            state.room_size <= MAX_ROOM_SIZE,
+            "Rooms of size {} can't be packed",
+            state.room_size
+        );
+        let code = |token: Token| token.target_room().0 as u128 + 1;
+        let hallway = (0..HALLWAY_CELLS)
+            .filter_map(|column| Some((column, state.hallway(HallwayPos(column))?)));
+        let rooms = RoomId::ALL.into_iter().flat_map(|room| {
+            let first = HALLWAY_CELLS + room.0 * MAX_ROOM_SIZE;
+            state
+                .room(room)
+                .iter()
+                .enumerate()
+                .map(move |(height, &token)| (first + height, token))
+        });
+        let cells = hallway.chain(rooms).fold(0, |packed, (index, token)| {
+            packed | code(token) << (index * CELL_BITS)
+        });
+        PackedState(cells | (state.room_size as u128) << Self::ROOM_SIZE_SHIFT)
+    }
+}
+
+impl From<PackedState> for GameState {
+    fn from(packed: PackedState) -> Self {
+        packed.unpack()
+    }
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Display for GameState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cell = |t: &Option<Token>| t.map_or(".".to_string(), |t| t.to_string());
+        writeln!(f, "#############")?;
+        let hallway: String = (0..11)
+            .map(|column| cell(&self.hallway(HallwayPos(column))))
+            .collect();
+        writeln!(f, "#{}#", hallway)?;
+        for depth in 0..self.room_size {
+            let (left, right) = if depth == 0 {
+                ("###", "##")
+            } else {
+                ("  #", "")
+            };
+            write!(f, "{}", left)?;
+            for room in self.rooms.iter() {
+                // Rooms are stored bottom to top
+                write!(
+                    f,
+                    "{}#",
+                    cell(&room.get(self.room_size - 1 - depth).copied())
+                )?;
+            }
+            writeln!(f, "{}", right)?;
+        }
+        write!(f, "  #########")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PathFindEntry {
+    state: PackedState,
+    score: usize,
+}
+
+impl PartialOrd for PathFindEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathFindEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+fn find_minimal_score(start: GameState, rules: &Rules) -> Option<usize> {
+    find_minimal_path(start, rules).map(|(score, _)| score)
+}
+
+/// Plays the seemingly best moves until the burrow is sorted, which gives an upper bound for the optimal cost.
+/// Dead ends are backtracked, but only up to a fixed number of states to keep this cheap. Moves are either
+/// ranked by their cost plus the remaining estimate or by the remaining estimate alone, which works
+/// better for deep rooms, and the cheaper result is used.
+fn greedy_rollout(start: &GameState, rules: &Rules) -> Option<usize> {
+    const BUDGET: usize = 10_000;
+
+    fn rollout(
+        state: GameState,
+        cost: usize,
+        goal: &GameState,
+        rules: &Rules,
+        by_total: bool,
+        visited: &mut HashSet<PackedState>,
+    ) -> Option<usize> {
+        if state == *goal {
+            return Some(cost);
+        }
+        if visited.len() >= BUDGET || !visited.insert(PackedState::from(&state)) {
+            return None;
+        }
+        state
+            .generate_next_states(rules)
+            .into_iter()
+            .sorted_by_key(|(move_cost, next)| {
+                let remaining = next.min_remaining_cost(rules);
+                if by_total {
+                    (move_cost + remaining, 0)
+                } else {
+                    (remaining, *move_cost)
+                }
+            })
+            .find_map(|(move_cost, next)| {
+                rollout(next, cost + move_cost, goal, rules, by_total, visited)
+            })
+    }
+
+    let goal = GameState::new_finished(start.room_size);
+    [true, false]
+        .into_iter()
+        .filter_map(|by_total| {
+            rollout(
+                start.clone(),
+                0,
+                &goal,
+                rules,
+                by_total,
+                &mut HashSet::new(),
+            )
+        })
+        .min()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchOptions {
+    /// Order the states by cost plus `min_remaining_cost` (A*) instead of the cost alone (Dijkstra)
+    heuristic: bool,
+    /// Skip states that can't beat the cost of a greedy rollout
+    prune: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            heuristic: true,
+            prune: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SearchStats {
+    generated: usize,
+    expanded: usize,
+    pruned: usize,
+    max_heap: usize,
+    upper_bound: Option<usize>,
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>9} generated {:>9} expanded {:>9} pruned {:>8} max heap",
+            self.generated, self.expanded, self.pruned, self.max_heap
+        )?;
+        if let Some(bound) = self.upper_bound {
+            write!(f, " (greedy bound {})", bound)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the cheapest way to sort the burrow, returning its cost and all states along the way.
+fn find_minimal_path(start: GameState, rules: &Rules) -> Option<(usize, Vec<GameState>)> {
+    search(start, rules, SearchOptions::default(), &mut ()).0
+}
+
+fn search<O: SearchObserver<GameState>>(
+    start: GameState,
+    rules: &Rules,
+    options: SearchOptions,
+    observer: &mut O,
+) -> (Option<(usize, Vec<GameState>)>, SearchStats) {
+    let mut stats = SearchStats::default();
+    let mut open_nodes = BinaryHeap::new();
+    let mut known_paths = HashMap::new();
+    let mut preds: HashMap<PackedState, (usize, PackedState)> = HashMap::new();
+
+    let goal = PackedState::from(&GameState::new_finished(start.room_size));
+    let estimate = |state: &GameState| {
+        if options.heuristic {
+            state.min_remaining_cost(rules)
+        } else {
+            0
+        }
+    };
+    if options.prune {
+        stats.upper_bound = greedy_rollout(&start, rules);
+    }
+
+    observer.discovered(&start);
+    let start = PackedState::from(&start);
+    open_nodes.push(Reverse(PathFindEntry {
+        score: estimate(&start.unpack()),
+        state: start,
+    }));
+    known_paths.insert(start, 0);
+
+    while let Some(Reverse(current)) = open_nodes.pop() {
+        stats.expanded += 1;
+        let state = current.state.unpack();
+        observer.expanded(&state);
+        let current_score = known_paths[&current.state];
+        if current.state == goal {
+            let mut current = current.state;
+            let mut path = vec![current];
+            while current != start {
+                current = preds[&current].1;
+                path.push(current);
+            }
+
+            let states: Vec<GameState> = path.into_iter().rev().map(GameState::from).collect();
+            observer.finished(&states);
+            return (Some((current_score, states)), stats);
+        }
+
+        let next_states = state.generate_next_states(rules);
+        stats.generated += next_states.len();
+        for (score, next_state) in next_states {
+            let cand_score = known_paths[&current.state] + score;
+            if stats
+                .upper_bound
+                .is_some_and(|bound| cand_score + next_state.min_remaining_cost(rules) > bound)
+            {
+                stats.pruned += 1;
+                continue;
+            }
+            let packed = PackedState::from(&next_state);
+            if known_paths
+                .get(&packed)
+                .iter()
+                .all(|&&current_best| cand_score < current_best)
+            {
+                open_nodes.push(Reverse(PathFindEntry {
+                    score: cand_score + estimate(&next_state),
+                    state: packed,
+                }));
+                known_paths.insert(packed, cand_score);
+                observer.discovered(&next_state);
+                preds.insert(packed, (score, current.state));
+            }
+        }
+        stats.max_heap = stats.max_heap.max(open_nodes.len());
+    }
+
+    (None, stats)
+}
+
+/// Compares the search effort of plain Dijkstra, A* and A* with pruning.
+fn explain(start: &GameState, rules: &Rules) -> String {
+    [
+        ("dijkstra", false, false),
+        ("a*", true, false),
+        ("a* + pruning", true, true),
+    ]
+    .into_iter()
+    .map(|(name, heuristic, prune)| {
+        let options = SearchOptions { heuristic, prune };
+        let (result, stats) = search(start.clone(), rules, options, &mut ());
+        let cost = result.map_or("-".to_string(), |(cost, _)| cost.to_string());
+        format!("{:<14} cost {:>6}: {}", name, cost, stats)
+    })
+    .join("\n")
+}
+
+/// Compact single line form of a state for the expansion log, the rows of the burrow separated by `|`.
+fn describe(state: &GameState, rules: &Rules) -> String {
+    let rows = state.to_string();
+    let rows = rows.lines().skip(1).take(1 + state.room_size);
+    format!(
+        "{} (at least {} to go)",
+        rows.map(|row| row
+            .chars()
+            .filter(|&c| c != ' ' && c != '#')
+            .collect::<String>())
+            .join("|"),
+        state.min_remaining_cost(rules)
+    )
+}
+
+/// The order in which the default search expands the states, followed by the optimal path.
+fn expansion_log(start: &GameState, rules: &Rules) -> String {
+    let mut record = SearchRecord::default();
+    search(start.clone(), rules, SearchOptions::default(), &mut record);
+    record.log(|state| describe(state, rules))
+}
+
+fn parse_input(lines: &[String], room_size: usize) -> Result<GameState> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[ABCD]").unwrap();
+    }
+    let mut state = GameState::new_empty(room_size);
+    for line in lines.iter().rev().skip(1).take(4) {
+        for (i, ts) in RE.find_iter(line).enumerate() {
+            let tok = match ts.as_str() {
+                "A" => Token::A,
+                "B" => Token::B,
+                "C" => Token::C,
+                "D" => Token::D,
+                _ => panic!("Should never get this token: {}", ts.as_str()),
+            };
+            state.room_mut(RoomId(i)).push(tok);
+        }
+    }
+
+    Ok(state)
+}
+
+fn read_start<I: Input>(input: I, unfolded: bool) -> Result<GameState> {
+    let mut lines: Vec<String> = stream_items_from_file(input)?.collect();
+    if unfolded {
+        lines.insert(3, "  #D#C#B#A#".to_string());
+        lines.insert(4, "  #D#B#A#C#".to_string());
+        parse_input(&lines, 4)
+    } else {
+        parse_input(&lines, 2)
+    }
+}
+
+/// A place an amphipod can stand on: a hallway position or the top of a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Hallway(HallwayPos),
+    Room(RoomId),
+}
+
+impl Location {
+    fn all() -> impl Iterator<Item = Location> {
+        HallwayPos::ALL
+            .into_iter()
+            .chain(HallwayPos::DOORS)
+            .map(Location::Hallway)
+            .chain(RoomId::ALL.into_iter().map(Location::Room))
+    }
+
+    fn occupants(&self, state: &GameState) -> usize {
+        match *self {
+            Location::Hallway(pos) => state.hallway(pos).is_some() as usize,
+            Location::Room(room) => state.room(room).len(),
+        }
+    }
+
+    fn take(&self, state: &mut GameState) -> Option<Token> {
+        match *self {
+            Location::Hallway(pos) => state.hallway_mut(pos).take(),
+            Location::Room(room) => state.room_mut(room).pop(),
+        }
+    }
+
+    fn put(&self, state: &mut GameState, token: Token) -> Result<()> {
+        match *self {
+            Location::Hallway(pos) => {
+                let cell = state.hallway_mut(pos);
+                if cell.is_some() {
+                    bail!("Hallway column {} is occupied", pos.column());
+                }
+                *cell = Some(token);
+            }
+            Location::Room(room) => {
+                if state.room(room).len() == state.room_size {
+                    bail!("Room {} is full", room.token());
+                }
+                state.room_mut(room).push(token);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Location {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "A" => Ok(Location::Room(RoomId(0))),
+            "B" => Ok(Location::Room(RoomId(1))),
+            "C" => Ok(Location::Room(RoomId(2))),
+            "D" => Ok(Location::Room(RoomId(3))),
+            column => {
+                let column = column
+                    .parse()
+                    .map_err(|_| anyhow!("{} is neither a room nor a hallway column", s))?;
+                let pos = HallwayPos::new(column)
+                    .ok_or_else(|| anyhow!("Amphipods can't stop in hallway column {}", column))?;
+                Ok(Location::Hallway(pos))
+            }
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Hallway(pos) => write!(f, "{}", pos.column()),
+            Location::Room(room) => write!(f, "{}", room.token()),
+        }
+    }
+}
+
+/// Names a move between two states in the notation of the play mode, e.g. `C 3`.
+fn describe_move(from: &GameState, to: &GameState) -> String {
+    let source = Location::all().find(|loc| loc.occupants(to) < loc.occupants(from));
+    let target = Location::all().find(|loc| loc.occupants(to) > loc.occupants(from));
+    match (source, target) {
+        (Some(source), Some(target)) => format!("{} {}", source, target),
+        _ => "?".to_string(),
+    }
+}
+
+/// Interactive mode in which the user sorts the burrow; moves are only accepted if `generate_next_states` allows them.
+struct PlaySession {
+    start: GameState,
+    rules: Rules,
+    history: Vec<(usize, GameState)>,
+}
+
+impl PlaySession {
+    fn new(start: GameState, rules: Rules) -> Self {
+        PlaySession {
+            start,
+            rules,
+            history: Vec::new(),
+        }
+    }
+
+    /// Like parsing a `Location`, but also accepts the doors if the rules allow stopping there.
+    fn location(&self, s: &str) -> Result<Location> {
+        match s.parse() {
+            Ok(column)
+                if self.rules.stop_outside_rooms
+                    && column % 2 == 0
+                    && (2..=8).contains(&column) =>
+            {
+                Ok(Location::Hallway(HallwayPos(column)))
+            }
+            _ => s.parse(),
+        }
+    }
+
+    fn state(&self) -> &GameState {
+        self.history.last().map_or(&self.start, |(_, state)| state)
+    }
+
+    fn cost(&self) -> usize {
+        self.history.iter().map(|(cost, _)| cost).sum()
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, GameState)> {
+        self.state().generate_next_states(&self.rules)
+    }
+
+    fn play(&mut self, from: Location, to: Location) -> Result<usize> {
+        let mut next = self.state().clone();
+        let token = from
+            .take(&mut next)
+            .ok_or_else(|| anyhow!("There is no amphipod at {}", from))?;
+        to.put(&mut next, token)?;
+        let (cost, next) = self
+            .legal_moves()
+            .into_iter()
+            .find(|(_, state)| *state == next)
+            .ok_or_else(|| anyhow!("{} can't move from {} to {}", token, from, to))?;
+        self.history.push((cost, next));
+        Ok(cost)
+    }
+
+    fn status(&self) -> String {
+        let state = self.state();
+        let footer = if *state == GameState::new_finished(state.room_size) {
+            format!("Sorted with a total cost of {}", self.cost())
+        } else if self.legal_moves().is_empty() {
+            format!("Stuck at cost {}, undo some moves", self.cost())
+        } else {
+            format!("Cost so far: {}", self.cost())
+        };
+        format!("{}\n{}", state, footer)
+    }
+
+    fn hint(&self) -> String {
+        match find_minimal_path(self.state().clone(), &self.rules) {
+            Some((cost, path)) if path.len() > 1 => format!(
+                "Move {}, the burrow can still be sorted for {} more ({} in total)",
+                describe_move(&path[0], &path[1]),
+                cost,
+                self.cost() + cost
+            ),
+            Some(_) => "Nothing left to do".to_string(),
+            None => "The burrow can't be sorted from here anymore".to_string(),
+        }
+    }
+}
+
+impl Repl for PlaySession {
+    fn eval(&mut self, command: &str) -> Result<String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words[..] {
+            ["move", from, to] => {
+                let cost = self.play(self.location(from)?, self.location(to)?)?;
+                Ok(format!("That cost {}\n{}", cost, self.status()))
+            }
+            ["moves"] => Ok(self
+                .legal_moves()
+                .iter()
+                .map(|(cost, next)| {
+                    format!("{} (cost {})", describe_move(self.state(), next), cost)
+                })
+                .join("\n")),
+            ["hint"] => Ok(self.hint()),
+            ["undo"] => {
+                self.history
+                    .pop()
+                    .ok_or_else(|| anyhow!("Nothing to undo"))?;
+                Ok(self.status())
+            }
+            ["reset"] => {
+                self.history.clear();
+                Ok(self.status())
+            }
+            ["show"] => Ok(self.status()),
+            _ => bail!("Unknown command {}", command),
+        }
+    }
+
+    fn help(&self) -> String {
+        [
+            "move <from> <to>: move an amphipod, rooms are A to D and hallway columns 0 to 10 from the left",
+            "moves: list the allowed moves",
+            "hint: show the next move of an optimal solution",
+            "undo: take back the last move",
+            "reset: start over",
+            "show: show the burrow and the cost so far",
+        ]
+        .join("\n")
+    }
+}
+
+/// The optimal sequences of moves for both parts.
+struct OptimalMoves {
+    part1: Vec<GameState>,
+    part2: Vec<GameState>,
+}
+
+impl Artifacts for OptimalMoves {
+    fn artifacts(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "part1_moves.txt".to_string(),
+                self.part1.iter().join("\n\n"),
+            ),
+            (
+                "part2_moves.txt".to_string(),
+                self.part2.iter().join("\n\n"),
+            ),
+        ]
+    }
+}
+
+/// The lowest energy and the states on the way there, which the artifacts show.
+fn solve<I: Input>(input: I, rules: &Rules, unfolded: bool) -> Result<(usize, Vec<GameState>)> {
+    find_minimal_path(read_start(input, unfolded)?, rules)
+        .ok_or_else(|| anyhow::anyhow!("No path to final state found!"))
+}
+
+fn part1<I: Input>(input: I, rules: &Rules) -> Result<(usize, Vec<GameState>)> {
+    solve(input, rules, false)
+}
+
+fn part2<I: Input>(input: I, rules: &Rules) -> Result<(usize, Vec<GameState>)> {
+    solve(input, rules, true)
+}
+
+const INPUT: &str = "input/day23.txt";
+
+/// The rules from the TOML file given with `--rules FILE`, or the puzzle's own rules.
+fn requested_rules() -> Result<Rules> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().position(|arg| arg == "--rules") {
+        Some(i) => Rules::load(
+            args.get(i + 1)
+                .ok_or_else(|| anyhow!("--rules needs a file"))?,
+        ),
+        None => Ok(Rules::default()),
+    }
+}
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let rules = &requested_rules()?;
+    if std::env::args().skip(1).any(|arg| arg == "--explain") {
+        println!("Part 1\n{}", explain(&read_start(input, false)?, rules));
+        println!("Part 2\n{}", explain(&read_start(input, true)?, rules));
+        return Ok(());
+    }
+    if std::env::args().skip(1).any(|arg| arg == "--expansions") {
+        println!(
+            "Part 1\n{}",
+            expansion_log(&read_start(input, false)?, rules)
+        );
+        println!(
+            "Part 2\n{}",
+            expansion_log(&read_start(input, true)?, rules)
+        );
+        return Ok(());
+    }
+    if std::env::args().skip(1).any(|arg| arg == "--play") {
+        let unfolded = std::env::args().skip(1).any(|arg| arg == "--unfolded");
+        let mut session = PlaySession::new(read_start(input, unfolded)?, *rules);
+        println!("{}\nType help for the commands", session.status());
+        return Ok(repl::run(&mut session)?);
+    }
+    let (answer1, part1) = measure("part 1", || part1(input, rules))?;
+    println!("Answer for part 1: {}", answer1);
+    let (answer2, part2) = measure("part 2", || part2(input, rules))?;
+    println!("Answer for part 2: {}", answer2);
+    artifacts::write_if_requested("day23", &OptimalMoves { part1, part2 })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, rc::Rc};
+
+    use aoc2021::test_helpers::{assert_completes_within, assert_snapshot, create_line_file};
+    use indoc::indoc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+            #############
+            #...........#
+            ###B#C#B#D###
+              #A#D#C#A#
+              #########"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_optimal_path_snapshot() {
+        let (dir, file) = example_file();
+        let lines: Vec<String> = stream_items_from_file(file).unwrap().collect();
+        let init = parse_input(&lines, 2).unwrap();
+        assert_eq!(init.to_string(), lines.join("\n"));
+
+        let (score, path) = find_minimal_path(init, &Rules::default()).unwrap();
+        assert_eq!(score, 12521);
+        assert_snapshot("day23_part1_path", &path.iter().join("\n\n"));
+        drop(dir);
+
+        let moves = OptimalMoves {
+            part1: path,
+            part2: Vec::new(),
+        };
+        assert_snapshot("day23_part1_path", &moves.artifacts()[0].1);
+    }
+
+    /// A state of the burrow, or an amphipod `remaining` energy units away from arriving at `target`.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum UnitStep {
+        At(GameState),
+        Moving {
+            target: Rc<GameState>,
+            remaining: usize,
+        },
+    }
+
+    /// The optimal cost found by a breadth-first search in which every step costs a single energy unit.
+    /// Far too slow for the real puzzle, but it doesn't rely on the priority queue and the heuristic of `search`.
+    fn unit_cost_bfs(start: GameState, rules: &Rules, max_cost: usize) -> Option<usize> {
+        let finished = GameState::new_finished(start.room_size);
+        let successors = |step: &UnitStep| match step {
+            UnitStep::At(state) => state
+                .generate_next_states(rules)
+                .into_iter()
+                .map(|(cost, next)| match cost {
+                    1 => UnitStep::At(next),
+                    _ => UnitStep::Moving {
+                        target: Rc::new(next),
+                        remaining: cost - 1,
+                    },
+                })
+                .collect_vec(),
+            UnitStep::Moving {
+                target,
+                remaining: 1,
+            } => vec![UnitStep::At((**target).clone())],
+            UnitStep::Moving { target, remaining } => vec![UnitStep::Moving {
+                target: target.clone(),
+                remaining: remaining - 1,
+            }],
+        };
+        let visited = bfs_states(UnitStep::At(start), successors, Some(max_cost));
+        visited.depth(&UnitStep::At(finished))
+    }
+
+    #[test]
+    fn test_packed_state() {
+        let (dir, file) = example_file();
+        let start = read_start(&file, true).unwrap();
+        let (_, path) =
+            find_minimal_path(read_start(&file, false).unwrap(), &Rules::default()).unwrap();
+        drop(dir);
+
+        let mut packed = HashSet::new();
+        for state in path.iter().chain([&start, &GameState::new_empty(3)]) {
+            assert_eq!(PackedState::from(state).unpack(), *state);
+            assert!(packed.insert(PackedState::from(state)));
+        }
+        assert_eq!(PackedState::from(&start).room_size(), 4);
+
+        // Doorways and rooms of different sizes are told apart
+        let mut waiting = GameState::new_empty(2);
+        *waiting.hallway_mut(HallwayPos(4)) = Some(Token::D);
+        assert_ne!(
+            PackedState::from(&waiting),
+            PackedState::from(&GameState::new_empty(2))
+        );
+        assert_ne!(
+            PackedState::from(&GameState::new_finished(2)),
+            PackedState::from(&GameState::new_finished(3))
+        );
+        assert_eq!(GameState::from(PackedState::from(&waiting)), waiting);
+    }
+
+    #[test]
+    fn test_unit_cost_bfs() {
+        // Near the end of the example: both D still wait in the hallway, an A as well
+        let mut late = GameState::new_empty(2);
+        *late.room_mut(RoomId(0)) = vec![Token::A];
+        *late.room_mut(RoomId(1)) = vec![Token::B, Token::B];
+        *late.room_mut(RoomId(2)) = vec![Token::C, Token::C];
+        for (column, token) in [(5, Token::D), (7, Token::D), (9, Token::A)] {
+            *late.hallway_mut(HallwayPos::new(column).unwrap()) = Some(token);
+        }
+        assert_eq!(
+            find_minimal_score(late.clone(), &Rules::default()),
+            Some(7008)
+        );
+        assert_eq!(
+            unit_cost_bfs(late.clone(), &Rules::default(), 7008),
+            Some(7008)
+        );
+        assert_eq!(unit_cost_bfs(late, &Rules::default(), 7007), None);
+
+        // The two B swapped between the first two rooms
+        let lines = [
+            "#############",
+            "#...........#",
+            "###A#B#C#D###",
+            "  #B#A#C#D#",
+            "  #########",
+        ];
+        let lines = lines.map(String::from);
+        let swapped = parse_input(&lines, 2).unwrap();
+        let optimal = find_minimal_score(swapped.clone(), &Rules::default()).unwrap();
+        assert_eq!(
+            unit_cost_bfs(swapped, &Rules::default(), optimal),
+            Some(optimal)
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules = Rules::parse("stop_outside_rooms = true\n\n[step_costs]\nB = 20\n").unwrap();
+        assert_eq!(rules.step_costs, [1, 20, 100, 1000]);
+        assert!(rules.stop_outside_rooms);
+        assert_eq!(Rules::parse("").unwrap(), Rules::default());
+        assert!(Rules::parse("[step_costs]\nE = 1").is_err());
+        assert!(Rules::parse("[step_costs]\nA = 0").is_err());
+        assert!(Rules::parse("stop_outside_rooms = 1").is_err());
+        assert!(Rules::parse("stop_inside_rooms = true").is_err());
+
+        let (dir, file) = example_file();
+        let start = read_start(&file, false).unwrap();
+        let expensive_b = Rules {
+            step_costs: [1, 20, 100, 1000],
+            ..Rules::default()
+        };
+        assert_eq!(find_minimal_score(start.clone(), &expensive_b), Some(12631));
+
+        // Stopping outside of the rooms only adds moves, which doesn't help in the example
+        let doors = Rules {
+            stop_outside_rooms: true,
+            ..Rules::default()
+        };
+        assert_eq!(part1(&file, &doors).unwrap().0, 12521);
+        let unit = Rules {
+            step_costs: [1; 4],
+            stop_outside_rooms: true,
+        };
+        let lines = [
+            "#############",
+            "#...........#",
+            "###B#A#C#D###",
+            "  #A#B#C#D#",
+            "  #########",
+        ];
+        let swapped = parse_input(&lines.map(String::from), 2).unwrap();
+        let cheapest = find_minimal_score(swapped.clone(), &unit).unwrap();
+        let without_doors = Rules {
+            stop_outside_rooms: false,
+            ..unit
+        };
+        assert!(cheapest <= find_minimal_score(swapped.clone(), &without_doors).unwrap());
+        assert_eq!(
+            unit_cost_bfs(swapped.clone(), &unit, cheapest),
+            Some(cheapest)
+        );
+        assert_eq!(unit_cost_bfs(swapped, &unit, cheapest - 1), None);
+
+        // An amphipod waiting outside of room B blocks it
+        assert!(PlaySession::new(start.clone(), Rules::default())
+            .location("4")
+            .is_err());
+        let mut session = PlaySession::new(start, doors);
+        let (from, to) = (
+            session.location("C").unwrap(),
+            session.location("4").unwrap(),
+        );
+        assert_eq!(session.play(from, to).unwrap(), 30);
+        assert_eq!(
+            session.state().to_string().lines().nth(1),
+            Some("#....B......#")
+        );
+        assert!(session
+            .legal_moves()
+            .iter()
+            .all(|(_, next)| next.room(RoomId(1)).len() == 2));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_heuristic_and_pruning() {
+        let (dir, file) = example_file();
+        let rules = &Rules::default();
+        for (unfolded, expected) in [(false, 12521), (true, 44169)] {
+            let start = read_start(&file, unfolded).unwrap();
+            assert!(start.min_remaining_cost(rules) <= expected);
+            assert!(greedy_rollout(&start, rules).unwrap() >= expected);
+
+            let stats = [(false, false), (true, false), (true, true)].map(|(heuristic, prune)| {
+                let options = SearchOptions { heuristic, prune };
+                let (result, stats) = search(start.clone(), rules, options, &mut ());
+                assert_eq!(result.unwrap().0, expected);
+                stats
+            });
+            assert!(stats[1].expanded < stats[0].expanded);
+            assert!(stats[2].pruned > 0);
+            assert!(stats[2].max_heap < stats[1].max_heap);
+        }
+        assert_eq!(GameState::new_finished(4).min_remaining_cost(rules), 0);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_expansion_log() {
+        let (dir, file) = example_file();
+        let start = read_start(&file, false).unwrap();
+        let mut record = SearchRecord::default();
+        let rules = &Rules::default();
+        let (result, stats) = search(start.clone(), rules, SearchOptions::default(), &mut record);
+        let (cost, path) = result.unwrap();
+        assert_eq!(cost, 12521);
+        assert_eq!(record.expansions.len(), stats.expanded);
+        assert_eq!(record.expansions[0], start);
+        assert_eq!(record.path, Some(path.clone()));
+
+        let log = expansion_log(&start, rules);
+        assert_eq!(log.lines().count(), stats.expanded + 1 + path.len());
+        assert!(log.starts_with("expand      1: ...........|BCBD|ADCA (at least"));
+        assert!(log.ends_with("...........|ABCD|ABCD (at least 0 to go)"));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_coordinates() {
+        assert_eq!(HallwayPos::new(2), None);
+        assert_eq!(HallwayPos::new(9), Some(HallwayPos(9)));
+        assert_eq!(RoomId::ALL.map(RoomId::door), [2, 4, 6, 8]);
+        assert_eq!(HallwayPos(0).distance_to(RoomId(0)), 2);
+        assert_eq!(HallwayPos(10).distance_to(RoomId(0)), 8);
+        assert_eq!(HallwayPos(5).distance_to(RoomId(2)), 1);
+        assert_eq!(
+            HallwayPos(0).between(RoomId(1)).collect_vec(),
+            [HallwayPos(1), HallwayPos(3)]
+        );
+        assert_eq!(HallwayPos(7).between(RoomId(3)).count(), 0);
+
+        // Every hallway position is drawn in its own column
+        for pos in HallwayPos::ALL {
+            let mut state = GameState::new_empty(2);
+            *state.hallway_mut(pos) = Some(Token::A);
+            assert_eq!(state.hallway(pos), Some(Token::A));
+            let hallway = state.to_string().lines().nth(1).unwrap().to_string();
+            assert_eq!(hallway.find('A'), Some(pos.column() + 1));
+        }
+    }
+
+    #[test]
+    fn test_play_session() {
+        let (dir, file) = example_file();
+        let mut session = PlaySession::new(read_start(&file, false).unwrap(), Rules::default());
+        assert_eq!(session.legal_moves().len(), 28);
+        assert!(session
+            .hint()
+            .starts_with("Move D 9, the burrow can still be sorted for 12521"));
+
+        // The first moves of the example in the puzzle text
+        assert_eq!(
+            session
+                .play("C".parse().unwrap(), "3".parse().unwrap())
+                .unwrap(),
+            40
+        );
+        assert!(session
+            .play("C".parse().unwrap(), "B".parse().unwrap())
+            .is_err());
+        assert!(session
+            .play("7".parse().unwrap(), "A".parse().unwrap())
+            .is_err());
+        assert!("2".parse::<Location>().is_err());
+        assert_eq!(session.cost(), 40);
+
+        let mut output = Vec::new();
+        repl::run_with(
+            &mut session,
+            "move b 5\nundo\nmove b 5\nmove 5 c\nmoves\nhint\nmove 3 a\nfoo\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("That cost 200\n"));
+        assert!(output.contains("Cost so far: 440"));
+        assert!(output.contains("B 5 (cost 3000)"));
+        assert!(output
+            .contains("Move B 5, the burrow can still be sorted for 12081 more (12521 in total)"));
+        assert!(output.contains("Error: Room A is full"));
+        assert!(output.contains("Error: Unknown command foo"));
+        drop(dir);
+    }
+
+    #[test]
+    #[ignore]
+    fn perf_example() {
+        let (dir, file) = example_file();
+        let answer = assert_completes_within("day23 part 1", Duration::from_secs(10), || {
+            part1(&file, &Rules::default()).unwrap().0
+        });
+        assert_eq!(answer, 12521);
+        let answer = assert_completes_within("day23 part 2", Duration::from_secs(30), || {
+            part2(&file, &Rules::default()).unwrap().0
+        });
+        assert_eq!(answer, 44169);
+        drop(dir);
+    }
+}
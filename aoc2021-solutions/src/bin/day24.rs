@@ -0,0 +1,1242 @@
+use anyhow::anyhow;
+use anyhow::{bail, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::report::Reporter;
+use aoc2021::stream_items_from_file_with_buf;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone)]
+enum RegisterOrConst {
+    Register(usize),
+    Const(isize),
+}
+
+#[derive(Debug, Clone)]
+enum Instruction {
+    Input(usize),
+    Add(usize, RegisterOrConst),
+    Mul(usize, RegisterOrConst),
+    Div(usize, RegisterOrConst),
+    Mod(usize, RegisterOrConst),
+    Equal(usize, RegisterOrConst),
+}
+
+fn get_register<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Result<usize> {
+    Ok(
+        match parts.next().ok_or(anyhow!("Missing register operand"))? {
+            "w" => 0,
+            "x" => 1,
+            "y" => 2,
+            "z" => 3,
+            u => bail!("Invalid register name {}", u),
+        },
+    )
+}
+
+fn get_register_or_const<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Result<RegisterOrConst> {
+    use RegisterOrConst::*;
+    Ok(
+        match parts.next().ok_or(anyhow!("Missing register operand"))? {
+            "w" => Register(0),
+            "x" => Register(1),
+            "y" => Register(2),
+            "z" => Register(3),
+            u => match u.parse::<isize>() {
+                Ok(v) => Const(v),
+                Err(_) => bail!("Invalid register or constant: {}", u),
+            },
+        },
+    )
+}
+
+impl FromStr for Instruction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Instruction::*;
+        let mut parts = s.split(' ');
+        let opcode = parts.next().ok_or(anyhow!("Empty input"))?;
+        Ok(match opcode {
+            "inp" => Input(get_register(&mut parts)?),
+            "add" => Add(
+                get_register(&mut parts)?,
+                get_register_or_const(&mut parts)?,
+            ),
+            "mul" => Mul(
+                get_register(&mut parts)?,
+                get_register_or_const(&mut parts)?,
+            ),
+            "div" => Div(
+                get_register(&mut parts)?,
+                get_register_or_const(&mut parts)?,
+            ),
+            "mod" => Mod(
+                get_register(&mut parts)?,
+                get_register_or_const(&mut parts)?,
+            ),
+            "eql" => Equal(
+                get_register(&mut parts)?,
+                get_register_or_const(&mut parts)?,
+            ),
+            _ => bail!("Invalid opcode {}", opcode),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+struct MachineState {
+    registers: [isize; 4],
+    // This is a hack and limits my mini VM to programs with only a single input, 
+    // BUT we can safe an allocation by not taking a Vec here and that safes about 1/5 of the total runtime.
+    // It only works because we split the input program on every input anyway.
+    input: isize,
+}
+
+impl RegisterOrConst {
+    fn resolve(&self, state: &MachineState) -> isize {
+        match self {
+            RegisterOrConst::Register(reg) => state.registers[*reg],
+            RegisterOrConst::Const(val) => *val,
+        }
+    }
+
+    fn as_code(&self, register_vars: &[&str; 4]) -> String {
+        match self {
+            RegisterOrConst::Register(r) => register_vars[*r].to_string(),
+            RegisterOrConst::Const(v) => format!("{}", v),
+        }
+    }
+}
+
+/// The ways an instruction can crash the ALU.
+#[derive(Debug, Error, PartialEq, Eq)]
+enum AluFault {
+    #[error("div {0} by zero")]
+    DivisionByZero(isize),
+    #[error("mod {value} by {modulus}, which needs a value >= 0 and a modulus > 0")]
+    InvalidModulo { value: isize, modulus: isize },
+    #[error("{0} overflowed")]
+    Overflow(&'static str),
+}
+
+/// `div a b` as specified: the quotient truncated towards zero.
+fn alu_div(a: isize, b: isize) -> Result<isize, AluFault> {
+    if b == 0 {
+        return Err(AluFault::DivisionByZero(a));
+    }
+    // Only isize::MIN / -1 does not fit
+    a.checked_div(b).ok_or(AluFault::Overflow("div"))
+}
+
+/// `mod a b` as specified: only defined for `a >= 0` and `b > 0`, where it never differs from Rust's `%`.
+fn alu_mod(a: isize, b: isize) -> Result<isize, AluFault> {
+    if a < 0 || b <= 0 {
+        return Err(AluFault::InvalidModulo { value: a, modulus: b });
+    }
+    Ok(a % b)
+}
+
+impl Instruction {
+    /// Runs a single instruction, faults like a division by zero crash the ALU instead of the solver.
+    fn execute(&self, mut state: MachineState) -> Result<MachineState, AluFault> {
+        match self {
+            Instruction::Input(target) => {
+                state.registers[*target] = state.input
+            }
+            Instruction::Add(target, operand) => {
+                let value = operand.resolve(&state);
+                state.registers[*target] = state.registers[*target]
+                    .checked_add(value)
+                    .ok_or(AluFault::Overflow("add"))?
+            }
+            Instruction::Mul(target, operand) => {
+                let value = operand.resolve(&state);
+                state.registers[*target] = state.registers[*target]
+                    .checked_mul(value)
+                    .ok_or(AluFault::Overflow("mul"))?
+            }
+            Instruction::Div(target, operand) => {
+                let value = operand.resolve(&state);
+                state.registers[*target] = alu_div(state.registers[*target], value)?
+            }
+            Instruction::Mod(target, operand) => {
+                let value = operand.resolve(&state);
+                state.registers[*target] = alu_mod(state.registers[*target], value)?
+            }
+            Instruction::Equal(target, operand) => {
+                state.registers[*target] = if state.registers[*target] == operand.resolve(&state) {
+                    1
+                } else {
+                    0
+                }
+            }
+        }
+        Ok(state)
+    }
+
+    #[allow(dead_code)]
+    fn code_gen(&self) -> String {
+        let registers = ["register_w", "register_x", "register_y", "register_z"];
+        match self {
+            Instruction::Input(var) => format!("{} = inputs.pop();", registers[*var]),
+            Instruction::Add(target, operand) => {
+                format!("{} += {}", registers[*target], operand.as_code(&registers))
+            }
+            Instruction::Mul(target, operand) => {
+                format!("{} *= {}", registers[*target], operand.as_code(&registers))
+            }
+            Instruction::Div(target, operand) => {
+                format!("{} /= {}", registers[*target], operand.as_code(&registers))
+            }
+            Instruction::Mod(target, operand) => {
+                format!("{} %= {}", registers[*target], operand.as_code(&registers))
+            }
+            Instruction::Equal(target, operand) => format!(
+                "{} = if {} == {} {{ 1 }} else {{ 0 }}",
+                registers[*target],
+                registers[*target],
+                operand.as_code(&registers)
+            ),
+        }
+    }
+}
+
+fn run_program_from_state(
+    program: &[Instruction],
+    init_state: MachineState,
+) -> Result<MachineState, AluFault> {
+    program
+        .iter()
+        .try_fold(init_state, |state, ins| ins.execute(state))
+}
+
+// The input programs has repeating parts that always start with an input instruction and very similar code after that.
+// Every part will clear the w, x and y registers so only the z register gets carried over to the next part.
+// We can use this to our advantage by splitting the program on input instructions and building 
+// a map of possible states instead of brute-forcing every single input.
+fn split_program(program: Vec<Instruction>) -> Vec<Vec<Instruction>> {
+    let mut cur = Vec::new();
+    let mut res = Vec::new();
+    for ins in program.into_iter() {
+        match ins {
+            Instruction::Input(_) => {
+                if !cur.is_empty() {
+                    res.push(cur);
+                    cur = Vec::new();
+                }
+                cur.push(ins);
+            }
+            _ => cur.push(ins)
+        }
+    }
+
+    if !cur.is_empty() {
+        res.push(cur);
+    }
+
+    res
+}
+
+/// The instructions of a program, a line that isn't one is an error instead of being left out.
+fn read_program<I: Input>(input: I) -> Result<Vec<Instruction>> {
+    Ok(stream_items_from_file_with_buf(input)?.collect::<Result<_, _>>()?)
+}
+
+/// A complete ALU program, for running it on whole model numbers instead of searching for them.
+struct Program(Vec<Vec<Instruction>>);
+
+impl Program {
+    fn new(program: Vec<Instruction>) -> Self {
+        Program(split_program(program))
+    }
+
+    fn load<I: Input>(input: I) -> Result<Self> {
+        Ok(Program::new(read_program(input)?))
+    }
+
+    /// Runs the program with the digits of `model_number` as inputs, the most significant first.
+    /// It is valid if it has a digit for every input, none of them is 0, the ALU doesn't crash
+    /// and z is 0 at the end.
+    fn validate(&self, model_number: u64) -> bool {
+        let digits = model_number.to_string();
+        let mut digits = digits.bytes().map(|digit| (digit - b'0') as isize);
+        let mut state = MachineState::default();
+        for block in &self.0 {
+            if let Some(Instruction::Input(_)) = block.first() {
+                match digits.next() {
+                    Some(digit) if digit != 0 => state.input = digit,
+                    _ => return false,
+                }
+            }
+            state = match run_program_from_state(block, state) {
+                Ok(state) => state,
+                Err(_) => return false,
+            };
+        }
+        digits.next().is_none() && state.registers[3] == 0
+    }
+
+    /// Like the sequential `valid_numbers`, but checks the candidates on all cores.
+    #[cfg(feature = "parallel")]
+    fn valid_numbers<C: IntoIterator<Item = u64>>(&self, candidates: C) -> Vec<u64> {
+        use rayon::prelude::*;
+
+        let candidates: Vec<u64> = candidates.into_iter().collect();
+        candidates
+            .into_par_iter()
+            .filter(|&number| self.validate(number))
+            .collect()
+    }
+
+    /// The valid numbers among the candidates, in the order of the candidates.
+    #[cfg(not(feature = "parallel"))]
+    fn valid_numbers<C: IntoIterator<Item = u64>>(&self, candidates: C) -> Vec<u64> {
+        candidates
+            .into_iter()
+            .filter(|&number| self.validate(number))
+            .collect()
+    }
+}
+
+/// A single model number or an inclusive range of them written as `FROM..TO`.
+fn parse_candidates(arg: &str) -> Result<std::ops::RangeInclusive<u64>> {
+    Ok(match arg.split_once("..") {
+        Some((from, to)) => from.parse()?..=to.parse()?,
+        None => {
+            let number = arg.parse()?;
+            number..=number
+        }
+    })
+}
+
+/// The interval of values a register can hold at some point of the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ValueRange {
+    min: isize,
+    max: isize,
+}
+
+impl ValueRange {
+    const UNKNOWN: ValueRange = ValueRange { min: isize::MIN, max: isize::MAX };
+    const DIGIT: ValueRange = ValueRange { min: 1, max: 9 };
+
+    fn new(min: isize, max: isize) -> Self {
+        ValueRange { min, max }
+    }
+
+    fn constant(value: isize) -> Self {
+        ValueRange::new(value, value)
+    }
+
+    fn as_constant(&self) -> Option<isize> {
+        (self.min == self.max).then_some(self.min)
+    }
+
+    fn from_corners(corners: impl IntoIterator<Item = Option<isize>>) -> Self {
+        corners
+            .into_iter()
+            .try_fold(ValueRange::new(isize::MAX, isize::MIN), |range, corner| {
+                corner.map(|c| ValueRange::new(range.min.min(c), range.max.max(c)))
+            })
+            .unwrap_or(ValueRange::UNKNOWN)
+    }
+
+    fn add(self, other: Self) -> Self {
+        ValueRange::from_corners([
+            self.min.checked_add(other.min),
+            self.max.checked_add(other.max),
+        ])
+    }
+
+    fn mul(self, other: Self) -> Self {
+        ValueRange::from_corners([
+            self.min.checked_mul(other.min),
+            self.min.checked_mul(other.max),
+            self.max.checked_mul(other.min),
+            self.max.checked_mul(other.max),
+        ])
+    }
+
+    fn div(self, other: Self) -> Self {
+        // Division by zero crashes the ALU, so only divisors of a fixed sign tell us anything
+        if other.min > 0 || other.max < 0 {
+            ValueRange::from_corners([
+                self.min.checked_div(other.min),
+                self.min.checked_div(other.max),
+                self.max.checked_div(other.min),
+                self.max.checked_div(other.max),
+            ])
+        } else {
+            ValueRange::UNKNOWN
+        }
+    }
+
+    fn rem(self, other: Self) -> Self {
+        // The ALU only allows non-negative dividends and positive divisors
+        if self.min >= 0 && other.min > 0 {
+            if self.max < other.min {
+                self
+            } else {
+                ValueRange::new(0, self.max.min(other.max - 1))
+            }
+        } else {
+            ValueRange::UNKNOWN
+        }
+    }
+
+    fn equal(self, other: Self) -> Self {
+        if self.max < other.min || other.max < self.min {
+            ValueRange::constant(0)
+        } else if self.as_constant().is_some() && self.as_constant() == other.as_constant() {
+            ValueRange::constant(1)
+        } else {
+            ValueRange::new(0, 1)
+        }
+    }
+}
+
+impl std::fmt::Display for ValueRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bound = |v: isize| match v {
+            isize::MIN => "-inf".to_string(),
+            isize::MAX => "inf".to_string(),
+            v => v.to_string(),
+        };
+        write!(f, "[{}, {}]", bound(self.min), bound(self.max))
+    }
+}
+
+impl RegisterOrConst {
+    fn range(&self, ranges: &[ValueRange; 4]) -> ValueRange {
+        match self {
+            RegisterOrConst::Register(reg) => ranges[*reg],
+            RegisterOrConst::Const(val) => ValueRange::constant(*val),
+        }
+    }
+}
+
+impl Instruction {
+    /// The range of the target register after this instruction, inputs are always digits
+    fn range_after(&self, ranges: &[ValueRange; 4]) -> (usize, ValueRange) {
+        let (target, operand) = match self {
+            Instruction::Input(target) => return (*target, ValueRange::DIGIT),
+            Instruction::Add(target, operand)
+            | Instruction::Mul(target, operand)
+            | Instruction::Div(target, operand)
+            | Instruction::Mod(target, operand)
+            | Instruction::Equal(target, operand) => (*target, operand.range(ranges)),
+        };
+        let current = ranges[target];
+        let range = match self {
+            Instruction::Add(..) => current.add(operand),
+            Instruction::Mul(..) => current.mul(operand),
+            Instruction::Div(..) => current.div(operand),
+            Instruction::Mod(..) => current.rem(operand),
+            _ => current.equal(operand),
+        };
+        (target, range)
+    }
+
+    /// Whether the instruction leaves its target unchanged, judging by the range of its operand
+    fn is_noop(&self, ranges: &[ValueRange; 4]) -> bool {
+        match self {
+            Instruction::Add(_, operand) => operand.range(ranges).as_constant() == Some(0),
+            Instruction::Mul(_, operand) | Instruction::Div(_, operand) => {
+                operand.range(ranges).as_constant() == Some(1)
+            }
+            _ => false,
+        }
+    }
+
+    /// Replaces register operands with a known value by a constant
+    fn with_constant_operand(self, ranges: &[ValueRange; 4]) -> Instruction {
+        let constant = |operand: RegisterOrConst| match operand.range(ranges).as_constant() {
+            Some(value) => RegisterOrConst::Const(value),
+            None => operand,
+        };
+        match self {
+            Instruction::Input(target) => Instruction::Input(target),
+            Instruction::Add(target, operand) => Instruction::Add(target, constant(operand)),
+            Instruction::Mul(target, operand) => Instruction::Mul(target, constant(operand)),
+            Instruction::Div(target, operand) => Instruction::Div(target, constant(operand)),
+            Instruction::Mod(target, operand) => Instruction::Mod(target, constant(operand)),
+            Instruction::Equal(target, operand) => Instruction::Equal(target, constant(operand)),
+        }
+    }
+}
+
+/// The result of running the range analysis over one block of the program.
+#[derive(Debug, Clone)]
+struct BlockAnalysis {
+    program: Vec<Instruction>,
+    original_len: usize,
+    /// The z values this block can produce, given the z values of the previous block
+    z_range: ValueRange,
+    pruned_comparisons: usize,
+}
+
+/// Tracks the range of every register through the block and simplifies it on the way:
+/// comparisons with a known outcome are replaced by a constant and no-ops are dropped.
+fn analyze_block(block: Vec<Instruction>, z_in: ValueRange) -> BlockAnalysis {
+    let original_len = block.len();
+    let zero = ValueRange::constant(0);
+    let mut ranges = [zero, zero, zero, z_in];
+    let mut program = Vec::with_capacity(block.len());
+    let mut pruned_comparisons = 0;
+    for ins in block {
+        let (target, range) = ins.range_after(&ranges);
+        match range.as_constant() {
+            // The register already holds the value
+            Some(value) if ranges[target].as_constant() == Some(value) => {}
+            Some(value) if matches!(ins, Instruction::Equal(..)) => {
+                pruned_comparisons += 1;
+                program.push(Instruction::Mul(target, RegisterOrConst::Const(0)));
+                if value != 0 {
+                    program.push(Instruction::Add(target, RegisterOrConst::Const(value)));
+                }
+            }
+            _ if ins.is_noop(&ranges) => {}
+            _ => program.push(ins.with_constant_operand(&ranges)),
+        }
+        ranges[target] = range;
+    }
+    BlockAnalysis {
+        program,
+        original_len,
+        z_range: ranges[3],
+        pruned_comparisons,
+    }
+}
+
+fn analyze_program(program: Vec<Instruction>) -> Vec<BlockAnalysis> {
+    let mut z_range = ValueRange::constant(0);
+    split_program(program)
+        .into_iter()
+        .map(|block| {
+            let analysis = analyze_block(block, z_range);
+            z_range = analysis.z_range;
+            analysis
+        })
+        .collect()
+}
+
+impl ValueRange {
+    fn overlaps(&self, other: &ValueRange) -> bool {
+        self.min <= other.max && other.min <= self.max
+    }
+}
+
+/// The smallest value in `[low, high]` for which `holds` is true, given that it stays true for all larger values.
+fn first_where(
+    mut low: isize,
+    mut high: isize,
+    mut holds: impl FnMut(isize) -> bool,
+) -> Option<isize> {
+    if !holds(high) {
+        return None;
+    }
+    while low < high {
+        let mid = (low as i128 + high as i128).div_euclid(2) as isize;
+        if holds(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Some(low)
+}
+
+/// The z values every block can start with and still end the program with z = 0, `None` if there are none.
+/// Works backwards from the last block: a range of z values is dropped if the range analysis proves that
+/// no digit takes it into the range of the following block. Shrinking the input range only ever shrinks
+/// the analyzed output range, so the bounds can be found with binary searches.
+fn zero_reachable_ranges(blocks: &[BlockAnalysis]) -> Vec<Option<ValueRange>> {
+    let mut ranges = vec![None; blocks.len()];
+    let mut target = Some(ValueRange::constant(0));
+    for (i, block) in blocks.iter().enumerate().rev() {
+        let z_in = match i {
+            0 => ValueRange::constant(0),
+            _ => blocks[i - 1].z_range,
+        };
+        target = target.and_then(|target| {
+            let reaches = |min, max| {
+                analyze_block(block.program.clone(), ValueRange::new(min, max))
+                    .z_range
+                    .overlaps(&target)
+            };
+            let max = match first_where(z_in.min, z_in.max, |t| !reaches(t, z_in.max)) {
+                None => z_in.max,
+                Some(t) if t == z_in.min => return None,
+                Some(t) => t - 1,
+            };
+            let min = first_where(z_in.min, max, |t| reaches(z_in.min, t))?;
+            Some(ValueRange::new(min, max))
+        });
+        ranges[i] = target;
+    }
+    ranges
+}
+
+fn range_report<I: Input>(input: I) -> Result<String> {
+    let program = read_program(input)?;
+    Ok(analyze_program(program)
+        .iter()
+        .enumerate()
+        .map(|(i, block)| {
+            format!(
+                "Block {:>2}: z in {}, {} of {} instructions left, {} comparisons pruned\n",
+                i,
+                block.z_range,
+                block.program.len(),
+                block.original_len,
+                block.pruned_comparisons
+            )
+        })
+        .collect())
+}
+
+/// Decides what we keep track of for all model number prefixes that lead to the same z register value.
+trait Objective {
+    type Value: Clone + Default;
+
+    /// The value for the empty prefix
+    fn initial(&self) -> Self::Value;
+    /// Appends another digit to the prefixes described by `value`
+    fn extend(&self, value: &Self::Value, digit: isize) -> Self::Value;
+    /// Merges the values of two sets of prefixes
+    fn combine(&self, a: Self::Value, b: Self::Value) -> Self::Value;
+}
+
+struct Maximize;
+
+impl Objective for Maximize {
+    type Value = isize;
+
+    fn initial(&self) -> isize {
+        0
+    }
+
+    fn extend(&self, value: &isize, digit: isize) -> isize {
+        value * 10 + digit
+    }
+
+    fn combine(&self, a: isize, b: isize) -> isize {
+        std::cmp::max(a, b)
+    }
+}
+
+struct Minimize;
+
+impl Objective for Minimize {
+    type Value = isize;
+
+    fn initial(&self) -> isize {
+        0
+    }
+
+    fn extend(&self, value: &isize, digit: isize) -> isize {
+        value * 10 + digit
+    }
+
+    fn combine(&self, a: isize, b: isize) -> isize {
+        std::cmp::min(a, b)
+    }
+}
+
+struct CountAll;
+
+impl Objective for CountAll {
+    type Value = u64;
+
+    fn initial(&self) -> u64 {
+        1
+    }
+
+    fn extend(&self, value: &u64, _digit: isize) -> u64 {
+        *value
+    }
+
+    fn combine(&self, a: u64, b: u64) -> u64 {
+        a + b
+    }
+}
+
+/// Keeps the K smallest model numbers
+struct FirstK(usize);
+
+impl Objective for FirstK {
+    type Value = Vec<isize>;
+
+    fn initial(&self) -> Vec<isize> {
+        vec![0]
+    }
+
+    fn extend(&self, value: &Vec<isize>, digit: isize) -> Vec<isize> {
+        value.iter().map(|v| v * 10 + digit).collect()
+    }
+
+    fn combine(&self, mut a: Vec<isize>, b: Vec<isize>) -> Vec<isize> {
+        a.extend(b);
+        a.sort_unstable();
+        a.truncate(self.0);
+        a
+    }
+}
+
+fn find_possible_states(input: isize, program: &[Instruction], state_inputs: &mut Vec<(isize, isize)>) {
+    state_inputs.clear();
+    for inp in 1..=9 {
+        let state = MachineState { registers: [0,0,0,input], input: inp };
+        // A digit that crashes the ALU can't be part of a valid model number
+        if let Ok(final_state) = run_program_from_state(program, state) {
+            state_inputs.push((final_state.registers[3], inp));
+        }
+    }
+}
+
+/// The value of the objective for every reachable z value, sorted by z. Compared to a hash map there is
+/// no overhead per entry, which matters with millions of z values, and lookups are binary searches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StateMap<V> {
+    entries: Vec<(isize, V)>,
+}
+
+impl<V: Default> StateMap<V> {
+    fn new() -> Self {
+        StateMap {
+            entries: Vec::new(),
+        }
+    }
+
+    fn from_entries(entries: Vec<(isize, V)>, combine: impl FnMut(V, V) -> V) -> Self {
+        let mut map = StateMap::new();
+        map.merge(entries, combine);
+        map
+    }
+
+    /// Adds the unsorted `entries`, the values of equal z values are merged with `combine`.
+    /// The merge happens in place: after sorting the new entries, both sorted sequences are merged from the back
+    /// into the space appended to the map, so no second copy of the map is needed.
+    fn merge(&mut self, mut entries: Vec<(isize, V)>, mut combine: impl FnMut(V, V) -> V) {
+        entries.sort_unstable_by_key(|&(z, _)| z);
+        let known = self.entries.len();
+        self.entries.reserve_exact(entries.len());
+        self.entries.resize_with(known + entries.len(), Default::default);
+        // Everything from `free` on is in its final place, the map's own entries are below `unmoved`
+        let (mut unmoved, mut free) = (known, self.entries.len());
+        while let Some(entry) = entries.pop() {
+            while unmoved > 0 && self.entries[unmoved - 1].0 > entry.0 {
+                unmoved -= 1;
+                free -= 1;
+                self.entries.swap(unmoved, free);
+            }
+            free -= 1;
+            self.entries[free] = entry;
+        }
+        self.entries.dedup_by(|next, kept| {
+            if next.0 != kept.0 {
+                return false;
+            }
+            let merged = combine(std::mem::take(&mut kept.1), std::mem::take(&mut next.1));
+            kept.1 = merged;
+            true
+        });
+    }
+
+    fn remove(&mut self, z: isize) -> Option<V> {
+        let i = self.entries.binary_search_by_key(&z, |&(z, _)| z).ok()?;
+        Some(self.entries.remove(i).1)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Drops every z value outside of `range`, which is cheap since they are at both ends.
+    fn retain_range(&mut self, range: ValueRange) {
+        let end = self.entries.partition_point(|&(z, _)| z <= range.max);
+        self.entries.truncate(end);
+        let start = self.entries.partition_point(|&(z, _)| z < range.min);
+        self.entries.drain(..start);
+    }
+}
+
+const MIN_BATCH: usize = 1 << 16;
+
+fn find_all_possible_states<O: Objective>(
+    program: Vec<Instruction>,
+    objective: &O,
+    prune: bool,
+    reporter: &Reporter,
+) -> StateMap<O::Value> {
+    let mut current_known = StateMap::from_entries(vec![(0, objective.initial())], |a, _| a);
+    let mut local_scratchpad = Vec::new();
+    let blocks = analyze_program(program);
+    let bounds = zero_reachable_ranges(&blocks);
+
+    for (i, block) in blocks.into_iter().enumerate() {
+        if prune {
+            let before = current_known.len();
+            match bounds[i] {
+                Some(range) => current_known.retain_range(range),
+                None => current_known.entries.clear(),
+            }
+            reporter.detail(format_args!(
+                "Pruned {} of {} z values that can't lead to 0 anymore before part {}",
+                before - current_known.len(),
+                before,
+                i
+            ));
+        }
+        let part = &block.program;
+        let mut next_known = StateMap::new();
+        // New states are collected in batches of a quarter of the map, which keeps both the memory for the batch
+        // and the number of merges small
+        let batch = |map: &StateMap<O::Value>| (map.len() / 4).max(MIN_BATCH);
+        let mut pending = Vec::with_capacity(batch(&next_known) + 9);
+        for (state, prefixes) in current_known.entries {
+            find_possible_states(state, part, &mut local_scratchpad);
+            for &(new_state, input) in local_scratchpad.iter() {
+                pending.push((new_state, objective.extend(&prefixes, input)));
+            }
+            if pending.len() >= batch(&next_known) {
+                next_known.merge(std::mem::take(&mut pending), |a, b| objective.combine(a, b));
+                pending.reserve_exact(batch(&next_known) + 9);
+            }
+        }
+        next_known.merge(pending, |a, b| objective.combine(a, b));
+        current_known = next_known;
+        reporter.info(format_args!(
+            "We currently know {} possible final states (After part {} with {} instructions)",
+            current_known.len(),
+            i,
+            part.len()
+        ));
+        reporter.detail(format_args!("Part {} can reach z values in {}", i, block.z_range));
+    }
+
+    current_known
+}
+
+/// With `prune` the z values that can't lead to 0 anymore are dropped before every block.
+fn solve<I: Input, O: Objective>(
+    input: I,
+    objective: &O,
+    prune: bool,
+    reporter: &Reporter,
+) -> Result<O::Value> {
+    let program = read_program(input)?;
+    find_all_possible_states(program, objective, prune, reporter)
+        .remove(0)
+        .ok_or_else(|| anyhow!("There is no valid model number"))
+}
+
+/// Searches the model number with `objective` and cross-checks it by running the program on it.
+fn solve_verified<I: Input, O: Objective<Value = isize>>(
+    input: I,
+    objective: &O,
+    prune: bool,
+    reporter: &Reporter,
+) -> Result<isize> {
+    let program = read_program(input)?;
+    let model_number = find_all_possible_states(program.clone(), objective, prune, reporter)
+        .remove(0)
+        .ok_or_else(|| anyhow!("There is no valid model number"))?;
+    if !Program::new(program).validate(model_number as u64) {
+        bail!(
+            "The search found {}, which the program rejects",
+            model_number
+        );
+    }
+    Ok(model_number)
+}
+
+fn part1<I: Input>(input: I, prune: bool, reporter: &Reporter) -> Result<isize> {
+    solve_verified(input, &Maximize, prune, reporter)
+}
+
+fn part2<I: Input>(input: I, prune: bool, reporter: &Reporter) -> Result<isize> {
+    solve_verified(input, &Minimize, prune, reporter)
+}
+
+const INPUT: &str = "input/day24.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let (reporter, mut args) = Reporter::from_args(std::env::args().skip(1))?;
+    let prune = args.iter().any(|arg| arg == "--prune");
+    args.retain(|arg| arg != "--prune");
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--ranges"] => print!("{}", range_report(input)?),
+        ["--validate", ref candidates @ ..] if !candidates.is_empty() => {
+            let program = Program::load(input)?;
+            for candidates in candidates {
+                for number in program.valid_numbers(parse_candidates(candidates)?) {
+                    println!("{}", number);
+                }
+            }
+        }
+        ["--count"] => println!(
+            "Valid model numbers: {}",
+            solve(input, &CountAll, prune, &reporter)?
+        ),
+        ["--first", k] => {
+            for number in solve(input, &FirstK(k.parse()?), prune, &reporter)? {
+                println!("{}", number);
+            }
+        }
+        _ => {
+            println!(
+                "Answer for part 1: {}",
+                measure("part 1", || part1(input, prune, &reporter))?
+            );
+            println!(
+                "Answer for part 2: {}",
+                measure("part 2", || part2(input, prune, &reporter))?
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::report::Verbosity;
+    use aoc2021::test_helpers::{assert_completes_within, create_line_file};
+    use indoc::indoc;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    // Accepts exactly the numbers made up of two equal digits
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                inp w
+                add z w
+                inp w
+                mul w -1
+                add z w"}]
+            .iter(),
+            None,
+        )
+    }
+
+    fn run(program: &str, input: isize) -> Result<[isize; 4], AluFault> {
+        let program: Vec<Instruction> = program.lines().map(|l| l.parse().unwrap()).collect();
+        let state = MachineState { registers: [0; 4], input };
+        run_program_from_state(&program, state).map(|state| state.registers)
+    }
+
+    #[test]
+    fn test_alu_semantics() {
+        // Division truncates towards zero, also for negative values
+        assert_eq!(alu_div(7, 2), Ok(3));
+        assert_eq!(alu_div(-7, 2), Ok(-3));
+        assert_eq!(alu_div(7, -2), Ok(-3));
+        assert_eq!(alu_div(-7, -2), Ok(3));
+        assert_eq!(alu_div(7, 0), Err(AluFault::DivisionByZero(7)));
+        assert_eq!(alu_div(isize::MIN, -1), Err(AluFault::Overflow("div")));
+        assert_eq!(alu_mod(7, 3), Ok(1));
+        assert_eq!(alu_mod(0, 3), Ok(0));
+        for (value, modulus) in [(-7, 3), (7, 0), (7, -3)] {
+            assert_eq!(alu_mod(value, modulus), Err(AluFault::InvalidModulo { value, modulus }));
+        }
+
+        // The examples of the puzzle that only take a single input
+        assert_eq!(run("inp x\nmul x -1", 5), Ok([0, -5, 0, 0]));
+        let binary = indoc! {"
+            inp w
+            add z w
+            mod z 2
+            div w 2
+            add y w
+            mod y 2
+            div w 2
+            add x w
+            mod x 2
+            div w 2
+            mod w 2"};
+        assert_eq!(run(binary, 13), Ok([1, 1, 0, 1]));
+        assert_eq!(run(binary, 6), Ok([0, 1, 1, 0]));
+        assert_eq!(run(binary, -3), Err(AluFault::InvalidModulo { value: -3, modulus: 2 }));
+        assert_eq!(
+            run("inp w\nadd z 1\nmul z 4611686018427387904\nmul z 2", 1),
+            Err(AluFault::Overflow("mul"))
+        );
+    }
+
+    #[test]
+    fn test_crashing_digits() {
+        // The digit 5 divides by zero, every other one is fine
+        let lines = ["inp w\nadd x w\nadd x -5\ndiv z x"];
+        let (dir, file) = create_line_file(lines.iter(), None);
+        assert_eq!(solve(&file, &CountAll, false, &Reporter::default()).unwrap(), 8);
+        assert_eq!(
+            solve(&file, &FirstK(5), false, &Reporter::default()).unwrap(),
+            vec![1, 2, 3, 4, 6]
+        );
+        drop(dir);
+    }
+
+    #[test]
+    fn test_objectives() {
+        let (dir, file) = example_file();
+        assert_eq!(solve(&file, &Maximize, false, &Reporter::default()).unwrap(), 99);
+        assert_eq!(solve(&file, &Minimize, false, &Reporter::default()).unwrap(), 11);
+        assert_eq!(solve(&file, &CountAll, false, &Reporter::default()).unwrap(), 9);
+        assert_eq!(
+            solve(&file, &FirstK(3), false, &Reporter::default()).unwrap(),
+            vec![11, 22, 33]
+        );
+        drop(dir);
+    }
+
+    // A shortened program with the structure of the real puzzle inputs: blocks either push a digit onto z
+    // (a base 26 stack) or pop one again, requiring a fixed difference between both digits
+    fn monad_file() -> (TempDir, impl AsRef<Path>) {
+        let blocks = [
+            (1, 12, 4),
+            (1, 11, 11),
+            (26, -10, 7),
+            (1, 13, 5),
+            (26, -3, 6),
+            (26, -9, 4),
+            (1, 14, 14),
+            (26, -10, 7),
+        ];
+        let program = blocks.into_iter().map(|(div, check, offset)| {
+            format!(
+                indoc! {"
+                    inp w
+                    mul x 0
+                    add x z
+                    mod x 26
+                    div z {}
+                    add x {}
+                    eql x w
+                    eql x 0
+                    mul y 0
+                    add y 25
+                    mul y x
+                    add y 1
+                    mul z y
+                    mul y 0
+                    add y w
+                    add y {}
+                    mul y x
+                    add z y"},
+                div, check, offset
+            )
+        });
+        create_line_file(program, None)
+    }
+
+    #[test]
+    #[ignore]
+    fn perf_monad() {
+        let (dir, file) = monad_file();
+        let max = assert_completes_within("day24 maximize", Duration::from_secs(5), || {
+            solve(&file, &Maximize, false, &Reporter::default()).unwrap()
+        });
+        let min = assert_completes_within("day24 minimize", Duration::from_secs(5), || {
+            solve(&file, &Minimize, false, &Reporter::default()).unwrap()
+        });
+        assert_eq!(max, 98979459);
+        assert_eq!(min, 61213115);
+        drop(dir);
+    }
+    /// Collects everything written to a reporter
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_reporter() {
+        let (dir, file) = example_file();
+        for (verbosity, lines) in [
+            (Verbosity::Quiet, 0),
+            (Verbosity::Normal, 2),
+            (Verbosity::Verbose, 4),
+        ] {
+            let captured = Captured::default();
+            let reporter = Reporter::new(verbosity, captured.clone());
+            assert_eq!(solve(&file, &Maximize, false, &reporter).unwrap(), 99);
+            assert_eq!(captured.text().lines().count(), lines);
+        }
+        drop(dir);
+
+        let log_dir = TempDir::new().unwrap();
+        let log = log_dir.path().join("day24.log");
+        let args = ["--count", "--log", log.to_str().unwrap(), "--verbose"].map(String::from);
+        let (reporter, remaining) = Reporter::from_args(args).unwrap();
+        assert_eq!(remaining, vec!["--count"]);
+        assert!(reporter.enabled(Verbosity::Verbose));
+        reporter.detail("details");
+        drop(reporter);
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "details\n");
+        assert!(Reporter::from_args(["--log".to_string()]).is_err());
+        assert!(!Reporter::default().enabled(Verbosity::Normal));
+    }
+
+    #[test]
+    fn test_range_analysis() {
+        let (dir, file) = monad_file();
+        let program = read_program(&file).unwrap();
+        let blocks = analyze_program(program.clone());
+        assert_eq!(
+            blocks.iter().map(|b| b.pruned_comparisons).collect::<Vec<_>>(),
+            vec![2, 2, 0, 2, 0, 0, 2, 0]
+        );
+        // Pushing blocks can't pass their check, so z at least gets multiplied by 26
+        assert_eq!(blocks[0].z_range, ValueRange::new(5, 13));
+        assert_eq!(blocks[1].z_range, ValueRange::new(5 * 26 + 12, 13 * 26 + 20));
+
+        // Within the analyzed z range the simplified blocks compute the same z values as the original ones
+        let mut z_in = ValueRange::constant(0);
+        for (block, simplified) in split_program(program).iter().zip(&blocks) {
+            for z in [z_in.min, (z_in.min + z_in.max) / 2, z_in.max] {
+                for input in 1..=9 {
+                    let state = MachineState { registers: [0, 0, 0, z], input };
+                    assert_eq!(
+                        run_program_from_state(block, state.clone()).unwrap().registers[3],
+                        run_program_from_state(&simplified.program, state).unwrap().registers[3]
+                    );
+                }
+            }
+            z_in = simplified.z_range;
+        }
+
+        let report = range_report(&file).unwrap();
+        assert_eq!(report.lines().count(), 8);
+        assert!(report
+            .starts_with("Block  0: z in [5, 13], 11 of 18 instructions left, 2 comparisons pruned"));
+        drop(dir);
+    }
+
+    #[test]
+    fn test_state_map() {
+        let entries = vec![(26, 3), (-1, 5), (26, 9), (0, 1), (26, 4)];
+        let mut map = StateMap::from_entries(entries, std::cmp::max);
+        assert_eq!(map.entries, vec![(-1, 5), (0, 1), (26, 9)]);
+        map.merge(vec![(30, 2), (-5, 1), (0, 7), (30, 1), (1, 1)], std::cmp::max);
+        assert_eq!(map.entries, vec![(-5, 1), (-1, 5), (0, 7), (1, 1), (26, 9), (30, 2)]);
+        map.retain_range(ValueRange::new(-1, 26));
+        assert_eq!(map.entries, vec![(-1, 5), (0, 7), (1, 1), (26, 9)]);
+        let mut map = StateMap::from_entries(vec![(0, 1), (26, 9)], std::cmp::max);
+        map.retain_range(ValueRange::new(0, 25));
+        assert_eq!(map.entries, vec![(0, 1)]);
+        assert_eq!(map.remove(0), Some(1));
+        assert_eq!(map.remove(0), None);
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn test_pruning() {
+        let (dir, file) = monad_file();
+        let program = read_program(&file).unwrap();
+        let bounds = zero_reachable_ranges(&analyze_program(program));
+        // The last block has to pop the only digit left on the base 26 stack, pushed by the block before
+        assert_eq!(bounds[0], Some(ValueRange::constant(0)));
+        assert_eq!(bounds[7], Some(ValueRange::new(15, 25)));
+        assert!(bounds.iter().all(Option::is_some));
+
+        let captured = Captured::default();
+        let reporter = Reporter::new(Verbosity::Verbose, captured.clone());
+        for prune in [false, true] {
+            assert_eq!(solve(&file, &CountAll, prune, &reporter).unwrap(), 1120);
+            assert_eq!(solve(&file, &Maximize, prune, &reporter).unwrap(), 98979459);
+        }
+        let largest_map = |text: &str| {
+            text.lines()
+                .filter_map(|line| line.strip_prefix("We currently know "))
+                .filter_map(|line| line.split(' ').next()?.parse::<usize>().ok())
+                .max()
+                .unwrap()
+        };
+        let text = captured.text();
+        let (unpruned, pruned) = text.split_at(text.find("Pruned").unwrap());
+        assert!(largest_map(pruned) < largest_map(unpruned));
+        drop(dir);
+
+        // Nothing to prune if the last block can't bring z back to 0
+        let lines = ["inp w\nadd z w"];
+        let (dir, file) = create_line_file(lines.iter(), None);
+        let program = read_program(&file).unwrap();
+        assert_eq!(zero_reachable_ranges(&analyze_program(program)), vec![None]);
+        assert!(solve(&file, &Maximize, true, &Reporter::default()).is_err());
+        drop(dir);
+
+        // A mistyped instruction would change the program if it was left out
+        let (dir, file) = create_line_file(["inp w", "ad z w", "mul z 2"].iter(), None);
+        let e = read_program(&file).unwrap_err().to_string();
+        assert!(e.contains(":2: Invalid opcode ad"), "{}", e);
+        drop(dir);
+    }
+
+    #[test]
+    fn test_validate() {
+        let (dir, file) = example_file();
+        let program = Program::load(&file).unwrap();
+        drop(dir);
+        assert!(program.validate(11));
+        assert!(program.validate(99));
+        assert!(!program.validate(12));
+        // Every input needs exactly one digit and 0 is no valid digit
+        assert!(!program.validate(1));
+        assert!(!program.validate(111));
+        assert!(!program.validate(100));
+        assert_eq!(program.valid_numbers(10..=40), vec![11, 22, 33]);
+        assert_eq!(parse_candidates("10..40").unwrap(), 10..=40);
+        assert_eq!(parse_candidates("22").unwrap(), 22..=22);
+        assert!(parse_candidates("1..x").is_err());
+
+        let (dir, file) = monad_file();
+        let program = Program::load(&file).unwrap();
+        let reporter = Reporter::default();
+        let max = part1(&file, true, &reporter).unwrap() as u64;
+        let min = part2(&file, true, &reporter).unwrap() as u64;
+        let first = solve(&file, &FirstK(20), true, &reporter).unwrap();
+        drop(dir);
+        let first: Vec<u64> = first.iter().map(|&number| number as u64).collect();
+        assert!(program.validate(max) && program.validate(min));
+        // Nothing above the maximum and nothing between the first valid numbers is valid
+        assert_eq!(program.valid_numbers(max..max + 20_000), vec![max]);
+        assert_eq!(program.valid_numbers(first.iter().copied()), first);
+        assert_eq!(program.valid_numbers(min - 20_000..=first[19]), first);
+    }
+
+    #[test]
+    fn test_value_ranges() {
+        let digit = ValueRange::DIGIT;
+        assert_eq!(digit.mul(ValueRange::constant(-2)), ValueRange::new(-18, -2));
+        assert_eq!(ValueRange::new(0, 100).rem(ValueRange::constant(26)), ValueRange::new(0, 25));
+        assert_eq!(ValueRange::new(-7, 100).div(ValueRange::constant(26)), ValueRange::new(0, 3));
+        assert_eq!(digit.equal(ValueRange::constant(10)), ValueRange::constant(0));
+        assert_eq!(digit.equal(ValueRange::constant(9)), ValueRange::new(0, 1));
+        assert_eq!(ValueRange::new(0, isize::MAX).add(digit), ValueRange::UNKNOWN);
+        assert_eq!(ValueRange::UNKNOWN.to_string(), "[-inf, inf]");
+    }
+}
@@ -0,0 +1,384 @@
+use anyhow::{anyhow, Result};
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+use aoc2021::{
+    checkpoint::{self, Checkpoints},
+    field2d::Field2D,
+    simulation::{iterate_until_within, DidNotConverge, Limits},
+    stream_grid_from_file,
+};
+
+type SeaCucumberField = Field2D<Option<SeaCucumber>>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum SeaCucumber {
+    East,
+    South
+}
+
+fn parse_cell(c: char) -> Result<Option<SeaCucumber>> {
+    match c {
+        'v' => Ok(Some(SeaCucumber::South)),
+        '>' => Ok(Some(SeaCucumber::East)),
+        '.' => Ok(None),
+        _ => Err(anyhow!("Invalid cell {:?}", c)),
+    }
+}
+
+fn render_cell(cell: &Option<SeaCucumber>) -> char {
+    match cell {
+        Some(SeaCucumber::East) => '>',
+        Some(SeaCucumber::South) => 'v',
+        None => '.',
+    }
+}
+
+fn read_field<I: Input>(input: I) -> Result<SeaCucumberField> {
+    Ok(stream_grid_from_file(input, parse_cell)?)
+}
+
+/// Whether an eastward cucumber occupies column `x` of `row` after the eastward cucumbers of that row moved.
+fn east_after_move(row: &[Option<SeaCucumber>], x: usize) -> bool {
+    let (prev, next) = ((x + row.len() - 1) % row.len(), (x + 1) % row.len());
+    match row[x] {
+        Some(SeaCucumber::East) => row[next].is_some(),
+        Some(SeaCucumber::South) => false,
+        None => row[prev] == Some(SeaCucumber::East),
+    }
+}
+
+/// Moves the eastward cucumbers of row `y` of `old` into the cleared `row`.
+fn east_phase(old: &SeaCucumberField, y: usize, row: &mut [Option<SeaCucumber>]) {
+    let old_row = old.row(y);
+    for (x, cell) in row.iter_mut().enumerate() {
+        if east_after_move(old_row, x) {
+            *cell = Some(SeaCucumber::East);
+        }
+    }
+}
+
+/// Adds the southward cucumbers to `row`, which already holds the moved eastward ones of row `y`.
+/// The eastward cucumbers of the next row are recomputed from `old`, so every row only needs itself and `old`.
+fn south_phase(old: &SeaCucumberField, y: usize, row: &mut [Option<SeaCucumber>]) {
+    let height = old.height();
+    let (above, current, below) = (
+        old.row((y + height - 1) % height),
+        old.row(y),
+        old.row((y + 1) % height),
+    );
+    let south = Some(SeaCucumber::South);
+    for (x, cell) in row.iter_mut().enumerate() {
+        if current[x] == south {
+            // Stays unless the cell below is free after the eastward move
+            if below[x] == south || east_after_move(below, x) {
+                *cell = south;
+            }
+        } else if above[x] == south && cell.is_none() {
+            *cell = south;
+        }
+    }
+}
+
+// Only the fallback without the `parallel` feature, and the reference for it in the tests
+#[cfg(any(test, not(feature = "parallel")))]
+fn step_rows_sequential(old: &SeaCucumberField, new: &mut SeaCucumberField) {
+    let width = old.width();
+    for (y, row) in new.as_mut_slice().chunks_mut(width).enumerate() {
+        east_phase(old, y, row);
+    }
+    for (y, row) in new.as_mut_slice().chunks_mut(width).enumerate() {
+        south_phase(old, y, row);
+    }
+}
+
+/// Both phases only write to their own row, so the rows are split among all cores.
+#[cfg(feature = "parallel")]
+fn step_rows_parallel(old: &SeaCucumberField, new: &mut SeaCucumberField) {
+    use rayon::prelude::*;
+
+    let width = old.width();
+    new.as_mut_slice()
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(y, row)| east_phase(old, y, row));
+    new.as_mut_slice()
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(y, row)| south_phase(old, y, row));
+}
+
+/// Writes the state after one step of `old` into `new`, reusing its allocation if the sizes match.
+fn step_into(old: &SeaCucumberField, new: &mut SeaCucumberField) {
+    if (new.width(), new.height()) != (old.width(), old.height()) {
+        *new = SeaCucumberField::new_empty(old.width(), old.height());
+    } else {
+        new.iter_mut().for_each(|cell| *cell = None);
+    }
+    #[cfg(feature = "parallel")]
+    step_rows_parallel(old, new);
+    #[cfg(not(feature = "parallel"))]
+    step_rows_sequential(old, new);
+}
+
+/// A single step into a new field, the tests compare the steps with the expected fields.
+#[cfg(test)]
+fn step(old: &SeaCucumberField) -> SeaCucumberField {
+    let mut res = SeaCucumberField::new_empty(old.width(), old.height());
+    step_into(old, &mut res);
+    res
+}
+
+/// Steps the field back and forth between two buffers until nothing moves anymore and returns the
+/// total number of steps for a field that already went through `done` steps.
+/// `after_step` receives the number of steps so far and the field after each step.
+fn count_steps<F>(
+    field: SeaCucumberField,
+    done: usize,
+    limits: &Limits,
+    mut after_step: F,
+) -> Result<usize, DidNotConverge>
+where
+    F: FnMut(usize, &SeaCucumberField),
+{
+    let next = SeaCucumberField::new_empty(field.width(), field.height());
+    let mut step = done;
+    iterate_until_within(
+        (field, next, false),
+        |(current, mut next, _)| {
+            step_into(&current, &mut next);
+            let converged = next == current;
+            step += 1;
+            after_step(step, &next);
+            (next, current, converged)
+        },
+        |&(_, _, converged)| converged,
+        limits,
+    )
+    .map(|(_, steps)| done + steps)
+}
+
+/// With checkpoints the field is saved every few steps and a resumed run continues from the
+/// saved one, the limits apply to the steps of the current run.
+fn steps_until_stuck<I: Input>(
+    input: I,
+    limits: &Limits,
+    checkpoints: Option<&Checkpoints>,
+) -> Result<usize> {
+    let (done, field) = match checkpoints.map(|c| c.restore(DAY)).transpose()?.flatten() {
+        Some((done, state)) => (done, checkpoint::field_from_json(&state, parse_cell)?),
+        None => (0, read_field(input)?),
+    };
+    let mut saved = Ok(());
+    let steps = count_steps(field, done, limits, |done, field| {
+        if let Some(checkpoints) = checkpoints.filter(|c| c.due(done) && saved.is_ok()) {
+            saved = checkpoints.save(DAY, done, checkpoint::field_to_json(field, render_cell));
+        }
+    });
+    saved?;
+    Ok(steps?)
+}
+
+fn part2<I: Input>(_input: I) -> Result<usize> {
+    Ok(0)
+}
+
+const INPUT: &str = "input/day25.txt";
+const DAY: &str = "day25";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    let limits = Limits::from_args(std::env::args().skip(1))?;
+    let (checkpoints, _) = Checkpoints::from_args(std::env::args().skip(1))?;
+    println!(
+        "Answer for part 1: {}",
+        measure("part 1", || steps_until_stuck(input, &limits, checkpoints.as_ref()))?
+    );
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use aoc2021::test_helpers::create_line_file;
+    use indoc::indoc;
+    use itertools::Itertools;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn example_file() -> (TempDir, impl AsRef<Path>) {
+        create_line_file(
+            [indoc! {"
+                v...>>.vv>
+                .vv>>.vv..
+                >>.>v>...v
+                >>v>>.>.v.
+                v>v.vv.v..
+                >.>>..v...
+                .vv..>.>v.
+                v.v..>>v.v
+                ....v..v.>"}]
+            .iter(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_single_step() {
+        let (dir, file) = example_file();
+        let field = read_field(file).unwrap();
+        let expected = aoc2021::parse_grid(
+            indoc! {"
+                ....>.>v.>
+                v.v>.>v.v.
+                >v>>..>v..
+                >>v>v>.>.v
+                .>v.v...v.
+                v>>.>vvv..
+                ..v...>>..
+                vv...>>vv.
+                >.v.v..v.v"}
+            .lines(),
+            1,
+            parse_cell,
+        )
+        .unwrap();
+        let actual = step(&field);
+        assert!(
+            actual.diff(&expected).is_empty(),
+            "{}",
+            actual.render_diff(&expected, render_cell)
+        );
+        assert_eq!(field.diff(&expected).len(), 44);
+        drop(dir);
+    }
+
+    /// A random `width` x `height` field, roughly a third of it eastward and a third southward cucumbers.
+    fn synthetic_field(width: usize, height: usize, mut seed: u64) -> SeaCucumberField {
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % 3
+        };
+        Field2D::parse(0..height, |_| {
+            (0..width)
+                .map(|_| match next() {
+                    0 => Some(SeaCucumber::East),
+                    1 => Some(SeaCucumber::South),
+                    _ => None,
+                })
+                .collect_vec()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_step_into() {
+        let mut current = synthetic_field(37, 23, 0x2525);
+        let mut next = SeaCucumberField::new_empty(1, 1);
+        for _ in 0..20 {
+            let expected = step(&current);
+            step_into(&current, &mut next);
+            assert_eq!(next, expected);
+            std::mem::swap(&mut current, &mut next);
+        }
+        // Nothing gets lost or created
+        let count = |field: &SeaCucumberField, kind| {
+            field.iter().filter(|c| **c == Some(kind)).count()
+        };
+        let start = synthetic_field(37, 23, 0x2525);
+        for kind in [SeaCucumber::East, SeaCucumber::South] {
+            assert_eq!(count(&current, kind), count(&start, kind));
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_step() {
+        let field = synthetic_field(120, 80, 0x0525);
+        let mut sequential = SeaCucumberField::new_empty(120, 80);
+        let mut parallel = SeaCucumberField::new_empty(120, 80);
+        step_rows_sequential(&field, &mut sequential);
+        step_rows_parallel(&field, &mut parallel);
+        assert_eq!(sequential, parallel);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[ignore]
+    fn bench_parallel_step() {
+        let field = synthetic_field(3_000, 3_000, 0x2021);
+        let mut buffer = SeaCucumberField::new_empty(field.width(), field.height());
+        let now = std::time::Instant::now();
+        for _ in 0..10 {
+            buffer.iter_mut().for_each(|cell| *cell = None);
+            step_rows_sequential(&field, &mut buffer);
+        }
+        let sequential_time = now.elapsed();
+        let sequential = buffer.clone();
+        let now = std::time::Instant::now();
+        for _ in 0..10 {
+            buffer.iter_mut().for_each(|cell| *cell = None);
+            step_rows_parallel(&field, &mut buffer);
+        }
+        let parallel_time = now.elapsed();
+        println!(
+            "10 steps of a 3000x3000 field, sequential {:?}, parallel {:?}",
+            sequential_time, parallel_time
+        );
+        assert_eq!(sequential, buffer);
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let (dir, file) = example_file();
+        let error = steps_until_stuck(&file, &Limits::max_steps(10), None).unwrap_err();
+        assert_eq!(
+            error.downcast_ref(),
+            Some(&aoc2021::simulation::DidNotConverge { steps: 10 })
+        );
+        assert_eq!(
+            steps_until_stuck(&file, &Limits::max_steps(58), None).unwrap(),
+            58
+        );
+        drop(dir);
+
+        // A lone cucumber in a ring never stops moving
+        let (dir, file) = create_line_file([">."].iter(), None);
+        assert!(steps_until_stuck(&file, &Limits::max_steps(1000), None).is_err());
+        drop(dir);
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let (dir, file) = example_file();
+        let checkpoints = Checkpoints {
+            file: dir.path().join("day25.json"),
+            every: 10,
+            resume: true,
+        };
+        // Runs out of steps after saving at step 20, the resumed run only needs the remaining steps
+        assert!(steps_until_stuck(&file, &Limits::max_steps(25), Some(&checkpoints)).is_err());
+        assert_eq!(checkpoints.restore(DAY).unwrap().unwrap().0, 20);
+        assert!(steps_until_stuck(&file, &Limits::max_steps(30), Some(&checkpoints)).is_err());
+        assert_eq!(checkpoints.restore(DAY).unwrap().unwrap().0, 50);
+        assert_eq!(
+            steps_until_stuck(&file, &Limits::max_steps(8), Some(&checkpoints)).unwrap(),
+            58
+        );
+        assert!(checkpoints.restore("day20").is_err());
+
+        let fresh = Checkpoints {
+            resume: false,
+            ..checkpoints
+        };
+        assert_eq!(
+            steps_until_stuck(&file, &Limits::default(), Some(&fresh)).unwrap(),
+            58
+        );
+        drop(dir);
+    }
+}
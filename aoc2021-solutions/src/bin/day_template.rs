@@ -0,0 +1,22 @@
+use anyhow::Result;
+use aoc2021::alloc_stats::measure;
+use aoc2021::{Input, InputSource};
+
+fn part1<I: Input>(_input: I) -> Result<usize> {
+    Ok(0)
+}
+
+fn part2<I: Input>(_input: I) -> Result<usize> {
+    Ok(0)
+}
+
+const INPUT: &str = "input/dayXX.txt";
+
+fn main() -> Result<()> {
+    let input = &InputSource::detect(INPUT)?;
+    println!("Answer for part 1: {}", measure("part 1", || part1(input))?);
+    println!("Answer for part 2: {}", measure("part 2", || part2(input))?);
+    Ok(())
+}
+
+// The example answers go into `aoc2021::examples`, with the input in `tests/fixtures/dayXX.txt`
@@ -0,0 +1,143 @@
+//! Checkpoints of long simulations, so an experiment that was interrupted or ran out of its limits
+//! can be continued later. Days save one with `--checkpoint FILE` every `--checkpoint-every N`
+//! steps and continue from it with `--resume`, the runner passes the flags on to days 20 and 25.
+
+use crate::{error::InputError, field2d::Field2D, rle_field::RleField};
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf};
+
+/// Steps between two checkpoints if `--checkpoint-every` isn't given.
+pub const DEFAULT_INTERVAL: usize = 100;
+
+/// Where and how often a day saves its state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoints {
+    pub file: PathBuf,
+    pub every: usize,
+    /// Continue from the file if it exists instead of starting over
+    pub resume: bool,
+}
+
+impl Checkpoints {
+    /// Takes `--checkpoint FILE`, `--checkpoint-every N` and `--resume` out of the arguments
+    /// and returns the remaining ones.
+    pub fn from_args<I: IntoIterator<Item = String>>(
+        args: I,
+    ) -> Result<(Option<Checkpoints>, Vec<String>)> {
+        let (mut file, mut every, mut resume) = (None, DEFAULT_INTERVAL, false);
+        let mut remaining = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+            match arg.as_str() {
+                "--checkpoint" => file = Some(PathBuf::from(value()?)),
+                "--checkpoint-every" => every = value()?.parse()?,
+                "--resume" => resume = true,
+                _ => remaining.push(arg),
+            }
+        }
+        if every == 0 {
+            bail!("--checkpoint-every needs at least one step");
+        }
+        match file {
+            Some(file) => Ok((
+                Some(Checkpoints {
+                    file,
+                    every,
+                    resume,
+                }),
+                remaining,
+            )),
+            None if resume => bail!("--resume needs the file to resume from as --checkpoint FILE"),
+            None => Ok((None, remaining)),
+        }
+    }
+
+    /// Whether a checkpoint is due after `step` steps in total.
+    pub fn due(&self, step: usize) -> bool {
+        step.is_multiple_of(self.every)
+    }
+
+    /// Replaces the checkpoint with `state` after `step` steps. The file is written next to the old
+    /// one first, so an interruption while saving leaves the previous checkpoint intact.
+    pub fn save(&self, day: &str, step: usize, state: Value) -> Result<()> {
+        let json = json!({ "day": day, "step": step, "state": state });
+        let partial = self.file.with_extension("partial");
+        fs::write(&partial, serde_json::to_string(&json)?)?;
+        fs::rename(&partial, &self.file)?;
+        Ok(())
+    }
+
+    /// The step count and state to continue from, `None` if resuming wasn't requested
+    /// or there is no checkpoint yet.
+    pub fn restore(&self, day: &str) -> Result<Option<(usize, Value)>> {
+        if !self.resume || !self.file.exists() {
+            return Ok(None);
+        }
+        let mut json: Value = serde_json::from_str(&fs::read_to_string(&self.file)?)?;
+        if json["day"] != day {
+            bail!(
+                "{} is a checkpoint of {}, not of {}",
+                self.file.display(),
+                json["day"],
+                day
+            );
+        }
+        let step = json["step"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("The checkpoint has no step count"))?;
+        Ok(Some((step as usize, json["state"].take())))
+    }
+}
+
+/// A field as one string per row, `render` turns every cell into a single character.
+pub fn field_to_json<T, F: Fn(&T) -> char>(field: &Field2D<T>, render: F) -> Value {
+    let rows: Vec<String> = (0..field.height())
+        .map(|y| field.row(y).iter().map(&render).collect())
+        .collect();
+    json!(rows)
+}
+
+/// The inverse of `field_to_json`.
+pub fn field_from_json<T, F>(value: &Value, parse: F) -> Result<Field2D<T>>
+where
+    F: Fn(char) -> Result<T>,
+{
+    let rows = value
+        .as_array()
+        .ok_or_else(|| anyhow!("A field needs a list of rows"))?
+        .iter()
+        .map(|row| {
+            row.as_str()
+                .ok_or_else(|| anyhow!("Rows have to be strings"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if rows.first().is_none_or(|row| row.is_empty()) {
+        bail!("The field is empty");
+    }
+    let field = Field2D::try_parse(rows.into_iter(), |row| {
+        row.chars()
+            .map(&parse)
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| InputError::from(e).or_token(row))
+    })?;
+    Ok(field)
+}
+
+/// A run-length encoded field as its width and the columns at which each row flips,
+/// which stays small for images that are too large to store densely.
+pub fn rle_field_to_json(field: &RleField) -> Value {
+    let rows: Vec<&[usize]> = (0..field.height()).map(|y| field.toggles(y)).collect();
+    json!({ "width": field.width(), "rows": rows })
+}
+
+/// The inverse of `rle_field_to_json`.
+pub fn rle_field_from_json(value: &Value) -> Result<RleField> {
+    let width = value["width"]
+        .as_u64()
+        .ok_or_else(|| anyhow!("The field has no width"))?;
+    let rows: Vec<Vec<usize>> = serde_json::from_value(value["rows"].clone())?;
+    RleField::from_toggles(width as usize, rows)
+        .ok_or_else(|| anyhow!("The rows don't fit into a field of width {}", width))
+}
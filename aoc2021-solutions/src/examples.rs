@@ -0,0 +1,168 @@
+//! The worked examples from the puzzle texts with their expected answers.
+//!
+//! The inputs live in `tests/fixtures/<fixture>.txt`, a day can have several of them. They are embedded into the
+//! binaries, so `aoc run --day N --example` runs a day on its first one without the repository at hand.
+//! All examples are checked by the `examples` integration test.
+
+use std::{io::Write, path::PathBuf};
+use tempfile::{NamedTempFile, TempPath};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub day: u32,
+    pub part: u32,
+    pub fixture: &'static str,
+    /// The content of the fixture
+    pub input: &'static str,
+    pub answer: &'static str,
+}
+
+macro_rules! example {
+    ($day:expr, $part:expr, $fixture:literal, $answer:expr) => {
+        Example {
+            day: $day,
+            part: $part,
+            fixture: $fixture,
+            input: include_str!(concat!("../tests/fixtures/", $fixture, ".txt")),
+            answer: $answer,
+        }
+    };
+}
+
+pub const EXAMPLES: &[Example] = &[
+    example!(1, 1, "day01", "7"),
+    example!(1, 2, "day01", "5"),
+    example!(2, 1, "day02", "150"),
+    example!(2, 2, "day02", "900"),
+    example!(3, 1, "day03", "198"),
+    example!(3, 2, "day03", "230"),
+    example!(4, 1, "day04", "4512"),
+    example!(4, 2, "day04", "1924"),
+    example!(5, 1, "day05", "5"),
+    example!(5, 2, "day05", "12"),
+    example!(6, 1, "day06", "5934"),
+    example!(6, 2, "day06", "26984457539"),
+    example!(7, 1, "day07", "37"),
+    example!(7, 2, "day07", "168"),
+    example!(8, 1, "day08", "26"),
+    example!(8, 2, "day08", "61229"),
+    example!(9, 1, "day09", "15"),
+    example!(9, 2, "day09", "1134"),
+    example!(10, 1, "day10", "26397"),
+    example!(10, 2, "day10", "288957"),
+    example!(11, 1, "day11", "1656"),
+    example!(11, 2, "day11", "195"),
+    example!(12, 1, "day12", "10"),
+    example!(12, 2, "day12", "36"),
+    example!(12, 1, "day12_medium", "19"),
+    example!(12, 2, "day12_medium", "103"),
+    example!(12, 1, "day12_large", "226"),
+    example!(12, 2, "day12_large", "3509"),
+    // The answer to part 2 is the folded paper, it is compared with a snapshot in the day's tests
+    example!(13, 1, "day13", "17"),
+    example!(14, 1, "day14", "1588"),
+    example!(14, 2, "day14", "2188189693529"),
+    example!(15, 1, "day15", "40"),
+    example!(15, 2, "day15", "315"),
+    example!(16, 1, "day16", "16"),
+    example!(16, 1, "day16_operator_length", "12"),
+    example!(16, 1, "day16_operator_count", "23"),
+    example!(16, 1, "day16_nested", "31"),
+    example!(16, 2, "day16_sum", "3"),
+    example!(17, 1, "day17", "45"),
+    example!(17, 2, "day17", "112"),
+    example!(18, 1, "day18", "4140"),
+    example!(18, 2, "day18", "3993"),
+    example!(18, 1, "day18_small", "3488"),
+    example!(19, 1, "day19", "79"),
+    example!(19, 2, "day19", "3621"),
+    example!(20, 1, "day20", "35"),
+    example!(20, 2, "day20", "3351"),
+    example!(21, 1, "day21", "739785"),
+    example!(21, 2, "day21", "444356092776315"),
+    example!(22, 1, "day22", "474140"),
+    example!(22, 2, "day22", "2758514936282235"),
+    example!(22, 1, "day22_tiny", "16"),
+    example!(22, 1, "day22_small", "39"),
+    example!(22, 1, "day22_medium", "590784"),
+    example!(23, 1, "day23", "12521"),
+    example!(23, 2, "day23", "44169"),
+    // Not from the puzzle, the program accepts exactly the numbers made up of two equal digits
+    example!(24, 1, "day24", "99"),
+    example!(24, 2, "day24", "11"),
+    example!(25, 1, "day25", "58"),
+    example!(25, 2, "day25", "0"),
+];
+
+pub fn fixture_path(fixture: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(format!("{}.txt", fixture))
+}
+
+pub fn for_day(day: u32) -> impl Iterator<Item = &'static Example> {
+    EXAMPLES.iter().filter(move |example| example.day == day)
+}
+
+/// The fixtures of a day in the order of the table, the first one is the day's main example.
+pub fn fixtures(day: u32) -> Vec<&'static str> {
+    let mut fixtures: Vec<&str> = Vec::new();
+    for example in for_day(day) {
+        if !fixtures.contains(&example.fixture) {
+            fixtures.push(example.fixture);
+        }
+    }
+    fixtures
+}
+
+/// The embedded content of a fixture.
+pub fn input(fixture: &str) -> Option<&'static str> {
+    EXAMPLES
+        .iter()
+        .find(|example| example.fixture == fixture)
+        .map(|example| example.input)
+}
+
+/// Writes the embedded content of a fixture to a temporary file for the days, which read their input from files.
+/// The file is deleted when the returned path is dropped.
+pub fn input_file(fixture: &str) -> std::io::Result<TempPath> {
+    let input = input(fixture).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no fixture {}", fixture),
+        )
+    })?;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(input.as_bytes())?;
+    Ok(file.into_temp_path())
+}
+
+/// Picks the answers out of the output of a day's binary, one per part.
+pub fn parse_answers(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter(|line| line.starts_with("Answer for part "))
+        .filter_map(|line| line.split_once(": ").map(|(_, answer)| answer.to_string()))
+        .collect()
+}
+
+/// Compares the answers of a day run on `fixture` with the expected ones, returns a description of every mismatch.
+pub fn check_answers(day: u32, fixture: &str, answers: &[String]) -> Vec<String> {
+    for_day(day)
+        .filter(|example| example.fixture == fixture)
+        .filter_map(|example| {
+            let actual = answers.get(example.part as usize - 1).map(String::as_str);
+            (actual != Some(example.answer)).then(|| {
+                format!(
+                    "day {} part {} on {}: expected {}, got {}",
+                    day,
+                    example.part,
+                    fixture,
+                    example.answer,
+                    actual.unwrap_or("nothing")
+                )
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,109 @@
+//! Allocation-free variants of some of the simpler days.
+//!
+//! These work on raw byte buffers (usually a memory-mapped input file) instead of going through
+//! `BufReader::lines`, so no `String` is allocated per line.
+//! The straightforward implementations in the day binaries are the reference, see their tests.
+
+use crate::MappedFile;
+use std::{io, path::Path};
+
+const ONES: u64 = 0x0101_0101_0101_0101;
+const LOW_BITS: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+const NEWLINES: u64 = ONES * b'\n' as u64;
+
+/// Returns a word with the high bit set in every byte of `word` that is a newline.
+///
+/// This is the exact variant of the "has zero byte" trick, so there are no false positives
+/// caused by borrows from neighboring bytes.
+fn newline_mask(word: u64) -> u64 {
+    let v = word ^ NEWLINES;
+    !(((v & LOW_BITS).wrapping_add(LOW_BITS)) | v | LOW_BITS)
+}
+
+fn parse_number(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+    digits.iter().try_fold(0u64, |acc, &digit| {
+        digit
+            .is_ascii_digit()
+            .then(|| acc * 10 + (digit - b'0') as u64)
+    })
+}
+
+/// The position of the first newline in `buf`, scanning one 64 bit word at a time.
+pub fn find_newline(buf: &[u8]) -> Option<usize> {
+    let mut chunks = buf.chunks_exact(8);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let mask = newline_mask(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if mask != 0 {
+            return Some(i * 8 + (mask.trailing_zeros() / 8) as usize);
+        }
+    }
+    let offset = buf.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map(|i| offset + i)
+}
+
+/// Splits off the first line of `buf`, the terminator (`\n` or `\r\n`) belongs to neither part.
+/// This is the line splitter of all memory-mapped inputs, see `MappedFile`.
+pub fn next_line(buf: &[u8]) -> (&[u8], &[u8]) {
+    let (line, rest) = match find_newline(buf) {
+        Some(end) => (&buf[..end], &buf[end + 1..]),
+        None => (buf, &[][..]),
+    };
+    (line.strip_suffix(b"\r").unwrap_or(line), rest)
+}
+
+/// Calls `f` with every line of `buf` without its terminator.
+pub fn for_each_line_in_bytes<F: FnMut(&[u8])>(mut buf: &[u8], mut f: F) {
+    while !buf.is_empty() {
+        let (line, rest) = next_line(buf);
+        f(line);
+        buf = rest;
+    }
+}
+
+/// Counts how often the sum of a sliding window of `window` reads increases.
+///
+/// Two neighboring windows share all but one element, so comparing the sums is the same as
+/// comparing the reads that are `window` positions apart.
+pub fn count_increases(buf: &[u8], window: usize) -> usize {
+    assert!(window > 0, "Window size must be at least 1");
+    let mut history = vec![0; window];
+    let mut seen = 0;
+    let mut increases = 0;
+    for_each_line_in_bytes(buf, |line| {
+        if let Some(value) = parse_number(line) {
+            let slot = seen % window;
+            if seen >= window && value > history[slot] {
+                increases += 1;
+            }
+            history[slot] = value;
+            seen += 1;
+        }
+    });
+    increases
+}
+
+/// Memory-maps the file at `path` and runs [`count_increases`] on it.
+pub fn count_increases_in_file<P: AsRef<Path>>(path: P, window: usize) -> io::Result<usize> {
+    Ok(count_increases(MappedFile::open(path)?.bytes(), window))
+}
+
+/// Simulates lanternfish buckets indexed by their timer value.
+///
+/// Instead of shifting all buckets down every day, the bucket holding the fish with timer 0 moves
+/// through the array.
+/// The parents re-enter the cycle at timer 6, while their offspring simply stay in the old bucket
+/// which becomes the timer 8 bucket once the start index advances.
+pub fn simulate_lanternfish(mut buckets: [u64; 9], days: usize) -> u64 {
+    for day in 0..days {
+        let zero = day % 9;
+        buckets[(zero + 7) % 9] += buckets[zero];
+    }
+    buckets.iter().sum()
+}
@@ -0,0 +1,45 @@
+//! `--lenient` solves a day with the valid lines of its input instead of stopping at the first invalid one,
+//! which helps when experimenting with hand-edited inputs. The skipped lines are summarized on stderr.
+
+use crate::{parse::Lenient, Input};
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+/// Checks whether the binary was started with `--lenient`.
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--lenient")
+}
+
+/// Parses every line of the file with `parse`, the lines that fail are recorded as issues.
+pub fn read_with<P, U, E, F>(path: P, parse: F) -> io::Result<Lenient<Vec<U>>>
+where
+    P: Input,
+    F: FnMut(&str) -> Result<U, E>,
+    E: Display,
+{
+    let lines = BufReader::new(path.open()?)
+        .lines()
+        .collect::<io::Result<Vec<_>>>()?;
+    Ok(Lenient::parse_lines(lines, parse))
+}
+
+/// Like `read_with`, for inputs with one item per line.
+pub fn read_items<P, T>(path: P) -> io::Result<Lenient<Vec<T>>>
+where
+    P: Input,
+    T: FromStr,
+    T::Err: Display,
+{
+    read_with(path, str::parse)
+}
+
+/// Prints the summary of the skipped lines to stderr, if there are any, and hands out what was parsed.
+pub fn report<T>(lenient: Lenient<T>) -> T {
+    if !lenient.issues.is_empty() {
+        eprintln!("{}", lenient.summary());
+    }
+    lenient.parsed
+}
@@ -0,0 +1,901 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader, IsTerminal};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+pub mod alloc_stats;
+pub mod artifacts;
+pub mod checkpoint;
+pub mod examples;
+pub mod lenient;
+#[cfg(feature = "fastpaths")]
+pub mod fastpaths;
+pub mod prelude;
+pub mod repl;
+pub mod report;
+pub mod solution;
+pub mod stats;
+
+pub use aoc_core::{
+    bidirange, bits, error, field2d, geometry, graph, parse, rle_field, search, simulation,
+    syntax, transform2d, vec2d, vecn, walk,
+};
+
+/// Environment variable that makes a day read its input from another file than its default one.
+pub const INPUT_VAR: &str = "AOC_INPUT";
+
+pub fn input_path(default: &str) -> PathBuf {
+    std::env::var_os(INPUT_VAR).map_or_else(|| PathBuf::from(default), PathBuf::from)
+}
+
+/// Something a day can read its input from, every call to `open` starts reading from the beginning.
+pub trait Input {
+    fn open(&self) -> io::Result<Box<dyn Read>>;
+
+    /// What parse errors call the input, usually its path.
+    fn name(&self) -> Option<String> {
+        None
+    }
+
+    /// The file behind the input, which can be read without copying it, see `MappedFile`.
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn read_to_string(&self) -> io::Result<String> {
+        let mut content = String::new();
+        self.open()?.read_to_string(&mut content)?;
+        Ok(content)
+    }
+}
+
+impl<P: AsRef<Path>> Input for P {
+    fn open(&self) -> io::Result<Box<dyn Read>> {
+        Ok(Box::new(File::open(self)?))
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.as_ref().display().to_string())
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(self.as_ref())
+    }
+}
+
+/// Where a day reads its input from. Standard input and other readers can only be consumed once,
+/// so they are read completely up front and both parts read from that copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin(Vec<u8>),
+    Str(String),
+    Reader(Vec<u8>),
+}
+
+impl InputSource {
+    pub fn stdin() -> io::Result<Self> {
+        let mut content = Vec::new();
+        io::stdin().lock().read_to_end(&mut content)?;
+        Ok(InputSource::Stdin(content))
+    }
+
+    pub fn reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content)?;
+        Ok(InputSource::Reader(content))
+    }
+
+    /// The input of a day binary: the file named by `INPUT_VAR` (`-` for stdin), otherwise whatever
+    /// is piped into the binary, otherwise the `default` file, see `default_file` if it is missing.
+    /// With `--repl` or `--play` stdin carries the commands, so only `INPUT_VAR=-` reads the input from it.
+    pub fn detect(default: &str) -> io::Result<Self> {
+        let interactive = std::env::args()
+            .skip(1)
+            .any(|arg| arg == "--repl" || arg == "--play");
+        match std::env::var_os(INPUT_VAR) {
+            Some(path) if path == "-" => Self::stdin(),
+            Some(path) => Ok(InputSource::File(path.into())),
+            None if !interactive && !io::stdin().is_terminal() => match Self::stdin()? {
+                // Binaries started without a stdin, like the ones run by `aoc`, see an empty one
+                InputSource::Stdin(content) if content.is_empty() => Self::default_file(default),
+                stdin => Ok(stdin),
+            },
+            None => Self::default_file(default),
+        }
+    }
+
+    /// The default input file of a day, downloaded first if it is missing.
+    fn default_file(default: &str) -> io::Result<Self> {
+        let path = Path::new(default);
+        if !path.exists() {
+            fetch_input(path)?;
+        }
+        Ok(InputSource::File(default.into()))
+    }
+}
+
+/// Downloads the missing input file `input/dayNN.txt` with `aoc fetch`. The downloader is part of
+/// the runner in `aoc-cli`, which is built next to the day binaries.
+fn fetch_input(path: &Path) -> io::Result<()> {
+    let day = path
+        .file_stem()
+        .and_then(|stem| stem.to_str()?.strip_prefix("day")?.parse::<u32>().ok())
+        .ok_or_else(|| io::Error::other(format!("{} is missing", path.display())))?;
+    let runner = std::env::current_exe()?
+        .with_file_name(format!("aoc{}", std::env::consts::EXE_SUFFIX));
+    if !runner.exists() {
+        return Err(io::Error::other(format!(
+            "{} is missing, copy the input of day {} there or build the runner with \
+             `cargo build --bin aoc --features online` to download it",
+            path.display(),
+            day
+        )));
+    }
+    let status = Command::new(runner)
+        .args(["fetch", "--day", &day.to_string()])
+        .status()?;
+    if !status.success() || !path.exists() {
+        return Err(io::Error::other(format!(
+            "{} is missing and `aoc fetch` couldn't download it",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+impl From<&str> for InputSource {
+    fn from(content: &str) -> Self {
+        InputSource::Str(content.to_string())
+    }
+}
+
+impl Input for InputSource {
+    fn open(&self) -> io::Result<Box<dyn Read>> {
+        Ok(match self {
+            InputSource::File(path) => Box::new(File::open(path)?),
+            InputSource::Stdin(content) | InputSource::Reader(content) => {
+                Box::new(io::Cursor::new(content.clone()))
+            }
+            InputSource::Str(content) => Box::new(io::Cursor::new(content.clone().into_bytes())),
+        })
+    }
+
+    fn name(&self) -> Option<String> {
+        match self {
+            InputSource::File(path) => path.name(),
+            InputSource::Stdin(_) => Some("<stdin>".to_string()),
+            InputSource::Str(_) | InputSource::Reader(_) => None,
+        }
+    }
+
+    fn path(&self) -> Option<&Path> {
+        match self {
+            InputSource::File(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl Input for &InputSource {
+    fn open(&self) -> io::Result<Box<dyn Read>> {
+        (*self).open()
+    }
+
+    fn name(&self) -> Option<String> {
+        (*self).name()
+    }
+
+    fn path(&self) -> Option<&Path> {
+        (*self).path()
+    }
+}
+
+/// Parses one item per line and skips the lines that don't parse, `try_stream_items` reports
+/// them instead. The stream ends at the first line that can't be read.
+pub fn stream_ints<I, T>(input: I) -> impl Iterator<Item = T>
+where
+    I: Read,
+    T: FromStr,
+{
+    BufReader::new(input)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| T::from_str(&line).ok())
+}
+
+pub fn stream_items_from_file<P: Input, T: FromStr>(
+    path: P,
+) -> std::io::Result<impl Iterator<Item = T>> {
+    Ok(stream_ints(path.open()?))
+}
+
+/// Why `try_stream_items` couldn't produce an item.
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("opening the input failed: {0}")]
+    Open(#[source] io::Error),
+    #[error("reading line {line} failed: {source}")]
+    Io {
+        line: usize,
+        #[source]
+        source: io::Error,
+    },
+    #[error("invalid input, {0}")]
+    Invalid(error::InputError),
+    #[error("invalid block {block} starting at line {line}: {reason}")]
+    Block {
+        block: usize,
+        line: usize,
+        reason: String,
+    },
+}
+
+impl ParseError {
+    /// Names the input an invalid item came from, if it has a name.
+    fn in_input(self, name: &Option<String>) -> Self {
+        match (self, name) {
+            (ParseError::Invalid(e), Some(name)) => ParseError::Invalid(e.in_file(name)),
+            (e, _) => e,
+        }
+    }
+}
+
+/// Like `stream_ints`, but every line that doesn't parse yields an error with its line number and
+/// content instead of silently ending the stream. Blank lines are parsed like any other line,
+/// only the ones at the end of the input are dropped.
+pub fn try_stream_items<R, T>(input: R) -> impl Iterator<Item = Result<T, ParseError>>
+where
+    R: Read,
+    T: FromStr,
+    T::Err: Into<error::InputError>,
+{
+    let mut lines = BufReader::new(input).lines().enumerate();
+    // Blank lines wait here until a line with content shows that they aren't trailing ones
+    let mut queue = std::collections::VecDeque::<(usize, String)>::new();
+    std::iter::from_fn(move || {
+        while queue.back().is_none_or(|(_, line)| line.trim().is_empty()) {
+            match lines.next()? {
+                (index, Ok(line)) => queue.push_back((index, line)),
+                (index, Err(source)) => {
+                    return Some(Err(ParseError::Io {
+                        line: index + 1,
+                        source,
+                    }))
+                }
+            }
+        }
+        let (index, line) = queue.pop_front()?;
+        Some(T::from_str(&line).map_err(|e| {
+            ParseError::Invalid(e.into().or_token(&line).at_line(index + 1))
+        }))
+    })
+}
+
+/// Like `try_stream_items`, errors name the file the invalid line is in.
+pub fn try_stream_items_from_file<P, T>(
+    path: P,
+) -> io::Result<impl Iterator<Item = Result<T, ParseError>>>
+where
+    P: Input,
+    T: FromStr,
+    T::Err: Into<error::InputError>,
+{
+    let name = path.name();
+    Ok(try_stream_items(path.open()?).map(move |item| item.map_err(|e| e.in_input(&name))))
+}
+
+/// Parses the comma-separated items on all lines of the input. Whitespace around an item is
+/// ignored, just like empty fields such as the one after a trailing comma.
+pub fn stream_csv_items<R, T>(input: R) -> impl Iterator<Item = Result<T, ParseError>>
+where
+    R: Read,
+    T: FromStr,
+    T::Err: Into<error::InputError>,
+{
+    BufReader::new(input)
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| -> Vec<Result<T, ParseError>> {
+            let line_number = index + 1;
+            match line {
+                Err(source) => vec![Err(ParseError::Io {
+                    line: line_number,
+                    source,
+                })],
+                Ok(line) => line
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| {
+                        T::from_str(item).map_err(|e| {
+                            // Columns of the item's own error count from the start of the item
+                            let start = error::column_of(&line, item).unwrap_or(1);
+                            let e = e.into().or_token(item).at_line(line_number);
+                            let column = e.column.map_or(start, |column| start + column - 1);
+                            ParseError::Invalid(e.at_column(column))
+                        })
+                    })
+                    .collect(),
+            }
+        })
+}
+
+pub fn stream_csv_items_from_file<P, T>(
+    path: P,
+) -> io::Result<impl Iterator<Item = Result<T, ParseError>>>
+where
+    P: Input,
+    T: FromStr,
+    T::Err: Into<error::InputError>,
+{
+    let name = path.name();
+    Ok(stream_csv_items(path.open()?).map(move |item| item.map_err(|e| e.in_input(&name))))
+}
+
+/// Counts the neighbouring windows of `window` items for which `cmp(previous, next)` holds.
+///
+/// Both windows are summed before comparing, so a window of 1 compares the items themselves.
+/// Panics if `window` is 0.
+pub fn count_adjacent<I, T, F>(input: I, window: usize, mut cmp: F) -> usize
+where
+    I: IntoIterator<Item = T>,
+    T: Copy + std::iter::Sum<T>,
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(window > 0, "the window needs at least one item");
+    let mut buffer = std::collections::VecDeque::with_capacity(window + 1);
+    let mut count = 0;
+    for item in input {
+        if buffer.len() > window {
+            buffer.pop_front();
+        }
+        buffer.push_back(item);
+        if buffer.len() > window {
+            let previous: T = buffer.iter().take(window).copied().sum();
+            let next: T = buffer.iter().skip(1).copied().sum();
+            count += usize::from(cmp(&previous, &next));
+        }
+    }
+    count
+}
+
+/// Calls `f` for every line of `input` without allocating a new `String` per line.
+///
+/// The line terminator is not part of the slice passed to `f`.
+pub fn for_each_line<R, F>(input: R, mut f: F) -> std::io::Result<()>
+where
+    R: Read,
+    F: FnMut(&str),
+{
+    let mut reader = BufReader::new(input);
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if reader.read_line(&mut buf)? == 0 {
+            return Ok(());
+        }
+        f(trim_line_end(&buf));
+    }
+}
+
+/// Like `for_each_line`, but with the `fastpaths` feature an input file is memory-mapped instead
+/// of being read through a buffer.
+pub fn for_each_input_line<I: Input, F: FnMut(&str)>(input: &I, f: F) -> io::Result<()> {
+    #[cfg(feature = "fastpaths")]
+    if let Some(path) = input.path() {
+        return MappedFile::open(path)?.for_each_line(f);
+    }
+    for_each_line(input.open()?, f)
+}
+
+fn trim_line_end(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Like `try_stream_items`, but all lines are read into a single reused buffer.
+pub struct BufferedItemStream<R, T> {
+    reader: BufReader<R>,
+    buf: String,
+    /// Number of lines read so far
+    line: usize,
+    /// The first line number and the number of blank lines that are not handed out yet
+    blanks: Option<(usize, usize)>,
+    /// Whether `buf` holds a line that waits for the blank lines before it
+    pending: bool,
+    /// What errors call the input
+    name: Option<String>,
+    _phantom: PhantomData<T>,
+}
+
+impl<R, T> BufferedItemStream<R, T>
+where
+    T: FromStr,
+    T::Err: Into<error::InputError>,
+{
+    fn parse(&self, line_number: usize, line: &str) -> Result<T, ParseError> {
+        T::from_str(line).map_err(|e| {
+            ParseError::Invalid(e.into().or_token(line).at_line(line_number)).in_input(&self.name)
+        })
+    }
+}
+
+impl<R, T> Iterator for BufferedItemStream<R, T>
+where
+    R: Read,
+    T: FromStr,
+    T::Err: Into<error::InputError>,
+{
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.pending {
+            self.buf.clear();
+            match self.reader.read_line(&mut self.buf) {
+                // Blank lines at the end of the input are dropped
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(source) => {
+                    return Some(Err(ParseError::Io {
+                        line: self.line + 1,
+                        source,
+                    }))
+                }
+            }
+            if trim_line_end(&self.buf).trim().is_empty() {
+                self.blanks.get_or_insert((self.line, 0)).1 += 1;
+            } else {
+                self.pending = true;
+            }
+        }
+        // Blank lines with content after them are parsed like any other line
+        if let Some((first, count)) = self.blanks.take() {
+            if count > 1 {
+                self.blanks = Some((first + 1, count - 1));
+            }
+            return Some(self.parse(first, ""));
+        }
+        self.pending = false;
+        Some(self.parse(self.line, trim_line_end(&self.buf)))
+    }
+}
+
+pub fn stream_items_with_buf<R: Read, T: FromStr>(input: R) -> BufferedItemStream<R, T> {
+    BufferedItemStream {
+        reader: BufReader::new(input),
+        buf: String::new(),
+        line: 0,
+        blanks: None,
+        pending: false,
+        name: None,
+        _phantom: PhantomData,
+    }
+}
+
+pub fn stream_items_from_file_with_buf<P: Input, T: FromStr>(
+    path: P,
+) -> std::io::Result<BufferedItemStream<Box<dyn Read>, T>> {
+    Ok(BufferedItemStream {
+        name: path.name(),
+        ..stream_items_with_buf(path.open()?)
+    })
+}
+
+/// A memory-mapped input file whose lines are handed out as slices of the mapping.
+#[cfg(feature = "fastpaths")]
+pub struct MappedFile {
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "fastpaths")]
+impl MappedFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: The input files are not expected to be modified while we are reading them.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(MappedFile { map })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// The lines without their terminators, the lines end at the first one that isn't UTF-8.
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        let mut rest: &[u8] = &self.map;
+        std::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let (line, next) = fastpaths::next_line(rest);
+            rest = next;
+            std::str::from_utf8(line).ok()
+        })
+    }
+
+    /// Like `for_each_line`, a line that isn't UTF-8 is an `InvalidData` error.
+    pub fn for_each_line<F: FnMut(&str)>(&self, mut f: F) -> io::Result<()> {
+        let mut result = Ok(());
+        fastpaths::for_each_line_in_bytes(&self.map, |line| {
+            if result.is_ok() {
+                match std::str::from_utf8(line) {
+                    Ok(line) => f(line),
+                    Err(e) => result = Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+                }
+            }
+        });
+        result
+    }
+}
+
+/// Like `stream_ints`, but the items are parsed straight from a memory mapping of the file,
+/// so unlike `BufReader::lines` no `String` is allocated per line.
+#[cfg(feature = "fastpaths")]
+pub struct MappedItemStream<T> {
+    file: MappedFile,
+    position: usize,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(feature = "fastpaths")]
+impl<T: FromStr> Iterator for MappedItemStream<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.file.map[self.position..];
+            if rest.is_empty() {
+                return None;
+            }
+            let (line, next) = fastpaths::next_line(rest);
+            self.position = self.file.map.len() - next.len();
+            // Like a read error in `stream_ints`, a line that isn't UTF-8 ends the stream
+            if let Ok(item) = T::from_str(std::str::from_utf8(line).ok()?) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "fastpaths")]
+pub fn stream_items_mmap<P: AsRef<Path>, T: FromStr>(
+    path: P,
+) -> io::Result<MappedItemStream<T>> {
+    Ok(MappedItemStream {
+        file: MappedFile::open(path)?,
+        position: 0,
+        _phantom: PhantomData,
+    })
+}
+
+pub struct BlockCollector<T, I, F> {
+    input: T,
+    predicate: F,
+    _phantom: PhantomData<I>,
+}
+
+impl<T, I, F> BlockCollector<T, I, F> {
+    fn new(input: T, predicate: F) -> Self {
+        BlockCollector {
+            input,
+            predicate,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, I, F> Iterator for BlockCollector<T, I, F>
+where
+    T: Iterator<Item = I>,
+    F: FnMut(&I) -> bool,
+{
+    type Item = Vec<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut group = Vec::new();
+        loop {
+            match self.input.next() {
+                Some(line) => {
+                    if (self.predicate)(&line) {
+                        return Some(group);
+                    } else {
+                        group.push(line);
+                    }
+                }
+                None => {
+                    if !group.is_empty() {
+                        return Some(group);
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn stream_file_blocks<P: Input>(
+    path: P,
+) -> std::io::Result<impl Iterator<Item = Vec<String>>> {
+    let file = path.open()?;
+    let lines = BufReader::new(file).lines().map_while(Result::ok);
+    Ok(BlockCollector::new(lines, |line: &String| line.is_empty()))
+}
+
+/// The lines of one blank-line-separated block of an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// Position of the block in the input, starting at 1
+    pub number: usize,
+    /// Line number of the first line of the block
+    pub line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Block {
+    /// Turns the block into a domain type, failures name the block and where it starts.
+    pub fn parse<T>(self) -> Result<T, ParseError>
+    where
+        T: TryFrom<Vec<String>>,
+        T::Error: Display,
+    {
+        let (block, line) = (self.number, self.line);
+        T::try_from(self.lines).map_err(|e| ParseError::Block {
+            block,
+            line,
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Splits the input at blank lines like `stream_file_blocks`, but keeps track of where each block
+/// starts and reports read errors. Runs of several blank lines don't produce empty blocks.
+pub fn try_stream_blocks<R: Read>(input: R) -> impl Iterator<Item = Result<Block, ParseError>> {
+    let mut lines = BufReader::new(input).lines().enumerate();
+    let mut number = 0;
+    std::iter::from_fn(move || {
+        let mut block: Option<Block> = None;
+        loop {
+            match lines.next() {
+                Some((index, Err(source))) => {
+                    return Some(Err(ParseError::Io {
+                        line: index + 1,
+                        source,
+                    }))
+                }
+                Some((_, Ok(line))) if line.trim().is_empty() => {
+                    if block.is_some() {
+                        return block.map(Ok);
+                    }
+                }
+                Some((index, Ok(line))) => match block.as_mut() {
+                    Some(block) => block.lines.push(line),
+                    None => {
+                        number += 1;
+                        block = Some(Block {
+                            number,
+                            line: index + 1,
+                            lines: vec![line],
+                        });
+                    }
+                },
+                None => return block.map(Ok),
+            }
+        }
+    })
+}
+
+pub fn try_stream_blocks_from_file<P: Input>(
+    path: P,
+) -> io::Result<impl Iterator<Item = Result<Block, ParseError>>> {
+    Ok(try_stream_blocks(path.open()?))
+}
+
+/// Parses every blank-line-separated block of the input into a `T`.
+pub fn stream_typed_blocks<R, T>(input: R) -> impl Iterator<Item = Result<T, ParseError>>
+where
+    R: Read,
+    T: TryFrom<Vec<String>>,
+    T::Error: Display,
+{
+    try_stream_blocks(input).map(|block| block.and_then(Block::parse))
+}
+
+pub fn stream_typed_blocks_from_file<P, T>(
+    path: P,
+) -> io::Result<impl Iterator<Item = Result<T, ParseError>>>
+where
+    P: Input,
+    T: TryFrom<Vec<String>>,
+    T::Error: Display,
+{
+    Ok(stream_typed_blocks(path.open()?))
+}
+
+/// Parses a grid with one cell per character. Blank lines before and after the grid are
+/// skipped, all rows in between must have the same number of cells. `first_line` is the line
+/// number of the first line in error messages.
+pub fn parse_grid<L, S, T, F, E>(
+    lines: L,
+    first_line: usize,
+    cell_parser: F,
+) -> Result<field2d::Field2D<T>, ParseError>
+where
+    L: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    F: FnMut(char) -> Result<T, E>,
+    E: Display,
+{
+    collect_grid(lines.into_iter().map(Ok), first_line, cell_parser)
+}
+
+fn collect_grid<S, T, F, E>(
+    lines: impl Iterator<Item = io::Result<S>>,
+    first_line: usize,
+    mut cell_parser: F,
+) -> Result<field2d::Field2D<T>, ParseError>
+where
+    S: AsRef<str>,
+    F: FnMut(char) -> Result<T, E>,
+    E: Display,
+{
+    // The rows of the grid with their line numbers
+    let mut rows: Vec<(usize, String)> = Vec::new();
+    // The first blank line after some rows, only blank lines may follow it
+    let mut end = None;
+    for (index, line) in lines.enumerate() {
+        let line_number = first_line + index;
+        let line = line.map_err(|source| ParseError::Io {
+            line: line_number,
+            source,
+        })?;
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            if !rows.is_empty() {
+                end.get_or_insert(line_number);
+            }
+            continue;
+        }
+        if let Some(end) = end {
+            let reason = format!("the grid already ended at line {}", end);
+            return Err(ParseError::Invalid(
+                error::InputError::new(line, reason).at_line(line_number),
+            ));
+        }
+        rows.push((line_number, line.to_string()));
+    }
+    field2d::Field2D::try_parse(rows.iter(), |(_, row)| {
+        row.chars()
+            .enumerate()
+            .map(|(column, cell)| {
+                cell_parser(cell).map_err(|e| {
+                    error::InputError::new(&cell.to_string(), format!("invalid cell, {}", e))
+                        .at_column(column + 1)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })
+    .map_err(|e| {
+        // `try_parse` counts the rows, which skips the blank lines before the grid
+        let (line, e) = match e.line {
+            Some(row) => {
+                let (line, content) = &rows[row - 1];
+                (*line, e.or_token(content))
+            }
+            None => (first_line, e),
+        };
+        ParseError::Invalid(error::InputError { line: Some(line), ..e })
+    })
+}
+
+/// Reads a grid with one cell per character, see `parse_grid`.
+pub fn stream_grid<R, T, F, E>(input: R, cell_parser: F) -> Result<field2d::Field2D<T>, ParseError>
+where
+    R: Read,
+    F: FnMut(char) -> Result<T, E>,
+    E: Display,
+{
+    collect_grid(BufReader::new(input).lines(), 1, cell_parser)
+}
+
+pub fn stream_grid_from_file<P, T, F, E>(
+    path: P,
+    cell_parser: F,
+) -> Result<field2d::Field2D<T>, ParseError>
+where
+    P: Input,
+    F: FnMut(char) -> Result<T, E>,
+    E: Display,
+{
+    stream_grid(path.open().map_err(ParseError::Open)?, cell_parser)
+        .map_err(|e| e.in_input(&path.name()))
+}
+
+pub mod test_helpers {
+    use crate::alloc_stats;
+    use std::{
+        fmt::Display,
+        fs,
+        fs::File,
+        io::Write,
+        path::Path,
+        time::{Duration, Instant},
+    };
+    use tempfile::{tempdir, TempDir};
+
+    pub fn create_line_file<T: Display, I: Iterator<Item = T>>(
+        inp: I,
+        dir: Option<TempDir>,
+    ) -> (TempDir, impl AsRef<Path>) {
+        let dir = dir.unwrap_or_else(|| tempdir().expect("Failed to create tempdir"));
+        let filepath = dir.path().join("tempinput.txt");
+        let mut file = File::create(&filepath).expect("Could not create file");
+        inp.for_each(|item| writeln!(file, "{}", item).expect("Could not write to file"));
+        (dir, filepath)
+    }
+
+    /// Setting this environment variable (to anything) overwrites all golden files with the current output.
+    pub const UPDATE_SNAPSHOTS_VAR: &str = "UPDATE_SNAPSHOTS";
+
+    /// Compares a rendered artifact with the golden file `tests/snapshots/<name>.txt`.
+    /// Missing golden files are created, but the assertion still fails so they get reviewed.
+    pub fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+            .join(format!("{}.txt", name));
+        let update = std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some();
+        let expected = fs::read_to_string(&path).ok();
+
+        if update || expected.is_none() {
+            fs::create_dir_all(path.parent().unwrap())
+                .expect("Could not create snapshot directory");
+            fs::write(&path, actual).expect("Could not write snapshot");
+        }
+        match expected {
+            Some(expected) => {
+                if !update {
+                    assert!(
+                        expected == actual,
+                        "Snapshot {} does not match, rerun with {}=1 to update it.\nExpected:\n{}\nActual:\n{}",
+                        name,
+                        UPDATE_SNAPSHOTS_VAR,
+                        expected,
+                        actual
+                    );
+                }
+            }
+            None => {
+                if !update {
+                    panic!("Created new snapshot {}, please review it", path.display());
+                }
+            }
+        }
+    }
+
+    /// Perf guard for `#[ignore]`d tests, fails if `f` takes longer than `limit`.
+    /// Reports the runtime and allocations like the benchmarks do, visible with `--nocapture`.
+    pub fn assert_completes_within<T, F: FnOnce() -> T>(label: &str, limit: Duration, f: F) -> T {
+        let allocations_before = alloc_stats::allocations();
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+        // Other tests may reset the counter while `f` runs
+        let allocations = alloc_stats::allocations().saturating_sub(allocations_before);
+        println!("{:<32} {:>10} allocations {:>10.2?}", label, allocations, elapsed);
+        assert!(
+            elapsed <= limit,
+            "{} took {:.2?}, the limit is {:.2?}",
+            label,
+            elapsed,
+            limit
+        );
+        result
+    }
+}
@@ -0,0 +1,98 @@
+//! The reusable parts of the solutions in one import, for use in other Advent of Code projects.
+//!
+//! ```
+//! use aoc2021::prelude::*;
+//! ```
+//!
+//! # Grids
+//!
+//! Parse a grid of digits and look at the neighbors of a cell:
+//!
+//! ```
+//! use aoc2021::prelude::*;
+//!
+//! let lines = ["123", "456", "789"];
+//! let grid = Field2D::parse(lines.iter(), |line| {
+//!     line.chars().map(|c| c.to_digit(10).unwrap()).collect::<Vec<_>>()
+//! })
+//! .unwrap();
+//! assert_eq!((grid.width(), grid.height()), (3, 3));
+//! assert_eq!(grid[(2, 1)], 6);
+//! let around_center: u32 = grid.neighbors(1, 1).map(|pos| grid[pos]).sum();
+//! assert_eq!(around_center, 2 + 4 + 6 + 8);
+//! ```
+//!
+//! # Path finding
+//!
+//! Find the cheapest way through the same grid with A*, entering a cell costs its digit:
+//!
+//! ```
+//! use aoc2021::prelude::*;
+//!
+//! let grid = Field2D::parse(["131", "191", "111"].iter(), |line| {
+//!     line.chars().map(|c| c.to_digit(10).unwrap()).collect::<Vec<_>>()
+//! })
+//! .unwrap();
+//! let goal = (2, 2);
+//! let (cost, path) = astar(
+//!     (0, 0),
+//!     |&(x, y)| grid.neighbors(x, y).map(|pos| (pos, grid[pos])),
+//!     |&(x, y): &(usize, usize)| (goal.0 - x + goal.1 - y) as u32,
+//!     |&pos| pos == goal,
+//!     &mut (),
+//! )
+//! .unwrap();
+//! assert_eq!(cost, 4);
+//! assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+//!
+//! // Where every move costs the same, a breadth-first search is enough
+//! let reachable = bfs_states(1u32, |&n| [n + 1, n * 2], Some(3));
+//! assert_eq!(reachable.path_to(&8), Some(vec![1, 2, 4, 8]));
+//! ```
+//!
+//! # Geometry
+//!
+//! Subtracting a cuboid from another one leaves up to six non-overlapping cuboids:
+//!
+//! ```
+//! use aoc2021::prelude::*;
+//!
+//! let cube: Cuboid = "x=0..9,y=0..9,z=0..9".parse().unwrap();
+//! let hole: Cuboid = "x=3..5,y=3..5,z=-20..20".parse().unwrap();
+//! let rest = &cube - &hole;
+//! assert_eq!(total_volume(&rest), Ok(1000 - 90));
+//! assert!(rest.iter().all(|part| cube.contains(part) && !part.intersects(&hole)));
+//! ```
+//!
+//! # Parsing
+//!
+//! Pull the numbers out of a line without writing a regex:
+//!
+//! ```
+//! use aoc2021::prelude::*;
+//!
+//! let [x1, y1, x2, y2]: [i32; 4] = extract_ints("target area: x=20..30, y=-10..-5").unwrap();
+//! assert_eq!((x1, y1, x2, y2), (20, 30, -10, -5));
+//! assert_eq!(extract_all_ints::<u8>("1,2 and 3").unwrap(), vec![1, 2, 3]);
+//! let point: UVec2D = "6,10".parse().unwrap();
+//! assert_eq!(Fold::along_x(5).apply(point), Some(UVec2D::new(4, 10)));
+//! ```
+
+pub use crate::{
+    field2d::Field2D,
+    geometry::{total_volume, Cuboid, Interval, KdTree, Point, Transform},
+    graph::DotGraph,
+    parse::{extract_all_ints, extract_ints, int_tokens},
+    parse_grid,
+    rle_field::RleField,
+    search::{astar, bfs_states, weighted_astar, BfsStates, SearchObserver, SearchRecord},
+    simulation::{
+        find_fixed_point, iterate_n, iterate_until, iterate_until_within, run_until,
+        DidNotConverge, Limits,
+    },
+    stream_file_blocks, stream_grid_from_file, stream_items_from_file,
+    stream_typed_blocks_from_file,
+    transform2d::{Axis, Fold, Transform2D},
+    vec2d::{IVec2D, UVec2D},
+    vecn::{Vec2D, Vec3D},
+};
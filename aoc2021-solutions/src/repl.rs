@@ -0,0 +1,46 @@
+use std::io::{self, BufRead, Write};
+
+/// An interactive mode for exploring the parsed input of a day.
+/// The input is only parsed once, afterwards every command line is passed to `eval`.
+pub trait Repl {
+    /// Handles a single command and returns the text that should be shown to the user.
+    fn eval(&mut self, command: &str) -> anyhow::Result<String>;
+
+    /// A short description of the supported commands.
+    fn help(&self) -> String;
+}
+
+/// Checks whether the binary was started with `--repl`.
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--repl")
+}
+
+/// Runs the REPL on stdin/stdout until the input ends or the user quits.
+pub fn run<R: Repl>(repl: &mut R) -> io::Result<()> {
+    run_with(repl, io::stdin().lock(), io::stdout().lock())
+}
+
+pub fn run_with<R, I, O>(repl: &mut R, input: I, mut output: O) -> io::Result<()>
+where
+    R: Repl,
+    I: BufRead,
+    O: Write,
+{
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        match line.trim() {
+            "" => (),
+            "quit" | "exit" => break,
+            "help" => writeln!(output, "{}", repl.help())?,
+            command => match repl.eval(command) {
+                Ok(result) => writeln!(output, "{}", result)?,
+                Err(e) => writeln!(output, "Error: {}", e)?,
+            },
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! Progress and diagnostic output of the solvers.
+//!
+//! Solvers never print on their own, they get a `Reporter` from `main` instead.
+//! The default reporter discards everything, so code embedding the solvers stays quiet.
+
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, Write},
+    str::FromStr,
+    sync::Mutex,
+};
+
+/// Environment variable with the default verbosity of the days, the runner uses it to pass on `--quiet` and `--verbose`.
+pub const VERBOSITY_VAR: &str = "AOC_VERBOSITY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+impl FromStr for Verbosity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "quiet" => Ok(Verbosity::Quiet),
+            "normal" => Ok(Verbosity::Normal),
+            "verbose" => Ok(Verbosity::Verbose),
+            _ => Err(anyhow!("Unknown verbosity {}", s)),
+        }
+    }
+}
+
+impl Display for Verbosity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Verbosity::Quiet => "quiet",
+            Verbosity::Normal => "normal",
+            Verbosity::Verbose => "verbose",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub struct Reporter {
+    verbosity: Verbosity,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Default for Reporter {
+    fn default() -> Self {
+        Reporter::new(Verbosity::Quiet, io::sink())
+    }
+}
+
+impl Reporter {
+    pub fn new<W: Write + Send + 'static>(verbosity: Verbosity, sink: W) -> Self {
+        Reporter {
+            verbosity,
+            sink: Mutex::new(Box::new(sink)),
+        }
+    }
+
+    pub fn stdout(verbosity: Verbosity) -> Self {
+        Reporter::new(verbosity, io::stdout())
+    }
+
+    /// Takes `--quiet`, `--verbose` and `--log FILE` out of the arguments and returns the reporter and the remaining arguments.
+    /// Without any of them, the verbosity comes from `AOC_VERBOSITY` and the output goes to stdout.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<(Reporter, Vec<String>)> {
+        let mut verbosity = match std::env::var(VERBOSITY_VAR) {
+            Ok(value) => value.parse()?,
+            Err(_) => Verbosity::Normal,
+        };
+        let mut log = None;
+        let mut remaining = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--quiet" => verbosity = Verbosity::Quiet,
+                "--verbose" => verbosity = Verbosity::Verbose,
+                "--log" => log = Some(args.next().ok_or_else(|| anyhow!("--log needs a file"))?),
+                _ => remaining.push(arg),
+            }
+        }
+        let reporter = match log {
+            Some(path) => Reporter::new(verbosity, File::create(path)?),
+            None => Reporter::stdout(verbosity),
+        };
+        Ok((reporter, remaining))
+    }
+
+    pub fn enabled(&self, level: Verbosity) -> bool {
+        level != Verbosity::Quiet && level <= self.verbosity
+    }
+
+    /// Writes a line if the reporter is at least as verbose as `level`.
+    /// Failing to write a diagnostic is not worth aborting a solver for, so errors are ignored.
+    pub fn report<D: Display>(&self, level: Verbosity, message: D) {
+        if self.enabled(level) {
+            let mut sink = self.sink.lock().unwrap();
+            let _ = writeln!(sink, "{}", message);
+        }
+    }
+
+    /// Progress and results of intermediate steps, shown unless `--quiet` is given.
+    pub fn info<D: Display>(&self, message: D) {
+        self.report(Verbosity::Normal, message)
+    }
+
+    /// Details that are only interesting when debugging, shown with `--verbose`.
+    pub fn detail<D: Display>(&self, message: D) {
+        self.report(Verbosity::Verbose, message)
+    }
+}
@@ -0,0 +1,46 @@
+//! A common driver for the days: the input is parsed once and both parts are answered from it.
+//! `solve_both` answers the parts one after the other by default, days whose parts share expensive work
+//! (day 19 assembles the same map for both) override it to do that work only once.
+//! The answers are printed in the lines `aoc` reads back with `examples::parse_answers`.
+
+use crate::alloc_stats::measure;
+use crate::Input;
+use anyhow::Result;
+use std::fmt::Display;
+
+/// A day whose answers are computed from a parsed input. `self` carries options of the day, like the
+/// overlap threshold of day 19, days without options implement it for a unit struct.
+pub trait Solution {
+    type Parsed;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse<I: Input>(&self, input: I) -> Result<Self::Parsed>;
+
+    fn part1(&self, parsed: &Self::Parsed) -> Result<Self::Answer1>;
+
+    fn part2(&self, parsed: &Self::Parsed) -> Result<Self::Answer2>;
+
+    /// Both answers from the same parsed input, `part1` and then `part2` unless a day overrides it.
+    fn solve_both(&self, parsed: &Self::Parsed) -> Result<(Self::Answer1, Self::Answer2)> {
+        Ok((self.part1(parsed)?, self.part2(parsed)?))
+    }
+}
+
+/// Answers both parts with `solve_both`, for days that need the parsed input for more than the answers.
+pub fn solve<S: Solution>(solution: &S, parsed: &S::Parsed) -> Result<(S::Answer1, S::Answer2)> {
+    measure("both parts", || solution.solve_both(parsed))
+}
+
+/// Prints the answers the way every day does, one line per part.
+pub fn print_answers<A: Display, B: Display>((answer1, answer2): (A, B)) {
+    println!("Answer for part 1: {}", answer1);
+    println!("Answer for part 2: {}", answer2);
+}
+
+/// Parses `input` once, solves both parts and prints the answers, what the `main` of a day ends with.
+pub fn run<S: Solution, I: Input>(solution: &S, input: I) -> Result<()> {
+    let parsed = solution.parse(input)?;
+    print_answers(solve(solution, &parsed)?);
+    Ok(())
+}
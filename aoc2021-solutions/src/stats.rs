@@ -0,0 +1,147 @@
+//! Structural statistics of a parsed input, printed by a day started with `--stats` and collected by `aoc stats`.
+//! They help to spot malformed inputs before spending time on solving them.
+//! The helpers for distributions of values serve the `--explain` outputs of the days.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+/// A parsed input that can describe its shape, days opt in by implementing it.
+pub trait Stats {
+    /// Pairs of a description and a value, in the order they should be printed.
+    fn stats(&self) -> Vec<(String, String)>;
+}
+
+/// Checks whether the binary was started with `--stats`.
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--stats")
+}
+
+/// One line per statistic with the values aligned behind the descriptions.
+pub fn render<S: Stats + ?Sized>(stats: &S) -> String {
+    let stats = stats.stats();
+    let width = stats.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    stats
+        .iter()
+        .map(|(name, value)| format!("{:<width$} : {}\n", name, value, width = width))
+        .collect()
+}
+
+/// The smallest and the largest item in a single pass, `None` if there are no items.
+pub fn minmax<T: Ord + Copy, I: IntoIterator<Item = T>>(items: I) -> Option<(T, T)> {
+    items.into_iter().fold(None, |acc, item| match acc {
+        None => Some((item, item)),
+        Some((min, max)) => Some((min.min(item), max.max(item))),
+    })
+}
+
+/// The `n` largest items, largest first, without sorting all of them.
+pub fn largest<T: Ord, I: IntoIterator<Item = T>>(items: I, n: usize) -> Vec<T> {
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for item in items {
+        heap.push(Reverse(item));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(item)| item)
+        .collect()
+}
+
+/// The nearest-rank percentile, the smallest value that at least `percent` percent of the values don't exceed.
+pub fn percentile<T: Ord + Copy>(values: &[T], percent: f64) -> Option<T> {
+    if values.is_empty() {
+        return None;
+    }
+    let rank = ((percent.clamp(0.0, 100.0) / 100.0 * values.len() as f64).ceil() as usize).max(1);
+    let mut values = values.to_vec();
+    Some(*values.select_nth_unstable(rank - 1).1)
+}
+
+/// Labelled horizontal bars scaled so the largest value fills `width` characters.
+pub fn bar_chart<L: AsRef<str>>(bars: &[(L, usize)], width: usize) -> String {
+    let label_width = bars
+        .iter()
+        .map(|(label, _)| label.as_ref().len())
+        .max()
+        .unwrap_or(0);
+    let largest = bars
+        .iter()
+        .map(|&(_, value)| value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    bars.iter()
+        .map(|(label, value)| {
+            format!(
+                "{:>label_width$} | {:<width$} {}\n",
+                label.as_ref(),
+                "#".repeat(value * width / largest),
+                value,
+                label_width = label_width,
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// Counts of values in buckets of equal width, the first bucket starts at 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    bucket_width: usize,
+    counts: BTreeMap<usize, usize>,
+}
+
+impl Histogram {
+    pub fn new(bucket_width: usize) -> Self {
+        Histogram {
+            bucket_width: bucket_width.max(1),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// A histogram of the values with about `buckets` buckets between 0 and the largest value.
+    pub fn of(values: &[usize], buckets: usize) -> Self {
+        let largest = values.iter().copied().max().unwrap_or(0);
+        let mut histogram = Histogram::new((largest + 1).div_ceil(buckets.max(1)));
+        values.iter().for_each(|&value| histogram.add(value));
+        histogram
+    }
+
+    pub fn add(&mut self, value: usize) {
+        *self.counts.entry(value / self.bucket_width).or_default() += 1;
+    }
+
+    /// The inclusive range of every bucket from the first to the last non-empty one, with its count.
+    pub fn buckets(&self) -> Vec<((usize, usize), usize)> {
+        let (first, last) = match minmax(self.counts.keys().copied()) {
+            Some(range) => range,
+            None => return Vec::new(),
+        };
+        (first..=last)
+            .map(|bucket| {
+                let from = bucket * self.bucket_width;
+                let count = self.counts.get(&bucket).copied().unwrap_or(0);
+                ((from, from + self.bucket_width - 1), count)
+            })
+            .collect()
+    }
+
+    pub fn render(&self, width: usize) -> String {
+        let bars = self
+            .buckets()
+            .into_iter()
+            .map(|((from, to), count)| {
+                if from == to {
+                    (from.to_string(), count)
+                } else {
+                    (format!("{}..{}", from, to), count)
+                }
+            })
+            .collect::<Vec<_>>();
+        bar_chart(&bars, width)
+    }
+}
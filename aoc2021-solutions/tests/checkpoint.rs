@@ -0,0 +1,66 @@
+//! Argument handling and the JSON encodings of fields in `aoc2021::checkpoint`.
+
+use aoc2021::checkpoint::{
+    field_from_json, field_to_json, rle_field_from_json, rle_field_to_json, Checkpoints,
+    DEFAULT_INTERVAL,
+};
+use aoc2021::{field2d::Field2D, rle_field::RleField};
+use serde_json::json;
+use std::path::PathBuf;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_from_args() {
+    let (checkpoints, remaining) = Checkpoints::from_args(args(&[
+        "--steps",
+        "10",
+        "--checkpoint",
+        "c.json",
+        "--resume",
+    ]))
+    .unwrap();
+    assert_eq!(
+        checkpoints,
+        Some(Checkpoints {
+            file: PathBuf::from("c.json"),
+            every: DEFAULT_INTERVAL,
+            resume: true
+        })
+    );
+    assert_eq!(remaining, args(&["--steps", "10"]));
+
+    let (checkpoints, _) =
+        Checkpoints::from_args(args(&["--checkpoint", "c.json", "--checkpoint-every", "5"]))
+            .unwrap();
+    let checkpoints = checkpoints.unwrap();
+    assert!(!checkpoints.resume);
+    assert!(checkpoints.due(10) && !checkpoints.due(11));
+
+    assert_eq!(Checkpoints::from_args(args(&["--quiet"])).unwrap().0, None);
+    assert!(Checkpoints::from_args(args(&["--resume"])).is_err());
+    assert!(Checkpoints::from_args(args(&["--checkpoint"])).is_err());
+    assert!(
+        Checkpoints::from_args(args(&["--checkpoint", "c", "--checkpoint-every", "0"])).is_err()
+    );
+}
+
+#[test]
+fn test_field_encodings() {
+    let field = Field2D::parse(["#..", ".#."].iter(), |row| row.chars().map(|c| c == '#')).unwrap();
+    let json = field_to_json(&field, |&lit| if lit { '#' } else { '.' });
+    assert_eq!(json, json!(["#..", ".#."]));
+    let parsed = field_from_json(&json, |c| Ok(c == '#')).unwrap();
+    assert_eq!(parsed, field);
+    assert!(field_from_json(&json!(["#..", "."]), |c| Ok(c == '#')).is_err());
+    assert!(field_from_json(&json!([]), |c| Ok(c == '#')).is_err());
+
+    let rle = RleField::from(&field);
+    let json = rle_field_to_json(&rle);
+    assert_eq!(json, json!({ "width": 3, "rows": [[0, 1], [1, 2]] }));
+    assert_eq!(rle_field_from_json(&json).unwrap(), rle);
+    assert!(rle_field_from_json(&json!({ "width": 3, "rows": [[2, 1]] })).is_err());
+    assert!(rle_field_from_json(&json!({ "width": 3, "rows": [[3]] })).is_err());
+}
@@ -0,0 +1,65 @@
+//! Runs every day on the examples embedded in `aoc2021::examples` and compares the answers.
+
+use aoc2021::examples::{self, check_answers, parse_answers};
+use std::{path::PathBuf, process::Command};
+
+fn day_binary(day: u32) -> PathBuf {
+    // Cargo builds all binaries of the crate for the integration tests, the days are next to each other
+    let name = format!("day{:02}{}", day, std::env::consts::EXE_SUFFIX);
+    PathBuf::from(env!("CARGO_BIN_EXE_day01")).with_file_name(name)
+}
+
+#[test]
+fn test_examples() {
+    let mut mismatches = Vec::new();
+    for day in 1..=25 {
+        for fixture in examples::fixtures(day) {
+            let input = examples::input_file(fixture).unwrap();
+            let output = Command::new(day_binary(day))
+                .env(aoc2021::INPUT_VAR, &input)
+                .output()
+                .expect("Could not run the day");
+            if !output.status.success() {
+                mismatches.push(format!(
+                    "day {} failed on {}: {}",
+                    day,
+                    fixture,
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+                continue;
+            }
+            let answers = parse_answers(&String::from_utf8_lossy(&output.stdout));
+            mismatches.extend(check_answers(day, fixture, &answers));
+        }
+    }
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn test_fixtures_exist() {
+    for example in examples::EXAMPLES {
+        assert!(
+            examples::fixture_path(example.fixture).exists(),
+            "Missing fixture {}",
+            example.fixture
+        );
+    }
+}
+
+#[test]
+fn test_embedded_inputs() {
+    for example in examples::EXAMPLES {
+        let on_disk = std::fs::read_to_string(examples::fixture_path(example.fixture)).unwrap();
+        assert_eq!(example.input, on_disk);
+        assert_eq!(examples::input(example.fixture), Some(example.input));
+    }
+    let file = examples::input_file("day01").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file).unwrap(),
+        examples::EXAMPLES[0].input
+    );
+    let path = file.to_path_buf();
+    drop(file);
+    assert!(!path.exists());
+    assert!(examples::input_file("day99").is_err());
+}
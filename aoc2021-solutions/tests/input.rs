@@ -0,0 +1,45 @@
+//! The input sources a day can read from.
+
+use std::io::Write;
+
+use aoc2021::{lenient, stream_csv_items_from_file, stream_items_from_file, Input, InputSource};
+
+#[test]
+fn test_sources_read_the_same() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    write!(file, "3,4\n5\n").unwrap();
+    let sources = [
+        InputSource::File(file.path().to_path_buf()),
+        InputSource::from("3,4\n5\n"),
+        InputSource::reader("3,4\n5\n".as_bytes()).unwrap(),
+    ];
+    for source in &sources {
+        assert_eq!(source.read_to_string().unwrap(), "3,4\n5\n");
+        // Every read starts from the beginning again
+        for _ in 0..2 {
+            let items: Vec<usize> = stream_csv_items_from_file(source)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(items, vec![3, 4, 5]);
+        }
+    }
+    assert_eq!(file.path().read_to_string().unwrap(), "3,4\n5\n");
+}
+
+#[test]
+fn test_helpers_accept_sources() {
+    let source = InputSource::from("199\n200\nx\n208\n");
+    let reads: Vec<usize> = stream_items_from_file(&source).unwrap().collect();
+    assert_eq!(reads, vec![199, 200, 208]);
+    let lenient = lenient::read_items::<_, usize>(source).unwrap();
+    assert_eq!(lenient.parsed, vec![199, 200, 208]);
+    assert_eq!(lenient.issues.len(), 1);
+}
+
+#[test]
+fn test_missing_file() {
+    assert!(InputSource::File("does/not/exist.txt".into())
+        .open()
+        .is_err());
+}
@@ -0,0 +1,64 @@
+//! Lenient parsing with `aoc2021::parse::Lenient` and the file helpers of `aoc2021::lenient`.
+
+use aoc2021::lenient;
+use aoc2021::parse::{Lenient, ParseIssue};
+use aoc2021::test_helpers::create_line_file;
+
+#[test]
+fn test_parse_lines() {
+    let lines = ["199", "200", "2o8", "", "210", "-3"];
+    let parsed: Lenient<Vec<u32>> = Lenient::parse_lines(lines, str::parse);
+    assert_eq!(parsed.parsed, vec![199, 200, 210]);
+    assert_eq!(
+        parsed.issues.iter().map(|i| i.line).collect::<Vec<_>>(),
+        vec![3, 6]
+    );
+    assert_eq!(
+        parsed.summary(),
+        "Skipped 2 invalid lines\n  \
+         line 3: 2o8 (invalid digit found in string)\n  \
+         line 6: -3 (invalid digit found in string)"
+    );
+    assert_eq!(
+        parsed.clone().strict(),
+        Err(ParseIssue {
+            line: 3,
+            content: "2o8".to_string(),
+            reason: "invalid digit found in string".to_string(),
+        })
+    );
+
+    // Anything that can be collected works, not just vectors
+    let (word, issues) =
+        Lenient::<String>::parse_lines(["a", "bc", "d"], str::parse::<char>).into_parts();
+    assert_eq!((word.as_str(), issues.len()), ("ad", 1));
+    assert_eq!(
+        Lenient::<Vec<u32>>::parse_lines(["1"], str::parse).strict(),
+        Ok(vec![1])
+    );
+}
+
+#[test]
+fn test_read_items() {
+    let (dir, file) = create_line_file(["forward 5", "sideways 3", "down 2"].iter(), None);
+    let parsed = lenient::read_with(&file, |line| match line.split_once(' ') {
+        Some(("forward" | "down" | "up", amount)) => {
+            amount.parse::<i32>().map_err(|e| e.to_string())
+        }
+        _ => Err("unknown command".to_string()),
+    })
+    .unwrap();
+    assert_eq!(parsed.parsed, vec![5, 2]);
+    assert_eq!(
+        parsed.issues[0].to_string(),
+        "line 2: sideways 3 (unknown command)"
+    );
+    assert_eq!(lenient::report(parsed), vec![5, 2]);
+
+    let numbers = lenient::read_items::<_, i64>(&file).unwrap();
+    assert!(numbers.parsed.is_empty());
+    assert_eq!(numbers.issues.len(), 3);
+    drop(dir);
+
+    assert!(lenient::read_items::<_, i64>("does/not/exist.txt").is_err());
+}
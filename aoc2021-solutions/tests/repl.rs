@@ -0,0 +1,65 @@
+//! Drives the interactive modes of the days through a pipe, like a script would.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use aoc2021::examples;
+
+fn day_binary(day: u32) -> PathBuf {
+    let name = format!("day{:02}{}", day, std::env::consts::EXE_SUFFIX);
+    PathBuf::from(env!("CARGO_BIN_EXE_day01")).with_file_name(name)
+}
+
+/// Runs a day with `--repl` and writes the commands to its stdin.
+fn run_repl(command: &mut Command, commands: &str) -> Output {
+    let mut child = command
+        .arg("--repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Could not run the day");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(commands.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn test_piped_commands() {
+    let input = examples::input_file("day16_sum").unwrap();
+    let output = run_repl(
+        Command::new(day_binary(16)).env(aoc2021::INPUT_VAR, &input),
+        "eval\nquit\n",
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "> 3\n> ");
+}
+
+#[test]
+fn test_piped_commands_are_not_the_input() {
+    // Without `AOC_INPUT` the day has to fall back to its input file instead of parsing the commands
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("input")).unwrap();
+    std::fs::write(
+        dir.path().join("input/day16.txt"),
+        examples::input("day16_sum").unwrap(),
+    )
+    .unwrap();
+    let output = run_repl(
+        Command::new(day_binary(16))
+            .env_remove(aoc2021::INPUT_VAR)
+            .current_dir(dir.path()),
+        "eval\nquit\n",
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "> 3\n> ");
+}
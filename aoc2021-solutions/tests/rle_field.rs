@@ -0,0 +1,44 @@
+//! Building `aoc2021::rle_field::RleField`s row by row, day 20 only covers rows of the full width.
+
+use aoc2021::rle_field::RleField;
+
+#[test]
+fn test_from_rows() {
+    let field = RleField::from_rows(
+        5,
+        vec![vec![true, true], vec![false, true, true, true, true]],
+    );
+    assert_eq!(field.row(0), vec![true, true, false, false, false]);
+    assert_eq!(field.toggles(0), &[0, 2]);
+    assert_eq!(field.row(1), vec![false, true, true, true, true]);
+    assert_eq!(field.toggles(1), &[1]);
+    assert_eq!(field.count_set(), 6);
+
+    // Longer rows are cut off at the width
+    let field = RleField::from_rows(2, vec![vec![false, true, true, false]]);
+    assert_eq!(field.row(0), vec![false, true]);
+    assert_eq!(field.count_set(), 1);
+}
+
+#[test]
+fn test_push_row() {
+    let mut field = RleField::new_empty(4, 1);
+    field.push_row([]);
+    field.push_row([true, false, true]);
+    field.push_row([true; 4]);
+    assert_eq!(field.height(), 4);
+    assert_eq!(field.row(1), vec![false; 4]);
+    assert_eq!(field.row(2), vec![true, false, true, false]);
+    assert!(!field.get(3, 2));
+    assert_eq!(field.toggles(3), &[0]);
+    assert_eq!(field.count_set(), 6);
+
+    field.set(3, 2, true);
+    assert_eq!(field.toggles(2), &[0, 1, 2]);
+    assert_eq!(
+        RleField::from_toggles(4, vec![vec![0, 1, 2]])
+            .unwrap()
+            .row(0),
+        field.row(2)
+    );
+}
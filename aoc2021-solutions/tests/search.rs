@@ -0,0 +1,56 @@
+//! `aoc2021::search::bfs_states` on small state spaces whose distances are easy to check by hand.
+
+use aoc2021::search::bfs_states;
+
+#[test]
+fn test_bfs_states() {
+    // From 1, every move either adds one or doubles
+    let successors = |n: &u32| [n + 1, n * 2];
+    let visited = bfs_states(1, successors, Some(4));
+    assert_eq!(visited.depth(&1), Some(0));
+    assert_eq!(visited.depth(&2), Some(1));
+    assert_eq!(visited.depth(&10), Some(4));
+    assert_eq!(visited.path_to(&10), Some(vec![1, 2, 4, 5, 10]));
+    assert_eq!(visited.parent(&10), Some(&5));
+    assert_eq!(visited.parent(&1), None);
+    // 11 needs five moves
+    assert!(!visited.contains(&11));
+    assert_eq!(visited.path_to(&11), None);
+    assert_eq!(visited.order[..4], [1, 2, 3, 4]);
+    assert!(visited
+        .order
+        .windows(2)
+        .all(|w| visited.depth(&w[0]) <= visited.depth(&w[1])));
+    assert_eq!(visited.find(|n| n % 7 == 0), Some(&7));
+
+    // Every state is visited once, even if it can be reached in many ways
+    let ring = bfs_states(0, |n: &u32| [(n + 1) % 10, (n + 9) % 10], None);
+    assert_eq!(ring.order.len(), 10);
+    assert_eq!(ring.depth(&5), Some(5));
+    assert_eq!(ring.depth(&9), Some(1));
+
+    let nothing = bfs_states("start", |_: &&str| Vec::new(), Some(0));
+    assert_eq!(nothing.order, vec!["start"]);
+}
+
+#[test]
+fn test_weighted_astar() {
+    use aoc2021::search::{astar, weighted_astar};
+
+    // A detour of cheap steps (0 -> 1 -> 2 -> 3 -> 10) against a single expensive step (0 -> 10)
+    let successors = |&n: &u32| match n {
+        0 => vec![(1, 1), (10, 5)],
+        1..=2 => vec![(n + 1, 1)],
+        3 => vec![(10, 1)],
+        _ => vec![],
+    };
+    let heuristic = |&n: &u32| if n == 10 { 0 } else { 1 };
+    let optimal = astar(0, successors, heuristic, |&n| n == 10, &mut ());
+    assert_eq!(optimal, Some((4, vec![0, 1, 2, 3, 10])));
+
+    // Inflated estimates make the direct step look better than walking the detour
+    let weighted = weighted_astar(0, successors, heuristic, (3, 1), |&n| n == 10, &mut ());
+    assert_eq!(weighted, Some((5, vec![0, 10])));
+    let unweighted = weighted_astar(0, successors, heuristic, (1, 1), |&n| n == 10, &mut ());
+    assert_eq!(unweighted, optimal);
+}
@@ -0,0 +1,60 @@
+//! The simulation drivers of `aoc_core::simulation` on small state machines with known step counts.
+
+use aoc2021::simulation::{
+    find_fixed_point, iterate_n, iterate_until, iterate_until_within, run_until, DidNotConverge,
+    Limits,
+};
+
+#[test]
+fn test_iterate_n() {
+    assert_eq!(iterate_n(1u64, 10, |value, _| value * 2), (1024, 10));
+    assert_eq!(iterate_n(7, 0, |_, _| unreachable!()), (7, 0));
+    let (indices, steps) = iterate_n(Vec::new(), 4, |mut indices, i| {
+        indices.push(i);
+        indices
+    });
+    assert_eq!((indices, steps), (vec![0, 1, 2, 3], 4));
+}
+
+#[test]
+fn test_iterate_until() {
+    // The Collatz sequence of 27 reaches 1 after 111 steps
+    let collatz = |n: u64| if n.is_multiple_of(2) { n / 2 } else { 3 * n + 1 };
+    assert_eq!(iterate_until(27, collatz, |&n| n == 1), (1, 111));
+    // The condition is only checked after a step
+    assert_eq!(iterate_until(1, collatz, |&n| n == 1), (1, 3));
+
+    assert_eq!(
+        iterate_until_within(27, collatz, |&n| n == 1, &Limits::max_steps(50)),
+        Err(DidNotConverge { steps: 50 })
+    );
+    assert_eq!(
+        iterate_until_within(27, collatz, |&n| n == 1, &Limits::max_steps(110)),
+        Err(DidNotConverge { steps: 110 })
+    );
+    assert_eq!(
+        iterate_until_within(27, collatz, |&n| n == 1, &Limits::max_steps(111)),
+        Ok((1, 111))
+    );
+}
+
+#[test]
+fn test_run_until_and_fixed_point() {
+    let mut calls = 0;
+    let steps = run_until(
+        || {
+            calls += 1;
+            calls == 5
+        },
+        &Limits::default(),
+    );
+    assert_eq!(steps, Ok(5));
+    assert_eq!(
+        find_fixed_point(100, |&n| n / 2, &Limits::default()),
+        Ok((0, 8))
+    );
+    assert_eq!(
+        find_fixed_point(100, |&n| n / 2, &Limits::max_steps(3)),
+        Err(DidNotConverge { steps: 3 })
+    );
+}
@@ -0,0 +1,74 @@
+//! The distribution helpers of `aoc2021::stats` on small inputs with known results.
+
+use aoc2021::stats::{bar_chart, largest, minmax, percentile, Histogram};
+
+#[test]
+fn test_minmax() {
+    assert_eq!(minmax([3, 1, 4, 1, 5, 9, 2, 6]), Some((1, 9)));
+    assert_eq!(minmax([7]), Some((7, 7)));
+    assert_eq!(minmax(Vec::<u8>::new()), None);
+}
+
+#[test]
+fn test_largest() {
+    assert_eq!(largest([3, 1, 4, 1, 5, 9, 2, 6], 3), vec![9, 6, 5]);
+    assert_eq!(largest([2, 1], 3), vec![2, 1]);
+    assert!(largest([2, 1], 0).is_empty());
+}
+
+#[test]
+fn test_percentile() {
+    let values = [15, 20, 35, 40, 50];
+    assert_eq!(percentile(&values, 0.0), Some(15));
+    assert_eq!(percentile(&values, 30.0), Some(20));
+    assert_eq!(percentile(&values, 40.0), Some(20));
+    assert_eq!(percentile(&values, 50.0), Some(35));
+    assert_eq!(percentile(&values, 100.0), Some(50));
+    assert_eq!(percentile(&[50, 15, 40, 35, 20], 50.0), Some(35));
+    assert_eq!(percentile::<u32>(&[], 50.0), None);
+}
+
+#[test]
+fn test_histogram() {
+    let mut histogram = Histogram::new(5);
+    [12, 3, 14, 10, 27].iter().for_each(|&v| histogram.add(v));
+    assert_eq!(
+        histogram.buckets(),
+        vec![
+            ((0, 4), 1),
+            ((5, 9), 0),
+            ((10, 14), 3),
+            ((15, 19), 0),
+            ((20, 24), 0),
+            ((25, 29), 1)
+        ]
+    );
+    assert_eq!(
+        histogram.render(6),
+        concat!(
+            "  0..4 | ##     1\n",
+            "  5..9 |        0\n",
+            "10..14 | ###### 3\n",
+            "15..19 |        0\n",
+            "20..24 |        0\n",
+            "25..29 | ##     1\n",
+        )
+    );
+
+    assert_eq!(
+        Histogram::of(&[0, 1, 2, 3], 2).buckets(),
+        vec![((0, 1), 2), ((2, 3), 2)]
+    );
+    assert_eq!(Histogram::of(&[4, 4], 10).render(4), "4 | #### 2\n");
+    assert!(Histogram::of(&[], 3).buckets().is_empty());
+}
+
+#[test]
+fn test_bar_chart() {
+    let chart = bar_chart(&[("long label", 10), ("b", 5), ("c", 0)], 4);
+    assert_eq!(
+        chart,
+        "long label | #### 10\n         b | ##   5\n         c |      0\n"
+    );
+    assert_eq!(bar_chart::<&str>(&[], 10), "");
+}
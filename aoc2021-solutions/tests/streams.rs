@@ -0,0 +1,297 @@
+//! Line streams and grids of `aoc2021` on inputs with invalid and blank lines.
+
+use aoc2021::{
+    for_each_input_line, parse_grid, stream_csv_items, stream_grid, stream_grid_from_file,
+    stream_ints, stream_items_with_buf, stream_typed_blocks, try_stream_blocks, try_stream_items,
+    Input, InputSource, ParseError,
+};
+
+const INPUT: &str = "199\n200\n\n208\nx10\n210\n";
+
+#[test]
+fn test_stream_ints_skips_invalid_lines() {
+    let reads: Vec<usize> = stream_ints(INPUT.as_bytes()).collect();
+    assert_eq!(reads, vec![199, 200, 208, 210]);
+}
+
+#[test]
+fn test_try_stream_items() {
+    let reads: Vec<_> = try_stream_items::<_, usize>(INPUT.as_bytes()).collect();
+    assert_eq!(reads.len(), 6);
+    assert_eq!(reads[0].as_ref().unwrap(), &199);
+    assert_eq!(reads[5].as_ref().unwrap(), &210);
+
+    let invalid = |item: &Result<usize, ParseError>| match item {
+        Err(ParseError::Invalid(e)) => Some((e.line, e.token.clone())),
+        _ => None,
+    };
+    assert_eq!(invalid(&reads[2]), Some((Some(3), String::new())));
+    assert_eq!(invalid(&reads[4]), Some((Some(5), "x10".to_string())));
+    assert_eq!(
+        reads[4].as_ref().unwrap_err().to_string(),
+        "invalid input, line 5: invalid digit found in string at \"x10\""
+    );
+
+    let first_error = try_stream_items::<_, usize>(INPUT.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!(
+        first_error.to_string(),
+        "invalid input, line 3: cannot parse integer from empty string"
+    );
+    // Blank lines at the end of the input are no error
+    assert_eq!(
+        try_stream_items::<_, usize>("1\n2\n\n\n".as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![1, 2]
+    );
+    let lines = try_stream_items::<_, String>("a\n\nb\n\n".as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(lines, vec!["a", "", "b"]);
+}
+
+#[test]
+fn test_stream_items_with_buf() {
+    let reads: Vec<_> = stream_items_with_buf::<_, usize>(INPUT.as_bytes()).collect();
+    let try_reads: Vec<_> = try_stream_items::<_, usize>(INPUT.as_bytes()).collect();
+    assert_eq!(reads.len(), try_reads.len());
+    for (read, try_read) in reads.iter().zip(&try_reads) {
+        assert_eq!(format!("{:?}", read), format!("{:?}", try_read));
+    }
+    let lines = stream_items_with_buf::<_, String>("a\r\n\n\nb\n\n".as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(lines, vec!["a", "", "", "b"]);
+}
+
+#[derive(Debug, PartialEq)]
+struct Pair(usize, usize);
+
+impl TryFrom<Vec<String>> for Pair {
+    type Error = String;
+
+    fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
+        match lines.as_slice() {
+            [a, b] => Ok(Pair(
+                a.parse().map_err(|_| format!("bad number {}", a))?,
+                b.parse().map_err(|_| format!("bad number {}", b))?,
+            )),
+            _ => Err(format!("expected 2 lines, got {}", lines.len())),
+        }
+    }
+}
+
+#[test]
+fn test_try_stream_blocks() {
+    let blocks: Vec<_> = try_stream_blocks("a\nb\n\n\n\nc\n\n".as_bytes())
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|block| (block.number, block.line, block.lines.len()))
+            .collect::<Vec<_>>(),
+        vec![(1, 1, 2), (2, 6, 1)]
+    );
+}
+
+#[test]
+fn test_stream_typed_blocks() {
+    let pairs: Vec<Result<Pair, _>> =
+        stream_typed_blocks("1\n2\n\n3\n\n4\nx\n".as_bytes()).collect();
+    assert_eq!(pairs.len(), 3);
+    assert_eq!(pairs[0].as_ref().unwrap(), &Pair(1, 2));
+    assert_eq!(
+        pairs[1].as_ref().unwrap_err().to_string(),
+        "invalid block 2 starting at line 4: expected 2 lines, got 1"
+    );
+    assert!(matches!(
+        pairs[2],
+        Err(ParseError::Block {
+            block: 3,
+            line: 6,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_stream_csv_items() {
+    let items: Vec<Result<usize, _>> =
+        stream_csv_items("3,4, 3\n1,2 ,\n\n5,x\n".as_bytes()).collect();
+    assert_eq!(items.len(), 7);
+    assert_eq!(
+        items[..6]
+            .iter()
+            .map(|item| *item.as_ref().unwrap())
+            .collect::<Vec<_>>(),
+        vec![3, 4, 3, 1, 2, 5]
+    );
+    assert_eq!(
+        items[6].as_ref().unwrap_err().to_string(),
+        "invalid input, line 4, column 3: invalid digit found in string at \"x\""
+    );
+}
+
+#[test]
+fn test_input_error_locations() {
+    use aoc2021::{error::InputError, test_helpers::create_line_file, try_stream_items_from_file};
+
+    let (dir, file) = create_line_file(["1", "2", "3x"].iter(), None);
+    let error = try_stream_items_from_file::<_, usize>(&file)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "invalid input, {}:3: invalid digit found in string at \"3x\"",
+            file.as_ref().display()
+        )
+    );
+    drop(dir);
+
+    // Columns of an item's own error are moved to where the item is on the line
+    #[derive(Debug)]
+    struct Flag;
+    impl std::str::FromStr for Flag {
+        type Err = InputError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s.find('!') {
+                Some(index) => Err(InputError::in_text(s, &s[index..], "unexpected mark")),
+                None => Ok(Flag),
+            }
+        }
+    }
+    let items: Vec<Result<Flag, _>> = stream_csv_items("ab, cd!\n".as_bytes()).collect();
+    match &items[1] {
+        Err(ParseError::Invalid(e)) => assert_eq!((e.line, e.column), (Some(1), Some(7))),
+        other => panic!("Unexpected item {:?}", other),
+    }
+
+    // Passing the error on through anyhow keeps its location
+    let error = InputError::new("x", "not a number")
+        .at_line(4)
+        .in_file("input.txt");
+    let error = InputError::from(anyhow::Error::new(error).context("reading the input"));
+    assert_eq!(error.to_string(), "input.txt:4: not a number at \"x\"");
+    assert_eq!(
+        InputError::from(anyhow::anyhow!("no location")).to_string(),
+        "no location"
+    );
+}
+
+#[cfg(feature = "fastpaths")]
+#[test]
+fn test_stream_items_mmap() {
+    use aoc2021::{stream_items_mmap, test_helpers::create_line_file, MappedFile};
+
+    let (dir, file) = create_line_file(INPUT.lines(), None);
+    let reads: Vec<usize> = stream_items_mmap(&file).unwrap().collect();
+    assert_eq!(reads, stream_ints(INPUT.as_bytes()).collect::<Vec<usize>>());
+    let mapped = MappedFile::open(&file).unwrap();
+    assert_eq!(
+        mapped.lines().collect::<Vec<_>>(),
+        INPUT.lines().collect::<Vec<_>>()
+    );
+
+    let crlf = dir.path().join("crlf.txt");
+    std::fs::write(&crlf, "1\r\n2\r\n3").unwrap();
+    let reads: Vec<usize> = stream_items_mmap(&crlf).unwrap().collect();
+    assert_eq!(reads, vec![1, 2, 3]);
+    let empty = dir.path().join("empty.txt");
+    std::fs::write(&empty, "").unwrap();
+    assert_eq!(stream_items_mmap::<_, usize>(&empty).unwrap().count(), 0);
+    assert!(stream_items_mmap::<_, usize>(dir.path().join("missing")).is_err());
+    drop(dir);
+}
+
+fn collect_lines<I: Input>(input: &I) -> Vec<String> {
+    let mut lines = Vec::new();
+    for_each_input_line(input, |line| lines.push(line.to_string())).unwrap();
+    lines
+}
+
+#[test]
+fn test_for_each_input_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("lines.txt");
+    let content = "first line\r\n\nthe third one, longer than a word\nlast";
+    std::fs::write(&file, content).unwrap();
+    let expected = vec![
+        "first line",
+        "",
+        "the third one, longer than a word",
+        "last",
+    ];
+    assert_eq!(collect_lines(&file), expected);
+    assert_eq!(collect_lines(&InputSource::from(content)), expected);
+
+    std::fs::write(&file, b"ok\n\xff\n").unwrap();
+    assert!(for_each_input_line(&file, |_| ()).is_err());
+    drop(dir);
+}
+
+#[cfg(feature = "fastpaths")]
+#[test]
+fn test_shared_line_splitter() {
+    use aoc2021::fastpaths::{find_newline, for_each_line_in_bytes, next_line};
+
+    let text = b"0123456789\nab\r\n\n0123456\n";
+    assert_eq!(find_newline(text), Some(10));
+    assert_eq!(find_newline(&text[11..]), Some(3));
+    assert_eq!(find_newline(b"no newline at all, not in any word"), None);
+    assert_eq!(next_line(&text[11..]), (&b"ab"[..], &text[15..]));
+    assert_eq!(next_line(b"last"), (&b"last"[..], &b""[..]));
+    let mut lines = Vec::new();
+    for_each_line_in_bytes(text, |line| lines.push(line.to_vec()));
+    assert_eq!(lines, [&b"0123456789"[..], b"ab", b"", b"0123456"]);
+}
+
+#[test]
+fn test_stream_grid() {
+    let digit = |c: char| c.to_digit(10).ok_or("not a digit");
+    let grid = stream_grid("\n123\n456\n\n\n".as_bytes(), digit).unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(2, 1)], 6);
+
+    let issue = |input: &str| match stream_grid(input.as_bytes(), digit) {
+        Err(ParseError::Invalid(e)) => (e.line.unwrap(), e.token, e.reason),
+        other => panic!("Unexpected result {:?}", other.map(|grid| grid.len())),
+    };
+    assert_eq!(
+        issue("123\n45\n"),
+        (
+            2,
+            "45".to_string(),
+            "2 cells, the rows above have 3".to_string()
+        )
+    );
+    assert_eq!(
+        issue("123\n4x6\n"),
+        (2, "x".to_string(), "invalid cell, not a digit".to_string())
+    );
+    assert_eq!(
+        issue("123\n\n456\n"),
+        (
+            3,
+            "456".to_string(),
+            "the grid already ended at line 2".to_string()
+        )
+    );
+    assert_eq!(issue("\n").2, "the grid has no rows");
+
+    let grid = parse_grid(["#.", ".#"], 3, |c| Ok::<_, String>(c == '#')).unwrap();
+    assert!(grid[(1, 1)] && !grid[(0, 1)]);
+    match parse_grid(["#.", "."], 3, |c| Ok::<_, String>(c == '#')) {
+        Err(ParseError::Invalid(e)) => assert_eq!(e.line, Some(4)),
+        _ => panic!("The ragged row was accepted"),
+    }
+    assert!(matches!(
+        stream_grid_from_file("does/not/exist.txt", digit),
+        Err(ParseError::Open(_))
+    ));
+}
@@ -0,0 +1,83 @@
+//! The reflections and folds of `aoc2021::transform2d` on their own, day 13 only covers folds of its inputs.
+
+use aoc2021::{
+    transform2d::{Fold, Transform2D},
+    vec2d::{IVec2D, UVec2D},
+};
+use std::collections::HashSet;
+
+#[test]
+fn test_reflections() {
+    let point = IVec2D::new(7, -2);
+    assert_eq!(Transform2D::reflect_x(5).apply(point), IVec2D::new(3, -2));
+    assert_eq!(Transform2D::reflect_y(1).apply(point), IVec2D::new(7, 4));
+    assert_eq!(
+        Transform2D::translation(IVec2D::new(-7, 2)).apply(point),
+        IVec2D::new(0, 0)
+    );
+    for transform in [
+        Transform2D::reflect_x(5),
+        Transform2D::reflect_y(-3),
+        Transform2D::translation(IVec2D::new(4, 9)),
+    ] {
+        assert_eq!(
+            transform.then(&transform.inverse()),
+            Transform2D::identity()
+        );
+    }
+    // Reflecting twice at the same line changes nothing
+    let twice = Transform2D::reflect_x(4).then(&Transform2D::reflect_x(4));
+    assert_eq!(twice, Transform2D::identity());
+}
+
+#[test]
+fn test_composition() {
+    let transforms = [
+        Transform2D::reflect_x(3),
+        Transform2D::translation(IVec2D::new(-2, 5)),
+        Transform2D::reflect_y(-1),
+        Transform2D::reflect_x(-4),
+    ];
+    let composed = transforms
+        .iter()
+        .fold(Transform2D::identity(), |acc, t| acc.then(t));
+    for x in -5..5 {
+        for y in -5..5 {
+            let point = IVec2D::new(x, y);
+            let stepwise = transforms.iter().fold(point, |p, t| t.apply(p));
+            assert_eq!(composed.apply(point), stepwise);
+            assert_eq!(composed.inverse().apply(stepwise), point);
+        }
+    }
+    // Two reflections at parallel lines are a translation by twice their distance
+    assert_eq!(
+        Transform2D::reflect_x(1).then(&Transform2D::reflect_x(4)),
+        Transform2D::translation(IVec2D::new(6, 0))
+    );
+
+    let points = [IVec2D::new(0, 0), IVec2D::new(1, 2)];
+    let moved: Vec<IVec2D> = transforms[1].apply_all(&points);
+    assert_eq!(moved, vec![IVec2D::new(-2, 5), IVec2D::new(-1, 7)]);
+}
+
+#[test]
+fn test_folds() {
+    let fold = Fold::along_y(7);
+    assert_eq!(fold.apply(UVec2D::new(3, 2)), Some(UVec2D::new(3, 2)));
+    assert_eq!(fold.apply(UVec2D::new(3, 7)), Some(UVec2D::new(3, 7)));
+    assert_eq!(fold.apply(UVec2D::new(3, 10)), Some(UVec2D::new(3, 4)));
+    assert_eq!(fold.apply(UVec2D::new(3, 14)), Some(UVec2D::new(3, 0)));
+    // Beyond the edge of the paper
+    assert_eq!(fold.apply(UVec2D::new(3, 15)), None);
+
+    let paper: HashSet<UVec2D> = [(0, 0), (4, 1), (6, 1), (8, 0)]
+        .into_iter()
+        .map(|(x, y)| UVec2D::new(x, y))
+        .collect();
+    let folded: HashSet<UVec2D> = Fold::along_x(5).apply_all(&paper);
+    assert_eq!(
+        folded,
+        HashSet::from([UVec2D::new(0, 0), UVec2D::new(4, 1), UVec2D::new(2, 0)])
+    );
+    assert_eq!(Fold::along_x(5).reflection(), Transform2D::reflect_x(5));
+}
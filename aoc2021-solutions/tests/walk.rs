@@ -0,0 +1,96 @@
+//! `aoc2021::walk` on a small tree of numbers.
+
+use aoc2021::walk::{self, Visit, Visitor, Walk};
+
+struct Node {
+    value: u32,
+    children: Vec<Node>,
+}
+
+fn node(value: u32, children: Vec<Node>) -> Node {
+    Node { value, children }
+}
+
+impl Walk for Node {
+    fn for_each_child<F: FnMut(&Self) -> bool>(&self, f: F) -> bool {
+        self.children.iter().all(f)
+    }
+}
+
+/// Records the callbacks as `+value@depth` and `-value`, stops when entering `stop_at`.
+struct Trace {
+    events: Vec<String>,
+    skip: u32,
+    stop_at: u32,
+}
+
+impl Visitor<Node> for Trace {
+    fn enter(&mut self, node: &Node, depth: usize) -> Visit {
+        self.events.push(format!("+{}@{}", node.value, depth));
+        if node.value == self.stop_at {
+            Visit::Stop
+        } else if node.value == self.skip {
+            Visit::SkipChildren
+        } else {
+            Visit::Continue
+        }
+    }
+
+    fn leave(&mut self, node: &Node, _depth: usize) -> Visit {
+        self.events.push(format!("-{}", node.value));
+        Visit::Continue
+    }
+}
+
+fn tree() -> Node {
+    node(
+        1,
+        vec![
+            node(2, vec![node(3, vec![]), node(4, vec![])]),
+            node(5, vec![node(6, vec![node(7, vec![])])]),
+        ],
+    )
+}
+
+fn trace(skip: u32, stop_at: u32) -> (bool, String) {
+    let mut trace = Trace {
+        events: Vec::new(),
+        skip,
+        stop_at,
+    };
+    let complete = walk::walk(&tree(), &mut trace);
+    (complete, trace.events.join(" "))
+}
+
+#[test]
+fn test_walk_order() {
+    assert_eq!(
+        trace(0, 0),
+        (
+            true,
+            "+1@0 +2@1 +3@2 -3 +4@2 -4 -2 +5@1 +6@2 +7@3 -7 -6 -5 -1".to_string()
+        )
+    );
+    assert_eq!(
+        trace(2, 0),
+        (true, "+1@0 +2@1 -2 +5@1 +6@2 +7@3 -7 -6 -5 -1".to_string())
+    );
+    assert_eq!(trace(0, 4), (false, "+1@0 +2@1 +3@2 -3 +4@2".to_string()));
+}
+
+#[test]
+fn test_shape_and_find() {
+    let tree = tree();
+    let shape = walk::shape(&tree);
+    assert_eq!((shape.nodes, shape.depth), (7, 3));
+    assert_eq!(walk::shape(&node(1, vec![])).depth, 0);
+
+    let mut entered = 0;
+    let found = walk::find(&tree, |node, _| {
+        entered += 1;
+        node.value % 2 == 0 && node.children.is_empty()
+    });
+    assert_eq!(found, Some(2));
+    assert_eq!(entered, 4);
+    assert_eq!(walk::find(&tree, |_, depth| depth > 3), None);
+}
@@ -1,53 +0,0 @@
-use anyhow::Result;
-use std::path::Path;
-
-fn part1<P: AsRef<Path>>(_input: P) -> Result<usize> {
-    Ok(0)
-}
-
-fn part2<P: AsRef<Path>>(_input: P) -> Result<usize> {
-    Ok(0)
-}
-
-const INPUT: &str = "input/dayXX.txt";
-
-fn main() -> Result<()> {
-    println!("Answer for part 1: {}", part1(INPUT)?);
-    println!("Answer for part 2: {}", part2(INPUT)?);
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use std::path::Path;
-
-    use aoc2021::test_helpers::create_line_file;
-    use indoc::indoc;
-    use tempfile::TempDir;
-
-    use super::*;
-
-    fn example_file() -> (TempDir, impl AsRef<Path>) {
-        create_line_file(
-            [indoc! {"
-                
-            "}]
-            .iter(),
-            None,
-        )
-    }
-
-    #[test]
-    fn test_part1() {
-        let (dir, file) = example_file();
-        assert_eq!(part1(file).unwrap(), 0);
-        drop(dir);
-    }
-
-    #[test]
-    fn test_part2() {
-        let (dir, file) = example_file();
-        assert_eq!(part2(file).unwrap(), 0);
-        drop(dir);
-    }
-}